@@ -0,0 +1,46 @@
+use std::str::FromStr;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use kml::types::{Coord, LineString, Placemark, Polygon};
+use kml::{Kml, KmlWriter};
+
+fn write_benchmark(c: &mut Criterion) {
+    c.bench_function("write (countries.kml)", |bencher| {
+        let kml = Kml::<f64>::from_str(include_str!("../tests/fixtures/countries.kml")).unwrap();
+        bencher.iter(|| {
+            let mut buf = Vec::new();
+            KmlWriter::from_writer(&mut buf).write(&kml).unwrap();
+        });
+    });
+
+    c.bench_function("write (100k vertex LineString)", |bencher| {
+        let coords = (0..100_000)
+            .map(|i| Coord::new(i as f64 * 1e-3, i as f64 * 2e-3, Some(10.)))
+            .collect::<Vec<_>>();
+        let kml = Kml::Placemark(Placemark::default().with_geometry(LineString::from(coords)));
+        bencher.iter(|| {
+            let mut buf = Vec::new();
+            KmlWriter::from_writer(&mut buf).write(&kml).unwrap();
+        });
+    });
+
+    c.bench_function("write (100k vertex oriented Polygon)", |bencher| {
+        let ring = (0..100_000)
+            .map(|i| {
+                let angle = -(i as f64) / 100_000. * std::f64::consts::TAU;
+                Coord::new(angle.cos(), angle.sin(), None)
+            })
+            .collect::<Vec<_>>();
+        let kml = Kml::Polygon(Polygon::new(ring.into(), Vec::new()));
+        bencher.iter(|| {
+            let mut buf = Vec::new();
+            KmlWriter::from_writer(&mut buf)
+                .orient_polygons(true)
+                .write(&kml)
+                .unwrap();
+        });
+    });
+}
+
+criterion_group!(benches, write_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,289 @@
+//! Conversion of altitudes between units
+//!
+//! KML altitudes are in meters, but source data like flight logs is often recorded in feet.
+//! Converting every altitude in a tree at once keeps coordinates, locations, and views consistent.
+use crate::types::{
+    lit, AltitudeUnit, Camera, Coord, CoordType, Element, Geometry, GroundOverlay, Kml,
+    LatLonAltBox, LineString, LinearRing, Location, LookAt, Point, Track,
+};
+use crate::visit::{walk_ground_overlay, KmlVisitor};
+
+fn factor<T: CoordType>(from: AltitudeUnit, to: AltitudeUnit) -> T {
+    lit(from.meters() / to.meters())
+}
+
+impl<T> Coord<T>
+where
+    T: CoordType,
+{
+    /// Converts the altitude from one unit to another, returning `true` if the coordinate has an
+    /// altitude
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{AltitudeUnit, Coord};
+    ///
+    /// let mut coord = Coord::from((1., 1., 1000.));
+    /// assert!(coord.convert_altitude(AltitudeUnit::Feet, AltitudeUnit::Meters));
+    /// assert_eq!(coord.z, Some(304.8));
+    /// ```
+    pub fn convert_altitude(&mut self, from: AltitudeUnit, to: AltitudeUnit) -> bool {
+        match self.z.as_mut() {
+            Some(z) => {
+                *z = *z * factor(from, to);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Multiplies every altitude by the factor, counting the altitudes converted
+struct ConvertAltitudes(f64, usize);
+
+impl ConvertAltitudes {
+    fn convert_coords<T: CoordType>(&mut self, coords: &mut [Coord<T>]) {
+        for z in coords.iter_mut().filter_map(|c| c.z.as_mut()) {
+            *z = *z * lit(self.0);
+            self.1 += 1;
+        }
+    }
+
+    fn convert<T: CoordType>(&mut self, value: &mut T) {
+        *value = *value * lit(self.0);
+        self.1 += 1;
+    }
+}
+
+impl<T> KmlVisitor<T> for ConvertAltitudes
+where
+    T: CoordType,
+{
+    fn visit_ground_overlay(&mut self, ground_overlay: &mut GroundOverlay<T>) {
+        self.convert(&mut ground_overlay.altitude);
+        walk_ground_overlay(self, ground_overlay);
+    }
+
+    fn visit_camera(&mut self, camera: &mut Camera<T>) {
+        self.convert(&mut camera.altitude);
+    }
+
+    fn visit_look_at(&mut self, look_at: &mut LookAt<T>) {
+        self.convert(&mut look_at.altitude);
+        self.convert(&mut look_at.range);
+    }
+
+    fn visit_lat_lon_alt_box(&mut self, lat_lon_alt_box: &mut LatLonAltBox<T>) {
+        self.convert(&mut lat_lon_alt_box.min_altitude);
+        self.convert(&mut lat_lon_alt_box.max_altitude);
+    }
+
+    fn visit_point(&mut self, point: &mut Point<T>) {
+        self.convert_coords(std::slice::from_mut(&mut point.coord));
+    }
+
+    fn visit_line_string(&mut self, line_string: &mut LineString<T>) {
+        self.convert_coords(&mut line_string.coords);
+    }
+
+    fn visit_linear_ring(&mut self, linear_ring: &mut LinearRing<T>) {
+        self.convert_coords(&mut linear_ring.coords);
+    }
+
+    fn visit_track(&mut self, track: &mut Track<T>) {
+        self.convert_coords(&mut track.coords);
+    }
+
+    fn visit_location(&mut self, location: &mut Location<T>) {
+        self.convert(&mut location.altitude);
+    }
+
+    fn visit_element(&mut self, element: &mut Element) {
+        self.1 += convert_element_altitudes(element, self.0);
+    }
+}
+
+impl<T> Geometry<T>
+where
+    T: CoordType,
+{
+    /// Converts every altitude in the geometry from one unit to another, returning the number of
+    /// altitudes converted. See [`Kml::convert_altitudes`](../enum.Kml.html#method.convert_altitudes)
+    pub fn convert_altitudes(&mut self, from: AltitudeUnit, to: AltitudeUnit) -> usize {
+        if from == to {
+            return 0;
+        }
+        let mut visitor = ConvertAltitudes(from.meters() / to.meters(), 0);
+        self.walk(&mut visitor);
+        visitor.1
+    }
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Converts every altitude in the tree from one unit to another, returning the number of
+    /// altitudes converted
+    ///
+    /// This covers coordinates of geometries including tracks, `kml:Location` including that of a
+    /// `kml:Model`, the altitudes of views like `kml:Camera` and the `range` of `kml:LookAt`, the
+    /// altitude limits of `kml:LatLonAltBox`, and the same values in elements without a typed
+    /// representation that are read as [`Element`](types/struct.Element.html), like each
+    /// `gx:coord` of a `gx:Track` in a custom element. Values that can't be parsed are left as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, types::AbstractView};
+    /// use kml::types::AltitudeUnit;
+    ///
+    /// let mut kml: Kml = r#"<Placemark>
+    ///   <LookAt><altitude>1000</altitude><range>500</range></LookAt>
+    ///   <LineString><coordinates>1,1,100 2,2,200 3,3</coordinates></LineString>
+    /// </Placemark>"#.parse().unwrap();
+    /// assert_eq!(kml.convert_altitudes(AltitudeUnit::Feet, AltitudeUnit::Meters), 4);
+    /// if let Kml::Placemark(p) = kml {
+    ///     assert!(matches!(p.view, Some(AbstractView::LookAt(l)) if l.altitude == 304.8));
+    /// }
+    /// ```
+    pub fn convert_altitudes(&mut self, from: AltitudeUnit, to: AltitudeUnit) -> usize {
+        if from == to {
+            return 0;
+        }
+        let mut visitor = ConvertAltitudes(from.meters() / to.meters(), 0);
+        self.walk(&mut visitor);
+        visitor.1
+    }
+}
+
+// Converts altitudes in the children of elements without a typed representation, leaving values
+// that can't be parsed as is
+fn convert_element_altitudes(element: &mut Element, factor: f64) -> usize {
+    let mut changed = 0;
+    let parent = element.local_name().to_string();
+    for child in element.children.iter_mut() {
+        let is_altitude = matches!(
+            (parent.as_str(), child.local_name()),
+            ("Camera" | "LookAt" | "Location", "altitude")
+                | ("LookAt", "range")
+                | ("LatLonAltBox", "minAltitude" | "maxAltitude")
+        );
+        let content = child.content.as_deref().map(str::trim);
+        if is_altitude {
+            if let Some(value) = content.and_then(|c| c.parse::<f64>().ok()) {
+                child.content = Some((value * factor).to_string());
+                changed += 1;
+            }
+        } else if parent == "Track" && child.local_name() == "coord" {
+            let values = content.and_then(|c| {
+                c.split_whitespace()
+                    .map(|v| v.parse::<f64>().ok())
+                    .collect::<Option<Vec<f64>>>()
+            });
+            if let Some([x, y, z]) = values.as_deref() {
+                child.content = Some(format!("{} {} {}", x, y, z * factor));
+                changed += 1;
+            }
+        } else {
+            changed += convert_element_altitudes(child, factor);
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_altitudes() {
+        let mut kml: Kml = r#"<Document>
+          <Location><longitude>1</longitude><latitude>1</latitude><altitude>10</altitude></Location>
+          <Placemark>
+            <Model><Location><altitude>10</altitude></Location></Model>
+            <Region><LatLonAltBox><minAltitude>0</minAltitude><maxAltitude>100</maxAltitude></LatLonAltBox></Region>
+          </Placemark>
+          <Placemark><Track><when>2010-05-28T02:02:09Z</when><coord>1 2 10</coord></Track></Placemark>
+          <Polygon><outerBoundaryIs><LinearRing>
+            <coordinates>0,0,10 1,0,10 1,1,10 0,0,10</coordinates>
+          </LinearRing></outerBoundaryIs></Polygon>
+        </Document>"#
+            .parse()
+            .unwrap();
+        let original = kml.clone();
+
+        assert_eq!(
+            kml.convert_altitudes(AltitudeUnit::Meters, AltitudeUnit::Meters),
+            0
+        );
+        assert_eq!(
+            kml.convert_altitudes(AltitudeUnit::Meters, AltitudeUnit::Feet),
+            9
+        );
+        assert_ne!(kml, original);
+        if let Kml::Document { elements, .. } = &kml {
+            assert!(
+                matches!(&elements[0], Kml::Location(l) if (l.altitude - 32.8084).abs() < 1e-4)
+            );
+        }
+        assert!(kml
+            .to_string()
+            .contains("<maxAltitude>328.0839895013123</maxAltitude>"));
+        assert!(kml
+            .to_string()
+            .contains("<gx:coord>1 2 32.80839895013123</gx:coord>"));
+    }
+
+    #[test]
+    fn test_convert_geometry_altitudes() {
+        // Coordinates without an altitude aren't counted
+        let mut geometry = Geometry::LineString(LineString::from(vec![
+            Coord::new(1., 1., None),
+            Coord::new(2., 2., Some(1000.)),
+        ]));
+        assert_eq!(
+            geometry.convert_altitudes(AltitudeUnit::Feet, AltitudeUnit::Feet),
+            0
+        );
+        assert_eq!(
+            geometry.convert_altitudes(AltitudeUnit::Feet, AltitudeUnit::Meters),
+            1
+        );
+        match geometry {
+            Geometry::LineString(l) => {
+                assert_eq!(l.coords[0].z, None);
+                assert_eq!(l.coords[1].z, Some(304.8));
+            }
+            g => panic!("expected LineString, found {:?}", g),
+        }
+
+        let mut geometry: Geometry = Geometry::Point(Point::new(1., 1., None));
+        assert_eq!(
+            geometry.convert_altitudes(AltitudeUnit::Meters, AltitudeUnit::Feet),
+            0
+        );
+    }
+
+    #[test]
+    fn test_convert_invalid_element_altitudes() {
+        // The invalid view and the custom element are read as elements, where values that can't
+        // be parsed and `gx:coord` without an altitude are left as is
+        let mut kml: Kml = r#"<Placemark>
+          <Camera><altitude>high</altitude><longitude>east</longitude></Camera>
+          <Custom><Track><coord>1 2</coord><coord>1 2 10</coord><coord>1 2 up</coord></Track></Custom>
+        </Placemark>"#
+            .parse()
+            .unwrap();
+        assert_eq!(
+            kml.convert_altitudes(AltitudeUnit::Meters, AltitudeUnit::Feet),
+            1
+        );
+        let kml_str = kml.to_string();
+        assert!(kml_str.contains("<altitude>high</altitude>"));
+        assert!(kml_str.contains("<coord>1 2</coord>"));
+        assert!(kml_str.contains("<coord>1 2 32.80839895013123</coord>"));
+        assert!(kml_str.contains("<coord>1 2 up</coord>"));
+    }
+}
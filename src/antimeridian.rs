@@ -0,0 +1,554 @@
+//! Detection and normalization of geometries crossing the antimeridian
+//!
+//! Coordinates are assumed to be in the range -180 to 180 longitude, so consecutive coordinates
+//! more than 180 degrees of longitude apart are treated as crossing the antimeridian. Viewers like
+//! Google Earth draw these segments the long way around the globe unless they're split.
+use std::mem;
+
+use crate::types::{
+    lit, Camera, Coord, CoordType, Geometry, Kml, LineString, LinearRing, Location, LookAt,
+    MultiGeometry, Point, Polygon, Track,
+};
+use crate::visit::{walk_geometry, walk_kml, KmlVisitor};
+
+pub(crate) fn crosses<T: CoordType>(coords: &[Coord<T>]) -> bool {
+    coords
+        .windows(2)
+        .any(|w| (w[1].x - w[0].x).abs() > lit(180.))
+}
+
+// Interpolates the coordinate where the segment from `a` to `b` (already unwrapped so they're
+// within 180 degrees of each other) crosses the meridian at `x`
+fn intersect<T: CoordType>(a: &Coord<T>, b: &Coord<T>, x: T) -> Coord<T> {
+    let t = (x - a.x) / (b.x - a.x);
+    Coord {
+        x,
+        y: a.y + t * (b.y - a.y),
+        z: match (a.z, b.z) {
+            (Some(az), Some(bz)) => Some(az + t * (bz - az)),
+            _ => None,
+        },
+    }
+}
+
+// Shifts longitudes by multiples of 360 so no consecutive coordinates are more than 180 apart,
+// starting from a reference longitude
+fn unwrap<T: CoordType>(coords: &[Coord<T>], reference: T) -> Vec<Coord<T>> {
+    let mut prev = reference;
+    coords
+        .iter()
+        .map(|c| {
+            let mut c = *c;
+            while c.x - prev > lit(180.) {
+                c.x = c.x - lit(360.);
+            }
+            while prev - c.x > lit(180.) {
+                c.x = c.x + lit(360.);
+            }
+            prev = c.x;
+            c
+        })
+        .collect()
+}
+
+// Sutherland-Hodgman clipping of a closed ring against the meridian at `x`, keeping the side
+// west of it if `west` is true
+fn clip<T: CoordType>(ring: &[Coord<T>], x: T, west: bool) -> Vec<Coord<T>> {
+    let inside = |c: &Coord<T>| if west { c.x <= x } else { c.x >= x };
+    let open = &ring[..ring.len().saturating_sub(1)];
+    let mut clipped = Vec::new();
+    for (i, current) in open.iter().enumerate() {
+        let prev = &open[(i + open.len() - 1) % open.len()];
+        match (inside(prev), inside(current)) {
+            (true, true) => clipped.push(*current),
+            (true, false) => clipped.push(intersect(prev, current, x)),
+            (false, true) => {
+                clipped.push(intersect(prev, current, x));
+                clipped.push(*current);
+            }
+            (false, false) => {}
+        }
+    }
+    if let Some(first) = clipped.first().copied() {
+        clipped.push(first);
+    }
+    clipped
+}
+
+fn shift<T: CoordType>(mut coords: Vec<Coord<T>>, offset: T) -> Vec<Coord<T>> {
+    for c in coords.iter_mut() {
+        c.x = c.x + offset;
+    }
+    coords
+}
+
+impl<T> Coord<T>
+where
+    T: CoordType,
+{
+    /// Shifts the longitude by multiples of 360 so it's between -180 and 180, returning `true` if
+    /// the coordinate was changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::Coord;
+    ///
+    /// let mut coord = Coord::from((190., 10.));
+    /// assert!(coord.wrap_longitude());
+    /// assert_eq!(coord, Coord::from((-170., 10.)));
+    /// ```
+    pub fn wrap_longitude(&mut self) -> bool {
+        if self.x >= lit(-180.) && self.x <= lit(180.) {
+            return false;
+        }
+        let wrapped = (self.x + lit(180.)) % lit(360.);
+        self.x = if wrapped < T::zero() {
+            wrapped + lit(180.)
+        } else {
+            wrapped - lit(180.)
+        };
+        true
+    }
+}
+
+impl<T> LineString<T>
+where
+    T: CoordType + Default,
+{
+    /// Returns `true` if any segment of the line crosses the antimeridian
+    pub fn crosses_antimeridian(&self) -> bool {
+        crosses(&self.coords)
+    }
+
+    /// Splits the line at each crossing of the antimeridian, interpolating coordinates on either
+    /// side of it. Lines that don't cross are returned as the only element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Coord, LineString};
+    ///
+    /// let line = LineString::from(vec![Coord::from((170., 0.)), Coord::from((-170., 10.))]);
+    /// let parts = line.split_antimeridian();
+    ///
+    /// assert_eq!(parts[0].coords, vec![Coord::from((170., 0.)), Coord::from((180., 5.))]);
+    /// assert_eq!(parts[1].coords, vec![Coord::from((-180., 5.)), Coord::from((-170., 10.))]);
+    /// ```
+    pub fn split_antimeridian(&self) -> Vec<LineString<T>> {
+        let mut parts: Vec<Vec<Coord<T>>> = vec![vec![]];
+        for (i, c) in self.coords.iter().enumerate() {
+            if let Some(prev) = i.checked_sub(1).map(|j| &self.coords[j]) {
+                if (c.x - prev.x).abs() > lit(180.) {
+                    let (edge, offset): (T, T) = if prev.x > T::zero() {
+                        (lit(180.), lit(360.))
+                    } else {
+                        (lit(-180.), lit(-360.))
+                    };
+                    let unwrapped = Coord {
+                        x: c.x + offset,
+                        ..*c
+                    };
+                    let crossing = intersect(prev, &unwrapped, edge);
+                    parts.last_mut().unwrap().push(crossing);
+                    parts.push(vec![Coord {
+                        x: -edge,
+                        ..crossing
+                    }]);
+                }
+            }
+            parts.last_mut().unwrap().push(*c);
+        }
+        parts
+            .into_iter()
+            .map(|coords| LineString {
+                coords,
+                extrude: self.extrude,
+                tessellate: self.tessellate,
+                altitude_mode: self.altitude_mode,
+                ..Default::default()
+            })
+            .collect()
+    }
+}
+
+impl<T> LinearRing<T>
+where
+    T: CoordType,
+{
+    /// Returns `true` if any segment of the ring crosses the antimeridian
+    pub fn crosses_antimeridian(&self) -> bool {
+        crosses(&self.coords)
+    }
+}
+
+impl<T> Polygon<T>
+where
+    T: CoordType + Default,
+{
+    /// Returns `true` if any segment of the outer or inner rings crosses the antimeridian
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.outer.crosses_antimeridian() || self.inner.iter().any(|r| r.crosses_antimeridian())
+    }
+
+    /// Clips the polygon along the antimeridian into the part west of it and the part east of it.
+    /// Polygons that don't cross are returned as the only element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Coord, LinearRing, Polygon};
+    ///
+    /// let outer = LinearRing::from(vec![
+    ///     Coord::from((170., 0.)),
+    ///     Coord::from((-170., 0.)),
+    ///     Coord::from((-170., 10.)),
+    ///     Coord::from((170., 10.)),
+    ///     Coord::from((170., 0.)),
+    /// ]);
+    /// let parts = Polygon::new(outer, vec![]).split_antimeridian();
+    ///
+    /// assert_eq!(parts.len(), 2);
+    /// assert!(parts.iter().all(|p| !p.crosses_antimeridian()));
+    /// ```
+    pub fn split_antimeridian(&self) -> Vec<Polygon<T>> {
+        if !self.crosses_antimeridian() || self.outer.coords.is_empty() {
+            return vec![self.clone()];
+        }
+        let outer = unwrap(&self.outer.coords, self.outer.coords[0].x);
+        let inner: Vec<Vec<Coord<T>>> = self
+            .inner
+            .iter()
+            .filter(|r| !r.coords.is_empty())
+            .map(|r| unwrap(&r.coords, outer[0].x))
+            .collect();
+
+        let max = outer.iter().fold(outer[0].x, |acc, c| acc.max(c.x));
+        let edge: T = if max > lit(180.) {
+            lit(180.)
+        } else {
+            lit(-180.)
+        };
+        let ring = |coords: Vec<Coord<T>>, template: &LinearRing<T>| LinearRing {
+            coords,
+            extrude: template.extrude,
+            tessellate: template.tessellate,
+            altitude_mode: template.altitude_mode,
+            ..Default::default()
+        };
+
+        [true, false]
+            .iter()
+            .filter_map(|&west| {
+                // Whichever side is beyond the edge is shifted back into range
+                let beyond = (edge > T::zero()) != west;
+                let offset = if !beyond {
+                    T::zero()
+                } else if edge > T::zero() {
+                    lit(-360.)
+                } else {
+                    lit(360.)
+                };
+                let outer_part = shift(clip(&outer, edge, west), offset);
+                if outer_part.len() < 4 {
+                    return None;
+                }
+                let inner_parts = inner
+                    .iter()
+                    .zip(self.inner.iter())
+                    .map(|(coords, template)| (shift(clip(coords, edge, west), offset), template))
+                    .filter(|(coords, _)| coords.len() >= 4)
+                    .map(|(coords, template)| ring(coords, template))
+                    .collect();
+                Some(Polygon {
+                    outer: ring(outer_part, &self.outer),
+                    inner: inner_parts,
+                    extrude: self.extrude,
+                    tessellate: self.tessellate,
+                    altitude_mode: self.altitude_mode,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
+
+impl<T> Geometry<T>
+where
+    T: CoordType + Default,
+{
+    /// Returns `true` if any segment of the geometry crosses the antimeridian
+    pub fn crosses_antimeridian(&self) -> bool {
+        match self {
+            Geometry::LineString(l) => l.crosses_antimeridian(),
+            Geometry::LinearRing(l) => l.crosses_antimeridian(),
+            Geometry::Polygon(p) => p.crosses_antimeridian(),
+            Geometry::MultiGeometry(g) => g.geometries.iter().any(Geometry::crosses_antimeridian),
+            Geometry::Track(t) => crosses(&t.coords),
+            Geometry::MultiTrack(t) => t.tracks.iter().any(|t| crosses(&t.coords)),
+            _ => false,
+        }
+    }
+
+    /// Replaces any `kml:LineString` or `kml:Polygon` crossing the antimeridian with a
+    /// `kml:MultiGeometry` of its parts on either side, returning the number of geometries split
+    ///
+    /// `kml:LinearRing` geometries outside of a polygon aren't split since the parts wouldn't be
+    /// closed rings.
+    pub fn split_antimeridian(&mut self) -> usize {
+        let mut visitor = SplitAntimeridian(0);
+        self.walk(&mut visitor);
+        visitor.0
+    }
+
+    /// Shifts every longitude in the geometry into the range -180 to 180, returning the number of
+    /// coordinates changed
+    pub fn wrap_longitudes(&mut self) -> usize {
+        let mut visitor = WrapLongitudes(0);
+        self.walk(&mut visitor);
+        visitor.0
+    }
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType + Default,
+{
+    /// Replaces any `kml:LineString` or `kml:Polygon` in the tree crossing the antimeridian with a
+    /// `kml:MultiGeometry` of its parts on either side, returning the number of geometries split
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::Kml;
+    ///
+    /// let mut kml: Kml = r#"<Placemark>
+    ///   <LineString><coordinates>170,0 -170,10</coordinates></LineString>
+    /// </Placemark>"#.parse().unwrap();
+    ///
+    /// assert_eq!(kml.split_antimeridian(), 1);
+    /// if let Kml::Placemark(placemark) = kml {
+    ///     assert!(!placemark.geometry.unwrap().crosses_antimeridian());
+    /// }
+    /// ```
+    pub fn split_antimeridian(&mut self) -> usize {
+        let mut visitor = SplitAntimeridian(0);
+        self.walk(&mut visitor);
+        visitor.0
+    }
+
+    /// Shifts every longitude in the tree into the range -180 to 180, returning the number of
+    /// positions changed
+    ///
+    /// This covers coordinates of geometries, `kml:Location`, and views like `kml:Camera`. The
+    /// edges of boxes like `kml:LatLonBox` are left as is, since wrapping them could swap east and
+    /// west.
+    pub fn wrap_longitudes(&mut self) -> usize {
+        let mut visitor = WrapLongitudes(0);
+        self.walk(&mut visitor);
+        visitor.0
+    }
+}
+
+// Replaces geometries crossing the antimeridian with a `kml:MultiGeometry` of their parts,
+// counting the geometries split
+struct SplitAntimeridian(usize);
+
+impl<T> KmlVisitor<T> for SplitAntimeridian
+where
+    T: CoordType + Default,
+{
+    fn visit_kml(&mut self, kml: &mut Kml<T>) {
+        let mut geometry = match kml {
+            Kml::LineString(l) => Geometry::LineString(mem::take(l)),
+            Kml::Polygon(p) => Geometry::Polygon(mem::take(p)),
+            Kml::MultiGeometry(g) => Geometry::MultiGeometry(mem::take(g)),
+            _ => return walk_kml(self, kml),
+        };
+        self.visit_geometry(&mut geometry);
+        *kml = match geometry {
+            Geometry::LineString(l) => Kml::LineString(l),
+            Geometry::Polygon(p) => Kml::Polygon(p),
+            Geometry::MultiGeometry(g) => Kml::MultiGeometry(g),
+            _ => unreachable!(),
+        };
+    }
+
+    fn visit_geometry(&mut self, geometry: &mut Geometry<T>) {
+        let (parts, attrs) = match geometry {
+            Geometry::LineString(l) if l.crosses_antimeridian() => (
+                l.split_antimeridian()
+                    .into_iter()
+                    .map(Geometry::LineString)
+                    .collect(),
+                mem::take(&mut l.attrs),
+            ),
+            Geometry::Polygon(p) if p.crosses_antimeridian() => (
+                p.split_antimeridian()
+                    .into_iter()
+                    .map(Geometry::Polygon)
+                    .collect(),
+                mem::take(&mut p.attrs),
+            ),
+            _ => return walk_geometry(self, geometry),
+        };
+        *geometry = Geometry::MultiGeometry(MultiGeometry {
+            geometries: parts,
+            attrs,
+        });
+        self.0 += 1;
+    }
+}
+
+// Wraps longitudes into the range -180 to 180, counting the positions changed
+struct WrapLongitudes(usize);
+
+impl WrapLongitudes {
+    fn wrap<T: CoordType>(&mut self, coords: &mut [Coord<T>]) {
+        self.0 += coords
+            .iter_mut()
+            .map(|c| c.wrap_longitude() as usize)
+            .sum::<usize>();
+    }
+
+    fn wrap_position<T: CoordType>(&mut self, longitude: &mut T, latitude: T) {
+        let mut coord = Coord::new(*longitude, latitude, None);
+        self.wrap(std::slice::from_mut(&mut coord));
+        *longitude = coord.x;
+    }
+}
+
+impl<T> KmlVisitor<T> for WrapLongitudes
+where
+    T: CoordType,
+{
+    fn visit_camera(&mut self, camera: &mut Camera<T>) {
+        self.wrap_position(&mut camera.longitude, camera.latitude);
+    }
+
+    fn visit_look_at(&mut self, look_at: &mut LookAt<T>) {
+        self.wrap_position(&mut look_at.longitude, look_at.latitude);
+    }
+
+    fn visit_point(&mut self, point: &mut Point<T>) {
+        self.wrap(std::slice::from_mut(&mut point.coord));
+    }
+
+    fn visit_line_string(&mut self, line_string: &mut LineString<T>) {
+        self.wrap(&mut line_string.coords);
+    }
+
+    fn visit_linear_ring(&mut self, linear_ring: &mut LinearRing<T>) {
+        self.wrap(&mut linear_ring.coords);
+    }
+
+    fn visit_track(&mut self, track: &mut Track<T>) {
+        self.wrap(&mut track.coords);
+    }
+
+    fn visit_location(&mut self, location: &mut Location<T>) {
+        self.wrap_position(&mut location.longitude, location.latitude);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_line_string_westward() {
+        let line = LineString::from(vec![
+            Coord::new(-175., 0., Some(0.)),
+            Coord::new(175., 10., Some(100.)),
+            Coord::new(170., 10., Some(100.)),
+        ]);
+        let parts = line.split_antimeridian();
+        assert_eq!(
+            parts
+                .into_iter()
+                .map(|l| l.coords)
+                .collect::<Vec<Vec<Coord>>>(),
+            vec![
+                vec![
+                    Coord::new(-175., 0., Some(0.)),
+                    Coord::new(-180., 5., Some(50.))
+                ],
+                vec![
+                    Coord::new(180., 5., Some(50.)),
+                    Coord::new(175., 10., Some(100.)),
+                    Coord::new(170., 10., Some(100.))
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_polygon() {
+        let ring = |coords: Vec<(f64, f64)>| {
+            LinearRing::from(coords.into_iter().map(Coord::from).collect::<Vec<_>>())
+        };
+        let polygon = Polygon::new(
+            ring(vec![
+                (170., 0.),
+                (-170., 0.),
+                (-170., 10.),
+                (170., 10.),
+                (170., 0.),
+            ]),
+            vec![ring(vec![
+                (-175., 2.),
+                (-172., 2.),
+                (-172., 4.),
+                (-175., 4.),
+                (-175., 2.),
+            ])],
+        );
+
+        let parts = polygon.split_antimeridian();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(
+            parts[0].outer,
+            ring(vec![
+                (170., 0.),
+                (180., 0.),
+                (180., 10.),
+                (170., 10.),
+                (170., 0.)
+            ])
+        );
+        assert!(parts[0].inner.is_empty());
+        assert_eq!(
+            parts[1].outer,
+            ring(vec![
+                (-180., 0.),
+                (-170., 0.),
+                (-170., 10.),
+                (-180., 10.),
+                (-180., 0.)
+            ])
+        );
+        assert_eq!(parts[1].inner, polygon.inner);
+    }
+
+    #[test]
+    fn test_wrap_longitudes() {
+        let mut kml: Kml = Kml::MultiGeometry(MultiGeometry::new(vec![
+            Geometry::Point(Point::new(-190., 0., None)),
+            Geometry::LineString(LineString::from(vec![
+                Coord::from((540., 0.)),
+                Coord::from((180., 0.)),
+            ])),
+        ]));
+        assert_eq!(kml.wrap_longitudes(), 2);
+        assert_eq!(
+            kml,
+            Kml::MultiGeometry(MultiGeometry::new(vec![
+                Geometry::Point(Point::new(170., 0., None)),
+                Geometry::LineString(LineString::from(vec![
+                    Coord::from((-180., 0.)),
+                    Coord::from((180., 0.)),
+                ])),
+            ]))
+        );
+    }
+}
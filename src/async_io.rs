@@ -0,0 +1,244 @@
+use std::io;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::errors::Error;
+use crate::reader::{KmlReader, KmlReaderOptions};
+use crate::types::{CoordType, Kml, KmlVersion};
+use crate::writer::{KmlWriter, KmlWriterOptions};
+
+/// Reads KML from an [`AsyncRead`](https://docs.rs/tokio/1/tokio/io/trait.AsyncRead.html) like a
+/// socket or an HTTP response body, such as a feed linked from a `kml:NetworkLink`
+///
+/// The parser isn't asynchronous, so the whole input is buffered before it's parsed with
+/// [`KmlReader`](struct.KmlReader.html) and the same options. Parsing runs on tokio's blocking
+/// thread pool with [`spawn_blocking`](https://docs.rs/tokio/1/tokio/task/fn.spawn_blocking.html)
+/// so large documents don't stall other tasks, which needs a tokio runtime.
+///
+/// # Example
+///
+/// ```
+/// use kml::{AsyncKmlReader, Kml};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let point_str = "<Point><coordinates>1,1,1</coordinates></Point>";
+/// let mut reader = AsyncKmlReader::<_, f64>::from_reader(point_str.as_bytes());
+/// let kml = reader.read().await.unwrap();
+/// assert!(matches!(kml, Kml::Point(_)));
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub struct AsyncKmlReader<R: AsyncRead + Unpin, T: CoordType + FromStr + Default = f64> {
+    reader: R,
+    options: KmlReaderOptions,
+    warnings: Vec<Error>,
+    _phantom: PhantomData<T>,
+}
+
+impl<R, T> AsyncKmlReader<R, T>
+where
+    R: AsyncRead + Unpin,
+    T: CoordType + FromStr + Default + Send + 'static,
+{
+    /// Reads from any type that implements `AsyncRead`
+    pub fn from_reader(r: R) -> AsyncKmlReader<R, T> {
+        AsyncKmlReader {
+            reader: r,
+            options: KmlReaderOptions::default(),
+            warnings: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Replaces all of the options, see [`KmlReaderOptions`](struct.KmlReaderOptions.html)
+    pub fn with_options(mut self, options: KmlReaderOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Errors skipped while reading in lenient mode, in the order they occurred
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    /// Reads the rest of the input and parses it, see [`KmlReader::read`](struct.KmlReader.html#method.read)
+    pub async fn read(&mut self) -> Result<Kml<T>, Error> {
+        let mut buf = Vec::new();
+        self.reader.read_to_end(&mut buf).await?;
+        let options = self.options.clone();
+        let (result, warnings) = tokio::task::spawn_blocking(move || {
+            let mut reader = KmlReader::<_, T>::from_reader(buf.as_slice()).with_options(options);
+            let result = reader.read();
+            (result, reader.take_warnings())
+        })
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        self.warnings = warnings;
+        result
+    }
+}
+
+/// Writes KML to an [`AsyncWrite`](https://docs.rs/tokio/1/tokio/io/trait.AsyncWrite.html)
+///
+/// Each document is written to a buffer with [`KmlWriter`](struct.KmlWriter.html) and the same
+/// options, which is then written to the output and flushed.
+///
+/// # Example
+///
+/// ```
+/// use kml::{AsyncKmlWriter, Kml, types::Point};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let kml = Kml::Point(Point::new(1., 1., None));
+/// let mut buf = Vec::new();
+/// let mut writer = AsyncKmlWriter::from_writer(&mut buf);
+/// writer.write(&kml).await.unwrap();
+/// assert_eq!(String::from_utf8(buf).unwrap(), kml.to_string());
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub struct AsyncKmlWriter<W: AsyncWrite + Unpin, T: CoordType + FromStr + Default = f64> {
+    writer: W,
+    kml_writer: KmlWriter<Vec<u8>, T>,
+}
+
+impl<W, T> AsyncKmlWriter<W, T>
+where
+    W: AsyncWrite + Unpin,
+    T: CoordType + FromStr + Default + std::fmt::Display,
+{
+    /// Creates `AsyncKmlWriter` from an output that implements `AsyncWrite`
+    pub fn from_writer(w: W) -> AsyncKmlWriter<W, T> {
+        AsyncKmlWriter {
+            writer: w,
+            kml_writer: KmlWriter::from_writer(Vec::new()),
+        }
+    }
+
+    /// Creates `AsyncKmlWriter` from an output, formatting it with the options, see
+    /// [`KmlWriterOptions`](struct.KmlWriterOptions.html)
+    pub fn from_writer_with_options(w: W, options: KmlWriterOptions) -> AsyncKmlWriter<W, T> {
+        AsyncKmlWriter {
+            writer: w,
+            kml_writer: KmlWriter::from_writer_with_options(Vec::new(), options),
+        }
+    }
+
+    /// See [`KmlWriter::orient_polygons`](struct.KmlWriter.html#method.orient_polygons)
+    pub fn orient_polygons(mut self, orient_polygons: bool) -> Self {
+        self.kml_writer = self.kml_writer.orient_polygons(orient_polygons);
+        self
+    }
+
+    /// See [`KmlWriter::normalize_altitude_modes`](struct.KmlWriter.html#method.normalize_altitude_modes)
+    pub fn normalize_altitude_modes(mut self, normalize_altitude_modes: bool) -> Self {
+        self.kml_writer = self
+            .kml_writer
+            .normalize_altitude_modes(normalize_altitude_modes);
+        self
+    }
+
+    /// See [`KmlWriter::version`](struct.KmlWriter.html#method.version)
+    pub fn version(mut self, version: KmlVersion) -> Self {
+        self.kml_writer = self.kml_writer.version(version);
+        self
+    }
+
+    /// Writes KML to the output, see [`KmlWriter::write`](struct.KmlWriter.html#method.write)
+    pub async fn write(&mut self, kml: &Kml<T>) -> Result<(), Error> {
+        self.kml_writer.write(kml)?;
+        let buf = std::mem::take(self.kml_writer.inner());
+        self.writer.write_all(&buf).await?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use tokio::io::ReadBuf;
+
+    use super::*;
+    use crate::reader::ReadMode;
+    use crate::types::Point;
+
+    #[tokio::test]
+    async fn test_async_round_trip() {
+        let kml_str = r#"<Folder>
+          <Placemark><name>A</name><Point><coordinates>1,2</coordinates></Point></Placemark>
+          <Placemark><LineString><altitudeMode>sideways</altitudeMode></LineString></Placemark>
+        </Folder>"#;
+        let mut reader = AsyncKmlReader::<_, f64>::from_reader(kml_str.as_bytes());
+        assert!(reader.read().await.is_err());
+
+        let options = KmlReaderOptions::default().with_mode(ReadMode::Lenient);
+        let mut reader =
+            AsyncKmlReader::<_, f64>::from_reader(kml_str.as_bytes()).with_options(options);
+        let kml = reader.read().await.unwrap();
+        assert_eq!(kml.placemarks().count(), 1);
+        assert_eq!(reader.warnings().len(), 1);
+
+        let point = Kml::Point(Point::new(3., 4., None));
+        let mut buf = Vec::new();
+        let mut writer = AsyncKmlWriter::from_writer(&mut buf);
+        writer.write(&kml).await.unwrap();
+        writer.write(&point).await.unwrap();
+        let expected = format!("{}{}", kml, point);
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_async_read_invalid_input() {
+        let mut reader = AsyncKmlReader::<_, f64>::from_reader("".as_bytes());
+        assert!(matches!(reader.read().await, Err(Error::NoElements)));
+
+        let mut reader = AsyncKmlReader::<_, f64>::from_reader("<Folder><Placemark>".as_bytes());
+        let err = reader.read().await.unwrap_err();
+        assert!(matches!(err.root_cause(), Error::InvalidInput));
+    }
+
+    #[tokio::test]
+    async fn test_async_io_errors() {
+        struct Failing;
+
+        impl AsyncRead for Failing {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                _: &mut ReadBuf<'_>,
+            ) -> Poll<io::Result<()>> {
+                Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, "failed")))
+            }
+        }
+
+        impl AsyncWrite for Failing {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                _: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, "failed")))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        let mut reader = AsyncKmlReader::<_, f64>::from_reader(Failing);
+        assert!(matches!(reader.read().await, Err(Error::IoError(_))));
+
+        let mut writer = AsyncKmlWriter::from_writer(Failing);
+        let point = Kml::Point(Point::new(3., 4., None));
+        assert!(matches!(writer.write(&point).await, Err(Error::IoError(_))));
+    }
+}
@@ -0,0 +1,362 @@
+//! Bounding boxes and vertex counts of geometries
+//!
+//! Bounds are calculated in the plane of longitude and latitude, so the box of a geometry crossing
+//! the antimeridian spans nearly all longitudes. Split those geometries with
+//! [`Kml::split_antimeridian`](enum.Kml.html#method.split_antimeridian) first if that matters.
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    lit, Coord, CoordType, Geometry, Kml, LatLonAltBox, LineString, LinearRing, Model, MultiTrack,
+    Placemark, Point, Polygon, Track,
+};
+use crate::visit::by_ref::KmlVisitorRef;
+
+/// Extent of coordinates from the minimum to the maximum of each axis
+///
+/// The altitudes are only set if at least one of the coordinates has an altitude.
+///
+/// # Example
+///
+/// ```
+/// use kml::{BoundingBox, Kml, types::Coord};
+///
+/// let kml: Kml = r#"<Document>
+///   <Placemark><Point><coordinates>-105,40,1600</coordinates></Point></Placemark>
+///   <Folder>
+///     <Placemark><LineString><coordinates>-104,39 -103,41</coordinates></LineString></Placemark>
+///   </Folder>
+/// </Document>"#.parse().unwrap();
+///
+/// let bbox = kml.bounding_box().unwrap();
+/// assert_eq!(bbox.min, Coord::new(-105., 39., Some(1600.)));
+/// assert_eq!(bbox.max, Coord::new(-103., 41., Some(1600.)));
+/// assert_eq!(bbox.center(), Coord::new(-104., 40., None));
+/// assert_eq!(kml.num_vertices(), 3);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoundingBox<T: CoordType = f64> {
+    pub min: Coord<T>,
+    pub max: Coord<T>,
+}
+
+impl<T> BoundingBox<T>
+where
+    T: CoordType,
+{
+    /// Returns the box of a single coordinate
+    pub fn from_coord(coord: Coord<T>) -> Self {
+        BoundingBox {
+            min: coord,
+            max: coord,
+        }
+    }
+
+    /// Returns the box of the coordinates, or `None` if there are none
+    pub fn from_coords<'a>(coords: impl IntoIterator<Item = &'a Coord<T>>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        coords
+            .into_iter()
+            .map(|c| BoundingBox::from_coord(*c))
+            .reduce(BoundingBox::union)
+    }
+
+    /// Returns the smallest box containing both boxes
+    pub fn union(self, other: Self) -> Self {
+        let altitude = |a: Option<T>, b: Option<T>, f: fn(T, T) -> T| match (a, b) {
+            (Some(a), Some(b)) => Some(f(a, b)),
+            (a, b) => a.or(b),
+        };
+        BoundingBox {
+            min: Coord::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                altitude(self.min.z, other.min.z, T::min),
+            ),
+            max: Coord::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                altitude(self.max.z, other.max.z, T::max),
+            ),
+        }
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y
+    }
+
+    /// Returns the middle of the box's longitudes and latitudes, without an altitude
+    pub fn center(&self) -> Coord<T> {
+        Coord::new(
+            (self.min.x + self.max.x) / lit(2.),
+            (self.min.y + self.max.y) / lit(2.),
+            None,
+        )
+    }
+
+    /// Returns `true` if the coordinate's longitude and latitude are inside or on the edge of the
+    /// box
+    pub fn contains(&self, coord: &Coord<T>) -> bool {
+        coord.x >= self.min.x
+            && coord.x <= self.max.x
+            && coord.y >= self.min.y
+            && coord.y <= self.max.y
+    }
+}
+
+impl<T> From<BoundingBox<T>> for LatLonAltBox<T>
+where
+    T: CoordType,
+{
+    /// Converts to a `kml:LatLonAltBox` for a `kml:Region`, with the altitudes of the box if it has
+    /// them
+    fn from(bbox: BoundingBox<T>) -> LatLonAltBox<T> {
+        let mut lat_lon_alt_box = LatLonAltBox::new(bbox.max.y, bbox.min.y, bbox.max.x, bbox.min.x);
+        if let (Some(min), Some(max)) = (bbox.min.z, bbox.max.z) {
+            lat_lon_alt_box.min_altitude = min;
+            lat_lon_alt_box.max_altitude = max;
+        }
+        lat_lon_alt_box
+    }
+}
+
+// Collects the bounds and counts of the geometries in a tree. Only geometries are measured, so
+// views, the boxes of regions and overlays, and locations outside of models are skipped.
+struct Bounds<T: CoordType> {
+    bbox: Option<BoundingBox<T>>,
+    vertices: usize,
+    geometries: usize,
+}
+
+impl<T> Default for Bounds<T>
+where
+    T: CoordType,
+{
+    fn default() -> Self {
+        Bounds {
+            bbox: None,
+            vertices: 0,
+            geometries: 0,
+        }
+    }
+}
+
+impl<T> Bounds<T>
+where
+    T: CoordType,
+{
+    fn add(&mut self, coords: &[Coord<T>]) {
+        self.add_box(BoundingBox::from_coords(coords));
+        self.vertices += coords.len();
+        self.geometries += 1;
+    }
+
+    fn add_box(&mut self, bbox: Option<BoundingBox<T>>) {
+        self.bbox = match (self.bbox, bbox) {
+            (Some(a), Some(b)) => Some(a.union(b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+impl<T> KmlVisitorRef<T> for Bounds<T>
+where
+    T: CoordType,
+{
+    fn visit_point(&mut self, point: &Point<T>) {
+        self.add(std::slice::from_ref(&point.coord));
+    }
+
+    fn visit_line_string(&mut self, line_string: &LineString<T>) {
+        self.add(&line_string.coords);
+    }
+
+    fn visit_linear_ring(&mut self, linear_ring: &LinearRing<T>) {
+        self.add(&linear_ring.coords);
+    }
+
+    fn visit_polygon(&mut self, polygon: &Polygon<T>) {
+        // Holes are inside the outer ring
+        self.add(&polygon.outer.coords);
+        self.vertices += polygon.inner.iter().map(|r| r.coords.len()).sum::<usize>();
+    }
+
+    fn visit_track(&mut self, track: &Track<T>) {
+        self.add(&track.coords);
+    }
+
+    fn visit_multi_track(&mut self, multi_track: &MultiTrack<T>) {
+        for track in &multi_track.tracks {
+            self.add(&track.coords);
+        }
+    }
+
+    fn visit_model(&mut self, model: &Model<T>) {
+        // Models are placed at their location
+        let coords: Vec<Coord<T>> = model
+            .location
+            .iter()
+            .map(|l| Coord::new(l.longitude, l.latitude, Some(l.altitude)))
+            .collect();
+        self.add(&coords);
+    }
+}
+
+impl<T> Geometry<T>
+where
+    T: CoordType,
+{
+    /// Returns the bounding box of the geometry's coordinates, or `None` if it has none or is a
+    /// [`Geometry::Element`](enum.Geometry.html#variant.Element)
+    pub fn bounding_box(&self) -> Option<BoundingBox<T>> {
+        let mut visitor = Bounds::default();
+        self.walk_ref(&mut visitor);
+        visitor.bbox
+    }
+
+    /// Returns the number of coordinates in the geometry, counting the repeated first coordinate
+    /// of closed rings
+    pub fn num_vertices(&self) -> usize {
+        let mut visitor = Bounds::default();
+        self.walk_ref(&mut visitor);
+        visitor.vertices
+    }
+
+    /// Returns the number of geometries, counting each geometry of a `kml:MultiGeometry` and each
+    /// track of a `gx:MultiTrack` separately
+    pub fn num_geometries(&self) -> usize {
+        let mut visitor = Bounds::default();
+        self.walk_ref(&mut visitor);
+        visitor.geometries
+    }
+}
+
+impl<T> Placemark<T>
+where
+    T: CoordType,
+{
+    /// Returns the bounding box of the placemark's geometry, see
+    /// [`Geometry::bounding_box`](enum.Geometry.html#method.bounding_box)
+    pub fn bounding_box(&self) -> Option<BoundingBox<T>> {
+        self.geometry.as_ref()?.bounding_box()
+    }
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Returns the bounding box of the geometries in the tree, including those of placemarks in
+    /// documents and folders, or `None` if there are no coordinates
+    ///
+    /// Only geometries are included, so the positions of views and the boxes of overlays and
+    /// regions aren't.
+    pub fn bounding_box(&self) -> Option<BoundingBox<T>> {
+        let mut visitor = Bounds::default();
+        self.walk_ref(&mut visitor);
+        visitor.bbox
+    }
+
+    /// Returns the number of coordinates of the geometries in the tree, see
+    /// [`Geometry::num_vertices`](enum.Geometry.html#method.num_vertices)
+    pub fn num_vertices(&self) -> usize {
+        let mut visitor = Bounds::default();
+        self.walk_ref(&mut visitor);
+        visitor.vertices
+    }
+
+    /// Returns the number of geometries in the tree, see
+    /// [`Geometry::num_geometries`](enum.Geometry.html#method.num_geometries)
+    pub fn num_geometries(&self) -> usize {
+        let mut visitor = Bounds::default();
+        self.walk_ref(&mut visitor);
+        visitor.geometries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Element;
+
+    #[test]
+    fn test_bounding_box() {
+        let kml: Kml = r#"<Document>
+          <Placemark>
+            <MultiGeometry>
+              <Point><coordinates>5,5</coordinates></Point>
+              <Polygon>
+                <outerBoundaryIs><LinearRing><coordinates>0,0 4,0 4,4 0,0</coordinates></LinearRing></outerBoundaryIs>
+                <innerBoundaryIs><LinearRing><coordinates>1,1 2,1 2,2 1,1</coordinates></LinearRing></innerBoundaryIs>
+              </Polygon>
+            </MultiGeometry>
+          </Placemark>
+          <Placemark><name>Empty</name></Placemark>
+          <LineString><coordinates>-1,2,10 0,3,-5</coordinates></LineString>
+        </Document>"#
+            .parse()
+            .unwrap();
+        let bbox = kml.bounding_box().unwrap();
+        assert_eq!(bbox.min, Coord::new(-1., 0., Some(-5.)));
+        assert_eq!(bbox.max, Coord::new(5., 5., Some(10.)));
+        assert_eq!((bbox.width(), bbox.height()), (6., 5.));
+        assert!(bbox.contains(&Coord::new(0., 0., None)));
+        assert_eq!(kml.num_vertices(), 11);
+        assert_eq!(kml.num_geometries(), 3);
+
+        let region_box = LatLonAltBox::from(bbox);
+        assert_eq!(
+            (
+                region_box.north,
+                region_box.south,
+                region_box.east,
+                region_box.west
+            ),
+            (5., 0., 5., -1.)
+        );
+        assert_eq!(
+            (region_box.min_altitude, region_box.max_altitude),
+            (-5., 10.)
+        );
+
+        let empty: Kml = "<Folder><Placemark/></Folder>".parse().unwrap();
+        assert_eq!(empty.bounding_box(), None);
+        assert_eq!(empty.num_vertices(), 0);
+    }
+
+    #[test]
+    fn test_bounding_box_edge_cases() {
+        assert_eq!(BoundingBox::<f64>::from_coords(&[]), None);
+
+        // Geometries without coordinates are still counted
+        let line = Geometry::LineString(LineString::<f64>::from(vec![]));
+        assert_eq!(line.bounding_box(), None);
+        assert_eq!((line.num_vertices(), line.num_geometries()), (0, 1));
+        let model = Geometry::Model(Model::<f64>::default());
+        assert_eq!(model.bounding_box(), None);
+        assert_eq!((model.num_vertices(), model.num_geometries()), (0, 1));
+
+        let element = Geometry::<f64>::Element(Element::default());
+        assert_eq!(element.bounding_box(), None);
+        assert_eq!(element.num_geometries(), 0);
+
+        // Longitudes aren't wrapped, so a line across the antimeridian spans the other way
+        let line = Geometry::LineString(LineString::from(vec![
+            Coord::new(179., 10., None),
+            Coord::new(-179., 11., None),
+        ]));
+        let bbox = line.bounding_box().unwrap();
+        assert_eq!((bbox.min.x, bbox.max.x, bbox.width()), (-179., 179., 358.));
+        assert_eq!(bbox.min.z, None);
+        assert!(bbox.contains(&Coord::new(0., 10., None)));
+        assert!(bbox.contains(&Coord::new(179., 11., None)));
+        assert!(!bbox.contains(&Coord::new(180., 10., None)));
+    }
+}
@@ -0,0 +1,514 @@
+//! Centroids and label points of geometries
+//!
+//! Centroids are calculated in the plane of longitude and latitude like the `Centroid` and
+//! `InteriorPoint` algorithms of the `geo` crate, which is accurate enough for placing labels on
+//! features that don't span large parts of the globe. Only the highest dimension of a geometry
+//! contributes, so the centroid of a multi-geometry with polygons ignores its lines and points.
+use crate::types::{
+    lit, Coord, CoordType, Geometry, LineString, LinearRing, Model, MultiGeometry, Placemark,
+    Point, Polygon,
+};
+
+// Weighted sum of coordinates for a dimension, where points are 0, lines are 1, and areas are 2
+#[derive(Clone, Copy)]
+struct Accumulator<T: CoordType> {
+    dimension: u8,
+    weight: T,
+    x: T,
+    y: T,
+}
+
+impl<T: CoordType> Accumulator<T> {
+    fn new(dimension: u8, weight: T, coord: Coord<T>) -> Self {
+        Accumulator {
+            dimension,
+            weight,
+            x: coord.x * weight,
+            y: coord.y * weight,
+        }
+    }
+
+    // Keeps only the highest dimension, since lower dimensions have no weight relative to it
+    fn add(self, other: Self) -> Self {
+        if other.dimension > self.dimension {
+            return other;
+        }
+        if other.dimension < self.dimension {
+            return self;
+        }
+        Accumulator {
+            dimension: self.dimension,
+            weight: self.weight + other.weight,
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+
+    fn centroid(&self) -> Option<Coord<T>> {
+        if self.weight == T::zero() {
+            return None;
+        }
+        Some(Coord::new(self.x / self.weight, self.y / self.weight, None))
+    }
+}
+
+fn sum<T: CoordType>(accumulators: impl Iterator<Item = Accumulator<T>>) -> Option<Accumulator<T>> {
+    accumulators.reduce(Accumulator::add)
+}
+
+fn point_accumulator<T: CoordType>(coords: &[Coord<T>]) -> Option<Accumulator<T>> {
+    sum(coords.iter().map(|c| Accumulator::new(0, T::one(), *c)))
+}
+
+// Segments weighted by length, or the vertices if all segments have zero length
+fn line_accumulator<T: CoordType>(coords: &[Coord<T>]) -> Option<Accumulator<T>> {
+    sum(coords.windows(2).map(|pair| {
+        let (a, b) = (pair[0], pair[1]);
+        let length = (b.x - a.x).hypot(b.y - a.y);
+        let mid = Coord::new((a.x + b.x) / lit(2.), (a.y + b.y) / lit(2.), None);
+        Accumulator::new(1, length, mid)
+    }))
+    .filter(|a| a.weight > T::zero())
+    .or_else(|| point_accumulator(coords))
+}
+
+// Signed area of the ring with the centroid of its triangles, from the shoelace formula
+fn ring_area<T: CoordType>(coords: &[Coord<T>]) -> (T, Coord<T>) {
+    let mut area = T::zero();
+    let (mut x, mut y) = (T::zero(), T::zero());
+    if let Some(origin) = coords.first() {
+        for pair in coords.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let (ax, ay) = (a.x - origin.x, a.y - origin.y);
+            let (bx, by) = (b.x - origin.x, b.y - origin.y);
+            let cross = ax * by - bx * ay;
+            area = area + cross;
+            x = x + (ax + bx) * cross;
+            y = y + (ay + by) * cross;
+        }
+        if area != T::zero() {
+            x = x / (lit::<T>(3.) * area) + origin.x;
+            y = y / (lit::<T>(3.) * area) + origin.y;
+        }
+    }
+    (area / lit(2.), Coord::new(x, y, None))
+}
+
+fn polygon_accumulator<T: CoordType>(polygon: &Polygon<T>) -> Option<Accumulator<T>> {
+    let (outer_area, outer_centroid) = ring_area(&polygon.outer.coords);
+    let area = Accumulator::new(2, outer_area.abs(), outer_centroid);
+    let area = polygon.inner.iter().fold(area, |area, ring| {
+        let (inner_area, inner_centroid) = ring_area(&ring.coords);
+        area.add(Accumulator::new(2, -inner_area.abs(), inner_centroid))
+    });
+    Some(area)
+        .filter(|a| a.weight > T::zero())
+        .or_else(|| line_accumulator(&polygon.outer.coords))
+}
+
+// Models are placed at the longitude and latitude of their location
+fn model_coord<T: CoordType>(model: &Model<T>) -> Option<Coord<T>> {
+    let location = model.location.as_ref()?;
+    Some(Coord::new(location.longitude, location.latitude, None))
+}
+
+fn geometry_accumulator<T: CoordType>(geometry: &Geometry<T>) -> Option<Accumulator<T>> {
+    match geometry {
+        Geometry::Point(p) => point_accumulator(&[p.coord]),
+        Geometry::LineString(l) => line_accumulator(&l.coords),
+        Geometry::LinearRing(l) => line_accumulator(&l.coords),
+        Geometry::Polygon(p) => polygon_accumulator(p),
+        Geometry::MultiGeometry(g) => sum(g.geometries.iter().filter_map(geometry_accumulator)),
+        Geometry::Track(t) => line_accumulator(&t.coords),
+        Geometry::MultiTrack(t) => sum(t.tracks.iter().filter_map(|t| line_accumulator(&t.coords))),
+        Geometry::Model(m) => point_accumulator(&[model_coord(m)?]),
+        Geometry::Element(_) => None,
+    }
+}
+
+// Returns `true` if the coordinate is inside the ring by the even-odd rule
+fn ring_contains<T: CoordType>(coords: &[Coord<T>], coord: &Coord<T>) -> bool {
+    let mut inside = false;
+    for pair in coords.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if (a.y > coord.y) != (b.y > coord.y)
+            && coord.x < a.x + (coord.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn polygon_contains<T: CoordType>(polygon: &Polygon<T>, coord: &Coord<T>) -> bool {
+    ring_contains(&polygon.outer.coords, coord)
+        && !polygon
+            .inner
+            .iter()
+            .any(|r| ring_contains(&r.coords, coord))
+}
+
+// Midpoint of the widest span of the polygon's interior along the horizontal line at `y`
+fn widest_span<T: CoordType>(polygon: &Polygon<T>, y: T) -> Option<(T, Coord<T>)> {
+    let mut crossings: Vec<T> = std::iter::once(&polygon.outer)
+        .chain(polygon.inner.iter())
+        .flat_map(|r| r.coords.windows(2))
+        .filter(|pair| (pair[0].y > y) != (pair[1].y > y))
+        .map(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x)
+        })
+        .collect();
+    crossings.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    crossings
+        .chunks_exact(2)
+        .map(|span| {
+            let mid = Coord::new((span[0] + span[1]) / lit(2.), y, None);
+            (span[1] - span[0], mid)
+        })
+        .fold(None, |widest: Option<(T, Coord<T>)>, span| match widest {
+            Some(w) if w.0 >= span.0 => Some(w),
+            _ => Some(span),
+        })
+}
+
+fn polygon_interior_point<T: CoordType>(polygon: &Polygon<T>) -> Option<(T, Coord<T>)> {
+    let accumulator = polygon_accumulator(polygon)?;
+    let centroid = accumulator.centroid()?;
+    let weight = accumulator.weight;
+    if accumulator.dimension < 2 {
+        return line_interior_point(&polygon.outer.coords);
+    }
+    if polygon_contains(polygon, &centroid) {
+        return Some((weight, centroid));
+    }
+    // The centroid of concave polygons can fall outside of them, so try the horizontal line
+    // through the centroid and then the middle of the polygon's extent
+    let (min_y, max_y) = polygon
+        .outer
+        .coords
+        .iter()
+        .fold((T::infinity(), T::neg_infinity()), |(min, max), c| {
+            (min.min(c.y), max.max(c.y))
+        });
+    widest_span(polygon, centroid.y)
+        .or_else(|| widest_span(polygon, (min_y + max_y) / lit(2.)))
+        .map(|(_, coord)| (weight, coord))
+}
+
+// Vertex closest to the centroid of the line, preferring vertices other than the ends
+fn line_interior_point<T: CoordType>(coords: &[Coord<T>]) -> Option<(T, Coord<T>)> {
+    let accumulator = line_accumulator(coords)?;
+    let centroid = accumulator.centroid()?;
+    let candidates = if coords.len() > 2 {
+        &coords[1..coords.len() - 1]
+    } else {
+        coords
+    };
+    candidates
+        .iter()
+        .min_by(|a, b| {
+            let da = (a.x - centroid.x).hypot(a.y - centroid.y);
+            let db = (b.x - centroid.x).hypot(b.y - centroid.y);
+            da.partial_cmp(&db).unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|c| (accumulator.weight, Coord::new(c.x, c.y, None)))
+}
+
+// Interior point of the geometry along with its dimension and weight, so that multi-geometries
+// can pick the point of their largest part
+fn geometry_interior_point<T: CoordType>(geometry: &Geometry<T>) -> Option<(u8, T, Coord<T>)> {
+    match geometry {
+        Geometry::Point(p) => Some((0, T::one(), Coord::new(p.coord.x, p.coord.y, None))),
+        Geometry::LineString(l) => line_interior_point(&l.coords).map(|(w, c)| (1, w, c)),
+        Geometry::LinearRing(l) => line_interior_point(&l.coords).map(|(w, c)| (1, w, c)),
+        Geometry::Polygon(p) => {
+            let dimension = polygon_accumulator(p)?.dimension;
+            polygon_interior_point(p).map(|(w, c)| (dimension, w, c))
+        }
+        Geometry::MultiGeometry(g) => largest_interior_point(&g.geometries),
+        Geometry::Track(t) => line_interior_point(&t.coords).map(|(w, c)| (1, w, c)),
+        Geometry::MultiTrack(t) => t
+            .tracks
+            .iter()
+            .filter_map(|t| line_interior_point(&t.coords))
+            .fold(
+                None,
+                |longest: Option<(T, Coord<T>)>, point| match longest {
+                    Some(l) if l.0 >= point.0 => Some(l),
+                    _ => Some(point),
+                },
+            )
+            .map(|(w, c)| (1, w, c)),
+        Geometry::Model(m) => Some((0, T::one(), model_coord(m)?)),
+        Geometry::Element(_) => None,
+    }
+}
+
+fn largest_interior_point<T: CoordType>(geometries: &[Geometry<T>]) -> Option<(u8, T, Coord<T>)> {
+    geometries
+        .iter()
+        .filter_map(geometry_interior_point)
+        .fold(None, |largest, point| match largest {
+            Some(l) if (l.0, l.1) >= (point.0, point.1) => Some(l),
+            _ => Some(point),
+        })
+}
+
+impl<T> LineString<T>
+where
+    T: CoordType,
+{
+    /// Returns the centroid of the line, which is the average of the midpoints of its segments
+    /// weighted by their length, or `None` if it has no coordinates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Coord, LineString};
+    ///
+    /// let line = LineString::from_coords(vec![(0., 0.), (2., 0.), (2., 2.)]);
+    /// assert_eq!(line.centroid(), Some(Coord::new(1.5, 0.5, None)));
+    /// ```
+    pub fn centroid(&self) -> Option<Coord<T>> {
+        line_accumulator(&self.coords)?.centroid()
+    }
+
+    /// Returns the vertex closest to the centroid, preferring vertices other than the ends, so the
+    /// point is always on the line
+    pub fn representative_point(&self) -> Option<Coord<T>> {
+        line_interior_point(&self.coords).map(|(_, c)| c)
+    }
+}
+
+impl<T> LinearRing<T>
+where
+    T: CoordType,
+{
+    /// Returns the centroid of the ring as a line. See
+    /// [`LineString::centroid`](struct.LineString.html#method.centroid)
+    pub fn centroid(&self) -> Option<Coord<T>> {
+        line_accumulator(&self.coords)?.centroid()
+    }
+
+    /// Returns the vertex closest to the centroid. See
+    /// [`LineString::representative_point`](struct.LineString.html#method.representative_point)
+    pub fn representative_point(&self) -> Option<Coord<T>> {
+        line_interior_point(&self.coords).map(|(_, c)| c)
+    }
+}
+
+impl<T> Polygon<T>
+where
+    T: CoordType,
+{
+    /// Returns the centroid of the polygon's area excluding its holes, or the centroid of its outer
+    /// ring as a line if it has no area
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Coord, LinearRing, Polygon};
+    ///
+    /// let outer = LinearRing::from(vec![
+    ///     Coord::new(0., 0., None),
+    ///     Coord::new(4., 0., None),
+    ///     Coord::new(4., 2., None),
+    ///     Coord::new(0., 2., None),
+    ///     Coord::new(0., 0., None),
+    /// ]);
+    /// let polygon = Polygon::new(outer, vec![]);
+    /// assert_eq!(polygon.centroid(), Some(Coord::new(2., 1., None)));
+    /// ```
+    pub fn centroid(&self) -> Option<Coord<T>> {
+        polygon_accumulator(self)?.centroid()
+    }
+
+    /// Returns a point inside the polygon and outside of its holes, which is the centroid when
+    /// that's inside and otherwise the middle of the widest span of the polygon along a horizontal
+    /// line
+    ///
+    /// Unlike the centroid, the point is always inside polygons with an area, like a U-shaped
+    /// polygon whose centroid falls between its arms, which makes it suited to placing labels.
+    pub fn representative_point(&self) -> Option<Coord<T>> {
+        polygon_interior_point(self).map(|(_, c)| c)
+    }
+}
+
+impl<T> MultiGeometry<T>
+where
+    T: CoordType,
+{
+    /// Returns the centroid of the highest dimension geometries, so points and lines are ignored
+    /// if there are polygons with an area
+    pub fn centroid(&self) -> Option<Coord<T>> {
+        sum(self.geometries.iter().filter_map(geometry_accumulator))?.centroid()
+    }
+
+    /// Returns the representative point of the largest of the highest dimension geometries
+    pub fn representative_point(&self) -> Option<Coord<T>> {
+        largest_interior_point(&self.geometries).map(|(_, _, c)| c)
+    }
+}
+
+impl<T> Geometry<T>
+where
+    T: CoordType,
+{
+    /// Returns the centroid of the geometry, or `None` if it's empty or a
+    /// [`Geometry::Element`](enum.Geometry.html#variant.Element)
+    pub fn centroid(&self) -> Option<Coord<T>> {
+        geometry_accumulator(self)?.centroid()
+    }
+
+    /// Returns a point on the geometry suitable for placing a label, see
+    /// [`Polygon::representative_point`](struct.Polygon.html#method.representative_point)
+    pub fn representative_point(&self) -> Option<Coord<T>> {
+        geometry_interior_point(self).map(|(_, _, c)| c)
+    }
+}
+
+impl<T> Placemark<T>
+where
+    T: CoordType + Default,
+{
+    /// Creates a placemark with a `kml:Point` at the representative point of the geometry and the
+    /// same name, or `None` if the placemark has no geometry or the geometry is empty
+    ///
+    /// Lines and polygons don't show their name on the map, so a point placemark at the
+    /// representative point is the usual way to label them. Use a style with an `IconStyle` scale
+    /// of zero on the label placemark to show the name without an icon.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Coord, Geometry, LinearRing, Placemark, Polygon};
+    ///
+    /// let outer = LinearRing::from(vec![
+    ///     Coord::new(0., 0., None),
+    ///     Coord::new(4., 0., None),
+    ///     Coord::new(4., 2., None),
+    ///     Coord::new(0., 2., None),
+    ///     Coord::new(0., 0., None),
+    /// ]);
+    /// let park: Placemark = Placemark::default()
+    ///     .with_name("Park")
+    ///     .with_geometry(Geometry::Polygon(Polygon::new(outer, vec![])));
+    ///
+    /// let label = park.label_placemark().unwrap();
+    /// assert_eq!(label.name.as_deref(), Some("Park"));
+    /// assert!(matches!(label.geometry, Some(Geometry::Point(p)) if p.coord == Coord::new(2., 1., None)));
+    /// ```
+    pub fn label_placemark(&self) -> Option<Placemark<T>> {
+        let coord = self.geometry.as_ref()?.representative_point()?;
+        Some(Placemark {
+            name: self.name.clone(),
+            geometry: Some(Geometry::Point(Point::from(coord))),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring(coords: &[(f64, f64)]) -> LinearRing {
+        LinearRing::from(
+            coords
+                .iter()
+                .map(|(x, y)| Coord::new(*x, *y, None))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_polygon_centroid_and_representative_point() {
+        // U-shaped polygon whose centroid falls between its arms
+        let u = Polygon::new(
+            ring(&[
+                (0., 0.),
+                (3., 0.),
+                (3., 3.),
+                (2., 3.),
+                (2., 1.),
+                (1., 1.),
+                (1., 3.),
+                (0., 3.),
+                (0., 0.),
+            ]),
+            vec![],
+        );
+        let centroid = u.centroid().unwrap();
+        assert!((centroid.x - 1.5).abs() < 1e-9);
+        assert!((centroid.y - 1.3571428571428572).abs() < 1e-9);
+        assert!(!polygon_contains(&u, &centroid));
+        let point = u.representative_point().unwrap();
+        assert!(polygon_contains(&u, &point));
+
+        // Holes are subtracted from the area
+        let square = ring(&[(0., 0.), (4., 0.), (4., 4.), (0., 4.), (0., 0.)]);
+        let holed = Polygon::new(
+            square.clone(),
+            vec![ring(&[(0., 0.), (2., 0.), (2., 4.), (0., 4.), (0., 0.)])],
+        );
+        assert_eq!(holed.centroid(), Some(Coord::new(3., 2., None)));
+
+        // Polygons outweigh lines and points in multi-geometries
+        let multi = MultiGeometry::new(vec![
+            Geometry::Point(Point::new(100., 100., None)),
+            Geometry::Polygon(Polygon::new(square, vec![])),
+            Geometry::Polygon(u),
+        ]);
+        assert_eq!(multi.representative_point(), Some(Coord::new(2., 2., None)));
+        assert_eq!(Polygon::<f64>::default().centroid(), None);
+    }
+
+    #[test]
+    fn test_degenerate_centroids() {
+        let empty = LineString::<f64>::from(vec![]);
+        assert_eq!(empty.centroid(), None);
+        assert_eq!(empty.representative_point(), None);
+        assert_eq!(MultiGeometry::<f64>::new(vec![]).centroid(), None);
+        assert_eq!(Geometry::Model(Model::<f64>::default()).centroid(), None);
+        assert_eq!(
+            Geometry::<f64>::Element(Default::default()).representative_point(),
+            None
+        );
+
+        // Segments of zero length fall back to the vertices
+        let repeated = LineString::from_coords(vec![(1., 1.), (1., 1.)]);
+        assert_eq!(repeated.centroid(), Some(Coord::new(1., 1., None)));
+
+        // Polygons without an area are treated as their outer ring
+        let flat = Polygon::new(ring(&[(0., 0.), (2., 0.), (0., 0.)]), vec![]);
+        assert_eq!(flat.centroid(), Some(Coord::new(1., 0., None)));
+        assert_eq!(flat.representative_point(), Some(Coord::new(2., 0., None)));
+
+        // Centroids are planar, so a line across the antimeridian is centered on the other side
+        let crossing = LineString::from_coords(vec![(179., 0.), (-179., 0.)]);
+        assert_eq!(crossing.centroid(), Some(Coord::new(0., 0., None)));
+    }
+
+    #[test]
+    fn test_label_placemark() {
+        let placemark: Placemark = Placemark::default().with_name("Empty");
+        assert!(placemark.label_placemark().is_none());
+        let placemark = placemark.with_geometry(Geometry::LineString(LineString::from(vec![])));
+        assert!(placemark.label_placemark().is_none());
+
+        let placemark =
+            Placemark::default()
+                .with_name("Trail")
+                .with_geometry(Geometry::LineString(LineString::from_coords(vec![
+                    (0., 0.),
+                    (1., 1.),
+                    (2., 0.),
+                ])));
+        let label = placemark.label_placemark().unwrap();
+        assert_eq!(label.name.as_deref(), Some("Trail"));
+        assert!(
+            matches!(label.geometry, Some(Geometry::Point(p)) if p.coord == Coord::new(1., 1., None))
+        );
+    }
+}
@@ -12,28 +12,34 @@
 //! ```
 use std::convert::TryFrom;
 
+use crate::bounds::BoundingBox;
 use crate::errors::Error;
 use crate::types::{
-    Coord, CoordType, Geometry, Kml, LineString, LinearRing, MultiGeometry, Point, Polygon,
+    Coord, CoordType, FeatureProps, Geometry, Kml, LineString, LinearRing, MultiGeometry,
+    MultiTrack, Placemark, Point, Polygon, Track,
 };
 
+// `geo_types::Coordinate` is deprecated in later 0.7 releases, but is the only name available in 0.6
+#[allow(deprecated)]
+type GeoCoordinate<T> = geo_types::Coordinate<T>;
+
 #[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
-impl<T> From<geo_types::Coordinate<T>> for Coord<T>
+impl<T> From<GeoCoordinate<T>> for Coord<T>
 where
     T: CoordType,
 {
-    fn from(val: geo_types::Coordinate<T>) -> Coord<T> {
+    fn from(val: GeoCoordinate<T>) -> Coord<T> {
         Coord::from((val.x, val.y))
     }
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
-impl<T> From<Coord<T>> for geo_types::Coordinate<T>
+impl<T> From<Coord<T>> for GeoCoordinate<T>
 where
     T: CoordType,
 {
-    fn from(val: Coord<T>) -> geo_types::Coordinate<T> {
-        geo_types::Coordinate::from((val.x, val.y))
+    fn from(val: Coord<T>) -> GeoCoordinate<T> {
+        GeoCoordinate::from((val.x, val.y))
     }
 }
 
@@ -53,7 +59,7 @@ where
     T: CoordType,
 {
     fn from(val: Point<T>) -> geo_types::Point<T> {
-        geo_types::Point::from(geo_types::Coordinate::from(val.coord))
+        geo_types::Point::from(GeoCoordinate::from(val.coord))
     }
 }
 
@@ -88,10 +94,30 @@ where
     T: CoordType,
 {
     fn from(val: LineString<T>) -> geo_types::LineString<T> {
-        geo_types::LineString(
-            val.coords
+        geo_types::LineString(val.coords.into_iter().map(GeoCoordinate::from).collect())
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
+impl<T> From<Track<T>> for geo_types::LineString<T>
+where
+    T: CoordType,
+{
+    fn from(val: Track<T>) -> geo_types::LineString<T> {
+        geo_types::LineString(val.coords.into_iter().map(GeoCoordinate::from).collect())
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
+impl<T> From<MultiTrack<T>> for geo_types::MultiLineString<T>
+where
+    T: CoordType,
+{
+    fn from(val: MultiTrack<T>) -> geo_types::MultiLineString<T> {
+        geo_types::MultiLineString(
+            val.tracks
                 .into_iter()
-                .map(geo_types::Coordinate::from)
+                .map(geo_types::LineString::from)
                 .collect(),
         )
     }
@@ -118,12 +144,7 @@ where
     T: CoordType,
 {
     fn from(val: LinearRing<T>) -> geo_types::LineString<T> {
-        geo_types::LineString(
-            val.coords
-                .into_iter()
-                .map(geo_types::Coordinate::from)
-                .collect(),
-        )
+        geo_types::LineString(val.coords.into_iter().map(GeoCoordinate::from).collect())
     }
 }
 
@@ -154,6 +175,29 @@ where
     }
 }
 
+#[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
+impl<T> From<BoundingBox<T>> for geo_types::Rect<T>
+where
+    T: CoordType,
+{
+    fn from(val: BoundingBox<T>) -> geo_types::Rect<T> {
+        geo_types::Rect::new(GeoCoordinate::from(val.min), GeoCoordinate::from(val.max))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
+impl<T> From<geo_types::Rect<T>> for BoundingBox<T>
+where
+    T: CoordType,
+{
+    fn from(val: geo_types::Rect<T>) -> BoundingBox<T> {
+        BoundingBox {
+            min: Coord::from(val.min()),
+            max: Coord::from(val.max()),
+        }
+    }
+}
+
 #[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
 impl<T> From<geo_types::Triangle<T>> for Polygon<T>
 where
@@ -298,6 +342,12 @@ where
             Geometry::MultiGeometry(g) => Ok(geo_types::Geometry::GeometryCollection(
                 geo_types::GeometryCollection::try_from(g)?,
             )),
+            Geometry::Track(t) => Ok(geo_types::Geometry::LineString(
+                geo_types::LineString::from(t),
+            )),
+            Geometry::MultiTrack(t) => Ok(geo_types::Geometry::MultiLineString(
+                geo_types::MultiLineString::from(t),
+            )),
             _ => Err(Error::InvalidGeometry("Can't convert geometry".to_string())),
         }
     }
@@ -337,6 +387,12 @@ where
             1
         ]),
         Kml::MultiGeometry(g) => Ok(geo_types::GeometryCollection::try_from(g)?.0),
+        Kml::Track(t) => Ok(vec![geo_types::Geometry::LineString(
+            geo_types::LineString::from(t),
+        )]),
+        Kml::MultiTrack(t) => Ok(vec![geo_types::Geometry::MultiLineString(
+            geo_types::MultiLineString::from(t),
+        )]),
         Kml::Placemark(p) => Ok(if let Some(g) = p.geometry {
             vec![geo_types::Geometry::try_from(g)?; 1]
         } else {
@@ -388,12 +444,64 @@ where
     Ok(geo_types::GeometryCollection(process_kml(k)?))
 }
 
+/// A shortcut for producing a `kml:Document` with a named `kml:Placemark` for each `geo-types`
+/// geometry, ready to be written
+///
+/// # Example
+///
+/// ```
+/// use geo_types::{polygon, MultiPolygon, Point};
+/// use kml::{quick_document, Kml};
+///
+/// let lake = polygon![(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 0.)];
+/// let doc: Kml = quick_document(vec![
+///     ("Lakes", geo_types::Geometry::from(MultiPolygon(vec![lake]))),
+///     ("Dock", geo_types::Geometry::from(Point::new(1., 1.))),
+/// ]);
+/// assert!(doc.to_string().starts_with("<Document><Placemark><name>Lakes</name><MultiGeometry>"));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "geo-types")))]
+pub fn quick_document<T, N, G>(geometries: impl IntoIterator<Item = (N, G)>) -> Kml<T>
+where
+    T: CoordType + Default,
+    N: Into<String>,
+    G: Into<geo_types::Geometry<T>>,
+{
+    let elements = geometries
+        .into_iter()
+        .map(|(name, geometry)| {
+            Kml::Placemark(
+                Placemark::default()
+                    .with_name(name)
+                    .with_geometry(Geometry::from(geometry.into())),
+            )
+        })
+        .collect();
+    Kml::Document {
+        attrs: Default::default(),
+        props: FeatureProps::default(),
+        elements,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::KmlDocument;
     use std::collections::HashMap;
 
+    #[test]
+    fn test_track_to_line_string() {
+        let track = Track {
+            coords: vec![Coord::from((1., 2.)), Coord::from((3., 4.))],
+            ..Default::default()
+        };
+        assert_eq!(
+            geo_types::Geometry::try_from(Geometry::Track(track)).unwrap(),
+            geo_types::Geometry::LineString(geo_types::LineString::from(vec![(1., 2.), (3., 4.)]))
+        );
+    }
+
     #[test]
     fn test_quick_collection() {
         let k = KmlDocument {
@@ -401,6 +509,7 @@ mod tests {
                 Kml::Point(Point::from(Coord::from((1., 1.)))),
                 Kml::Folder {
                     attrs: HashMap::new(),
+                    props: FeatureProps::default(),
                     elements: vec![
                         Kml::LineString(LineString::from(vec![
                             Coord::from((1., 1.)),
@@ -420,4 +529,44 @@ mod tests {
         ]);
         assert_eq!(quick_collection(Kml::KmlDocument(k)).unwrap(), gc);
     }
+
+    #[test]
+    fn test_quick_document() {
+        let geometries = [
+            geo_types::Geometry::Point(geo_types::Point::from((1., 1.))),
+            geo_types::Geometry::MultiLineString(geo_types::MultiLineString(vec![
+                geo_types::LineString::from(vec![(1., 1.), (2., 2.)]),
+            ])),
+        ];
+        let doc: Kml = quick_document(vec![
+            ("a", geometries[0].clone()),
+            ("b", geometries[1].clone()),
+        ]);
+        let names: Vec<_> = doc.placemarks().filter_map(|p| p.name.as_deref()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(
+            quick_collection(doc).unwrap(),
+            geo_types::GeometryCollection(vec![
+                geometries[0].clone(),
+                geo_types::Geometry::GeometryCollection(geo_types::GeometryCollection(vec![
+                    geo_types::Geometry::LineString(geo_types::LineString::from(vec![
+                        (1., 1.),
+                        (2., 2.)
+                    ]))
+                ])),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bounding_box_rect() {
+        let doc: Kml = quick_document(vec![(
+            "line",
+            geo_types::LineString::from(vec![(3., -1.), (1., 2.)]),
+        )]);
+        let rect = geo_types::Rect::from(doc.bounding_box().unwrap());
+        assert_eq!(rect.min(), GeoCoordinate::from((1., -1.)));
+        assert_eq!(rect.max(), GeoCoordinate::from((3., 2.)));
+        assert_eq!(BoundingBox::from(rect), doc.bounding_box().unwrap());
+    }
 }
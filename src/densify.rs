@@ -0,0 +1,206 @@
+//! Densification of lines along great circles
+//!
+//! KML draws segments as straight lines in latitude and longitude unless `tessellate` is enabled
+//! and the geometry is clamped to the ground, so long segments like flight paths drawn at altitude
+//! don't follow the curve of the earth. Inserting points along the great circle between each pair
+//! of coordinates makes them render as curves regardless.
+use crate::types::{lit, Coord, CoordType, Geometry, Kml, LineString, LinearRing, Polygon};
+use crate::visit::KmlVisitor;
+
+/// Mean radius of the earth in meters, as used by the haversine formula
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+// Central angle between the coordinates in radians
+fn central_angle<T: CoordType>(a: &Coord<T>, b: &Coord<T>) -> T {
+    let (lat_a, lat_b) = (a.y.to_radians(), b.y.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_lon = (b.x - a.x).to_radians();
+    let h = (d_lat / lit(2.)).sin().powi(2)
+        + lat_a.cos() * lat_b.cos() * (d_lon / lit(2.)).sin().powi(2);
+    lit::<T>(2.) * h.sqrt().min(T::one()).asin()
+}
+
+// Coordinate at the fraction of the way along the great circle from a to b, with altitude
+// interpolated linearly if both have one
+fn interpolate<T: CoordType>(a: &Coord<T>, b: &Coord<T>, angle: T, fraction: T) -> Coord<T> {
+    let (lat_a, lon_a) = (a.y.to_radians(), a.x.to_radians());
+    let (lat_b, lon_b) = (b.y.to_radians(), b.x.to_radians());
+    let wa = ((T::one() - fraction) * angle).sin() / angle.sin();
+    let wb = (fraction * angle).sin() / angle.sin();
+    let x = wa * lat_a.cos() * lon_a.cos() + wb * lat_b.cos() * lon_b.cos();
+    let y = wa * lat_a.cos() * lon_a.sin() + wb * lat_b.cos() * lon_b.sin();
+    let z = wa * lat_a.sin() + wb * lat_b.sin();
+    let lat = z.atan2((x * x + y * y).sqrt());
+    let lon = y.atan2(x);
+    let alt = match (a.z, b.z) {
+        (Some(za), Some(zb)) => Some(za + (zb - za) * fraction),
+        _ => None,
+    };
+    Coord::new(lon.to_degrees(), lat.to_degrees(), alt)
+}
+
+// Inserts coordinates so no segment is longer than the maximum, returning the number inserted
+fn densify_coords<T: CoordType>(coords: &mut Vec<Coord<T>>, max_segment_meters: T) -> usize {
+    if max_segment_meters <= T::zero() || !max_segment_meters.is_finite() || coords.len() < 2 {
+        return 0;
+    }
+    let mut densified = Vec::with_capacity(coords.len());
+    for pair in coords.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        densified.push(*a);
+        let angle = central_angle(a, b);
+        // Antipodal coordinates don't have a single great circle between them
+        if angle.sin() == T::zero() {
+            continue;
+        }
+        let segments = (angle * lit(EARTH_RADIUS) / max_segment_meters).ceil();
+        let segments = segments.to_usize().unwrap_or(1);
+        for i in 1..segments {
+            let fraction = lit::<T>(i as f64) / lit(segments as f64);
+            densified.push(interpolate(a, b, angle, fraction));
+        }
+    }
+    densified.push(coords[coords.len() - 1]);
+    let inserted = densified.len() - coords.len();
+    *coords = densified;
+    inserted
+}
+
+// Densifies lines and rings, counting the coordinates inserted
+struct Densify<T>(T, usize);
+
+impl<T> KmlVisitor<T> for Densify<T>
+where
+    T: CoordType,
+{
+    fn visit_line_string(&mut self, line_string: &mut LineString<T>) {
+        self.1 += line_string.densify(self.0);
+    }
+
+    fn visit_linear_ring(&mut self, linear_ring: &mut LinearRing<T>) {
+        self.1 += linear_ring.densify(self.0);
+    }
+}
+
+impl<T> LineString<T>
+where
+    T: CoordType,
+{
+    /// Inserts coordinates along the great circle between each pair of coordinates so that no
+    /// segment is longer than `max_segment_meters`, returning the number of coordinates inserted
+    ///
+    /// Distances are calculated on a sphere with the mean radius of the earth, and altitudes are
+    /// interpolated linearly when both ends of a segment have one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::LineString;
+    ///
+    /// // New York to London
+    /// let mut line = LineString::from_coords(vec![(-73.78, 40.64), (-0.45, 51.47)]);
+    /// assert_eq!(line.densify(100_000.), 55);
+    /// // The midpoint of the great circle is well north of either end
+    /// assert!(line.coords[28].y > 52.);
+    /// ```
+    pub fn densify(&mut self, max_segment_meters: T) -> usize {
+        densify_coords(&mut self.coords, max_segment_meters)
+    }
+}
+
+impl<T> LinearRing<T>
+where
+    T: CoordType,
+{
+    /// Inserts coordinates along the great circle between each pair of coordinates, returning the
+    /// number inserted. See [`LineString::densify`](struct.LineString.html#method.densify)
+    pub fn densify(&mut self, max_segment_meters: T) -> usize {
+        densify_coords(&mut self.coords, max_segment_meters)
+    }
+}
+
+impl<T> Polygon<T>
+where
+    T: CoordType,
+{
+    /// Densifies the outer and inner rings, returning the number of coordinates inserted. See
+    /// [`LineString::densify`](struct.LineString.html#method.densify)
+    pub fn densify(&mut self, max_segment_meters: T) -> usize {
+        self.outer.densify(max_segment_meters)
+            + self
+                .inner
+                .iter_mut()
+                .map(|r| r.densify(max_segment_meters))
+                .sum::<usize>()
+    }
+}
+
+impl<T> Geometry<T>
+where
+    T: CoordType,
+{
+    /// Densifies every line and ring in the geometry, returning the number of coordinates
+    /// inserted. See [`LineString::densify`](struct.LineString.html#method.densify)
+    pub fn densify(&mut self, max_segment_meters: T) -> usize {
+        let mut visitor = Densify(max_segment_meters, 0);
+        self.walk(&mut visitor);
+        visitor.1
+    }
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Densifies every line and ring in the tree along great circles, returning the number of
+    /// coordinates inserted. See [`LineString::densify`](types/struct.LineString.html#method.densify)
+    pub fn densify(&mut self, max_segment_meters: T) -> usize {
+        let mut visitor = Densify(max_segment_meters, 0);
+        self.walk(&mut visitor);
+        visitor.1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_densify_line_string() {
+        // One degree of longitude along the equator is about 111 km
+        let mut line: LineString = LineString::from(vec![
+            Coord::new(0., 0., Some(0.)),
+            Coord::new(1., 0., Some(100.)),
+            Coord::new(1., 0., None),
+        ]);
+        assert_eq!(line.densify(50_000.), 2);
+        assert_eq!(line.coords.len(), 5);
+        let mid = line.coords[1];
+        assert!((mid.x - 1. / 3.).abs() < 1e-9 && mid.y.abs() < 1e-9);
+        assert!((mid.z.unwrap() - 100. / 3.).abs() < 1e-9);
+        assert_eq!(line.coords[3], Coord::new(1., 0., Some(100.)));
+
+        // Segments already short enough and invalid maximums are left as is
+        assert_eq!(line.densify(50_000.), 0);
+        assert_eq!(line.densify(0.), 0);
+        assert_eq!(line.densify(f64::NAN), 0);
+    }
+
+    #[test]
+    fn test_densify_kml() {
+        let mut kml: Kml = r#"<Folder><Placemark><Polygon><outerBoundaryIs><LinearRing>
+          <coordinates>0,0 2,0 2,2 0,0</coordinates>
+        </LinearRing></outerBoundaryIs></Polygon></Placemark></Folder>"#
+            .parse()
+            .unwrap();
+        assert!(kml.densify(100_000.) > 0);
+        if let Kml::Folder { elements, .. } = &kml {
+            if let Kml::Placemark(p) = &elements[0] {
+                if let Some(Geometry::Polygon(p)) = &p.geometry {
+                    assert!(p.outer.is_closed());
+                    assert!(p.outer.coords.len() > 4);
+                }
+            }
+        }
+    }
+}
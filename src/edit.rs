@@ -0,0 +1,379 @@
+//! Editing of KML trees, like adding elements to documents and merging documents
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+
+use crate::errors::Error;
+use crate::types::{CoordType, Element, Kml};
+
+// Style references rewritten when merging, from the old id to the new one
+struct StyleChanges {
+    dropped: HashSet<String>,
+    renamed: HashMap<String, String>,
+}
+
+impl StyleChanges {
+    fn rewrite_url(&self, style_url: &mut String) {
+        let new_id = style_url
+            .trim()
+            .strip_prefix('#')
+            .and_then(|id| self.renamed.get(id));
+        if let Some(new_id) = new_id {
+            *style_url = format!("#{}", new_id);
+        }
+    }
+
+    fn is_dropped<T: CoordType>(&self, kml: &Kml<T>) -> bool {
+        matches!(kml, Kml::Style(_) | Kml::StyleMap(_))
+            && kml.id().is_some_and(|id| self.dropped.contains(id))
+    }
+
+    // Renames styles and drops those that are dropped, with references updated separately
+    fn apply<T: CoordType>(&self, kml: &mut Kml<T>) {
+        match kml {
+            Kml::KmlDocument(d) => self.apply_elements(&mut d.elements),
+            Kml::Document { elements, .. } | Kml::Folder { elements, .. } => {
+                self.apply_elements(elements)
+            }
+            Kml::Style(s) => {
+                if let Some(new_id) = self.renamed.get(&s.id) {
+                    s.id = new_id.clone();
+                }
+            }
+            Kml::StyleMap(s) => {
+                if let Some(new_id) = self.renamed.get(&s.id) {
+                    s.id = new_id.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_elements<T: CoordType>(&self, elements: &mut Vec<Kml<T>>) {
+        elements.retain(|k| !self.is_dropped(k));
+        for k in elements.iter_mut() {
+            self.apply(k);
+        }
+    }
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    fn elements_mut(&mut self) -> Option<&mut Vec<Kml<T>>> {
+        match self {
+            Kml::KmlDocument(d) => Some(&mut d.elements),
+            Kml::Document { elements, .. } | Kml::Folder { elements, .. } => Some(elements),
+            _ => None,
+        }
+    }
+
+    // Visits every `styleUrl` in the tree, including those of style map pairs and those kept as
+    // elements in the children of features
+    pub(crate) fn for_each_style_url(&mut self, f: &mut dyn FnMut(&mut String)) {
+        fn element_urls(children: &mut [Element], f: &mut dyn FnMut(&mut String)) {
+            let urls = children
+                .iter_mut()
+                .filter(|e| e.local_name() == "styleUrl")
+                .filter_map(|e| e.content.as_mut());
+            for style_url in urls {
+                f(style_url);
+            }
+        }
+
+        match self {
+            Kml::KmlDocument(d) => {
+                for k in d.elements.iter_mut() {
+                    k.for_each_style_url(f);
+                }
+            }
+            Kml::Document {
+                props, elements, ..
+            }
+            | Kml::Folder {
+                props, elements, ..
+            } => {
+                if let Some(style_url) = props.style_url.as_mut() {
+                    f(style_url);
+                }
+                for k in elements.iter_mut() {
+                    k.for_each_style_url(f);
+                }
+            }
+            Kml::Placemark(p) => {
+                if let Some(style_url) = p.style_url.as_mut() {
+                    f(style_url);
+                }
+                element_urls(&mut p.children, f);
+            }
+            Kml::NetworkLink(n) => element_urls(&mut n.children, f),
+            Kml::GroundOverlay(o) => element_urls(&mut o.children, f),
+            Kml::ScreenOverlay(o) => element_urls(&mut o.children, f),
+            Kml::PhotoOverlay(o) => element_urls(&mut o.children, f),
+            Kml::StyleMap(s) => {
+                for pair in s.pairs.iter_mut() {
+                    f(&mut pair.style_url);
+                }
+            }
+            Kml::Pair(p) => f(&mut p.style_url),
+            Kml::Element(e) => element_urls(std::slice::from_mut(e), f),
+            _ => {}
+        }
+    }
+
+    fn not_container(&self) -> Error {
+        Error::UnexpectedKml {
+            expected: "kml, Document, or Folder",
+            found: self.variant_name(),
+        }
+    }
+
+    /// Adds the element to the end of a `kml`, `kml:Document`, or `kml:Folder`, returning
+    /// [`Error::UnexpectedKml`](enum.Error.html#variant.UnexpectedKml) for any other element
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, types::{Placemark, Point}};
+    ///
+    /// let mut kml: Kml = "<Document><name>Stops</name></Document>".parse().unwrap();
+    /// let stop: Placemark = Placemark::builder()
+    ///     .name("Depot")
+    ///     .geometry(Point::new(-105., 40., None))
+    ///     .build();
+    /// kml.push_element(stop).unwrap();
+    /// assert_eq!(kml.placemarks().count(), 1);
+    /// ```
+    pub fn push_element(&mut self, element: impl Into<Kml<T>>) -> Result<(), Error> {
+        match self.elements_mut() {
+            Some(elements) => {
+                elements.push(element.into());
+                Ok(())
+            }
+            None => Err(self.not_container()),
+        }
+    }
+
+    // Elements that merged content is added to, which are those of the document if the element
+    // is a `kml` element with a single `kml:Document`
+    fn merge_target(&mut self) -> Option<&mut Vec<Kml<T>>> {
+        let single_document = match self {
+            Kml::KmlDocument(d) => matches!(d.elements.as_slice(), [Kml::Document { .. }]),
+            _ => false,
+        };
+        if single_document {
+            if let Kml::KmlDocument(d) = self {
+                return d.elements[0].elements_mut();
+            }
+        }
+        self.elements_mut()
+    }
+
+    /// Adds the contents of another document to the end of this `kml`, `kml:Document`, or
+    /// `kml:Folder`
+    ///
+    /// If `other` is a `kml` or `kml:Document` element, its contents are added rather than the
+    /// element itself, and a `kml` element with a single `kml:Document` is treated as the
+    /// document both here and in `other`. The name and other fields of a document whose contents
+    /// are added aren't kept, so convert it to a folder first to keep them.
+    ///
+    /// Shared styles and style maps in `other` with the same id as one in this document are
+    /// dropped if they're equal, and otherwise given a new id with a numbered suffix, with the
+    /// `styleUrl` of features, overlays, network links, and style map pairs in `other` updated to
+    /// match. References nested in other elements without a typed representation aren't updated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::Kml;
+    ///
+    /// let mut trips: Kml = r##"<kml><Document>
+    ///   <Style id="route"><LineStyle><width>2</width></LineStyle></Style>
+    ///   <Placemark><name>Monday</name><styleUrl>#route</styleUrl></Placemark>
+    /// </Document></kml>"##.parse().unwrap();
+    /// let other: Kml = r##"<kml><Document>
+    ///   <Style id="route"><LineStyle><width>4</width></LineStyle></Style>
+    ///   <Placemark><name>Tuesday</name><styleUrl>#route</styleUrl></Placemark>
+    /// </Document></kml>"##.parse().unwrap();
+    ///
+    /// trips.merge(other).unwrap();
+    /// let urls: Vec<_> = trips
+    ///     .placemarks()
+    ///     .map(|p| p.style_url.as_deref().unwrap())
+    ///     .collect();
+    /// assert_eq!(urls, ["#route", "#route-2"]);
+    /// assert!(matches!(trips.find_by_id("route-2"), Some(Kml::Style(_))));
+    /// ```
+    pub fn merge(&mut self, other: Kml<T>) -> Result<(), Error> {
+        if self.elements_mut().is_none() {
+            return Err(self.not_container());
+        }
+        let mut elements = match other {
+            Kml::KmlDocument(d) => match <[Kml<T>; 1]>::try_from(d.elements) {
+                Ok([Kml::Document { elements, .. }]) => elements,
+                Ok([element]) => vec![element],
+                Err(elements) => elements,
+            },
+            Kml::Document { elements, .. } => elements,
+            other => vec![other],
+        };
+        let changes = self.style_changes(&elements);
+        changes.apply_elements(&mut elements);
+        for k in elements.iter_mut() {
+            k.for_each_style_url(&mut |style_url| changes.rewrite_url(style_url));
+        }
+        // Checked at the start
+        if let Some(target) = self.merge_target() {
+            target.extend(elements);
+        }
+        Ok(())
+    }
+
+    // Finds the styles in `other` to drop or rename so their ids don't conflict with this one's
+    fn style_changes(&self, other: &[Kml<T>]) -> StyleChanges {
+        let is_style = |k: &&Kml<T>| matches!(k, Kml::Style(_) | Kml::StyleMap(_));
+        let styles: HashMap<&str, &Kml<T>> = self
+            .iter()
+            .filter(is_style)
+            .filter_map(|k| Some((k.id()?, k)))
+            .collect();
+        let mut used: HashSet<String> = self
+            .iter()
+            .chain(other.iter().flat_map(Kml::iter))
+            .filter_map(|k| k.id().map(String::from))
+            .collect();
+        let mut changes = StyleChanges {
+            dropped: HashSet::new(),
+            renamed: HashMap::new(),
+        };
+        for k in other.iter().flat_map(Kml::iter).filter(is_style) {
+            let (id, existing) = match k.id().and_then(|id| Some((id, *styles.get(id)?))) {
+                Some(existing) => existing,
+                None => continue,
+            };
+            if existing == k {
+                changes.dropped.insert(id.to_string());
+            } else if !changes.renamed.contains_key(id) {
+                let new_id = (2..)
+                    .map(|n| format!("{}-{}", id, n))
+                    .find(|new_id| !used.contains(new_id))
+                    .unwrap_or_default();
+                used.insert(new_id.clone());
+                changes.renamed.insert(id.to_string(), new_id);
+            }
+        }
+        // Styles with the id are all renamed if any of them differ
+        let renamed = &changes.renamed;
+        changes.dropped.retain(|id| !renamed.contains_key(id));
+        changes
+    }
+
+    /// Removes the first element in the tree with the `id` from the document or folder containing
+    /// it and returns it, or `None` if there isn't one. See [`Kml::id`](#method.id)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::Kml;
+    ///
+    /// let mut kml: Kml = r#"<Document>
+    ///   <Folder><Placemark id="old"/><Placemark id="new"/></Folder>
+    /// </Document>"#.parse().unwrap();
+    /// assert!(matches!(kml.remove_by_id("old"), Some(Kml::Placemark(_))));
+    /// assert_eq!(kml.remove_by_id("old"), None);
+    /// assert_eq!(kml.placemarks().count(), 1);
+    /// ```
+    pub fn remove_by_id(&mut self, id: &str) -> Option<Kml<T>> {
+        let elements = self.elements_mut()?;
+        if let Some(i) = elements.iter().position(|k| k.id() == Some(id)) {
+            return Some(elements.remove(i));
+        }
+        elements.iter_mut().find_map(|k| k.remove_by_id(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Placemark, Style};
+
+    #[test]
+    fn test_merge_styles() {
+        let mut kml: Kml = r##"<Document>
+          <Style id="same"><LineStyle><width>1</width></LineStyle></Style>
+          <Style id="diff"><LineStyle><width>1</width></LineStyle></Style>
+          <Placemark id="diff-2"/>
+        </Document>"##
+            .parse()
+            .unwrap();
+        let other: Kml = r##"<kml>
+          <Style id="same"><LineStyle><width>1</width></LineStyle></Style>
+          <Folder>
+            <Style id="diff"><LineStyle><width>2</width></LineStyle></Style>
+            <StyleMap id="map"><Pair><key>normal</key><styleUrl>#diff</styleUrl></Pair></StyleMap>
+            <Placemark><styleUrl>#same</styleUrl></Placemark>
+            <Placemark><styleUrl>#diff</styleUrl></Placemark>
+          </Folder>
+        </kml>"##
+            .parse()
+            .unwrap();
+        kml.merge(other).unwrap();
+
+        let expected: Kml = r##"<Document>
+          <Style id="same"><LineStyle><width>1</width></LineStyle></Style>
+          <Style id="diff"><LineStyle><width>1</width></LineStyle></Style>
+          <Placemark id="diff-2"/>
+          <Folder>
+            <Style id="diff-3"><LineStyle><width>2</width></LineStyle></Style>
+            <StyleMap id="map"><Pair><key>normal</key><styleUrl>#diff-3</styleUrl></Pair></StyleMap>
+            <Placemark><styleUrl>#same</styleUrl></Placemark>
+            <Placemark><styleUrl>#diff-3</styleUrl></Placemark>
+          </Folder>
+        </Document>"##
+            .parse()
+            .unwrap();
+        assert_eq!(kml, expected);
+    }
+
+    #[test]
+    fn test_merge_overlay_style_urls() {
+        let mut kml: Kml = r##"<Document>
+          <Style id="s"><LineStyle><width>1</width></LineStyle></Style>
+        </Document>"##
+            .parse()
+            .unwrap();
+        let other: Kml = r##"<Document>
+          <Style id="s"><LineStyle><width>2</width></LineStyle></Style>
+          <GroundOverlay><styleUrl>#s</styleUrl></GroundOverlay>
+          <NetworkLink><styleUrl>#s</styleUrl></NetworkLink>
+        </Document>"##
+            .parse()
+            .unwrap();
+        kml.merge(other).unwrap();
+
+        let style_urls: Vec<_> = kml
+            .iter()
+            .filter_map(|k| match k {
+                Kml::GroundOverlay(o) => o.children.first(),
+                Kml::NetworkLink(n) => n.children.first(),
+                _ => None,
+            })
+            .map(|e| e.content.as_deref().unwrap())
+            .collect();
+        assert_eq!(style_urls, ["#s-2", "#s-2"]);
+    }
+
+    #[test]
+    fn test_edit_non_container() {
+        let mut kml = Kml::Placemark(Placemark::<f64>::default());
+        assert!(matches!(
+            kml.push_element(Style::default()),
+            Err(Error::UnexpectedKml {
+                found: "Placemark",
+                ..
+            })
+        ));
+        assert!(kml.merge(Kml::Placemark(Placemark::default())).is_err());
+        assert_eq!(kml.remove_by_id("a"), None);
+    }
+}
@@ -7,7 +7,13 @@ pub enum Error {
     #[error("Invalid input supplied for XML")]
     InvalidInput,
     #[error("Encountered malformed XML: {0}")]
-    MalformedXml(#[from] quick_xml::Error),
+    MalformedXml(#[source] quick_xml::Error),
+    #[error("Expected closing tag </{expected}> but found </{found}>")]
+    TagMismatch {
+        expected: String,
+        found: String,
+        source: quick_xml::Error,
+    },
     #[error("Invalid XML event: {0}")]
     InvalidXmlEvent(String),
     #[error("Coordinate empty")]
@@ -24,6 +30,8 @@ pub enum Error {
     InvalidGeometry(String),
     #[error("Invalid altitude mode: {0}")]
     InvalidAltitudeMode(String),
+    #[error("Invalid color: {0}")]
+    InvalidColor(String),
     #[error("Invalid color mode: {0}")]
     InvalidColorMode(String),
     #[error("Invalid list item type: {0}")]
@@ -33,6 +41,95 @@ pub enum Error {
     #[cfg(feature = "zip")]
     #[error("ZIP error: {0}")]
     ZipError(#[from] zip::result::ZipError),
+    #[cfg(feature = "proj")]
+    #[error("Projection error: {0}")]
+    ProjError(#[from] proj4rs::errors::Error),
     #[error("Invalid units: {0}")]
     InvalidUnits(String),
+    #[error("Invalid flyToMode: {0}")]
+    InvalidFlyToMode(String),
+    #[error("Invalid refreshMode: {0}")]
+    InvalidRefreshMode(String),
+    #[error("Invalid viewRefreshMode: {0}")]
+    InvalidViewRefreshMode(String),
+    #[error("Invalid shape: {0}")]
+    InvalidShape(String),
+    #[error("Invalid gridOrigin: {0}")]
+    InvalidGridOrigin(String),
+    #[error("Invalid dateTime: {0}")]
+    InvalidDateTime(String),
+    #[error("Expected {expected} but found {found}")]
+    UnexpectedKml {
+        expected: &'static str,
+        found: &'static str,
+    },
+    #[error("Coordinate tuple \"{tuple}\" has {found} components, expected 2 or 3")]
+    InvalidCoordArity { tuple: String, found: usize },
+    #[error("Error reading {element} at byte {position}: {source}")]
+    InElement {
+        element: String,
+        position: usize,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Returns the underlying error without the context of the element it occurred in
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Error, Kml};
+    ///
+    /// let err = "<Placemark><Point><coordinates>a,1</coordinates></Point></Placemark>"
+    ///     .parse::<Kml>()
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Error reading Point at byte 18: Error parsing number from: a"
+    /// );
+    /// assert!(matches!(err.root_cause(), Error::NumParse(v) if v == "a"));
+    /// ```
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Error::InElement { source, .. } => source.root_cause(),
+            e => e,
+        }
+    }
+
+    /// Returns the name of the innermost element being read when the error occurred and the byte
+    /// position just after its start tag, if known
+    pub fn element(&self) -> Option<(&str, usize)> {
+        match self {
+            Error::InElement {
+                element, position, ..
+            } => Some((element, *position)),
+            _ => None,
+        }
+    }
+
+    // Adds the element to the error unless it already has the context of a nested element
+    pub(crate) fn in_element(self, element: &[u8], position: usize) -> Self {
+        match self {
+            e @ Error::InElement { .. } => e,
+            e => Error::InElement {
+                element: String::from_utf8_lossy(element).to_string(),
+                position,
+                source: Box::new(e),
+            },
+        }
+    }
+}
+
+impl From<quick_xml::Error> for Error {
+    fn from(e: quick_xml::Error) -> Self {
+        match &e {
+            quick_xml::Error::EndEventMismatch { expected, found } => Error::TagMismatch {
+                expected: expected.clone(),
+                found: found.clone(),
+                source: e,
+            },
+            _ => Error::MalformedXml(e),
+        }
+    }
 }
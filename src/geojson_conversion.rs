@@ -0,0 +1,463 @@
+//! Conversion between KML and `geojson` features
+//!
+//! Placemarks are converted to features, with `name`, `description`, and the values of
+//! `kml:ExtendedData` as properties. Values from `kml:Data` and `kml:SimpleData` are strings, and
+//! properties that aren't strings are written to `kml:Data` as JSON.
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use geojson::{feature::Id, Feature, FeatureCollection, JsonObject, JsonValue, Position, Value};
+
+use crate::errors::Error;
+use crate::types::{
+    Coord, CoordType, Data, ExtendedData, FeatureProps, Geometry, Kml, KmlDocument, KmlVersion,
+    LineString, LinearRing, MultiGeometry, Placemark, Point, Polygon,
+};
+
+fn position<T: CoordType>(coord: &Coord<T>) -> Result<Position, Error> {
+    let to_f64 = |v: T| {
+        v.to_f64()
+            .ok_or_else(|| Error::InvalidGeometry("Coordinate isn't a valid f64".to_string()))
+    };
+    let mut position = vec![to_f64(coord.x)?, to_f64(coord.y)?];
+    if let Some(z) = coord.z {
+        position.push(to_f64(z)?);
+    }
+    Ok(position)
+}
+
+fn positions<T: CoordType>(coords: &[Coord<T>]) -> Result<Vec<Position>, Error> {
+    coords.iter().map(position).collect()
+}
+
+fn coord<T: CoordType>(position: &[f64]) -> Result<Coord<T>, Error> {
+    let from_f64 = |v: f64| {
+        T::from(v)
+            .ok_or_else(|| Error::InvalidGeometry(format!("Can't convert {} to coordinate", v)))
+    };
+    match position {
+        [x, y] => Ok(Coord::new(from_f64(*x)?, from_f64(*y)?, None)),
+        [x, y, z, ..] => Ok(Coord::new(
+            from_f64(*x)?,
+            from_f64(*y)?,
+            Some(from_f64(*z)?),
+        )),
+        _ => Err(Error::InvalidGeometry(format!(
+            "Position has {} values, expected at least 2",
+            position.len()
+        ))),
+    }
+}
+
+fn coords<T: CoordType>(positions: &[Position]) -> Result<Vec<Coord<T>>, Error> {
+    positions.iter().map(|p| coord(p)).collect()
+}
+
+fn polygon_rings<T: CoordType>(polygon: &Polygon<T>) -> Result<Vec<Vec<Position>>, Error> {
+    std::iter::once(&polygon.outer)
+        .chain(&polygon.inner)
+        .map(|r| positions(&r.coords))
+        .collect()
+}
+
+fn polygon<T: CoordType + Default>(rings: &[Vec<Position>]) -> Result<Polygon<T>, Error> {
+    let mut rings = rings
+        .iter()
+        .map(|r| Ok(LinearRing::from(coords(r)?)))
+        .collect::<Result<Vec<LinearRing<T>>, Error>>()?;
+    if rings.is_empty() {
+        return Err(Error::InvalidGeometry(
+            "Polygon has no exterior ring".to_string(),
+        ));
+    }
+    let outer = rings.remove(0);
+    Ok(Polygon::new(outer, rings))
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+impl<T> TryFrom<&Geometry<T>> for geojson::Geometry
+where
+    T: CoordType,
+{
+    type Error = Error;
+
+    /// Converts KML geometry to GeoJSON, with multi-geometries of only points, lines, or polygons
+    /// converted to `MultiPoint`, `MultiLineString`, or `MultiPolygon`
+    fn try_from(val: &Geometry<T>) -> Result<geojson::Geometry, Self::Error> {
+        let value = match val {
+            Geometry::Point(p) => Value::Point(position(&p.coord)?),
+            Geometry::LineString(l) => Value::LineString(positions(&l.coords)?),
+            Geometry::LinearRing(l) => Value::LineString(positions(&l.coords)?),
+            Geometry::Track(t) => Value::LineString(positions(&t.coords)?),
+            Geometry::Polygon(p) => Value::Polygon(polygon_rings(p)?),
+            Geometry::MultiTrack(t) => Value::MultiLineString(
+                t.tracks
+                    .iter()
+                    .map(|t| positions(&t.coords))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Geometry::MultiGeometry(g) => multi_geometry_value(g)?,
+            _ => return Err(Error::InvalidGeometry("Can't convert geometry".to_string())),
+        };
+        Ok(geojson::Geometry::new(value))
+    }
+}
+
+fn multi_geometry_value<T: CoordType>(g: &MultiGeometry<T>) -> Result<Value, Error> {
+    let geometries = &g.geometries;
+    if geometries.is_empty() {
+        return Ok(Value::GeometryCollection(vec![]));
+    }
+    if geometries.iter().all(|g| matches!(g, Geometry::Point(_))) {
+        return Ok(Value::MultiPoint(
+            geometries
+                .iter()
+                .filter_map(|g| match g {
+                    Geometry::Point(p) => Some(position(&p.coord)),
+                    _ => None,
+                })
+                .collect::<Result<_, _>>()?,
+        ));
+    }
+    if geometries
+        .iter()
+        .all(|g| matches!(g, Geometry::LineString(_)))
+    {
+        return Ok(Value::MultiLineString(
+            geometries
+                .iter()
+                .filter_map(|g| match g {
+                    Geometry::LineString(l) => Some(positions(&l.coords)),
+                    _ => None,
+                })
+                .collect::<Result<_, _>>()?,
+        ));
+    }
+    if geometries.iter().all(|g| matches!(g, Geometry::Polygon(_))) {
+        return Ok(Value::MultiPolygon(
+            geometries
+                .iter()
+                .filter_map(|g| match g {
+                    Geometry::Polygon(p) => Some(polygon_rings(p)),
+                    _ => None,
+                })
+                .collect::<Result<_, _>>()?,
+        ));
+    }
+    Ok(Value::GeometryCollection(
+        geometries
+            .iter()
+            .map(geojson::Geometry::try_from)
+            .collect::<Result<_, _>>()?,
+    ))
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+impl<T> TryFrom<&geojson::Geometry> for Geometry<T>
+where
+    T: CoordType + Default,
+{
+    type Error = Error;
+
+    fn try_from(val: &geojson::Geometry) -> Result<Geometry<T>, Self::Error> {
+        let multi = |geometries: Result<Vec<Geometry<T>>, Error>| {
+            Ok(Geometry::MultiGeometry(MultiGeometry::new(geometries?)))
+        };
+        match &val.value {
+            Value::Point(p) => Ok(Geometry::Point(Point::from(coord(p)?))),
+            Value::LineString(l) => Ok(Geometry::LineString(LineString::from(coords(l)?))),
+            Value::Polygon(p) => Ok(Geometry::Polygon(polygon(p)?)),
+            Value::MultiPoint(points) => multi(
+                points
+                    .iter()
+                    .map(|p| Ok(Geometry::Point(Point::from(coord(p)?))))
+                    .collect(),
+            ),
+            Value::MultiLineString(lines) => multi(
+                lines
+                    .iter()
+                    .map(|l| Ok(Geometry::LineString(LineString::from(coords(l)?))))
+                    .collect(),
+            ),
+            Value::MultiPolygon(polygons) => multi(
+                polygons
+                    .iter()
+                    .map(|p| Ok(Geometry::Polygon(polygon(p)?)))
+                    .collect(),
+            ),
+            Value::GeometryCollection(geometries) => {
+                multi(geometries.iter().map(Geometry::try_from).collect())
+            }
+        }
+    }
+}
+
+// Adds the values of `kml:Data` and `kml:SimpleData` in `kml:ExtendedData` to properties
+fn add_extended_data(properties: &mut JsonObject, extended_data: &ExtendedData) {
+    for data in extended_data.data.iter() {
+        properties.insert(data.name.clone(), JsonValue::String(data.value.clone()));
+    }
+    for simple_data in extended_data.schema_data.iter().flat_map(|s| s.data.iter()) {
+        properties.insert(
+            simple_data.name.clone(),
+            JsonValue::String(simple_data.value.clone()),
+        );
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+impl<T> TryFrom<&Placemark<T>> for Feature
+where
+    T: CoordType,
+{
+    type Error = Error;
+
+    /// Converts a placemark to a feature with its `id` attribute as the feature's ID, and its
+    /// name, description, and extended data as properties
+    fn try_from(val: &Placemark<T>) -> Result<Feature, Self::Error> {
+        let mut properties = JsonObject::new();
+        if let Some(name) = &val.name {
+            properties.insert("name".to_string(), JsonValue::String(name.clone()));
+        }
+        if let Some(description) = &val.description {
+            properties.insert(
+                "description".to_string(),
+                JsonValue::String(description.clone()),
+            );
+        }
+        if let Some(extended_data) = &val.extended_data {
+            add_extended_data(&mut properties, extended_data);
+        }
+
+        Ok(Feature {
+            bbox: None,
+            geometry: val
+                .geometry
+                .as_ref()
+                .map(geojson::Geometry::try_from)
+                .transpose()?,
+            id: val.attrs.get("id").cloned().map(Id::String),
+            properties: Some(properties),
+            foreign_members: None,
+        })
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+impl<T> TryFrom<&Feature> for Placemark<T>
+where
+    T: CoordType + Default,
+{
+    type Error = Error;
+
+    /// Converts a feature to a placemark, with the `name` and `description` properties as the
+    /// placemark's name and description when they're strings, and other properties written to
+    /// `kml:Data` in `kml:ExtendedData`
+    fn try_from(val: &Feature) -> Result<Placemark<T>, Self::Error> {
+        let mut placemark = Placemark {
+            geometry: val.geometry.as_ref().map(Geometry::try_from).transpose()?,
+            ..Default::default()
+        };
+        match &val.id {
+            Some(Id::String(id)) => {
+                placemark.attrs.insert("id".to_string(), id.clone());
+            }
+            Some(Id::Number(id)) => {
+                placemark.attrs.insert("id".to_string(), id.to_string());
+            }
+            None => {}
+        }
+
+        let mut extended_data = ExtendedData::default();
+        for (name, value) in val.properties.iter().flatten() {
+            match (name.as_str(), value) {
+                ("name", JsonValue::String(v)) => placemark.name = Some(v.clone()),
+                ("description", JsonValue::String(v)) => placemark.description = Some(v.clone()),
+                _ => {
+                    let value = match value {
+                        JsonValue::String(v) => v.clone(),
+                        v => v.to_string(),
+                    };
+                    extended_data.data.push(Data::new(name.clone(), value));
+                }
+            }
+        }
+        if !extended_data.data.is_empty() {
+            placemark.extended_data = Some(extended_data);
+        }
+        Ok(placemark)
+    }
+}
+
+fn collect_features<T: CoordType>(kml: &Kml<T>, features: &mut Vec<Feature>) -> Result<(), Error> {
+    match kml {
+        Kml::KmlDocument(KmlDocument { elements, .. })
+        | Kml::Document { elements, .. }
+        | Kml::Folder { elements, .. } => {
+            for element in elements {
+                collect_features(element, features)?;
+            }
+        }
+        Kml::Placemark(p) => features.push(Feature::try_from(p)?),
+        k => {
+            let geometry = match k {
+                Kml::Point(p) => Geometry::Point(p.clone()),
+                Kml::LineString(l) => Geometry::LineString(l.clone()),
+                Kml::LinearRing(l) => Geometry::LinearRing(l.clone()),
+                Kml::Polygon(p) => Geometry::Polygon(p.clone()),
+                Kml::MultiGeometry(g) => Geometry::MultiGeometry(g.clone()),
+                Kml::Track(t) => Geometry::Track(t.clone()),
+                Kml::MultiTrack(t) => Geometry::MultiTrack(t.clone()),
+                _ => return Ok(()),
+            };
+            features.push(Feature::from(geojson::Geometry::try_from(&geometry)?));
+        }
+    }
+    Ok(())
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+impl<T> TryFrom<&Kml<T>> for FeatureCollection
+where
+    T: CoordType,
+{
+    type Error = Error;
+
+    /// Converts each placemark in documents and folders to a feature, along with geometries
+    /// outside of placemarks as features without properties
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use geojson::FeatureCollection;
+    /// use kml::Kml;
+    ///
+    /// let kml: Kml = r#"<Document><Folder><Placemark>
+    ///   <name>Trailhead</name>
+    ///   <ExtendedData><Data name="elevation"><value>1200</value></Data></ExtendedData>
+    ///   <Point><coordinates>-105.3,40.0</coordinates></Point>
+    /// </Placemark></Folder></Document>"#.parse().unwrap();
+    ///
+    /// let collection = FeatureCollection::try_from(&kml).unwrap();
+    /// let feature = &collection.features[0];
+    /// assert_eq!(feature.property("name").unwrap(), "Trailhead");
+    /// assert_eq!(feature.property("elevation").unwrap(), "1200");
+    ///
+    /// let round_trip = Kml::<f64>::try_from(&collection).unwrap();
+    /// assert!(round_trip.to_string().contains("<Data name=\"elevation\"><value>1200</value></Data>"));
+    /// ```
+    fn try_from(val: &Kml<T>) -> Result<FeatureCollection, Self::Error> {
+        let mut features = Vec::new();
+        collect_features(val, &mut features)?;
+        Ok(FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members: None,
+        })
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+impl<T> TryFrom<Kml<T>> for FeatureCollection
+where
+    T: CoordType,
+{
+    type Error = Error;
+
+    fn try_from(val: Kml<T>) -> Result<FeatureCollection, Self::Error> {
+        FeatureCollection::try_from(&val)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+impl<T> TryFrom<&FeatureCollection> for Kml<T>
+where
+    T: CoordType + Default,
+{
+    type Error = Error;
+
+    /// Converts features to placemarks in a `kml:Document` of a KML 2.2 document
+    fn try_from(val: &FeatureCollection) -> Result<Kml<T>, Self::Error> {
+        let placemarks = val
+            .features
+            .iter()
+            .map(|f| Ok(Kml::Placemark(Placemark::try_from(f)?)))
+            .collect::<Result<Vec<Kml<T>>, Error>>()?;
+        let mut doc = KmlDocument::new(KmlVersion::V22);
+        doc.elements.push(Kml::Document {
+            attrs: HashMap::new(),
+            props: FeatureProps::default(),
+            elements: placemarks,
+        });
+        Ok(Kml::KmlDocument(doc))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+impl<T> TryFrom<FeatureCollection> for Kml<T>
+where
+    T: CoordType + Default,
+{
+    type Error = Error;
+
+    fn try_from(val: FeatureCollection) -> Result<Kml<T>, Self::Error> {
+        Kml::try_from(&val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{elements, variant};
+
+    #[test]
+    fn test_geojson_round_trip() {
+        let kml: Kml = r##"<kml xmlns="http://www.opengis.net/kml/2.2"><Document>
+          <Placemark id="one">
+            <name>One</name>
+            <description>First</description>
+            <ExtendedData>
+              <Data name="kind"><value>trail</value></Data>
+              <SchemaData schemaUrl="#s"><SimpleData name="lanes">2</SimpleData></SchemaData>
+            </ExtendedData>
+            <MultiGeometry>
+              <Polygon><outerBoundaryIs><LinearRing>
+                <coordinates>0,0 1,0 1,1 0,0</coordinates>
+              </LinearRing></outerBoundaryIs></Polygon>
+              <Polygon><outerBoundaryIs><LinearRing>
+                <coordinates>2,2,1 3,2,1 3,3,1 2,2,1</coordinates>
+              </LinearRing></outerBoundaryIs></Polygon>
+            </MultiGeometry>
+          </Placemark>
+          <Point><coordinates>5,6</coordinates></Point>
+        </Document></kml>"##
+            .parse()
+            .unwrap();
+
+        let collection = FeatureCollection::try_from(&kml).unwrap();
+        assert_eq!(collection.features.len(), 2);
+        let feature = &collection.features[0];
+        assert_eq!(feature.id, Some(Id::String("one".to_string())));
+        assert_eq!(feature.property("description").unwrap(), "First");
+        assert_eq!(feature.property("kind").unwrap(), "trail");
+        assert_eq!(feature.property("lanes").unwrap(), "2");
+        assert!(matches!(
+            &feature.geometry.as_ref().unwrap().value,
+            Value::MultiPolygon(p) if p[1][0][0] == vec![2., 2., 1.]
+        ));
+        assert!(collection.features[1].properties.is_none());
+
+        let kml = Kml::<f64>::try_from(collection).unwrap();
+        let placemarks = elements(&variant::<KmlDocument>(&kml).elements[0]);
+        let placemark: &Placemark = variant(&placemarks[0]);
+        assert_eq!(placemark.name.as_deref(), Some("One"));
+        assert_eq!(placemark.attrs.get("id").map(String::as_str), Some("one"));
+        assert_eq!(placemark.extended_data.as_ref().unwrap().data.len(), 2);
+        assert!(matches!(
+            &placemark.geometry,
+            Some(Geometry::MultiGeometry(g)) if g.geometries.len() == 2
+        ));
+        assert!(matches!(&placemarks[1], Kml::Placemark(p) if p.name.is_none()));
+    }
+}
@@ -1,5 +1,5 @@
 use std::fs::File;
-use std::io::Cursor;
+use std::io::{Cursor, Read, Seek};
 use std::path::Path;
 use std::str::FromStr;
 
@@ -9,6 +9,91 @@ use crate::errors::Error;
 use crate::reader::KmlReader;
 use crate::types::CoordType;
 
+/// KMZ archive, giving access to its KML document along with other entries like icons, images,
+/// and models referenced by the document
+///
+/// The document is the `doc.kml` entry at the root of the archive if there is one, and otherwise
+/// the first entry with a `.kml` extension, which is how Google Earth finds it.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+/// use kml::{Kml, KmzArchive};
+///
+/// let kmz_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+///     .join("tests")
+///     .join("fixtures")
+///     .join("polygon.kmz");
+/// let mut archive = KmzArchive::from_path(kmz_path).unwrap();
+/// for name in archive.entry_names() {
+///     println!("{}", name);
+/// }
+/// let kml: Kml = archive.kml_reader().unwrap().read().unwrap();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
+pub struct KmzArchive<R: Read + Seek> {
+    archive: ZipArchive<R>,
+    names: Vec<String>,
+}
+
+impl KmzArchive<File> {
+    /// Opens a KMZ file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::new(File::open(path)?)
+    }
+}
+
+impl<R: Read + Seek> KmzArchive<R> {
+    /// Reads the directory of a KMZ archive from KMZ data in memory or any other seekable reader
+    pub fn new(reader: R) -> Result<Self, Error> {
+        let mut archive = ZipArchive::new(reader)?;
+        let names = (0..archive.len())
+            .map(|i| Ok(archive.by_index(i)?.name().to_string()))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(KmzArchive { archive, names })
+    }
+
+    /// Returns the names of all entries in the order they're stored, including the document
+    pub fn entry_names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+
+    /// Returns the name of the entry with the KML document, or `None` if there isn't one
+    pub fn kml_name(&self) -> Option<&str> {
+        self.entry_names()
+            .find(|n| n.eq_ignore_ascii_case("doc.kml"))
+            .or_else(|| {
+                self.entry_names()
+                    .find(|n| n.to_ascii_lowercase().ends_with(".kml"))
+            })
+    }
+
+    /// Reads the contents of the entry with the name, like the `files/icon.png` referenced by an
+    /// `href` in the document
+    pub fn read_entry(&mut self, name: &str) -> Result<Vec<u8>, Error> {
+        let mut entry = self.archive.by_name(name)?;
+        // The size comes from the archive's headers, so it isn't trusted for more than a MiB
+        let mut buf = Vec::with_capacity(entry.size().min(1 << 20) as usize);
+        std::io::copy(&mut entry, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Creates a [`KmlReader`](struct.KmlReader.html) for the KML document
+    pub fn kml_reader<T>(&mut self) -> Result<KmlReader<Cursor<Vec<u8>>, T>, Error>
+    where
+        T: CoordType + FromStr + Default,
+    {
+        let name = self.kml_name().ok_or(Error::InvalidInput)?.to_string();
+        Ok(KmlReader::from_reader(Cursor::new(self.read_entry(&name)?)))
+    }
+
+    /// Returns the underlying archive
+    pub fn into_inner(self) -> ZipArchive<R> {
+        self.archive
+    }
+}
+
 #[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
 impl<T> KmlReader<Cursor<Vec<u8>>, T>
 where
@@ -17,6 +102,9 @@ where
     #[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
     /// Create a [`KmlReader`](struct.KmlReader.html) from a KMZ file path
     ///
+    /// The document is read from `doc.kml`, or the first `.kml` entry if there isn't one. Use
+    /// [`KmzArchive`](struct.KmzArchive.html) to read the other entries of the archive.
+    ///
     /// # Example
     ///
     /// ```
@@ -31,21 +119,30 @@ where
     /// let kml = kml_reader.read().unwrap();
     /// ```
     pub fn from_kmz_path<P: AsRef<Path>>(path: P) -> Result<KmlReader<Cursor<Vec<u8>>, T>, Error> {
-        let file = File::open(path)?;
-        let mut archive = ZipArchive::new(file)?;
-
-        // Should parse the first file with a KML extension
-        for i in 0..archive.len() {
-            let mut kml_file = archive.by_index(i).map_err(|_| Error::InvalidInput)?;
-            if !kml_file.name().to_ascii_lowercase().ends_with(".kml") {
-                continue;
-            }
-            let mut buf = Vec::with_capacity(kml_file.size() as usize);
-            std::io::copy(&mut kml_file, &mut buf)?;
-            return Ok(KmlReader::from_reader(Cursor::new(buf)));
-        }
+        Self::from_kmz_reader(File::open(path)?)
+    }
 
-        Err(Error::InvalidInput)
+    #[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
+    /// Create a [`KmlReader`](struct.KmlReader.html) from KMZ data in memory or any other seekable
+    /// reader, without accessing the file system
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use kml::KmlReader;
+    ///
+    /// let kmz_bytes = include_bytes!(concat!(
+    ///     env!("CARGO_MANIFEST_DIR"),
+    ///     "/tests/fixtures/polygon.kmz"
+    /// ));
+    /// let mut kml_reader = KmlReader::<_, f64>::from_kmz_reader(Cursor::new(&kmz_bytes[..])).unwrap();
+    /// let kml = kml_reader.read().unwrap();
+    /// ```
+    pub fn from_kmz_reader<R: Read + Seek>(
+        reader: R,
+    ) -> Result<KmlReader<Cursor<Vec<u8>>, T>, Error> {
+        KmzArchive::new(reader)?.kml_reader()
     }
 }
 
@@ -65,4 +162,31 @@ mod tests {
 
         assert!(matches!(kml, Kml::Polygon(_)))
     }
+
+    #[test]
+    fn test_kmz_archive_prefers_doc_kml() {
+        use std::io::Write;
+        use zip::write::{FileOptions, ZipWriter};
+
+        let mut buf = Cursor::new(Vec::new());
+        let mut writer = ZipWriter::new(&mut buf);
+        for (name, content) in [
+            ("files/linked.kml", "<Folder/>"),
+            ("files/icon.png", "png"),
+            ("DOC.KML", "<Point><coordinates>1,1</coordinates></Point>"),
+        ] {
+            writer.start_file(name, FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+        drop(writer);
+
+        let mut archive = KmzArchive::new(buf).unwrap();
+        assert_eq!(archive.kml_name(), Some("DOC.KML"));
+        assert_eq!(archive.entry_names().count(), 3);
+        assert_eq!(archive.read_entry("files/icon.png").unwrap(), b"png");
+        assert!(archive.read_entry("missing.png").is_err());
+        let kml: Kml = archive.kml_reader().unwrap().read().unwrap();
+        assert!(matches!(kml, Kml::Point(_)));
+    }
 }
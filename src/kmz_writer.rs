@@ -0,0 +1,271 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{Seek, Write};
+use std::str::FromStr;
+
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use crate::errors::Error;
+use crate::types::{CoordType, Kml, KmlDocument, Link, NetworkLink};
+use crate::writer::KmlWriter;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
+impl<W, T> KmlWriter<W, T>
+where
+    W: Write + Seek,
+    T: CoordType + FromStr + Default + fmt::Display,
+{
+    #[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
+    /// Split top-level folders into their own entries of KMZ archives written with
+    /// [`write_kmz`](#method.write_kmz), replacing each folder in `doc.kml` with a `kml:NetworkLink`
+    /// to its entry
+    ///
+    /// Top-level folders are the folders in the root `kml` element or in its `kml:Document`. Large
+    /// documents split this way load faster in viewers, which only parse the entries of folders
+    /// that are visible. Shared styles stay in `doc.kml`, so references to them from a split
+    /// folder are rewritten to `../doc.kml#id`.
+    pub fn split_kmz_folders(mut self, split_kmz_folders: bool) -> Self {
+        self.split_kmz_folders = split_kmz_folders;
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
+    /// Writes KML as a KMZ archive with the document in a `doc.kml` entry
+    ///
+    /// With [`split_kmz_folders`](#method.split_kmz_folders), top-level folders are written to
+    /// `files/folder_1.kml`, `files/folder_2.kml`, and so on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use kml::{Kml, KmlReader, KmlWriter};
+    ///
+    /// let kml: Kml = r#"<kml xmlns="http://www.opengis.net/kml/2.2"><Document>
+    ///   <Folder><name>Trails</name><Placemark><name>Trailhead</name></Placemark></Folder>
+    /// </Document></kml>"#.parse().unwrap();
+    ///
+    /// let mut buf = Cursor::new(Vec::new());
+    /// KmlWriter::from_writer(&mut buf)
+    ///     .split_kmz_folders(true)
+    ///     .write_kmz(&kml)
+    ///     .unwrap();
+    ///
+    /// buf.set_position(0);
+    /// let doc = KmlReader::<_, f64>::from_kmz_reader(buf).unwrap().read().unwrap();
+    /// assert!(doc.to_string().contains("<href>files/folder_1.kml</href>"));
+    /// ```
+    pub fn write_kmz(&mut self, kml: &Kml<T>) -> Result<(), Error> {
+        self.write_kmz_with_entries(kml, Vec::<(&str, &[u8])>::new())
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
+    /// Writes KML as a KMZ archive like [`write_kmz`](#method.write_kmz), adding other entries
+    /// like icons and images referenced by the document after it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use kml::{Kml, KmlWriter, KmzArchive};
+    ///
+    /// let kml: Kml = r#"<Placemark><Style><IconStyle>
+    ///   <Icon><href>files/icon.png</href></Icon>
+    /// </IconStyle></Style></Placemark>"#.parse().unwrap();
+    /// let icon: &[u8] = &[0x89, b'P', b'N', b'G'];
+    ///
+    /// let mut buf = Cursor::new(Vec::new());
+    /// KmlWriter::from_writer(&mut buf)
+    ///     .write_kmz_with_entries(&kml, vec![("files/icon.png", icon)])
+    ///     .unwrap();
+    ///
+    /// let mut archive = KmzArchive::new(buf).unwrap();
+    /// assert_eq!(archive.entry_names().collect::<Vec<_>>(), vec!["doc.kml", "files/icon.png"]);
+    /// assert_eq!(archive.read_entry("files/icon.png").unwrap(), icon);
+    /// ```
+    pub fn write_kmz_with_entries<I, N, D>(&mut self, kml: &Kml<T>, entries: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (N, D)>,
+        N: Into<String>,
+        D: AsRef<[u8]>,
+    {
+        let (root, folders) = if self.split_kmz_folders {
+            self.split_folders(kml)
+        } else {
+            (kml.clone(), Vec::new())
+        };
+
+        let mut documents = vec![("doc.kml".to_string(), self.to_bytes(&root)?)];
+        for (href, folder) in folders {
+            documents.push((href, self.to_bytes(&folder)?));
+        }
+
+        let mut archive = ZipWriter::new(self.inner());
+        for (name, bytes) in documents {
+            archive.start_file(name, FileOptions::default())?;
+            archive.write_all(&bytes)?;
+        }
+        for (name, bytes) in entries {
+            archive.start_file(name, FileOptions::default())?;
+            archive.write_all(bytes.as_ref())?;
+        }
+        archive.finish()?;
+        Ok(())
+    }
+
+    fn to_bytes(&self, kml: &Kml<T>) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::new();
+        self.with_options(&mut buf).write(kml)?;
+        Ok(buf)
+    }
+
+    // Replaces top-level folders with network links, returning the root along with the entry name
+    // and document of each folder
+    fn split_folders(&self, kml: &Kml<T>) -> (Kml<T>, Vec<(String, Kml<T>)>) {
+        let mut root = kml.clone();
+        let (attrs, elements) = match &mut root {
+            Kml::KmlDocument(d) => match d.elements.as_mut_slice() {
+                [Kml::Document { elements, .. }] => (Some(d.attrs.clone()), elements),
+                _ => (Some(d.attrs.clone()), &mut d.elements),
+            },
+            Kml::Document { elements, .. } => (None, elements),
+            _ => return (root, Vec::new()),
+        };
+
+        let mut folders = Vec::new();
+        for element in elements.iter_mut() {
+            if !matches!(element, Kml::Folder { .. }) {
+                continue;
+            }
+            let href = format!("files/folder_{}.kml", folders.len() + 1);
+            let mut link = NetworkLink::new(Link::new(href.clone()));
+            if let Kml::Folder { props, .. } = element {
+                link.name = props.name.clone();
+            }
+
+            let mut folder = std::mem::replace(element, Kml::NetworkLink(link));
+            link_shared_styles(&mut folder);
+            let mut doc = KmlDocument::new(self.version_or_default());
+            if let Some(attrs) = &attrs {
+                doc.attrs = attrs.clone();
+            }
+            doc.elements.push(folder);
+            folders.push((href, Kml::KmlDocument(doc)));
+        }
+        (root, folders)
+    }
+}
+
+// Points references to styles that aren't in the folder at `doc.kml`, where the shared styles of
+// the document stay
+fn link_shared_styles<T: CoordType>(folder: &mut Kml<T>) {
+    let ids: HashSet<String> = folder
+        .iter()
+        .filter(|k| matches!(k, Kml::Style(_) | Kml::StyleMap(_)))
+        .filter_map(|k| k.id().map(String::from))
+        .collect();
+    folder.for_each_style_url(&mut |style_url| {
+        let id = match style_url.trim().strip_prefix('#') {
+            Some(id) if !ids.contains(id) => id,
+            _ => return,
+        };
+        *style_url = format!("../doc.kml#{}", id);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use zip::ZipArchive;
+
+    use super::*;
+    use crate::KmlReader;
+
+    #[test]
+    fn test_write_kmz_split_folders() {
+        let kml: Kml = r#"<kml xmlns="http://www.opengis.net/kml/2.2">
+          <Placemark><name>Root</name></Placemark>
+          <Folder><name>One</name><Placemark><name>A</name></Placemark></Folder>
+          <Folder><Placemark><name>B</name></Placemark></Folder>
+        </kml>"#
+            .parse()
+            .unwrap();
+
+        let mut buf = Cursor::new(Vec::new());
+        KmlWriter::from_writer(&mut buf)
+            .split_kmz_folders(true)
+            .write_kmz(&kml)
+            .unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(buf.get_ref().clone())).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert_eq!(names.len(), 3);
+        let mut folder = String::new();
+        archive
+            .by_name("files/folder_2.kml")
+            .unwrap()
+            .read_to_string(&mut folder)
+            .unwrap();
+        assert!(folder.contains(r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#));
+        assert!(folder.contains("<name>B</name>"));
+
+        buf.set_position(0);
+        let doc = KmlReader::<_, f64>::from_kmz_reader(buf)
+            .unwrap()
+            .read()
+            .unwrap()
+            .to_string();
+        assert!(doc.contains("<name>Root</name>"));
+        assert!(doc.contains(
+            "<NetworkLink><name>One</name><Link><href>files/folder_1.kml</href></Link></NetworkLink>"
+        ));
+        assert!(doc.contains("<NetworkLink><Link><href>files/folder_2.kml</href></Link>"));
+        assert!(!doc.contains("<name>A</name>"));
+    }
+
+    #[test]
+    fn test_write_kmz_split_folder_styles() {
+        let kml: Kml = r##"<kml xmlns="http://www.opengis.net/kml/2.2"><Document>
+          <Style id="red"><LineStyle><color>ff0000ff</color></LineStyle></Style>
+          <Folder>
+            <Style id="blue"><LineStyle><color>ffff0000</color></LineStyle></Style>
+            <Placemark><name>A</name><styleUrl>#red</styleUrl></Placemark>
+            <Placemark><name>B</name><styleUrl>#blue</styleUrl></Placemark>
+          </Folder>
+        </Document></kml>"##
+            .parse()
+            .unwrap();
+
+        let mut buf = Cursor::new(Vec::new());
+        KmlWriter::from_writer(&mut buf)
+            .split_kmz_folders(true)
+            .write_kmz(&kml)
+            .unwrap();
+
+        let mut archive = ZipArchive::new(buf).unwrap();
+        let mut read_entry = |name: &str| -> Kml {
+            let mut entry = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut entry)
+                .unwrap();
+            entry.parse().unwrap()
+        };
+        let doc = read_entry("doc.kml");
+        let folder = read_entry("files/folder_1.kml");
+
+        // References resolve relative to the entry in `files/`
+        let style_urls: Vec<_> = folder
+            .placemarks()
+            .map(|p| p.style_url.as_deref().unwrap())
+            .collect();
+        assert_eq!(style_urls, ["../doc.kml#red", "#blue"]);
+        let (path, id) = style_urls[0].split_once('#').unwrap();
+        assert_eq!(path, "../doc.kml");
+        assert!(matches!(doc.find_by_id(id), Some(Kml::Style(_))));
+        assert!(matches!(folder.find_by_id("blue"), Some(Kml::Style(_))));
+    }
+}
@@ -104,19 +104,56 @@ mod errors;
 pub use crate::errors::Error;
 
 pub mod reader;
-pub use crate::reader::KmlReader;
+pub use crate::reader::{KmlReader, KmlReaderOptions, ReadMode};
 
 pub mod writer;
-pub use crate::writer::KmlWriter;
+pub use crate::writer::{KmlWriter, KmlWriterOptions};
+
+pub mod validation;
+
+pub mod repair;
+
+pub mod style_resolver;
+pub use crate::style_resolver::{StyleResolver, StyleState};
+
+pub mod visit;
+pub use crate::visit::{by_ref::KmlVisitorRef, KmlVisitor};
+
+mod altitude;
+mod antimeridian;
+mod bounds;
+pub use crate::bounds::BoundingBox;
+mod centroid;
+mod densify;
+mod edit;
+mod transform;
+mod version;
+
+#[cfg(test)]
+mod test_utils;
+
+#[cfg(feature = "xsd")]
+#[cfg_attr(docsrs, doc(cfg(feature = "xsd")))]
+pub mod xsd;
 
 #[cfg(feature = "geo-types")]
 pub mod conversion;
 
 #[cfg(feature = "geo-types")]
-pub use conversion::quick_collection;
+pub use conversion::{quick_collection, quick_document};
+
+#[cfg(feature = "geojson")]
+mod geojson_conversion;
+
+#[cfg(feature = "async")]
+mod async_io;
+#[cfg(feature = "async")]
+pub use crate::async_io::{AsyncKmlReader, AsyncKmlWriter};
 
 #[cfg(feature = "zip")]
 mod kmz_reader;
+#[cfg(feature = "zip")]
+pub use crate::kmz_reader::KmzArchive;
 
 #[cfg(feature = "zip")]
-pub use kmz_reader::*;
+mod kmz_writer;
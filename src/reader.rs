@@ -1,6 +1,8 @@
 //! Module for reading KML sources into Rust types
+use std::any::Any;
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::marker::PhantomData;
@@ -15,21 +17,168 @@ use quick_xml::events::{BytesStart, Event};
 use crate::errors::Error;
 use crate::types::geom_props::GeomProps;
 use crate::types::{
-    self, coords_from_str, BalloonStyle, ColorMode, Coord, CoordType, Element, Geometry, Icon,
-    IconStyle, Kml, KmlDocument, KmlVersion, LabelStyle, LineString, LineStyle, LinearRing,
-    ListStyle, Location, MultiGeometry, Orientation, Pair, Placemark, Point, PolyStyle, Polygon,
-    Scale, Style, StyleMap, Units, Vec2,
+    self, split_tuples, AbstractView, BalloonStyle, Camera, Color, ColorMode, Coord, CoordType,
+    Custom, CustomElement, Element, ExtendedData, FeatureProps, Geometry, GroundOverlay, Icon,
+    IconStyle, ImagePyramid, Kml, KmlDocument, KmlVersion, LabelStyle, LatLonAltBox, LatLonBox,
+    LineString, LineStyle, LinearRing, Link, ListStyle, Location, Lod, LookAt, Model,
+    MultiGeometry, MultiTrack, NetworkLink, Orientation, Pair, PhotoOverlay, Placemark, Point,
+    PolyStyle, Polygon, Region, ResourceMap, Scale, Schema, ScreenOverlay, Style, StyleMap,
+    TimePrimitive, TimeSpan, TimeStamp, Tour, Track, Units, Vec2, ViewVolume, ATOM_NAMESPACE,
+    GX_NAMESPACE, XAL_NAMESPACE,
 };
 
+// Reads the next event, keeping the namespace declarations in scope and the depth up to date
+macro_rules! next_event {
+    ($self:ident) => {{
+        let e = $self.reader.read_event(&mut $self.buf)?;
+        $self.namespaces.update(&e);
+        match e {
+            Event::Start(_) => $self.depth += 1,
+            Event::End(_) => $self.depth = $self.depth.saturating_sub(1),
+            _ => {}
+        }
+        e
+    }};
+}
+
+/// How [`KmlReader`](struct.KmlReader.html) handles content it can't read
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ReadMode {
+    /// Return an error for the whole document
+    #[default]
+    Strict,
+    /// Skip coordinate tuples without 2 or 3 components and elements of documents and folders
+    /// that can't be read, recording each error in
+    /// [`KmlReader::warnings`](struct.KmlReader.html#method.warnings)
+    ///
+    /// Malformed XML like mismatched tags is still an error, since the structure of the rest of
+    /// the document can't be known.
+    Lenient,
+}
+
+/// Options for reading KML with [`KmlReader`](struct.KmlReader.html)
+///
+/// By default reading is strict, and content is read as-is.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, KmlReader, KmlReaderOptions, ReadMode};
+///
+/// let kml_str = r#"<Folder>
+///   <Placemark><name>Bad</name><Point><coordinates>a,1</coordinates></Point></Placemark>
+///   <Placemark><name>Good</name><Point><coordinates>1,1</coordinates></Point></Placemark>
+/// </Folder>"#;
+/// assert!(KmlReader::<_, f64>::from_string(kml_str).read().is_err());
+///
+/// let options = KmlReaderOptions::default().with_mode(ReadMode::Lenient);
+/// let mut reader = KmlReader::<_, f64>::from_string(kml_str).with_options(options);
+/// let kml = reader.read().unwrap();
+/// assert_eq!(kml.placemarks().count(), 1);
+/// assert_eq!(
+///     reader.warnings()[0].to_string(),
+///     "Error reading Point at byte 45: Error parsing number from: a"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KmlReaderOptions {
+    pub mode: ReadMode,
+    /// See [`KmlReader::close_rings`](struct.KmlReader.html#method.close_rings)
+    pub close_rings: bool,
+    /// See [`KmlReader::lossless`](struct.KmlReader.html#method.lossless)
+    pub lossless: bool,
+    /// See [`KmlReader::resolve_namespaces`](struct.KmlReader.html#method.resolve_namespaces)
+    pub resolve_namespaces: bool,
+}
+
+impl KmlReaderOptions {
+    pub fn with_mode(mut self, mode: ReadMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_close_rings(mut self, close_rings: bool) -> Self {
+        self.close_rings = close_rings;
+        self
+    }
+
+    pub fn with_lossless(mut self, lossless: bool) -> Self {
+        self.lossless = lossless;
+        self
+    }
+
+    pub fn with_resolve_namespaces(mut self, resolve_namespaces: bool) -> Self {
+        self.resolve_namespaces = resolve_namespaces;
+        self
+    }
+}
+
+// Stack of namespace declarations on the elements currently open, only tracked when needed to
+// resolve the namespaces of elements
+#[derive(Default)]
+struct Namespaces {
+    enabled: bool,
+    scopes: Vec<Vec<(Vec<u8>, Vec<u8>)>>,
+}
+
+impl Namespaces {
+    fn update(&mut self, event: &Event) {
+        if !self.enabled {
+            return;
+        }
+        match event {
+            Event::Start(e) => self.scopes.push(
+                e.attributes()
+                    .filter_map(Result::ok)
+                    .filter_map(|a| {
+                        let prefix = match a.key {
+                            b"xmlns" => &[][..],
+                            key => key.strip_prefix(b"xmlns:")?,
+                        };
+                        Some((prefix.to_vec(), a.value.into_owned()))
+                    })
+                    .collect(),
+            ),
+            Event::End(_) => {
+                self.scopes.pop();
+            }
+            _ => {}
+        }
+    }
+
+    // Resolves the namespace of a prefixed or unprefixed name, which is empty if not declared
+    fn resolve(&self, name: &[u8]) -> String {
+        let prefix = match name.iter().position(|b| *b == b':') {
+            Some(i) => &name[..i],
+            None => &[][..],
+        };
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter())
+            .find(|(p, _)| p == prefix)
+            .map(|(_, ns)| String::from_utf8_lossy(ns).to_string())
+            .unwrap_or_default()
+    }
+}
+
+type ElementParser = fn(&Element) -> Option<Custom>;
+
 /// Main struct for reading KML documents
 pub struct KmlReader<B: BufRead, T: CoordType + FromStr + Default = f64> {
     reader: quick_xml::Reader<B>,
     buf: Vec<u8>,
+    namespaces: Namespaces,
+    parsers: HashMap<(String, String), ElementParser>,
+    options: KmlReaderOptions,
+    // Number of elements currently open
+    depth: usize,
+    warnings: Vec<Error>,
     _version: KmlVersion, // TODO: How to incorporate this so it can be set before parsing?
     _phantom: PhantomData<T>,
 }
 
-impl<'a, T> KmlReader<&'a [u8], T>
+impl<T> KmlReader<&[u8], T>
 where
     T: CoordType + FromStr + Default,
 {
@@ -89,11 +238,204 @@ where
         KmlReader {
             reader,
             buf: Vec::new(),
+            namespaces: Namespaces::default(),
+            parsers: HashMap::new(),
+            options: KmlReaderOptions::default(),
+            depth: 0,
+            warnings: Vec::new(),
             _version: KmlVersion::Unknown,
             _phantom: PhantomData,
         }
     }
 
+    /// Close any `kml:LinearRing` whose first and last coordinates don't match while reading
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlReader};
+    ///
+    /// let ring_str = "<LinearRing><coordinates>0,0 1,0 1,1</coordinates></LinearRing>";
+    /// let ring: Kml<f64> = KmlReader::from_string(ring_str)
+    ///     .close_rings(true)
+    ///     .read()
+    ///     .unwrap();
+    /// assert!(matches!(ring, Kml::LinearRing(r) if r.is_closed()));
+    /// ```
+    pub fn close_rings(mut self, close_rings: bool) -> Self {
+        self.options.close_rings = close_rings;
+        self
+    }
+
+    /// Keep namespace prefixes in the names of elements read into [`Element`](types/struct.Element.html),
+    /// so extensions like `gx:Track` are written back into their namespace rather than KML's
+    ///
+    /// Unknown elements always keep all of their attributes (including `xmlns` declarations), text,
+    /// and CDATA content, though CDATA sections are written back as escaped text. Because
+    /// attributes are stored in a `HashMap`, their original order isn't preserved, and
+    /// [`KmlWriter`](struct.KmlWriter.html) writes them with namespace declarations first followed
+    /// by the rest sorted by name. Comments, processing instructions, whitespace between elements,
+    /// and whether an element was self-closing aren't preserved either.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlReader};
+    ///
+    /// let track_str = r#"<gx:Track xmlns:gx="http://www.google.com/kml/ext/2.2"><when>2010-05-28T02:02:09Z</when></gx:Track>"#;
+    /// let track: Kml<f64> = KmlReader::from_string(track_str)
+    ///     .lossless(true)
+    ///     .read()
+    ///     .unwrap();
+    /// assert_eq!(track.to_string(), track_str);
+    /// ```
+    pub fn lossless(mut self, lossless: bool) -> Self {
+        self.options.lossless = lossless;
+        self
+    }
+
+    /// Read in [`ReadMode::Lenient`](enum.ReadMode.html#variant.Lenient) if `true`, or
+    /// [`ReadMode::Strict`](enum.ReadMode.html#variant.Strict) otherwise
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlReader};
+    ///
+    /// let line_str = "<LineString><coordinates>0,0 1,1,1,1 2,2</coordinates></LineString>";
+    /// assert!(KmlReader::<_, f64>::from_string(line_str).read().is_err());
+    ///
+    /// let mut reader = KmlReader::<_, f64>::from_string(line_str).lenient(true);
+    /// let line = reader.read().unwrap();
+    /// assert!(matches!(line, Kml::LineString(l) if l.coords.len() == 2));
+    /// assert_eq!(reader.warnings().len(), 1);
+    /// ```
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.options.mode = if lenient {
+            ReadMode::Lenient
+        } else {
+            ReadMode::Strict
+        };
+        self
+    }
+
+    /// Resolve the namespace prefixes of elements in documents and folders, so only elements in
+    /// the KML namespace of any version or the `gx`, `atom`, or `xal` namespaces are read into
+    /// types
+    ///
+    /// Elements are otherwise matched by their local name, so `kml:Placemark` and `Placemark` are
+    /// read the same way either way, but so is a `Placemark` in an unrelated namespace. With
+    /// this set, elements in other namespaces are read into
+    /// [`Kml::Element`](enum.Kml.html#variant.Element), or [`Kml::Custom`](enum.Kml.html#variant.Custom)
+    /// if their type is [registered](#method.register). Elements without a namespace are read as
+    /// KML.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlReader};
+    ///
+    /// let kml_str = r#"<kml:Document xmlns:kml="http://www.opengis.net/kml/2.2" xmlns:acme="https://example.com/acme">
+    ///   <kml:Placemark><kml:name>Home</kml:name></kml:Placemark>
+    ///   <acme:Placemark/>
+    /// </kml:Document>"#;
+    /// let kml: Kml = KmlReader::from_string(kml_str)
+    ///     .resolve_namespaces(true)
+    ///     .read()
+    ///     .unwrap();
+    /// if let Kml::Document { elements, .. } = kml {
+    ///     assert!(matches!(&elements[0], Kml::Placemark(p) if p.name.as_deref() == Some("Home")));
+    ///     assert!(matches!(&elements[1], Kml::Element(_)));
+    /// }
+    /// ```
+    pub fn resolve_namespaces(mut self, resolve_namespaces: bool) -> Self {
+        self.options.resolve_namespaces = resolve_namespaces;
+        self.namespaces.enabled |= resolve_namespaces;
+        self
+    }
+
+    /// Replaces all of the options, see [`KmlReaderOptions`](struct.KmlReaderOptions.html)
+    pub fn with_options(mut self, options: KmlReaderOptions) -> Self {
+        self.namespaces.enabled |= options.resolve_namespaces;
+        self.options = options;
+        self
+    }
+
+    /// Errors skipped while reading in [`lenient`](#method.lenient) mode, along with elements like
+    /// `kml:Region` or `kml:TimeStamp` that are kept as [`Kml::Element`](enum.Kml.html#variant.Element)
+    /// in either mode because they couldn't be read into their type, and style colors in the
+    /// `rrggbb` order of HTML that were converted to [`Color`](types/struct.Color.html), in the
+    /// order they occurred
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn take_warnings(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Registers a type to read from extension elements with the namespace and local name that
+    /// aren't otherwise read into a type
+    ///
+    /// Matching elements in documents and folders are read into [`Element`](types/struct.Element.html)
+    /// and converted with [`CustomElement::from_element`](types/trait.CustomElement.html#tymethod.from_element).
+    /// If that returns a value, the element is read as [`Kml::Custom`](enum.Kml.html#variant.Custom)
+    /// with the value, and otherwise it stays as [`Kml::Element`](enum.Kml.html#variant.Element).
+    /// Elements that are children of other types, like
+    /// [`Placemark::children`](types/struct.Placemark.html#structfield.children), are always
+    /// read as `Element`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlReader};
+    /// use kml::types::{CustomElement, Element};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Beacon {
+    ///     serial: String,
+    /// }
+    ///
+    /// impl CustomElement for Beacon {
+    ///     fn from_element(element: &Element) -> Option<Self> {
+    ///         let serial = element.attrs.get("serial")?.clone();
+    ///         Some(Beacon { serial })
+    ///     }
+    ///
+    ///     fn to_element(&self) -> Element {
+    ///         Element::new("acme:Beacon").with_attr("serial", self.serial.as_str())
+    ///     }
+    /// }
+    ///
+    /// let kml_str = r#"<Document xmlns:acme="https://example.com/acme">
+    ///   <acme:Beacon serial="A1"/>
+    /// </Document>"#;
+    /// let kml: Kml = KmlReader::from_string(kml_str)
+    ///     .register::<Beacon>("https://example.com/acme", "Beacon")
+    ///     .read()
+    ///     .unwrap();
+    /// if let Kml::Document { elements, .. } = kml {
+    ///     let beacon = match &elements[0] {
+    ///         Kml::Custom(c) => c.downcast_ref::<Beacon>(),
+    ///         _ => None,
+    ///     };
+    ///     assert_eq!(beacon.unwrap().serial, "A1");
+    /// }
+    /// ```
+    pub fn register<C>(mut self, namespace: &str, local_name: &str) -> Self
+    where
+        C: CustomElement + Any + Send + Sync,
+    {
+        let parser: ElementParser = |element| {
+            C::from_element(element).map(|value| Custom::from_parts(element.clone(), value))
+        };
+        self.parsers
+            .insert((namespace.to_string(), local_name.to_string()), parser);
+        self.namespaces.enabled = true;
+        self
+    }
+
     /// Read content into [`Kml`](enum.Kml.html)
     ///
     /// # Example
@@ -105,71 +447,149 @@ where
     /// let kml_point: Kml<f64> = KmlReader::from_string(point_str).read().unwrap();
     /// ```
     pub fn read(&mut self) -> Result<Kml<T>, Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("read_kml").entered();
         let mut result = self.read_elements()?;
         // Converts multiple items at the same level to KmlDocument
-        match result.len().cmp(&1) {
-            Ordering::Greater => Ok(Kml::KmlDocument(KmlDocument {
+        let kml = match result.len().cmp(&1) {
+            Ordering::Greater => Kml::KmlDocument(KmlDocument {
                 elements: result,
                 ..Default::default()
-            })),
-            Ordering::Equal => Ok(result.remove(0)),
-            Ordering::Less => Err(Error::NoElements),
+            }),
+            Ordering::Equal => result.remove(0),
+            Ordering::Less => return Err(Error::NoElements),
+        };
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            elements = kml.count_elements(),
+            bytes = self.reader.buffer_position(),
+            "read KML"
+        );
+        Ok(kml)
+    }
+
+    /// Returns an iterator reading the features of the document one at a time, so large documents
+    /// can be processed without holding the whole tree in memory
+    ///
+    /// Containers like `kml:Document` and `kml:Folder` are entered rather than returned, so the
+    /// iterator yields everything inside them, like placemarks, styles, and elements read into
+    /// [`Kml::Element`](enum.Kml.html#variant.Element), in document order. The attributes of the
+    /// containers and fields like their `name` aren't returned. Options like
+    /// [`lossless`](#method.lossless) and registered types apply as they do with
+    /// [`read`](#method.read). The iterator stops after the first error, other than those skipped
+    /// in [`lenient`](#method.lenient) mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlReader};
+    ///
+    /// let kml_str = r#"<kml><Document>
+    ///   <Style id="red"/>
+    ///   <Folder>
+    ///     <Placemark><name>One</name></Placemark>
+    ///     <Placemark><name>Two</name></Placemark>
+    ///   </Folder>
+    /// </Document></kml>"#;
+    ///
+    /// let mut reader = KmlReader::<_, f64>::from_string(kml_str);
+    /// let mut names = Vec::new();
+    /// for feature in reader.features() {
+    ///     if let Kml::Placemark(p) = feature.unwrap() {
+    ///         names.push(p.name.unwrap());
+    ///     }
+    /// }
+    /// assert_eq!(names, vec!["One", "Two"]);
+    /// ```
+    pub fn features(&mut self) -> Features<'_, B, T> {
+        Features {
+            reader: self,
+            done: false,
+        }
+    }
+
+    /// Returns an iterator reading only the placemarks of the document one at a time, see
+    /// [`features`](#method.features)
+    pub fn placemarks(&mut self) -> impl Iterator<Item = Result<Placemark<T>, Error>> + '_ {
+        self.features().filter_map(|feature| match feature {
+            Ok(Kml::Placemark(p)) => Some(Ok(p)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    // Reads the next element that isn't a container, returning `None` at the end of the input
+    fn read_next_feature(&mut self) -> Result<Option<Kml<T>>, Error> {
+        loop {
+            // Nothing read so far is still borrowed, so the buffer only grows to the largest
+            // feature rather than the whole document
+            self.buf.clear();
+            let mut e = next_event!(self);
+            match e {
+                Event::Start(ref mut e) => {
+                    if matches!(e.local_name(), b"kml" | b"Document" | b"Folder") {
+                        continue;
+                    }
+                    let start = e.to_owned();
+                    let depth = self.depth;
+                    // Fields of the containers like their names aren't features
+                    if FeatureProps::is_field(start.local_name()) && !self.is_foreign(&start) {
+                        while self.depth >= depth {
+                            if let Event::Eof = next_event!(self) {
+                                return Err(Error::InvalidInput);
+                            }
+                        }
+                        continue;
+                    }
+                    match self.read_kml(&start) {
+                        Ok(kml) => return Ok(Some(kml)),
+                        Err(e) => self.skip_element(depth, e)?,
+                    }
+                }
+                Event::End(_)
+                | Event::Decl(_)
+                | Event::CData(_)
+                | Event::Empty(_)
+                | Event::Text(_) => {}
+                Event::Eof => return Ok(None),
+                _ => return Err(Error::InvalidInput),
+            }
         }
     }
 
     fn read_elements(&mut self) -> Result<Vec<Kml<T>>, Error> {
+        self.read_container(None)
+    }
+
+    // Reads the children of a container, with the fields of `kml:Document` and `kml:Folder` read
+    // into their props
+    fn read_container(
+        &mut self,
+        mut props: Option<&mut FeatureProps>,
+    ) -> Result<Vec<Kml<T>>, Error> {
         let mut elements: Vec<Kml<T>> = Vec::new();
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => {
-                    let attrs = Self::read_attrs(e.attributes());
-                    match e.local_name() {
-                        b"kml" => elements.push(Kml::KmlDocument(self.read_kml_document()?)),
-                        b"Scale" => elements.push(Kml::Scale(self.read_scale(attrs)?)),
-                        b"Orientation" => {
-                            elements.push(Kml::Orientation(self.read_orientation(attrs)?))
-                        }
-                        b"Point" => elements.push(Kml::Point(self.read_point(attrs)?)),
-                        b"Location" => elements.push(Kml::Location(self.read_location(attrs)?)),
-                        b"LineString" => {
-                            elements.push(Kml::LineString(self.read_line_string(attrs)?))
-                        }
-                        b"LinearRing" => {
-                            elements.push(Kml::LinearRing(self.read_linear_ring(attrs)?))
-                        }
-                        b"Polygon" => elements.push(Kml::Polygon(self.read_polygon(attrs)?)),
-                        b"MultiGeometry" => {
-                            elements.push(Kml::MultiGeometry(self.read_multi_geometry(attrs)?))
-                        }
-                        b"Placemark" => elements.push(Kml::Placemark(self.read_placemark(attrs)?)),
-                        b"Document" => elements.push(Kml::Document {
-                            attrs,
-                            elements: self.read_elements()?,
-                        }),
-                        b"Folder" => elements.push(Kml::Folder {
-                            attrs,
-                            elements: self.read_elements()?,
-                        }),
-                        b"Style" => elements.push(Kml::Style(self.read_style(attrs)?)),
-                        b"StyleMap" => elements.push(Kml::StyleMap(self.read_style_map(attrs)?)),
-                        b"Pair" => elements.push(Kml::Pair(self.read_pair(attrs)?)),
-                        b"BalloonStyle" => {
-                            elements.push(Kml::BalloonStyle(self.read_balloon_style(attrs)?))
-                        }
-                        b"IconStyle" => elements.push(Kml::IconStyle(self.read_icon_style(attrs)?)),
-                        b"Icon" => elements.push(Kml::Icon(self.read_icon()?)),
-                        b"LabelStyle" => {
-                            elements.push(Kml::LabelStyle(self.read_label_style(attrs)?))
-                        }
-                        b"LineStyle" => elements.push(Kml::LineStyle(self.read_line_style(attrs)?)),
-                        b"PolyStyle" => elements.push(Kml::PolyStyle(self.read_poly_style(attrs)?)),
-                        b"ListStyle" => elements.push(Kml::ListStyle(self.read_list_style(attrs)?)),
-                        _ => {
-                            let start = e.to_owned();
-                            elements.push(Kml::Element(self.read_element(&start, attrs)?));
+                    let start = e.to_owned();
+                    let depth = self.depth;
+                    match props.as_deref_mut() {
+                        Some(props)
+                            if FeatureProps::is_field(start.local_name())
+                                && !self.is_foreign(&start) =>
+                        {
+                            let attrs = Self::read_attrs(start.attributes());
+                            let element = self.read_element(&start, attrs)?;
+                            if !props.set_field(&element) {
+                                elements.push(Kml::Element(element));
+                            }
                         }
-                    };
+                        _ => match self.read_kml(&start) {
+                            Ok(kml) => elements.push(kml),
+                            Err(e) => self.skip_element(depth, e)?,
+                        },
+                    }
                 }
                 Event::End(ref mut e) => match e.local_name() {
                     b"Folder" | b"Document" => break,
@@ -184,12 +604,189 @@ where
         Ok(elements)
     }
 
-    fn read_kml_document(&mut self) -> Result<KmlDocument<T>, Error> {
-        // TODO: Should parse version, change version based on NS
-        Ok(KmlDocument {
+    // Returns the error in strict mode, and otherwise records it and skips the rest of the element
+    // that failed, which was opened at the depth
+    fn skip_element(&mut self, depth: usize, e: Error) -> Result<(), Error> {
+        if self.options.mode == ReadMode::Strict
+            || matches!(
+                e.root_cause(),
+                Error::MalformedXml(_) | Error::TagMismatch { .. } | Error::IoError(_)
+            )
+        {
+            return Err(e);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::warn!(error = %e, "skipped element");
+        while self.depth >= depth {
+            if let Event::Eof = next_event!(self) {
+                return Err(e);
+            }
+        }
+        self.warnings.push(e);
+        Ok(())
+    }
+
+    // Whether namespaces are resolved and the element is in one other than those of KML and the
+    // extensions it uses
+    fn is_foreign(&self, start: &BytesStart) -> bool {
+        if !self.options.resolve_namespaces {
+            return false;
+        }
+        let namespace = self.namespaces.resolve(start.name());
+        !(namespace.is_empty()
+            || KmlVersion::from_str(&namespace).is_ok()
+            || [GX_NAMESPACE, ATOM_NAMESPACE, XAL_NAMESPACE].contains(&namespace.as_str()))
+    }
+
+    // Reads the element started by the tag into the matching type
+    fn read_kml(&mut self, start: &BytesStart) -> Result<Kml<T>, Error> {
+        let attrs = Self::read_attrs(start.attributes());
+        if self.is_foreign(start) {
+            return self.read_custom(start, attrs);
+        }
+        let position = self.reader.buffer_position();
+        let tag = start.local_name().to_vec();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "read_element",
+            element = %String::from_utf8_lossy(&tag),
+            position
+        )
+        .entered();
+        let kml = match tag.as_slice() {
+            b"kml" => self.read_kml_document(attrs).map(Kml::KmlDocument),
+            b"Scale" => self.read_scale(attrs).map(Kml::Scale),
+            b"Orientation" => self.read_orientation(attrs).map(Kml::Orientation),
+            b"Point" => self.read_point(attrs).map(Kml::Point),
+            b"Location" => self.read_location(attrs).map(Kml::Location),
+            b"Model" => self.read_model(attrs).map(Kml::Model),
+            b"LineString" => self.read_line_string(attrs).map(Kml::LineString),
+            b"LinearRing" => self.read_linear_ring(attrs).map(Kml::LinearRing),
+            b"Polygon" => self.read_polygon(attrs).map(Kml::Polygon),
+            b"MultiGeometry" => self.read_multi_geometry(attrs).map(Kml::MultiGeometry),
+            b"Track" => self
+                .read_extension(start, attrs, Track::parse_element)
+                .map(Kml::Track),
+            b"MultiTrack" => self
+                .read_extension(start, attrs, MultiTrack::parse_element)
+                .map(Kml::MultiTrack),
+            b"Placemark" => self.read_placemark(attrs).map(Kml::Placemark),
+            b"Tour" => self
+                .read_extension(start, attrs, Tour::from_element)
+                .map(Kml::Tour),
+            b"TimeStamp" => self.read_typed(start, attrs, TimeStamp::from_element, Kml::TimeStamp),
+            b"TimeSpan" => self.read_typed(start, attrs, TimeSpan::from_element, Kml::TimeSpan),
+            b"NetworkLink" => {
+                self.read_typed_feature(start, attrs, NetworkLink::parse_element, Kml::NetworkLink)
+            }
+            b"Region" => self.read_typed(start, attrs, Region::parse_element, Kml::Region),
+            b"Lod" => self.read_typed(start, attrs, Lod::from_element, Kml::Lod),
+            b"LatLonAltBox" => {
+                self.read_typed(start, attrs, LatLonAltBox::parse_element, Kml::LatLonAltBox)
+            }
+            b"GroundOverlay" => self.read_typed_feature(
+                start,
+                attrs,
+                GroundOverlay::parse_element,
+                Kml::GroundOverlay,
+            ),
+            b"LatLonBox" => self.read_typed(start, attrs, LatLonBox::parse_element, Kml::LatLonBox),
+            b"ScreenOverlay" => self.read_typed_feature(
+                start,
+                attrs,
+                ScreenOverlay::from_element,
+                Kml::ScreenOverlay,
+            ),
+            b"PhotoOverlay" => self.read_typed_feature(
+                start,
+                attrs,
+                PhotoOverlay::parse_element,
+                Kml::PhotoOverlay,
+            ),
+            b"ViewVolume" => {
+                self.read_typed(start, attrs, ViewVolume::from_element, Kml::ViewVolume)
+            }
+            b"Schema" => self.read_typed(start, attrs, Schema::from_element, Kml::Schema),
+            b"Camera" => self.read_typed(start, attrs, Camera::parse_element, Kml::Camera),
+            b"LookAt" => self.read_typed(start, attrs, LookAt::parse_element, Kml::LookAt),
+            b"ImagePyramid" => {
+                self.read_typed(start, attrs, ImagePyramid::from_element, Kml::ImagePyramid)
+            }
+            b"Document" => {
+                let mut props = FeatureProps::default();
+                self.read_container(Some(&mut props))
+                    .map(|elements| Kml::Document {
+                        attrs,
+                        props,
+                        elements,
+                    })
+            }
+            b"Folder" => {
+                let mut props = FeatureProps::default();
+                self.read_container(Some(&mut props))
+                    .map(|elements| Kml::Folder {
+                        attrs,
+                        props,
+                        elements,
+                    })
+            }
+            b"Style" => self.read_style(attrs).map(Kml::Style),
+            b"StyleMap" => self.read_style_map(attrs).map(Kml::StyleMap),
+            b"Pair" => self.read_pair(attrs).map(Kml::Pair),
+            b"BalloonStyle" => self.read_balloon_style(attrs).map(Kml::BalloonStyle),
+            b"IconStyle" => self.read_icon_style(attrs).map(Kml::IconStyle),
+            b"Icon" => self.read_icon(attrs).map(Kml::Icon),
+            b"LabelStyle" => self.read_label_style(attrs).map(Kml::LabelStyle),
+            b"LineStyle" => self.read_line_style(attrs).map(Kml::LineStyle),
+            b"PolyStyle" => self.read_poly_style(attrs).map(Kml::PolyStyle),
+            b"ListStyle" => self.read_list_style(attrs).map(Kml::ListStyle),
+            _ => self.read_custom(start, attrs),
+        };
+        kml.map_err(|err| err.in_element(&tag, position))
+    }
+
+    // Reads a child element, adding the element and the position after its start tag to any error
+    fn read_in<R>(
+        &mut self,
+        tag: &[u8],
+        read: impl FnOnce(&mut Self) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let position = self.reader.buffer_position();
+        read(self).map_err(|e| e.in_element(tag, position))
+    }
+
+    // Reads an element, converting it with a registered parser if there is one
+    fn read_custom(
+        &mut self,
+        start: &BytesStart,
+        attrs: HashMap<String, String>,
+    ) -> Result<Kml<T>, Error> {
+        // Resolved before reading since the element's own declarations are in scope until its end
+        let parser = if self.parsers.is_empty() {
+            None
+        } else {
+            let namespace = self.namespaces.resolve(start.name());
+            let local_name = String::from_utf8_lossy(start.local_name()).to_string();
+            self.parsers.get(&(namespace, local_name)).copied()
+        };
+        let element = self.read_element(start, attrs)?;
+        Ok(match parser.and_then(|parse| parse(&element)) {
+            Some(custom) => Kml::Custom(custom),
+            None => Kml::Element(element),
+        })
+    }
+
+    fn read_kml_document(
+        &mut self,
+        attrs: HashMap<String, String>,
+    ) -> Result<KmlDocument<T>, Error> {
+        let mut document = KmlDocument {
+            attrs,
             elements: self.read_elements()?,
             ..Default::default()
-        })
+        };
+        document.version = document.declared_version();
+        Ok(document)
     }
 
     fn read_scale(&mut self, attrs: HashMap<String, String>) -> Result<Scale<T>, Error> {
@@ -198,7 +795,7 @@ where
         let mut z = One::one();
 
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
                     b"x" => x = self.read_float()?,
@@ -226,7 +823,7 @@ where
         let mut heading = Zero::zero();
 
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
                     b"roll" => roll = self.read_float()?,
@@ -266,7 +863,7 @@ where
         let mut altitude = Zero::zero();
 
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
                     b"longitude" => longitude = self.read_float()?,
@@ -290,6 +887,46 @@ where
         })
     }
 
+    fn read_model(&mut self, attrs: HashMap<String, String>) -> Result<Model<T>, Error> {
+        let mut model = Model {
+            attrs,
+            ..Default::default()
+        };
+        loop {
+            let e = next_event!(self);
+            match e {
+                Event::Start(ref e) => {
+                    let start = e.to_owned();
+                    let attrs = Self::read_attrs(start.attributes());
+                    match start.local_name() {
+                        b"altitudeMode" => {
+                            model.altitude_mode = types::AltitudeMode::from_str(&self.read_str()?)?
+                        }
+                        b"Location" => model.location = Some(self.read_location(attrs)?),
+                        b"Orientation" => model.orientation = Some(self.read_orientation(attrs)?),
+                        b"Scale" => model.scale = Some(self.read_scale(attrs)?),
+                        b"Link" | b"Url" => {
+                            model.link =
+                                Some(self.read_extension(&start, attrs, Link::from_element)?)
+                        }
+                        b"ResourceMap" => {
+                            model.resource_map = Some(self.read_extension(
+                                &start,
+                                attrs,
+                                ResourceMap::from_element,
+                            )?)
+                        }
+                        _ => model.children.push(self.read_element(&start, attrs)?),
+                    }
+                }
+                Event::End(ref e) if e.local_name() == b"Model" => break,
+                Event::Eof => return Err(Error::InvalidInput),
+                _ => {}
+            }
+        }
+        Ok(model)
+    }
+
     fn read_line_string(&mut self, attrs: HashMap<String, String>) -> Result<LineString<T>, Error> {
         let props = self.read_geom_props(b"LineString")?;
         Ok(LineString {
@@ -303,13 +940,17 @@ where
 
     fn read_linear_ring(&mut self, attrs: HashMap<String, String>) -> Result<LinearRing<T>, Error> {
         let props = self.read_geom_props(b"LinearRing")?;
-        Ok(LinearRing {
+        let mut linear_ring = LinearRing {
             coords: props.coords,
             altitude_mode: props.altitude_mode,
             extrude: props.extrude,
             tessellate: props.tessellate,
             attrs,
-        })
+        };
+        if self.options.close_rings {
+            linear_ring.close();
+        }
+        Ok(linear_ring)
     }
 
     fn read_polygon(&mut self, attrs: HashMap<String, String>) -> Result<Polygon<T>, Error> {
@@ -320,7 +961,7 @@ where
         let mut tessellate = false;
 
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
                     b"outerBoundaryIs" => {
@@ -364,22 +1005,15 @@ where
     ) -> Result<MultiGeometry<T>, Error> {
         let mut geometries: Vec<Geometry<T>> = Vec::new();
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref e) => {
-                    let attrs = Self::read_attrs(e.attributes());
-                    match e.local_name() {
-                        b"Point" => geometries.push(Geometry::Point(self.read_point(attrs)?)),
-                        b"LineString" => {
-                            geometries.push(Geometry::LineString(self.read_line_string(attrs)?))
-                        }
-                        b"LinearRing" => {
-                            geometries.push(Geometry::LinearRing(self.read_linear_ring(attrs)?))
-                        }
-                        b"Polygon" => geometries.push(Geometry::Polygon(self.read_polygon(attrs)?)),
-                        b"MultiGeometry" => geometries
-                            .push(Geometry::MultiGeometry(self.read_multi_geometry(attrs)?)),
-                        _ => {}
+                    let start = e.to_owned();
+                    let attrs = Self::read_attrs(start.attributes());
+                    let position = self.reader.buffer_position();
+                    let tag = start.local_name().to_vec();
+                    if let Some(geometry) = self.read_geometry(&start, attrs) {
+                        geometries.push(geometry.map_err(|err| err.in_element(&tag, position))?);
                     }
                 }
                 Event::End(ref mut e) => {
@@ -393,31 +1027,149 @@ where
         Ok(MultiGeometry { geometries, attrs })
     }
 
+    // Reads the geometry for the tag, or returns `None` if the tag isn't a geometry
+    fn read_geometry(
+        &mut self,
+        start: &BytesStart,
+        attrs: HashMap<String, String>,
+    ) -> Option<Result<Geometry<T>, Error>> {
+        Some(match start.local_name() {
+            b"Point" => self.read_point(attrs).map(Geometry::Point),
+            b"LineString" => self.read_line_string(attrs).map(Geometry::LineString),
+            b"LinearRing" => self.read_linear_ring(attrs).map(Geometry::LinearRing),
+            b"Polygon" => self.read_polygon(attrs).map(Geometry::Polygon),
+            b"MultiGeometry" => self.read_multi_geometry(attrs).map(Geometry::MultiGeometry),
+            b"Track" => self
+                .read_extension(start, attrs, Track::parse_element)
+                .map(Geometry::Track),
+            b"MultiTrack" => self
+                .read_extension(start, attrs, MultiTrack::parse_element)
+                .map(Geometry::MultiTrack),
+            b"Model" => self.read_model(attrs).map(Geometry::Model),
+            _ => return None,
+        })
+    }
+
+    // Reads an extension element like `gx:Track` that has a type, which fails if the element
+    // can't be converted to it
+    fn read_extension<R>(
+        &mut self,
+        start: &BytesStart,
+        attrs: HashMap<String, String>,
+        parse: fn(&Element) -> Option<R>,
+    ) -> Result<R, Error> {
+        let element = self.read_element(start, attrs)?;
+        parse(&element).ok_or(Error::InvalidKmlElement(element.name))
+    }
+
+    // Reads an element that has a type, keeping it as an element if it can't be converted so it
+    // can still be validated and written, and recording the error
+    fn read_typed<R>(
+        &mut self,
+        start: &BytesStart,
+        attrs: HashMap<String, String>,
+        parse: fn(&Element) -> Option<R>,
+        variant: fn(R) -> Kml<T>,
+    ) -> Result<Kml<T>, Error> {
+        let position = self.reader.buffer_position();
+        let element = self.read_element(start, attrs)?;
+        Ok(match parse(&element) {
+            Some(r) => variant(r),
+            None => self.keep_element(start, position, element),
+        })
+    }
+
+    // Reads a feature that has a type, like `kml:NetworkLink`, which fails in strict mode if the
+    // element can't be converted, and otherwise records the error and keeps it as an element
+    fn read_typed_feature<R>(
+        &mut self,
+        start: &BytesStart,
+        attrs: HashMap<String, String>,
+        parse: fn(&Element) -> Option<R>,
+        variant: fn(R) -> Kml<T>,
+    ) -> Result<Kml<T>, Error> {
+        let position = self.reader.buffer_position();
+        let element = self.read_element(start, attrs)?;
+        if let Some(r) = parse(&element) {
+            return Ok(variant(r));
+        }
+        if self.options.mode == ReadMode::Strict {
+            return Err(
+                Error::InvalidKmlElement(element.name).in_element(start.local_name(), position)
+            );
+        }
+        Ok(self.keep_element(start, position, element))
+    }
+
+    // Records the error for an element that couldn't be converted to its type, and keeps it as an
+    // element
+    fn keep_element(&mut self, start: &BytesStart, position: usize, element: Element) -> Kml<T> {
+        let e =
+            Error::InvalidKmlElement(element.name.clone()).in_element(start.local_name(), position);
+        #[cfg(feature = "tracing")]
+        tracing::warn!(error = %e, "kept element");
+        self.warnings.push(e);
+        Kml::Element(element)
+    }
+
     fn read_placemark(&mut self, attrs: HashMap<String, String>) -> Result<Placemark<T>, Error> {
-        let mut name: Option<String> = None;
-        let mut description: Option<String> = None;
+        let mut props = FeatureProps::default();
+        let mut view: Option<AbstractView<T>> = None;
+        let mut time_primitive: Option<TimePrimitive> = None;
+        let mut style: Option<Style> = None;
+        let mut extended_data: Option<ExtendedData> = None;
         let mut geometry: Option<Geometry<T>> = None;
         let mut children: Vec<Element> = Vec::new();
 
         loop {
-            let e = self.reader.read_event(&mut self.buf)?;
+            let e = next_event!(self);
             match e {
                 Event::Start(ref e) => {
                     let attrs = Self::read_attrs(e.attributes());
                     match e.local_name() {
-                        b"name" => name = Some(self.read_str()?),
-                        b"description" => description = Some(self.read_str()?),
-                        b"Point" => geometry = Some(Geometry::Point(self.read_point(attrs)?)),
-                        b"LineString" => {
-                            geometry = Some(Geometry::LineString(self.read_line_string(attrs)?))
+                        tag if FeatureProps::is_field(tag) => {
+                            let start = e.to_owned();
+                            let element = self.read_element(&start, attrs)?;
+                            if !props.set_field(&element) {
+                                children.push(element);
+                            }
                         }
-                        b"LinearRing" => {
-                            geometry = Some(Geometry::LinearRing(self.read_linear_ring(attrs)?))
+                        b"Camera" | b"LookAt" if view.is_none() => {
+                            let start = e.to_owned();
+                            let element = self.read_element(&start, attrs)?;
+                            match AbstractView::parse_element(&element) {
+                                Some(v) => view = Some(v),
+                                None => children.push(element),
+                            }
                         }
-                        b"Polygon" => geometry = Some(Geometry::Polygon(self.read_polygon(attrs)?)),
-                        b"MultiGeometry" => {
-                            geometry =
-                                Some(Geometry::MultiGeometry(self.read_multi_geometry(attrs)?))
+                        // Invalid time primitives are kept as elements so they can be validated
+                        b"TimeStamp" | b"TimeSpan" if time_primitive.is_none() => {
+                            let start = e.to_owned();
+                            let element = self.read_element(&start, attrs)?;
+                            match TimePrimitive::from_element(&element) {
+                                Some(t) => time_primitive = Some(t),
+                                None => children.push(element),
+                            }
+                        }
+                        b"Style" if style.is_none() => {
+                            style = Some(self.read_in(b"Style", |r| r.read_style(attrs))?)
+                        }
+                        b"ExtendedData" if extended_data.is_none() => {
+                            let start = e.to_owned();
+                            let element = self.read_element(&start, attrs)?;
+                            match ExtendedData::from_element(&element) {
+                                Some(d) => extended_data = Some(d),
+                                None => children.push(element),
+                            }
+                        }
+                        b"Point" | b"LineString" | b"LinearRing" | b"Polygon"
+                        | b"MultiGeometry" | b"Track" | b"MultiTrack" | b"Model" => {
+                            let position = self.reader.buffer_position();
+                            let start = e.to_owned();
+                            let tag = start.local_name().to_vec();
+                            if let Some(g) = self.read_geometry(&start, attrs) {
+                                geometry = Some(g.map_err(|err| err.in_element(&tag, position))?);
+                            }
                         }
                         _ => {
                             let start = e.to_owned();
@@ -426,40 +1178,81 @@ where
                         }
                     }
                 }
-                Event::End(ref e) => {
-                    if e.local_name() == b"Placemark" {
-                        break;
-                    }
-                }
+                Event::End(ref e) if e.local_name() == b"Placemark" => break,
+                Event::Eof => return Err(Error::InvalidInput),
                 _ => {}
             }
         }
+        let FeatureProps {
+            name,
+            visibility,
+            open,
+            author,
+            link,
+            address,
+            phone_number,
+            snippet,
+            description,
+            style_url,
+        } = props;
         Ok(Placemark {
             name,
+            visibility,
+            open,
+            author,
+            link,
+            address,
+            phone_number,
+            snippet,
             description,
+            view,
+            time_primitive,
+            style_url,
+            style,
+            extended_data,
             geometry,
             attrs,
             children,
         })
     }
 
-    fn read_style(&mut self, attrs: HashMap<String, String>) -> Result<Style, Error> {
-        let mut style = Style::default();
-        if let Some(id_str) = attrs.get("id") {
-            style.id = id_str.to_string();
-        }
+    fn read_style(&mut self, mut attrs: HashMap<String, String>) -> Result<Style, Error> {
+        let mut style = Style {
+            id: attrs.remove("id").unwrap_or_default(),
+            attrs,
+            ..Default::default()
+        };
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => {
                     let attrs = Self::read_attrs(e.attributes());
                     match e.local_name() {
-                        b"BalloonStyle" => style.balloon = Some(self.read_balloon_style(attrs)?),
-                        b"IconStyle" => style.icon = Some(self.read_icon_style(attrs)?),
-                        b"LabelStyle" => style.label = Some(self.read_label_style(attrs)?),
-                        b"LineStyle" => style.line = Some(self.read_line_style(attrs)?),
-                        b"PolyStyle" => style.poly = Some(self.read_poly_style(attrs)?),
-                        b"ListStyle" => style.list = Some(self.read_list_style(attrs)?),
+                        b"BalloonStyle" => {
+                            style.balloon = Some(
+                                self.read_in(b"BalloonStyle", |r| r.read_balloon_style(attrs))?,
+                            )
+                        }
+                        b"IconStyle" => {
+                            style.icon =
+                                Some(self.read_in(b"IconStyle", |r| r.read_icon_style(attrs))?)
+                        }
+                        b"LabelStyle" => {
+                            style.label =
+                                Some(self.read_in(b"LabelStyle", |r| r.read_label_style(attrs))?)
+                        }
+                        b"LineStyle" => {
+                            style.line =
+                                Some(self.read_in(b"LineStyle", |r| r.read_line_style(attrs))?)
+                        }
+                        b"PolyStyle" => {
+                            style.poly =
+                                Some(self.read_in(b"PolyStyle", |r| r.read_poly_style(attrs))?)
+                        }
+                        b"ListStyle" => {
+                            style.list =
+                                Some(self.read_in(b"ListStyle", |r| r.read_list_style(attrs))?)
+                        }
                         _ => {}
                     }
                 }
@@ -474,18 +1267,21 @@ where
         Ok(style)
     }
 
-    fn read_style_map(&mut self, attrs: HashMap<String, String>) -> Result<StyleMap, Error> {
-        let mut style_map = StyleMap::default();
-        if let Some(id_str) = attrs.get("id") {
-            style_map.id = id_str.to_string();
-        }
+    fn read_style_map(&mut self, mut attrs: HashMap<String, String>) -> Result<StyleMap, Error> {
+        let mut style_map = StyleMap {
+            id: attrs.remove("id").unwrap_or_default(),
+            attrs,
+            ..Default::default()
+        };
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => {
                     if e.local_name() == b"Pair" {
                         let pair_attrs = Self::read_attrs(e.attributes());
-                        style_map.pairs.push(self.read_pair(pair_attrs)?);
+                        style_map
+                            .pairs
+                            .push(self.read_in(b"Pair", |r| r.read_pair(pair_attrs))?);
                     }
                 }
                 Event::End(ref mut e) => {
@@ -506,7 +1302,7 @@ where
         };
 
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
                     b"key" => pair.key = self.read_str()?,
@@ -524,13 +1320,14 @@ where
         Ok(pair)
     }
 
-    fn read_icon_style(&mut self, attrs: HashMap<String, String>) -> Result<IconStyle, Error> {
-        let mut icon_style = IconStyle::default();
-        if let Some(id_str) = attrs.get("id") {
-            icon_style.id = id_str.to_string();
-        }
+    fn read_icon_style(&mut self, mut attrs: HashMap<String, String>) -> Result<IconStyle, Error> {
+        let mut icon_style = IconStyle {
+            id: attrs.remove("id").unwrap_or_default(),
+            attrs,
+            ..Default::default()
+        };
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
                     b"scale" => icon_style.scale = self.read_float()?,
@@ -560,8 +1357,11 @@ where
                             });
                         }
                     }
-                    b"Icon" => icon_style.icon = self.read_icon()?,
-                    b"color" => icon_style.color = self.read_str()?,
+                    b"Icon" => {
+                        let attrs = Self::read_attrs(e.attributes());
+                        icon_style.icon = self.read_icon(attrs)?
+                    }
+                    b"color" => icon_style.color = self.read_color(b"color")?,
                     b"colorMode" => {
                         icon_style.color_mode = self.read_str()?.parse::<ColorMode>()?
                     }
@@ -578,10 +1378,10 @@ where
         Ok(icon_style)
     }
 
-    fn read_icon(&mut self) -> Result<Icon, Error> {
+    fn read_icon(&mut self, attrs: HashMap<String, String>) -> Result<Icon, Error> {
         let mut href = String::new();
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => {
                     if e.local_name() == b"href" {
@@ -596,23 +1396,24 @@ where
                 _ => break,
             }
         }
-        Ok(Icon { href })
+        Ok(Icon { href, attrs })
     }
 
     fn read_balloon_style(
         &mut self,
-        attrs: HashMap<String, String>,
+        mut attrs: HashMap<String, String>,
     ) -> Result<BalloonStyle, Error> {
-        let mut balloon_style = BalloonStyle::default();
-        if let Some(id_str) = attrs.get("id") {
-            balloon_style.id = id_str.to_string();
-        }
+        let mut balloon_style = BalloonStyle {
+            id: attrs.remove("id").unwrap_or_default(),
+            attrs,
+            ..Default::default()
+        };
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
-                    b"bgColor" => balloon_style.bg_color = Some(self.read_str()?),
-                    b"textColor" => balloon_style.text_color = self.read_str()?,
+                    b"bgColor" => balloon_style.bg_color = Some(self.read_color(b"bgColor")?),
+                    b"textColor" => balloon_style.text_color = self.read_color(b"textColor")?,
                     b"text" => balloon_style.text = Some(self.read_str()?),
                     b"displayMode" => balloon_style.display = self.read_str()? != "hide",
                     _ => {}
@@ -628,16 +1429,20 @@ where
         Ok(balloon_style)
     }
 
-    fn read_label_style(&mut self, attrs: HashMap<String, String>) -> Result<LabelStyle, Error> {
-        let mut label_style = LabelStyle::default();
-        if let Some(id_str) = attrs.get("id") {
-            label_style.id = id_str.to_string();
-        }
+    fn read_label_style(
+        &mut self,
+        mut attrs: HashMap<String, String>,
+    ) -> Result<LabelStyle, Error> {
+        let mut label_style = LabelStyle {
+            id: attrs.remove("id").unwrap_or_default(),
+            attrs,
+            ..Default::default()
+        };
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
-                    b"color" => label_style.color = self.read_str()?,
+                    b"color" => label_style.color = self.read_color(b"color")?,
                     b"colorMode" => {
                         label_style.color_mode = self.read_str()?.parse::<ColorMode>()?;
                     }
@@ -655,16 +1460,17 @@ where
         Ok(label_style)
     }
 
-    fn read_line_style(&mut self, attrs: HashMap<String, String>) -> Result<LineStyle, Error> {
-        let mut line_style = LineStyle::default();
-        if let Some(id_str) = attrs.get("id") {
-            line_style.id = id_str.to_string();
-        }
+    fn read_line_style(&mut self, mut attrs: HashMap<String, String>) -> Result<LineStyle, Error> {
+        let mut line_style = LineStyle {
+            id: attrs.remove("id").unwrap_or_default(),
+            attrs,
+            ..Default::default()
+        };
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
-                    b"color" => line_style.color = self.read_str()?,
+                    b"color" => line_style.color = self.read_color(b"color")?,
                     b"colorMode" => {
                         line_style.color_mode = self.read_str()?.parse::<ColorMode>()?;
                     }
@@ -682,16 +1488,17 @@ where
         Ok(line_style)
     }
 
-    fn read_list_style(&mut self, attrs: HashMap<String, String>) -> Result<ListStyle, Error> {
-        let mut list_style = ListStyle::default();
-        if let Some(id_str) = attrs.get("id") {
-            list_style.id = id_str.to_string();
-        }
+    fn read_list_style(&mut self, mut attrs: HashMap<String, String>) -> Result<ListStyle, Error> {
+        let mut list_style = ListStyle {
+            id: attrs.remove("id").unwrap_or_default(),
+            attrs,
+            ..Default::default()
+        };
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
-                    b"bgColor" => list_style.bg_color = self.read_str()?,
+                    b"bgColor" => list_style.bg_color = self.read_color(b"bgColor")?,
                     b"maxSnippetLines" => {
                         let line_str = self.read_str()?;
                         list_style.max_snippet_lines = line_str
@@ -711,16 +1518,17 @@ where
         Ok(list_style)
     }
 
-    fn read_poly_style(&mut self, attrs: HashMap<String, String>) -> Result<PolyStyle, Error> {
-        let mut poly_style = PolyStyle::default();
-        if let Some(id_str) = attrs.get("id") {
-            poly_style.id = id_str.to_string();
-        }
+    fn read_poly_style(&mut self, mut attrs: HashMap<String, String>) -> Result<PolyStyle, Error> {
+        let mut poly_style = PolyStyle {
+            id: attrs.remove("id").unwrap_or_default(),
+            attrs,
+            ..Default::default()
+        };
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
-                    b"color" => poly_style.color = self.read_str()?,
+                    b"color" => poly_style.color = self.read_color(b"color")?,
                     b"colorMode" => {
                         poly_style.color_mode = self.read_str()?.parse::<ColorMode>()?;
                     }
@@ -752,10 +1560,15 @@ where
     ) -> Result<Element, Error> {
         let mut element = Element::default();
         let tag = start.local_name();
-        element.name = str::from_utf8(tag).unwrap().to_string();
+        let name = if self.options.lossless {
+            start.name()
+        } else {
+            tag
+        };
+        element.name = str::from_utf8(name).unwrap().to_string();
         element.attrs = attrs;
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(e) => {
                     let start = e.to_owned();
@@ -764,7 +1577,7 @@ where
                         .children
                         .push(self.read_element(&start, start_attrs)?);
                 }
-                Event::Text(ref mut e) => {
+                Event::Text(ref mut e) | Event::CData(ref mut e) => {
                     element.content = Some(
                         e.unescape_and_decode(&self.reader)
                             .unwrap_or_else(|_| String::from_utf8_lossy(e.escaped()).to_string()),
@@ -775,6 +1588,7 @@ where
                         break;
                     }
                 }
+                Event::Comment(_) | Event::PI(_) => {}
                 _ => break,
             }
         }
@@ -784,12 +1598,12 @@ where
     fn read_boundary(&mut self, end_tag: &[u8]) -> Result<Vec<LinearRing<T>>, Error> {
         let mut boundary: Vec<LinearRing<T>> = Vec::new();
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => {
                     let attrs = Self::read_attrs(e.attributes());
                     if e.local_name() == b"LinearRing" {
-                        boundary.push(self.read_linear_ring(attrs)?);
+                        boundary.push(self.read_in(b"LinearRing", |r| r.read_linear_ring(attrs))?);
                     }
                 }
                 Event::End(ref mut e) => {
@@ -803,18 +1617,18 @@ where
         Ok(boundary)
     }
 
-    fn read_geom_props(&mut self, end_tag: &[u8]) -> Result<GeomProps<T>, Error> {
+    fn read_geom_props(&mut self, end_tag: &[u8]) -> Result<GeomProps<Vec<Coord<T>>>, Error> {
         let mut coords: Vec<Coord<T>> = Vec::new();
         let mut altitude_mode = types::AltitudeMode::default();
         let mut extrude = false;
         let mut tessellate = false;
 
         loop {
-            let mut e = self.reader.read_event(&mut self.buf)?;
+            let mut e = next_event!(self);
             match e {
                 Event::Start(ref mut e) => match e.local_name() {
                     b"coordinates" => {
-                        coords = coords_from_str(&self.read_str()?)?;
+                        coords = self.read_coords()?;
                     }
                     b"altitudeMode" => {
                         altitude_mode = types::AltitudeMode::from_str(&self.read_str()?)?
@@ -823,11 +1637,8 @@ where
                     b"tessellate" => tessellate = self.read_str()? == "1",
                     _ => {}
                 },
-                Event::End(ref mut e) => {
-                    if e.local_name() == end_tag {
-                        break;
-                    }
-                }
+                Event::End(ref mut e) if e.local_name() == end_tag => break,
+                Event::Eof => return Err(Error::InvalidInput),
                 _ => {}
             }
         }
@@ -845,6 +1656,40 @@ where
         }
     }
 
+    fn read_coords(&mut self) -> Result<Vec<Coord<T>>, Error> {
+        // Position of the start of the coordinates element's content
+        let position = self.reader.buffer_position();
+        let mut coords = Vec::new();
+        for tuple in split_tuples(&self.read_str()?) {
+            match Coord::from_str(tuple) {
+                Ok(coord) => coords.push(coord),
+                Err(e @ Error::InvalidCoordArity { .. }) => {
+                    let e = e.in_element(b"coordinates", position);
+                    if self.options.mode == ReadMode::Strict {
+                        return Err(e);
+                    }
+                    self.warnings.push(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(coords)
+    }
+
+    // Colors in the `rrggbb` order of HTML are converted in either mode, recording the error
+    fn read_color(&mut self, tag: &[u8]) -> Result<Color, Error> {
+        let position = self.reader.buffer_position();
+        let color_str = self.read_str()?;
+        color_str.parse().or_else(|e: Error| {
+            let color = Color::parse_normalized(&color_str)?;
+            let e = e.in_element(tag, position);
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %e, "converted color");
+            self.warnings.push(e);
+            Ok(color)
+        })
+    }
+
     fn read_float<F: Float + FromStr>(&mut self) -> Result<F, Error> {
         let float_str = self.read_str()?;
         float_str
@@ -853,7 +1698,7 @@ where
     }
 
     fn read_str(&mut self) -> Result<String, Error> {
-        let e = self.reader.read_event(&mut self.buf)?;
+        let e = next_event!(self);
         match e {
             Event::Text(e) | Event::CData(e) => Ok(e
                 .unescape_and_decode(&self.reader)
@@ -867,15 +1712,40 @@ where
         attrs
             .filter_map(Result::ok)
             .map(|a| {
+                // Values are unescaped since the writer escapes them again
+                let value = a.unescaped_value().unwrap_or_else(|_| a.value.clone());
                 (
                     str::from_utf8(a.key).unwrap().to_string(),
-                    str::from_utf8(&a.value).unwrap().to_string(),
+                    String::from_utf8_lossy(&value).to_string(),
                 )
             })
             .collect()
     }
 }
 
+/// Iterator over the features of a document read one at a time, returned by
+/// [`KmlReader::features`](struct.KmlReader.html#method.features)
+pub struct Features<'a, B: BufRead, T: CoordType + FromStr + Default = f64> {
+    reader: &'a mut KmlReader<B, T>,
+    done: bool,
+}
+
+impl<'a, B: BufRead, T> Iterator for Features<'a, B, T>
+where
+    T: CoordType + FromStr + Default,
+{
+    type Item = Result<Kml<T>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let feature = self.reader.read_next_feature().transpose();
+        self.done = !matches!(feature, Some(Ok(_)));
+        feature
+    }
+}
+
 impl<T> FromStr for Kml<T>
 where
     T: CoordType + FromStr + Default,
@@ -887,9 +1757,708 @@ where
     }
 }
 
+// Implements `FromStr` for types with a `Kml` variant, returning `Error::UnexpectedKml` if the root
+// element is a different type. Types without coordinates are read with `f64` coordinates since
+// the type is unused.
+macro_rules! impl_from_str {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl FromStr for $ty {
+                type Err = Error;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    $ty::try_from(KmlReader::<&[u8], f64>::from_string(s).read()?)
+                }
+            }
+        )*
+    };
+    ($($ty:ident<T>),* $(,)?) => {
+        $(
+            impl<T> FromStr for $ty<T>
+            where
+                T: CoordType + FromStr + Default,
+            {
+                type Err = Error;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    $ty::try_from(KmlReader::<&[u8], T>::from_string(s).read()?)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_str!(
+    KmlDocument<T>,
+    Scale<T>,
+    Orientation<T>,
+    Point<T>,
+    Location<T>,
+    LineString<T>,
+    LinearRing<T>,
+    Polygon<T>,
+    MultiGeometry<T>,
+    Placemark<T>,
+);
+
+impl_from_str!(
+    Style,
+    StyleMap,
+    Pair,
+    BalloonStyle,
+    IconStyle,
+    Icon,
+    LabelStyle,
+    LineStyle,
+    PolyStyle,
+    ListStyle,
+    Element,
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{elements, parse_round_trip, variant};
+    use crate::types::{KmlDateTime, Snippet};
+    use std::error::Error as _;
+
+    #[derive(Debug, PartialEq)]
+    struct Speed(f64);
+
+    impl CustomElement for Speed {
+        fn from_element(element: &Element) -> Option<Self> {
+            element.content.as_deref()?.parse().ok().map(Speed)
+        }
+
+        fn to_element(&self) -> Element {
+            Element::new("speed").with_content(self.0.to_string())
+        }
+    }
+
+    #[test]
+    fn test_parse_registered_elements() {
+        let kml_str = r#"<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:a="urn:a">
+          <a:speed>1.5</a:speed>
+          <Folder xmlns:a="urn:other">
+            <a:speed>2</a:speed>
+            <speed xmlns="urn:a">3</speed>
+            <a:speed xmlns:a="urn:a">fast</a:speed>
+          </Folder>
+          <speed>4</speed>
+        </kml>"#;
+        let kml: Kml = KmlReader::from_string(kml_str)
+            .register::<Speed>("urn:a", "speed")
+            .read()
+            .unwrap();
+        let elements = match kml {
+            Kml::KmlDocument(d) => d.elements,
+            k => panic!("unexpected {:?}", k),
+        };
+        let speed = |k: &Kml| match k {
+            Kml::Custom(c) => c.downcast_ref::<Speed>().map(|s| s.0),
+            _ => None,
+        };
+        assert_eq!(speed(&elements[0]), Some(1.5));
+        assert_eq!(speed(&elements[2]), None);
+        let folder = match &elements[1] {
+            Kml::Folder { elements, .. } => elements,
+            k => panic!("unexpected {:?}", k),
+        };
+        assert_eq!(
+            folder.iter().map(speed).collect::<Vec<_>>(),
+            vec![None, Some(3.), None]
+        );
+        assert!(matches!(&folder[2], Kml::Element(e) if e.content.as_deref() == Some("fast")));
+        assert_eq!(elements[0].to_string(), "<speed>1.5</speed>");
+    }
+
+    #[test]
+    fn test_read_features() {
+        let kml_str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <kml xmlns="http://www.opengis.net/kml/2.2">
+          <Document>
+            <name>Doc</name>
+            <Folder>
+              <Placemark><name>One</name></Placemark>
+              <Folder><open>1</open><Placemark><name>Two</name></Placemark></Folder>
+            </Folder>
+            <Placemark><Point><coordinates>1,1</coordinates></Point></Placemark>
+            <Placemark><Point><coordinates>1</coordinates></Point></Placemark>
+            <Placemark><name>Unreached</name></Placemark>
+          </Document>
+        </kml>"#;
+
+        let mut reader = KmlReader::<_, f64>::from_string(kml_str);
+        let features: Vec<Result<Kml, Error>> = reader.features().collect();
+        assert_eq!(features.len(), 4);
+        assert!(matches!(&features[0], Ok(Kml::Placemark(p)) if p.name.as_deref() == Some("One")));
+        assert!(matches!(&features[1], Ok(Kml::Placemark(p)) if p.name.as_deref() == Some("Two")));
+        assert!(matches!(&features[3], Err(Error::InElement { .. })));
+
+        let mut reader = KmlReader::<_, f64>::from_string(kml_str);
+        let names: Vec<Option<String>> = reader
+            .placemarks()
+            .take(3)
+            .map(|p| p.unwrap().name)
+            .collect();
+        assert_eq!(
+            names,
+            vec![Some("One".to_string()), Some("Two".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn test_read_time_primitives() {
+        let kml_str = r#"<Document>
+          <TimeSpan><begin>2020-01</begin></TimeSpan>
+          <Placemark>
+            <name>Trip</name>
+            <TimeStamp id="ts"><when>2020-01-02T03:04:05Z</when></TimeStamp>
+          </Placemark>
+        </Document>"#;
+        let kml = parse_round_trip(kml_str);
+        let elements = elements(&kml);
+        assert_eq!(
+            elements[0],
+            Kml::TimeSpan(
+                types::TimeSpan::default().with_begin(KmlDateTime::YearMonth {
+                    year: 2020,
+                    month: 1
+                })
+            )
+        );
+        let placemark: &Placemark = variant(&elements[1]);
+        match &placemark.time_primitive {
+            Some(TimePrimitive::TimeStamp(t)) => {
+                assert_eq!(t.attrs.get("id").map(String::as_str), Some("ts"));
+                assert_eq!(t.when.unwrap().to_string(), "2020-01-02T03:04:05Z");
+            }
+            t => panic!("expected TimeStamp, found {:?}", t),
+        }
+        assert!(placemark.children.is_empty());
+    }
+
+    #[test]
+    fn test_read_network_link() {
+        let kml_str = r#"<NetworkLink id="tile">
+          <name>0/0/0</name>
+          <styleUrl>#tiles</styleUrl>
+          <Region>
+            <LatLonAltBox>
+              <north>90</north><south>-90</south><east>180</east><west>-180</west>
+            </LatLonAltBox>
+            <Lod><minLodPixels>128</minLodPixels></Lod>
+          </Region>
+          <Link>
+            <href>tiles/0/0/0.kml</href>
+            <viewRefreshMode>onRegion</viewRefreshMode>
+          </Link>
+        </NetworkLink>"#;
+        let kml = parse_round_trip(kml_str);
+        let network_link: &types::NetworkLink = variant(&kml);
+        assert_eq!(network_link.name.as_deref(), Some("0/0/0"));
+        assert_eq!(network_link.children.len(), 1);
+        let region = network_link.region.as_ref().unwrap();
+        assert_eq!(
+            region.lat_lon_alt_box,
+            types::LatLonAltBox::new(90., -90., 180., -180.)
+        );
+        assert_eq!(region.lod, Some(types::Lod::new(128., -1.)));
+        assert_eq!(
+            network_link.link,
+            Some(
+                types::Link::new("tiles/0/0/0.kml")
+                    .with_view_refresh_mode(types::ViewRefreshMode::OnRegion)
+            )
+        );
+    }
+
+    #[test]
+    fn test_read_invalid_typed_elements() {
+        let kml_str = r#"<Folder>
+          <Region><LatLonAltBox><north>north</north></LatLonAltBox></Region>
+          <TimeStamp><when>yesterday</when></TimeStamp>
+        </Folder>"#;
+        let mut r = KmlReader::<_, f64>::from_string(kml_str);
+        let kml = r.read().unwrap();
+        let elements = elements(&kml);
+        assert!(matches!(&elements[0], Kml::Element(e) if e.name == "Region"));
+        assert!(matches!(&elements[1], Kml::Element(e) if e.name == "TimeStamp"));
+        assert_eq!(r.warnings().len(), 2);
+        assert_eq!(
+            r.warnings()[0].element().map(|(name, _)| name),
+            Some("Region")
+        );
+        assert!(matches!(
+            r.warnings()[1].root_cause(),
+            Error::InvalidKmlElement(name) if name == "TimeStamp"
+        ));
+    }
+
+    #[test]
+    fn test_read_overlays() {
+        let kml_str = r#"<Folder>
+          <GroundOverlay>
+            <name>Map</name>
+            <description>Scanned map</description>
+            <color>7fffffff</color>
+            <drawOrder>1</drawOrder>
+            <Icon><href>files/map.png</href></Icon>
+            <LatLonBox>
+              <north>40</north><south>39</south><east>-104</east><west>-105</west>
+              <rotation>12.5</rotation>
+            </LatLonBox>
+          </GroundOverlay>
+          <ScreenOverlay>
+            <Icon><href>legend.png</href></Icon>
+            <overlayXY x="1" y="0" xunits="fraction" yunits="fraction"/>
+          </ScreenOverlay>
+        </Folder>"#;
+        let kml = parse_round_trip(kml_str);
+        let elements = elements(&kml);
+        let ground_overlay: &types::GroundOverlay = variant(&elements[0]);
+        assert_eq!(ground_overlay.name.as_deref(), Some("Map"));
+        assert_eq!(ground_overlay.color, Some(Color::WHITE.with_alpha(0x7f)));
+        assert_eq!(ground_overlay.draw_order, 1);
+        assert_eq!(ground_overlay.icon.as_ref().unwrap().href, "files/map.png");
+        assert_eq!(
+            ground_overlay.lat_lon_box,
+            Some(types::LatLonBox::new(40., 39., -104., -105.).with_rotation(12.5))
+        );
+        assert_eq!(ground_overlay.children.len(), 1);
+        let screen_overlay: &types::ScreenOverlay = variant(&elements[1]);
+        assert_eq!(screen_overlay.icon.href, "legend.png");
+        assert_eq!(
+            screen_overlay.overlay_xy,
+            Vec2::new(1., 0., Units::Fraction, Units::Fraction)
+        );
+    }
+
+    #[test]
+    fn test_read_extended_data() {
+        let kml_str = r##"<Document>
+          <Schema name="Trail" id="trail">
+            <SimpleField name="length" type="double"><displayName>Length</displayName></SimpleField>
+          </Schema>
+          <Placemark>
+            <ExtendedData>
+              <Data name="elevation"><displayName>Elevation</displayName><value>1200</value></Data>
+              <SchemaData schemaUrl="#trail"><SimpleData name="length">4.2</SimpleData></SchemaData>
+            </ExtendedData>
+          </Placemark>
+        </Document>"##;
+        let kml = parse_round_trip(kml_str);
+        let elements = elements(&kml);
+        assert_eq!(
+            elements[0],
+            Kml::Schema(types::Schema::new("trail").with_name("Trail").with_field(
+                types::SimpleField::new("length", "double").with_display_name("Length")
+            ))
+        );
+        let placemark: &Placemark = variant(&elements[1]);
+        assert_eq!(
+            placemark.extended_data,
+            Some(
+                types::ExtendedData::default()
+                    .with_data(types::Data::new("elevation", "1200").with_display_name("Elevation"))
+                    .with_schema_data(types::SchemaData::new("#trail").with_data("length", "4.2"))
+            )
+        );
+        assert!(placemark.children.is_empty());
+    }
+
+    #[test]
+    fn test_read_views() {
+        let kml_str = r#"<Document xmlns:gx="http://www.google.com/kml/ext/2.2">
+          <Camera id="start">
+            <longitude>-122.08</longitude><latitude>37.42</latitude><altitude>500</altitude>
+            <heading>45</heading><tilt>60</tilt><roll>5</roll>
+          </Camera>
+          <Placemark>
+            <name>Summit</name>
+            <LookAt>
+              <gx:TimeSpan><begin>2020-01</begin></gx:TimeSpan>
+              <longitude>-105.6</longitude><latitude>40.25</latitude>
+              <range>2000</range><tilt>45</tilt>
+              <gx:altitudeMode>relativeToSeaFloor</gx:altitudeMode>
+            </LookAt>
+          </Placemark>
+        </Document>"#;
+        let kml = parse_round_trip(kml_str);
+        let elements = elements(&kml);
+        let mut camera = Camera::new(-122.08, 37.42, 500.)
+            .with_heading(45.)
+            .with_tilt(60.)
+            .with_roll(5.);
+        camera.attrs.insert("id".to_string(), "start".to_string());
+        assert_eq!(elements[0], Kml::Camera(camera));
+        let placemark: &Placemark = variant(&elements[1]);
+        assert_eq!(
+            placemark.view,
+            Some(AbstractView::LookAt(
+                LookAt::new(-105.6, 40.25, 2000.)
+                    .with_tilt(45.)
+                    .with_altitude_mode(types::AltitudeMode::RelativeToSeaFloor)
+                    .with_time_primitive(types::TimeSpan::default().with_begin(
+                        KmlDateTime::YearMonth {
+                            year: 2020,
+                            month: 1
+                        }
+                    ))
+            ))
+        );
+        assert!(placemark.children.is_empty());
+        assert!(kml.to_string().contains("<gx:TimeSpan>"));
+    }
+
+    #[test]
+    fn test_read_model() {
+        let kml_str = r#"<Placemark>
+          <Model id="house">
+            <altitudeMode>relativeToGround</altitudeMode>
+            <Location><longitude>-105.2</longitude><latitude>39.5</latitude><altitude>2</altitude></Location>
+            <Orientation><heading>90</heading><tilt>0</tilt><roll>0</roll></Orientation>
+            <Scale><x>2</x><y>2</y><z>1</z></Scale>
+            <Link><href>models/house.dae</href></Link>
+            <ResourceMap>
+              <Alias><targetHref>textures/roof.png</targetHref><sourceHref>roof.png</sourceHref></Alias>
+            </ResourceMap>
+          </Model>
+        </Placemark>"#;
+        let kml = parse_round_trip(kml_str);
+        let model = match &kml {
+            Kml::Placemark(Placemark {
+                geometry: Some(Geometry::Model(m)),
+                ..
+            }) => m,
+            k => panic!("expected Placemark with a Model, found {:?}", k),
+        };
+        let mut expected = Model::new(Link::new("models/house.dae"))
+            .with_altitude_mode(types::AltitudeMode::RelativeToGround)
+            .with_location(Location::new(39.5, -105.2, 2.))
+            .with_orientation(Orientation::new(0., 0., 90.))
+            .with_scale(Scale::new(2., 2., 1.))
+            .with_resource_map(
+                ResourceMap::default()
+                    .with_alias(types::Alias::new("textures/roof.png", "roof.png")),
+            );
+        expected.attrs.insert("id".to_string(), "house".to_string());
+        assert_eq!(model, &expected);
+    }
+
+    #[test]
+    fn test_read_truncated_model() {
+        let kml_str = "<Placemark><Model><altitudeMode>absolute</altitudeMode>";
+        let err = kml_str.parse::<Kml>().unwrap_err();
+        assert!(matches!(err.root_cause(), Error::InvalidInput));
+    }
+
+    #[test]
+    fn test_read_truncated_input() {
+        // Unclosed features and geometries are errors instead of reading past the end
+        for kml_str in [
+            "<Folder><Placemark>",
+            "<Placemark><name>A</name>",
+            "<Placemark><LineString><coordinates>1,2 3,4</coordinates>",
+        ] {
+            let err = kml_str.parse::<Kml>().unwrap_err();
+            assert!(matches!(err.root_cause(), Error::InvalidInput));
+        }
+        assert!(matches!("".parse::<Kml>(), Err(Error::NoElements)));
+    }
+
+    #[test]
+    fn test_read_placemark_track() {
+        let kml_str = r#"<Placemark xmlns:gx="http://www.google.com/kml/ext/2.2">
+          <gx:MultiTrack>
+            <gx:interpolate>1</gx:interpolate>
+            <gx:Track>
+              <when>2020-01-01T00:00:00Z</when>
+              <when>2020-01-01T00:01:00Z</when>
+              <gx:coord>1 2 3</gx:coord>
+              <gx:coord>4 5 6</gx:coord>
+            </gx:Track>
+          </gx:MultiTrack>
+        </Placemark>"#;
+        let placemark = match KmlReader::<_, f64>::from_string(kml_str).read().unwrap() {
+            Kml::Placemark(p) => p,
+            k => panic!("expected Placemark, found {:?}", k),
+        };
+        let multi_track = match placemark.geometry {
+            Some(Geometry::MultiTrack(t)) => t,
+            g => panic!("expected MultiTrack, found {:?}", g),
+        };
+        assert!(multi_track.interpolate);
+        assert_eq!(multi_track.tracks.len(), 1);
+        assert_eq!(multi_track.tracks[0].when.len(), 2);
+        assert_eq!(
+            multi_track.tracks[0].coords,
+            vec![Coord::new(1., 2., Some(3.)), Coord::new(4., 5., Some(6.))]
+        );
+    }
+
+    #[test]
+    fn test_read_feature_fields() {
+        let kml_str = r##"<Placemark xmlns:atom="http://www.w3.org/2005/Atom">
+          <name>Trailhead</name>
+          <visibility>0</visibility>
+          <open>true</open>
+          <atom:author><atom:name>Parks</atom:name></atom:author>
+          <atom:link href="https://example.com/trail"/>
+          <address>1 Trail Rd</address>
+          <phoneNumber>tel:+1-555-0100</phoneNumber>
+          <Snippet maxLines="1">Parking</Snippet>
+          <description>Start here</description>
+          <styleUrl>#trail</styleUrl>
+          <visibility>maybe</visibility>
+        </Placemark>"##;
+        let placemark = match KmlReader::<_, f64>::from_string(kml_str).read().unwrap() {
+            Kml::Placemark(p) => p,
+            k => panic!("expected Placemark, found {:?}", k),
+        };
+        assert_eq!(placemark.name.as_deref(), Some("Trailhead"));
+        assert_eq!(placemark.visibility, Some(false));
+        assert_eq!(placemark.open, Some(true));
+        assert_eq!(placemark.author.as_deref(), Some("Parks"));
+        assert_eq!(placemark.link.as_deref(), Some("https://example.com/trail"));
+        assert_eq!(placemark.address.as_deref(), Some("1 Trail Rd"));
+        assert_eq!(placemark.phone_number.as_deref(), Some("tel:+1-555-0100"));
+        assert_eq!(
+            placemark.snippet,
+            Some(Snippet::new("Parking").with_max_lines(1u32))
+        );
+        assert_eq!(placemark.description.as_deref(), Some("Start here"));
+        assert_eq!(placemark.style_url.as_deref(), Some("#trail"));
+        assert_eq!(placemark.children.len(), 1);
+        assert_eq!(placemark.children[0].content.as_deref(), Some("maybe"));
+
+        let kml_str = r#"<Document>
+          <name>Trails</name>
+          <visibility>1</visibility>
+          <Snippet>Every trail</Snippet>
+          <Placemark><name>Trailhead</name></Placemark>
+        </Document>"#;
+        let (props, elements) = match KmlReader::<_, f64>::from_string(kml_str).read().unwrap() {
+            Kml::Document {
+                props, elements, ..
+            } => (props, elements),
+            k => panic!("expected Document, found {:?}", k),
+        };
+        assert_eq!(
+            props,
+            FeatureProps::default()
+                .with_name("Trails")
+                .with_visibility(true)
+                .with_snippet(Snippet::new("Every trail"))
+        );
+        assert_eq!(elements.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_error_context() {
+        let kml_str = r#"<Document>
+          <Style><LineStyle><width>wide</width></LineStyle></Style>
+        </Document>"#;
+        let err = kml_str.parse::<Kml>().unwrap_err();
+        assert_eq!(err.element(), Some(("LineStyle", 39)));
+        assert!(matches!(err.root_cause(), Error::NumParse(v) if v == "wide"));
+
+        let err = "<Folder><Placemark></Folder>".parse::<Kml>().unwrap_err();
+        assert_eq!(err.element(), Some(("Placemark", 19)));
+        match err.root_cause() {
+            Error::TagMismatch {
+                expected, found, ..
+            } => {
+                assert_eq!(expected, "Placemark");
+                assert_eq!(found, "Folder");
+            }
+            e => panic!("unexpected error {:?}", e),
+        }
+        let xml_err = err.source().and_then(|e| e.source()).unwrap();
+        assert!(xml_err.is::<quick_xml::Error>());
+    }
+
+    #[test]
+    fn test_parse_types_from_str() {
+        let style: Style = r#"<Style id="s"><LineStyle><width>2</width></LineStyle></Style>"#
+            .parse()
+            .unwrap();
+        assert_eq!(style.id, "s");
+        assert_eq!(style.line.unwrap().width, 2.);
+
+        let placemark: Placemark<f32> =
+            "<Placemark><name>p</name><Point><coordinates>1,2</coordinates></Point></Placemark>"
+                .parse()
+                .unwrap();
+        assert_eq!(placemark.name.as_deref(), Some("p"));
+
+        assert!(matches!(
+            "<Point><coordinates>1,2</coordinates></Point>".parse::<Style>(),
+            Err(Error::UnexpectedKml {
+                expected: "Style",
+                found: "Point"
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_coord_arity() {
+        let line_str = "<LineString><coordinates>0,0 1 2,2</coordinates></LineString>";
+        let err = KmlReader::<_, f64>::from_string(line_str)
+            .read()
+            .unwrap_err();
+        match err {
+            Error::InElement {
+                element,
+                position,
+                source,
+            } => {
+                assert_eq!(element, "coordinates");
+                assert_eq!(position, 25);
+                assert!(matches!(*source, Error::InvalidCoordArity { found: 1, .. }));
+            }
+            e => panic!("unexpected error {:?}", e),
+        }
+
+        let mut r = KmlReader::<_, f64>::from_string(line_str).lenient(true);
+        assert_eq!(
+            r.read().unwrap(),
+            Kml::LineString(LineString::from(vec![
+                Coord::from((0., 0.)),
+                Coord::from((2., 2.))
+            ]))
+        );
+        assert_eq!(r.warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_read_resolve_namespaces() {
+        let kml_str = r#"<Document xmlns:k="http://www.opengis.net/kml/2.2" xmlns:acme="https://example.com/acme">
+          <acme:name>Other</acme:name>
+          <k:name>Trip</k:name>
+          <k:Folder><acme:Point><coordinates>a</coordinates></acme:Point></k:Folder>
+        </Document>"#;
+        let kml: Kml = KmlReader::from_string(kml_str)
+            .with_options(KmlReaderOptions::default().with_resolve_namespaces(true))
+            .read()
+            .unwrap();
+        match kml {
+            Kml::Document {
+                props, elements, ..
+            } => {
+                assert_eq!(props.name.as_deref(), Some("Trip"));
+                assert!(matches!(&elements[0], Kml::Element(e) if e.name == "name"));
+                match &elements[1] {
+                    Kml::Folder { elements, .. } => {
+                        assert!(matches!(&elements[0], Kml::Element(e) if e.name == "Point"))
+                    }
+                    k => panic!("unexpected {:?}", k),
+                }
+            }
+            k => panic!("unexpected {:?}", k),
+        }
+
+        // Matched by local name by default
+        assert!(kml_str.parse::<Kml>().is_err());
+    }
+
+    #[test]
+    fn test_read_lenient_skips_elements() {
+        let kml_str = r#"<kml><Document>
+          <name>Trip</name>
+          <Folder>
+            <Placemark><Polygon><altitudeMode>sideways</altitudeMode><outerBoundaryIs><LinearRing><coordinates>0,0 1,0 1,1 0,0</coordinates></LinearRing></outerBoundaryIs></Polygon></Placemark>
+            <Placemark><name>Kept</name></Placemark>
+          </Folder>
+          <Style id="s"><LineStyle><width>wide</width></LineStyle></Style>
+          <Placemark><name>Last</name></Placemark>
+        </Document></kml>"#;
+        assert!(KmlReader::<_, f64>::from_string(kml_str).read().is_err());
+
+        let options = KmlReaderOptions::default().with_mode(ReadMode::Lenient);
+        let mut r = KmlReader::<_, f64>::from_string(kml_str).with_options(options.clone());
+        let kml = r.read().unwrap();
+        let names: Vec<_> = kml
+            .placemarks()
+            .map(|p| p.name.as_deref().unwrap())
+            .collect();
+        assert_eq!(names, vec!["Kept", "Last"]);
+        assert!(!kml.iter().any(|k| matches!(k, Kml::Style(_))));
+        assert_eq!(r.warnings().len(), 2);
+        assert!(matches!(
+            r.warnings()[0].root_cause(),
+            Error::InvalidAltitudeMode(_)
+        ));
+        assert!(matches!(r.warnings()[1].root_cause(), Error::NumParse(_)));
+
+        let mut r = KmlReader::<_, f64>::from_string(kml_str).with_options(options.clone());
+        assert_eq!(r.placemarks().filter_map(Result::ok).count(), 2);
+        assert_eq!(r.warnings().len(), 2);
+
+        let malformed = "<Folder><Placemark><name>A</Placemark></Folder>";
+        let mut r = KmlReader::<_, f64>::from_string(malformed).with_options(options);
+        assert!(matches!(
+            r.read().unwrap_err().root_cause(),
+            Error::TagMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn test_read_malformed_features() {
+        let kml_str = r#"<Folder>
+          <NetworkLink><Link><refreshMode>sometimes</refreshMode></Link></NetworkLink>
+          <GroundOverlay><drawOrder>first</drawOrder></GroundOverlay>
+        </Folder>"#;
+        let mut r = KmlReader::<_, f64>::from_string(kml_str);
+        let e = r.read().unwrap_err();
+        assert_eq!(e.element().map(|(name, _)| name), Some("NetworkLink"));
+        assert!(matches!(e.root_cause(), Error::InvalidKmlElement(_)));
+
+        let options = KmlReaderOptions::default().with_mode(ReadMode::Lenient);
+        let mut r = KmlReader::<_, f64>::from_string(kml_str).with_options(options);
+        let kml = r.read().unwrap();
+        let names: Vec<_> = elements(&kml)
+            .iter()
+            .map(|k| variant::<Element>(k).name.as_str())
+            .collect();
+        assert_eq!(names, vec!["NetworkLink", "GroundOverlay"]);
+        assert_eq!(r.warnings().len(), 2);
+        assert_eq!(
+            r.warnings()[1].element().map(|(name, _)| name),
+            Some("GroundOverlay")
+        );
+    }
+
+    #[test]
+    fn test_read_style_colors() {
+        let kml_str = r#"<Style>
+          <LineStyle><color>7F0000FF</color></LineStyle>
+          <PolyStyle><color>#00ff00</color></PolyStyle>
+          <LabelStyle><color>ff00ff</color></LabelStyle>
+        </Style>"#;
+        for lenient in [false, true] {
+            let mut r = KmlReader::<_, f64>::from_string(kml_str).lenient(lenient);
+            let style = match r.read().unwrap() {
+                Kml::Style(s) => s,
+                k => panic!("Expected Style, found {:?}", k),
+            };
+            assert_eq!(
+                style.line.unwrap().color,
+                Color::from_rgba(0xff, 0, 0, 0x7f)
+            );
+            assert_eq!(style.poly.unwrap().color, Color::from_rgb(0, 0xff, 0));
+            assert_eq!(style.label.unwrap().color, Color::from_rgb(0xff, 0, 0xff));
+            assert_eq!(r.warnings().len(), 2);
+            assert!(matches!(
+                r.warnings()[0].root_cause(),
+                Error::InvalidColor(_)
+            ));
+        }
+
+        let invalid = "<LineStyle><color>red</color></LineStyle>";
+        let mut r = KmlReader::<_, f64>::from_string(invalid).lenient(true);
+        assert!(r.read().is_err());
+    }
 
     #[test]
     fn test_parse_point() {
@@ -1054,6 +2623,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_close_rings() {
+        let poly_str = r#"<Polygon>
+        <outerBoundaryIs>
+          <LinearRing><coordinates>0,0 4,0 4,4 0,4</coordinates></LinearRing>
+        </outerBoundaryIs>
+        <innerBoundaryIs>
+          <LinearRing><coordinates>1,1 2,1 2,2 1,1</coordinates></LinearRing>
+        </innerBoundaryIs>
+      </Polygon>"#;
+
+        let p: Kml = KmlReader::from_string(poly_str).read().unwrap();
+        assert!(matches!(p, Kml::Polygon(ref p) if !p.outer.is_closed()));
+
+        let p: Kml = KmlReader::from_string(poly_str)
+            .close_rings(true)
+            .read()
+            .unwrap();
+        let p = match p {
+            Kml::Polygon(p) => p,
+            _ => unreachable!(),
+        };
+        assert_eq!(p.outer.coords.len(), 5);
+        assert_eq!(p.outer.coords[4], Coord::from((0., 0.)));
+        assert_eq!(p.inner[0].coords.len(), 4);
+    }
+
     #[test]
     fn test_parse_kml_document_default() {
         let kml_str ="<Point><coordinates>1,1,1</coordinates></Point><LineString><coordinates>1,1 2,1</coordinates></LineString>";
@@ -1142,6 +2738,7 @@ mod tests {
             e,
             Kml::Folder {
                 attrs: _,
+                props: _,
                 elements: _
             }
         )));
@@ -1167,7 +2764,7 @@ mod tests {
 
         let elements: Option<Vec<Kml<_>>> = match f {
             Kml::KmlDocument(d) => match &d.elements[0] {
-                Kml::Document { attrs: _, elements } => Some(elements.to_vec()),
+                Kml::Document { elements, .. } => Some(elements.to_vec()),
                 _ => None,
             },
             _ => None,
@@ -1179,6 +2776,7 @@ mod tests {
             e,
             Kml::Folder {
                 attrs: _,
+                props: _,
                 elements: _
             }
         )));
@@ -0,0 +1,373 @@
+//! Module for repairing common issues in KML from real-world exporters
+//!
+//! # Example
+//!
+//! ```
+//! use kml::Kml;
+//!
+//! let kml_str = r#"
+//! <Placemark>
+//!   <styleUrl></styleUrl>
+//!   <LineString><coordinates>45,170 46, 171</coordinates></LineString>
+//! </Placemark>"#;
+//! let mut kml: Kml = kml_str.parse().unwrap();
+//!
+//! let report = kml.repair();
+//! assert_eq!(report.swapped_geometries, 1);
+//! assert_eq!(report.removed_style_urls, 1);
+//! assert!(kml.validate().is_empty());
+//! ```
+use std::fmt;
+
+use crate::types::{
+    lit, Coord, CoordType, CustomElement, Element, Kml, LineString, LinearRing, Placemark, Point,
+    Polygon, Snippet, Track,
+};
+use crate::visit::{walk_children, walk_kml, walk_placemark, KmlVisitor};
+
+/// Counts of changes made by [`repair`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// `kml:LinearRing` elements closed by appending their first coordinate
+    pub closed_rings: usize,
+    /// Geometries with latitude and longitude swapped, where swapping them makes every coordinate
+    /// in the geometry valid
+    pub swapped_geometries: usize,
+    /// Empty `styleUrl` elements removed
+    pub removed_style_urls: usize,
+    /// Deprecated lowercase `snippet` elements renamed to `Snippet`, or `Snippet` elements with an
+    /// invalid `maxLines` attribute removed
+    pub fixed_snippets: usize,
+}
+
+impl RepairReport {
+    /// Returns `true` if nothing was changed
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for RepairReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "closed {} rings, swapped coordinates of {} geometries, removed {} empty styleUrls, fixed {} snippets",
+            self.closed_rings, self.swapped_geometries, self.removed_style_urls, self.fixed_snippets
+        )
+    }
+}
+
+/// Fixes common issues in KML from exporters in one pass, returning a report of what changed
+///
+/// - Unclosed rings are closed, see [`Kml::close_rings`](../enum.Kml.html#method.close_rings)
+/// - Geometries with coordinates in latitude, longitude order are swapped, but only when some
+///   latitude is out of range and swapping puts every coordinate in the geometry in range
+/// - Empty `styleUrl` elements are removed
+/// - Deprecated `snippet` elements are renamed to `Snippet`, and invalid `maxLines` attributes
+///   are removed, so they're set as the `snippet` of features that don't have one
+///
+/// Whitespace around the commas in coordinate tuples (i.e. `1, 2, 3`) is handled when reading, so
+/// it isn't included here.
+pub fn repair<T: CoordType>(kml: &mut Kml<T>) -> RepairReport {
+    let mut visitor = Repair(RepairReport {
+        closed_rings: kml.close_rings(),
+        ..Default::default()
+    });
+    kml.walk(&mut visitor);
+    visitor.0
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Fixes common issues from exporters, see [`repair`](repair/fn.repair.html)
+    pub fn repair(&mut self) -> RepairReport {
+        repair(self)
+    }
+}
+
+// Applies every repair but closing rings, which has its own pass, while counting the changes
+struct Repair(RepairReport);
+
+impl<T> KmlVisitor<T> for Repair
+where
+    T: CoordType,
+{
+    fn visit_kml(&mut self, kml: &mut Kml<T>) {
+        match kml {
+            Kml::KmlDocument(d) => self.remove_style_urls(&mut d.elements),
+            Kml::Document {
+                props, elements, ..
+            }
+            | Kml::Folder {
+                props, elements, ..
+            } => {
+                repair_style_url(&mut props.style_url, &mut self.0);
+                self.remove_style_urls(elements);
+            }
+            _ => {}
+        }
+        walk_kml(self, kml);
+        if let Kml::Document {
+            props, elements, ..
+        }
+        | Kml::Folder {
+            props, elements, ..
+        } = kml
+        {
+            if props.snippet.is_none() {
+                let snippet = elements.iter().position(|e| match e {
+                    Kml::Element(e) => Snippet::from_element(e).is_some(),
+                    _ => false,
+                });
+                if let Some(Kml::Element(e)) = snippet.map(|i| elements.remove(i)) {
+                    props.snippet = Snippet::from_element(&e);
+                }
+            }
+        }
+    }
+
+    fn visit_placemark(&mut self, placemark: &mut Placemark<T>) {
+        repair_style_url(&mut placemark.style_url, &mut self.0);
+        walk_placemark(self, placemark);
+        if placemark.snippet.is_none() {
+            let snippet = placemark
+                .children
+                .iter()
+                .position(|c| Snippet::from_element(c).is_some());
+            if let Some(c) = snippet.map(|i| placemark.children.remove(i)) {
+                placemark.snippet = Snippet::from_element(&c);
+            }
+        }
+    }
+
+    fn visit_point(&mut self, point: &mut Point<T>) {
+        swap_lat_lon(&mut [&mut point.coord], &mut self.0);
+    }
+
+    fn visit_line_string(&mut self, line_string: &mut LineString<T>) {
+        swap_lat_lon(&mut coords_mut(&mut line_string.coords), &mut self.0);
+    }
+
+    fn visit_linear_ring(&mut self, linear_ring: &mut LinearRing<T>) {
+        swap_lat_lon(&mut coords_mut(&mut linear_ring.coords), &mut self.0);
+    }
+
+    fn visit_polygon(&mut self, polygon: &mut Polygon<T>) {
+        swap_lat_lon(&mut polygon_coords(polygon), &mut self.0);
+    }
+
+    fn visit_track(&mut self, track: &mut Track<T>) {
+        swap_lat_lon(&mut coords_mut(&mut track.coords), &mut self.0);
+    }
+
+    fn visit_children(&mut self, children: &mut Vec<Element>) {
+        children.retain(|e| {
+            let empty = is_empty_style_url(e);
+            self.0.removed_style_urls += empty as usize;
+            !empty
+        });
+        walk_children::<T, _>(self, children);
+    }
+
+    fn visit_element(&mut self, element: &mut Element) {
+        repair_snippet(element, &mut self.0);
+    }
+}
+
+impl Repair {
+    fn remove_style_urls<T: CoordType>(&mut self, elements: &mut Vec<Kml<T>>) {
+        elements.retain(|e| match e {
+            Kml::Element(e) if is_empty_style_url(e) => {
+                self.0.removed_style_urls += 1;
+                false
+            }
+            _ => true,
+        });
+    }
+}
+
+fn repair_style_url(style_url: &mut Option<String>, report: &mut RepairReport) {
+    if style_url
+        .as_deref()
+        .is_some_and(|url| url.trim().is_empty())
+    {
+        *style_url = None;
+        report.removed_style_urls += 1;
+    }
+}
+
+fn is_empty_style_url(element: &Element) -> bool {
+    element.local_name() == "styleUrl"
+        && element
+            .content
+            .as_deref()
+            .unwrap_or_default()
+            .trim()
+            .is_empty()
+}
+
+fn repair_snippet(element: &mut Element, report: &mut RepairReport) {
+    let mut fixed = false;
+    if element.local_name() == "snippet" {
+        element.name = element.name.replace("snippet", "Snippet");
+        fixed = true;
+    }
+    if element.local_name() == "Snippet" {
+        let invalid_max_lines = element
+            .attrs
+            .get("maxLines")
+            .is_some_and(|v| v.trim().parse::<u32>().is_err());
+        if invalid_max_lines {
+            element.attrs.remove("maxLines");
+            fixed = true;
+        }
+    }
+    report.fixed_snippets += fixed as usize;
+}
+
+fn coords_mut<T: CoordType>(coords: &mut [Coord<T>]) -> Vec<&mut Coord<T>> {
+    coords.iter_mut().collect()
+}
+
+// Rings are swapped together so the polygon stays consistent
+fn polygon_coords<T: CoordType>(polygon: &mut Polygon<T>) -> Vec<&mut Coord<T>> {
+    polygon
+        .outer
+        .coords
+        .iter_mut()
+        .chain(polygon.inner.iter_mut().flat_map(|r| r.coords.iter_mut()))
+        .collect()
+}
+
+fn swap_lat_lon<T: CoordType>(coords: &mut [&mut Coord<T>], report: &mut RepairReport) {
+    let any_invalid = coords.iter().any(|c| c.y.abs() > lit(90.));
+    let valid_swapped = coords
+        .iter()
+        .all(|c| c.x.abs() <= lit(90.) && c.y.abs() <= lit(180.));
+    if any_invalid && valid_swapped {
+        for c in coords.iter_mut() {
+            std::mem::swap(&mut c.x, &mut c.y);
+        }
+        report.swapped_geometries += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repair() {
+        let kml_str = r#"
+        <Document>
+          <styleUrl> </styleUrl>
+          <snippet>Summary</snippet>
+          <Placemark>
+            <Snippet maxLines="two">Summary</Snippet>
+            <styleUrl>#style</styleUrl>
+            <Polygon>
+              <outerBoundaryIs>
+                <LinearRing><coordinates>10,100 10,110 20,110 20,100</coordinates></LinearRing>
+              </outerBoundaryIs>
+            </Polygon>
+          </Placemark>
+          <Placemark><Point><coordinates>100,10</coordinates></Point></Placemark>
+        </Document>"#;
+        let mut kml: Kml = kml_str.parse().unwrap();
+
+        let report = repair(&mut kml);
+        assert_eq!(
+            report,
+            RepairReport {
+                closed_rings: 1,
+                swapped_geometries: 1,
+                removed_style_urls: 1,
+                fixed_snippets: 2,
+            }
+        );
+
+        let expected: Kml = r#"
+        <Document>
+          <Snippet>Summary</Snippet>
+          <Placemark>
+            <Snippet>Summary</Snippet>
+            <styleUrl>#style</styleUrl>
+            <Polygon>
+              <outerBoundaryIs>
+                <LinearRing><coordinates>100,10 110,10 110,20 100,20 100,10</coordinates></LinearRing>
+              </outerBoundaryIs>
+            </Polygon>
+          </Placemark>
+          <Placemark><Point><coordinates>100,10</coordinates></Point></Placemark>
+        </Document>"#
+            .parse()
+            .unwrap();
+        assert_eq!(kml, expected);
+        assert!(repair(&mut kml).is_empty());
+    }
+
+    #[test]
+    fn test_repair_nothing() {
+        let mut kml: Kml = "<Document></Document>".parse().unwrap();
+        let report = repair(&mut kml);
+        assert!(report.is_empty());
+        assert_eq!(
+            report.to_string(),
+            "closed 0 rings, swapped coordinates of 0 geometries, removed 0 empty styleUrls, fixed 0 snippets"
+        );
+    }
+
+    #[test]
+    fn test_repair_invalid_coords() {
+        // Coordinates that are out of range either way aren't swapped, and a ring that can't be
+        // swapped keeps the other rings of the polygon from being swapped
+        let kml_str = r#"
+        <Document>
+          <Placemark><Point><coordinates>200,100</coordinates></Point></Placemark>
+          <Placemark>
+            <Polygon>
+              <outerBoundaryIs>
+                <LinearRing><coordinates>10,100 10,110 20,110 10,100</coordinates></LinearRing>
+              </outerBoundaryIs>
+              <innerBoundaryIs>
+                <LinearRing><coordinates>120,105 12,106 12,107 120,105</coordinates></LinearRing>
+              </innerBoundaryIs>
+            </Polygon>
+          </Placemark>
+        </Document>"#;
+        let mut kml: Kml = kml_str.parse().unwrap();
+        let original = kml.clone();
+        assert!(repair(&mut kml).is_empty());
+        assert_eq!(kml, original);
+    }
+
+    #[test]
+    fn test_repair_children() {
+        let kml_str = r#"
+        <Document>
+          <Placemark>
+            <snippet maxLines="1">Summary</snippet>
+            <Snippet maxLines="">Other</Snippet>
+          </Placemark>
+          <NetworkLink>
+            <styleUrl/>
+            <Link><href>https://example.com/feed.kml</href></Link>
+          </NetworkLink>
+        </Document>"#;
+        let mut kml: Kml = kml_str.parse().unwrap();
+        let report = repair(&mut kml);
+        assert_eq!(report.fixed_snippets, 2);
+        assert_eq!(report.removed_style_urls, 1);
+
+        // The first snippet is set on the placemark, keeping its valid `maxLines`
+        let placemarks: Vec<_> = kml.placemarks().collect();
+        let snippet = placemarks[0].snippet.as_ref().unwrap();
+        assert_eq!(
+            (snippet.text.as_str(), snippet.max_lines),
+            ("Summary", Some(1))
+        );
+        assert!(!kml.to_string().contains("styleUrl"));
+    }
+}
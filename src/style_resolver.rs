@@ -0,0 +1,288 @@
+//! Module for resolving the effective styles of placemarks
+use std::collections::HashMap;
+
+use crate::types::{CoordType, Kml, Placemark, Style, StyleMap};
+
+// Limit on StyleMap pairs referencing other StyleMaps, which also stops cycles
+const MAX_STYLE_MAP_DEPTH: usize = 8;
+
+/// State of a feature selecting the style of a `kml:StyleMap`, matching the `key` of its `kml:Pair`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum StyleState {
+    #[default]
+    Normal,
+    Highlight,
+}
+
+impl StyleState {
+    /// Returns the `kml:styleStateEnumType` value of the state
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StyleState::Normal => "normal",
+            StyleState::Highlight => "highlight",
+        }
+    }
+}
+
+/// Index of the shared `kml:Style` and `kml:StyleMap` elements of a document, for resolving the
+/// effective style of placemarks
+///
+/// A placemark's `styleUrl` is looked up by the fragment after `#`, following a `kml:StyleMap` to
+/// the style of the [`StyleState`], and sub-styles of its inline `kml:Style` replace those of the
+/// shared style. References to other files can't be resolved, so only the inline style applies to
+/// them. The first style with an id is used when ids are repeated.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, StyleResolver, StyleState};
+///
+/// let kml_str = r#"
+/// <Document>
+///   <Style id="normal"><LineStyle><width>2</width></LineStyle></Style>
+///   <Style id="highlight"><LineStyle><width>4</width></LineStyle></Style>
+///   <StyleMap id="route">
+///     <Pair><key>normal</key><styleUrl>#normal</styleUrl></Pair>
+///     <Pair><key>highlight</key><styleUrl>#highlight</styleUrl></Pair>
+///   </StyleMap>
+///   <Placemark>
+///     <styleUrl>#route</styleUrl>
+///     <Style><PolyStyle><fill>0</fill></PolyStyle></Style>
+///   </Placemark>
+/// </Document>"#;
+/// let kml: Kml = kml_str.parse().unwrap();
+/// let resolver = StyleResolver::new(&kml);
+///
+/// let placemark = kml.placemarks().next().unwrap();
+/// let style = resolver.resolve(placemark);
+/// assert_eq!(style.line.unwrap().width, 2.);
+/// assert!(!style.poly.unwrap().fill);
+///
+/// let highlight = resolver.resolve_state(placemark, StyleState::Highlight);
+/// assert_eq!(highlight.line.unwrap().width, 4.);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StyleResolver<'a> {
+    styles: HashMap<&'a str, &'a Style>,
+    style_maps: HashMap<&'a str, &'a StyleMap>,
+}
+
+impl<'a> StyleResolver<'a> {
+    /// Indexes the styles and style maps with ids anywhere in the document
+    pub fn new<T: CoordType>(kml: &'a Kml<T>) -> Self {
+        let mut resolver = StyleResolver::default();
+        for k in kml.iter() {
+            match k {
+                Kml::Style(s) if !s.id.is_empty() => {
+                    resolver.styles.entry(s.id.as_str()).or_insert(s);
+                }
+                Kml::StyleMap(s) if !s.id.is_empty() => {
+                    resolver.style_maps.entry(s.id.as_str()).or_insert(s);
+                }
+                _ => {}
+            }
+        }
+        resolver
+    }
+
+    /// Returns the shared style with the id
+    pub fn style(&self, id: &str) -> Option<&'a Style> {
+        self.styles.get(id).copied()
+    }
+
+    /// Returns the shared style map with the id
+    pub fn style_map(&self, id: &str) -> Option<&'a StyleMap> {
+        self.style_maps.get(id).copied()
+    }
+
+    /// Returns the shared style referenced by a `styleUrl` like `#id`, following style maps to the
+    /// style for the state, or `None` if it doesn't reference a style in the document
+    pub fn resolve_url(&self, style_url: &str, state: StyleState) -> Option<&'a Style> {
+        self.resolve_url_in(style_url, state, 0)
+    }
+
+    fn resolve_url_in(
+        &self,
+        style_url: &str,
+        state: StyleState,
+        depth: usize,
+    ) -> Option<&'a Style> {
+        let id = style_url.trim().strip_prefix('#')?;
+        if let Some(style) = self.style(id) {
+            return Some(style);
+        }
+        if depth >= MAX_STYLE_MAP_DEPTH {
+            return None;
+        }
+        let pair = self
+            .style_map(id)?
+            .pairs
+            .iter()
+            .find(|p| p.key.trim() == state.as_str())?;
+        self.resolve_url_in(&pair.style_url, state, depth + 1)
+    }
+
+    /// Returns the effective style of the placemark in its normal state
+    pub fn resolve<T: CoordType>(&self, placemark: &Placemark<T>) -> Style {
+        self.resolve_state(placemark, StyleState::Normal)
+    }
+
+    /// Returns the effective style of the placemark in the state, which is the shared style of its
+    /// `styleUrl` with the sub-styles of its inline style replacing those of the shared style
+    pub fn resolve_state<T: CoordType>(
+        &self,
+        placemark: &Placemark<T>,
+        state: StyleState,
+    ) -> Style {
+        let mut style = placemark
+            .style_url
+            .as_deref()
+            .and_then(|url| self.resolve_url(url, state))
+            .cloned()
+            .unwrap_or_default();
+        if let Some(inline) = &placemark.style {
+            override_style(&mut style, inline);
+        }
+        style
+    }
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Indexes the shared styles of the document, see [`StyleResolver`](struct.StyleResolver.html)
+    pub fn style_resolver(&self) -> StyleResolver<'_> {
+        StyleResolver::new(self)
+    }
+}
+
+fn override_style(style: &mut Style, inline: &Style) {
+    if inline.balloon.is_some() {
+        style.balloon = inline.balloon.clone();
+    }
+    if inline.icon.is_some() {
+        style.icon = inline.icon.clone();
+    }
+    if inline.label.is_some() {
+        style.label = inline.label.clone();
+    }
+    if inline.line.is_some() {
+        style.line = inline.line.clone();
+    }
+    if inline.poly.is_some() {
+        style.poly = inline.poly.clone();
+    }
+    if inline.list.is_some() {
+        style.list = inline.list.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_unresolved_urls() {
+        let kml: Kml = r##"<Document>
+          <Style id="a"><LineStyle><width>3</width></LineStyle></Style>
+          <StyleMap id="loop">
+            <Pair><key>normal</key><styleUrl>#loop</styleUrl></Pair>
+          </StyleMap>
+          <Placemark><styleUrl>#loop</styleUrl></Placemark>
+          <Placemark><styleUrl>styles.kml#a</styleUrl></Placemark>
+          <Placemark><styleUrl> #a </styleUrl></Placemark>
+        </Document>"##
+            .parse()
+            .unwrap();
+        let resolver = kml.style_resolver();
+        let styles: Vec<_> = kml.placemarks().map(|p| resolver.resolve(p)).collect();
+        assert_eq!(styles[0], Style::default());
+        assert_eq!(styles[1], Style::default());
+        assert_eq!(styles[2].line.as_ref().unwrap().width, 3.);
+        assert_eq!(resolver.resolve_url("#loop", StyleState::Highlight), None);
+    }
+
+    #[test]
+    fn test_resolve_without_styles() {
+        let kml: Kml = "<Document></Document>".parse().unwrap();
+        let resolver = kml.style_resolver();
+        assert_eq!(resolver.style(""), None);
+        assert_eq!(resolver.resolve_url("#a", StyleState::Normal), None);
+        let placemark = Placemark::<f64> {
+            style_url: Some("".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(resolver.resolve(&placemark), Style::default());
+    }
+
+    #[test]
+    fn test_resolve_ids() {
+        // Styles in nested folders are indexed, the first of a repeated id wins, and styles
+        // without an id can't be referenced
+        let kml: Kml = r##"<Document>
+          <Style><LineStyle><width>1</width></LineStyle></Style>
+          <Folder>
+            <Folder><Style id="a"><LineStyle><width>2</width></LineStyle></Style></Folder>
+            <Style id="a"><LineStyle><width>3</width></LineStyle></Style>
+          </Folder>
+          <Placemark>
+            <styleUrl>#a</styleUrl>
+            <Style><PolyStyle><fill>0</fill></PolyStyle></Style>
+          </Placemark>
+        </Document>"##
+            .parse()
+            .unwrap();
+        let resolver = kml.style_resolver();
+        assert!(resolver.style("").is_none());
+        assert_eq!(
+            resolver.style("a").unwrap().line.as_ref().unwrap().width,
+            2.
+        );
+
+        // The inline style only replaces the sub-styles it has
+        let style = resolver.resolve(kml.placemarks().next().unwrap());
+        assert_eq!(style.line.unwrap().width, 2.);
+        assert!(!style.poly.unwrap().fill);
+        assert!(style.icon.is_none());
+    }
+
+    #[test]
+    fn test_resolve_style_map_depth() {
+        // Each style map references the next, with the last referencing the style
+        fn chain(len: usize) -> String {
+            let maps: String = (0..len)
+                .map(|i| {
+                    let next = if i + 1 == len {
+                        "style".to_string()
+                    } else {
+                        format!("map{}", i + 1)
+                    };
+                    format!(
+                        r##"<StyleMap id="map{}"><Pair><key>highlight</key><styleUrl>#{}</styleUrl></Pair></StyleMap>"##,
+                        i, next
+                    )
+                })
+                .collect();
+            format!(
+                r#"<Document><Style id="style"><LineStyle><width>5</width></LineStyle></Style>{}</Document>"#,
+                maps
+            )
+        }
+
+        let kml: Kml = chain(MAX_STYLE_MAP_DEPTH).parse().unwrap();
+        let resolver = kml.style_resolver();
+        assert!(resolver
+            .resolve_url("#map0", StyleState::Highlight)
+            .is_some());
+        // Only the state's pair is followed
+        assert_eq!(resolver.resolve_url("#map0", StyleState::Normal), None);
+
+        let kml: Kml = chain(MAX_STYLE_MAP_DEPTH + 1).parse().unwrap();
+        let resolver = kml.style_resolver();
+        assert_eq!(resolver.resolve_url("#map0", StyleState::Highlight), None);
+        assert!(resolver
+            .resolve_url("#map1", StyleState::Highlight)
+            .is_some());
+    }
+}
@@ -0,0 +1,28 @@
+//! Helpers shared by the unit tests of several modules
+use std::convert::TryFrom;
+
+use crate::types::Kml;
+use crate::Error;
+
+/// Parses the KML and checks that writing it and reading it back gives the same tree
+pub(crate) fn parse_round_trip(kml_str: &str) -> Kml {
+    let kml: Kml = kml_str.parse().unwrap();
+    assert_eq!(kml.to_string().parse::<Kml>().unwrap(), kml);
+    kml
+}
+
+/// Returns the elements of a `kml:Document` or `kml:Folder`
+pub(crate) fn elements(kml: &Kml) -> &[Kml] {
+    match kml {
+        Kml::Document { elements, .. } | Kml::Folder { elements, .. } => elements,
+        k => panic!("expected Document or Folder, found {:?}", k),
+    }
+}
+
+/// Returns the value of the variant, like a `Placemark` of `Kml::Placemark`
+pub(crate) fn variant<'a, V>(kml: &'a Kml) -> &'a V
+where
+    &'a V: TryFrom<&'a Kml, Error = Error>,
+{
+    <&V>::try_from(kml).unwrap()
+}
@@ -0,0 +1,354 @@
+//! Transformation of coordinates with a user-supplied function
+//!
+//! KML coordinates are always longitude and latitude in WGS 84 (EPSG:4326), so data in projected
+//! coordinate reference systems like UTM or Web Mercator needs to be reprojected before writing.
+//! The transformation is a closure, or with the `proj` feature enabled, a pair of
+//! [`proj4rs`](https://docs.rs/proj4rs) projections passed to
+//! [`Kml::reproject`](../enum.Kml.html#method.reproject).
+#[cfg(feature = "proj")]
+use proj4rs::Proj;
+
+#[cfg(feature = "proj")]
+use crate::errors::Error;
+#[cfg(feature = "proj")]
+use crate::types::lit;
+use crate::types::{
+    Camera, Coord, CoordType, Geometry, Kml, LatLonAltBox, LatLonBox, LineString, LinearRing,
+    Location, LookAt, Point, Track,
+};
+use crate::visit::KmlVisitor;
+
+fn transform_all<T, F>(coords: &mut [Coord<T>], f: &mut F) -> usize
+where
+    T: CoordType,
+    F: FnMut(Coord<T>) -> Coord<T>,
+{
+    for c in coords.iter_mut() {
+        *c = f(*c);
+    }
+    coords.len()
+}
+
+// Transforms a position stored as separate fields, keeping the altitude if the result has none
+fn transform_position<T, F>(longitude: &mut T, latitude: &mut T, altitude: &mut T, f: &mut F)
+where
+    T: CoordType,
+    F: FnMut(Coord<T>) -> Coord<T>,
+{
+    let coord = f(Coord::new(*longitude, *latitude, Some(*altitude)));
+    *longitude = coord.x;
+    *latitude = coord.y;
+    *altitude = coord.z.unwrap_or(*altitude);
+}
+
+// Transforms the corners of a box stored as its edges, replacing it with the box around them
+fn transform_edges<T, F>(
+    north: &mut T,
+    south: &mut T,
+    east: &mut T,
+    west: &mut T,
+    f: &mut F,
+) -> usize
+where
+    T: CoordType,
+    F: FnMut(Coord<T>) -> Coord<T>,
+{
+    let corners = [
+        (*west, *south),
+        (*east, *south),
+        (*east, *north),
+        (*west, *north),
+    ]
+    .map(|(x, y)| f(Coord::new(x, y, None)));
+    *north = corners.iter().map(|c| c.y).fold(T::neg_infinity(), T::max);
+    *south = corners.iter().map(|c| c.y).fold(T::infinity(), T::min);
+    *east = corners.iter().map(|c| c.x).fold(T::neg_infinity(), T::max);
+    *west = corners.iter().map(|c| c.x).fold(T::infinity(), T::min);
+    corners.len()
+}
+
+// Replaces coordinates with the result of the function, counting the coordinates transformed
+struct TransformCoords<'a, F>(&'a mut F, usize);
+
+impl<T, F> KmlVisitor<T> for TransformCoords<'_, F>
+where
+    T: CoordType,
+    F: FnMut(Coord<T>) -> Coord<T>,
+{
+    fn visit_point(&mut self, point: &mut Point<T>) {
+        self.1 += transform_all(std::slice::from_mut(&mut point.coord), self.0);
+    }
+
+    fn visit_line_string(&mut self, line_string: &mut LineString<T>) {
+        self.1 += transform_all(&mut line_string.coords, self.0);
+    }
+
+    fn visit_linear_ring(&mut self, linear_ring: &mut LinearRing<T>) {
+        self.1 += transform_all(&mut linear_ring.coords, self.0);
+    }
+
+    fn visit_track(&mut self, track: &mut Track<T>) {
+        self.1 += transform_all(&mut track.coords, self.0);
+    }
+
+    fn visit_location(&mut self, l: &mut Location<T>) {
+        transform_position(&mut l.longitude, &mut l.latitude, &mut l.altitude, self.0);
+        self.1 += 1;
+    }
+
+    fn visit_camera(&mut self, c: &mut Camera<T>) {
+        transform_position(&mut c.longitude, &mut c.latitude, &mut c.altitude, self.0);
+        self.1 += 1;
+    }
+
+    fn visit_look_at(&mut self, l: &mut LookAt<T>) {
+        transform_position(&mut l.longitude, &mut l.latitude, &mut l.altitude, self.0);
+        self.1 += 1;
+    }
+
+    fn visit_lat_lon_box(&mut self, b: &mut LatLonBox<T>) {
+        self.1 += transform_edges(&mut b.north, &mut b.south, &mut b.east, &mut b.west, self.0);
+    }
+
+    fn visit_lat_lon_alt_box(&mut self, b: &mut LatLonAltBox<T>) {
+        self.1 += transform_edges(&mut b.north, &mut b.south, &mut b.east, &mut b.west, self.0);
+    }
+}
+
+impl<T> Geometry<T>
+where
+    T: CoordType,
+{
+    /// Replaces every coordinate in the geometry with the result of the function, returning the
+    /// number of coordinates transformed. See [`Kml::transform_coords`](../enum.Kml.html#method.transform_coords)
+    pub fn transform_coords<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(Coord<T>) -> Coord<T>,
+    {
+        let mut visitor = TransformCoords(&mut f, 0);
+        self.walk(&mut visitor);
+        visitor.1
+    }
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Replaces every coordinate in the tree with the result of the function, returning the number
+    /// of coordinates transformed
+    ///
+    /// This covers coordinates of geometries including tracks, `kml:Location` including that of a
+    /// `kml:Model`, the position of views like `kml:Camera`, the point of a `kml:PhotoOverlay`,
+    /// and bounding boxes like `kml:LatLonBox`, including those of regions and ground overlays.
+    /// Positions stored as separate fields keep their altitude if the function returns a
+    /// coordinate without one. Bounding boxes are replaced with the box around their transformed
+    /// corners, which keeps their `rotation`, and count as four coordinates. Elements without a
+    /// typed representation, like `gx:LatLonQuad`, aren't transformed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, types::{Coord, Geometry}};
+    ///
+    /// // Web Mercator (EPSG:3857) to WGS 84 (EPSG:4326)
+    /// fn to_wgs84(c: Coord) -> Coord {
+    ///     const R: f64 = 6_378_137.;
+    ///     let lon = (c.x / R).to_degrees();
+    ///     let lat = (2. * (c.y / R).exp().atan() - std::f64::consts::FRAC_PI_2).to_degrees();
+    ///     Coord::new(lon, lat, c.z)
+    /// }
+    ///
+    /// let mut kml: Kml = r#"<Placemark>
+    ///   <LineString><coordinates>0,0 1113194.9,0</coordinates></LineString>
+    /// </Placemark>"#.parse().unwrap();
+    /// assert_eq!(kml.transform_coords(to_wgs84), 2);
+    /// let placemark = kml.placemarks().next().unwrap();
+    /// if let Some(Geometry::LineString(l)) = &placemark.geometry {
+    ///     assert!((l.coords[1].x - 10.).abs() < 1e-6);
+    /// }
+    /// ```
+    pub fn transform_coords<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(Coord<T>) -> Coord<T>,
+    {
+        let mut visitor = TransformCoords(&mut f, 0);
+        self.walk(&mut visitor);
+        visitor.1
+    }
+
+    /// Reprojects every coordinate in the tree from one coordinate reference system to another,
+    /// returning the number of coordinates transformed
+    ///
+    /// This covers the same coordinates as [`transform_coords`](#method.transform_coords).
+    /// Geographic coordinates are in degrees on both sides, and altitudes are only transformed
+    /// for coordinates that have them. If any coordinate can't be reprojected, the error is
+    /// returned and the tree is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::Kml;
+    /// use proj4rs::Proj;
+    ///
+    /// let utm = Proj::from_proj_string("+proj=utm +zone=33 +datum=WGS84").unwrap();
+    /// let wgs84 = Proj::from_proj_string("+proj=longlat +datum=WGS84").unwrap();
+    ///
+    /// let mut kml: Kml = "<Point><coordinates>500000,0</coordinates></Point>".parse().unwrap();
+    /// assert_eq!(kml.reproject(&utm, &wgs84).unwrap(), 1);
+    /// if let Kml::Point(p) = kml {
+    ///     assert!((p.coord.x - 15.).abs() < 1e-9 && p.coord.y.abs() < 1e-9);
+    /// }
+    /// ```
+    #[cfg(feature = "proj")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "proj")))]
+    pub fn reproject(&mut self, from: &Proj, to: &Proj) -> Result<usize, Error> {
+        let mut kml = self.clone();
+        let mut error = None;
+        let count = kml.transform_coords(|c| {
+            if error.is_some() {
+                return c;
+            }
+            reproject_coord(c, from, to).unwrap_or_else(|e| {
+                error = Some(e);
+                c
+            })
+        });
+        match error {
+            Some(e) => Err(e),
+            None => {
+                *self = kml;
+                Ok(count)
+            }
+        }
+    }
+}
+
+// proj4rs takes and returns geographic coordinates in radians
+#[cfg(feature = "proj")]
+fn reproject_coord<T: CoordType>(
+    coord: Coord<T>,
+    from: &Proj,
+    to: &Proj,
+) -> Result<Coord<T>, Error> {
+    let (mut x, mut y) = (coord.x.to_f64().unwrap(), coord.y.to_f64().unwrap());
+    if from.is_latlong() {
+        x = x.to_radians();
+        y = y.to_radians();
+    }
+    let mut point = (x, y, coord.z.and_then(|z| z.to_f64()).unwrap_or(0.));
+    proj4rs::transform::transform(from, to, &mut point)?;
+    if to.is_latlong() {
+        point.0 = point.0.to_degrees();
+        point.1 = point.1.to_degrees();
+    }
+    Ok(Coord::new(
+        lit(point.0),
+        lit(point.1),
+        coord.z.map(|_| lit(point.2)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_coords() {
+        let mut kml: Kml = r#"<Document>
+          <Placemark>
+            <LookAt><longitude>1</longitude><latitude>2</latitude><altitude>3</altitude></LookAt>
+            <MultiGeometry>
+              <Point><coordinates>1,2,3</coordinates></Point>
+              <Polygon>
+                <outerBoundaryIs><LinearRing><coordinates>0,0 1,0 1,1 0,0</coordinates></LinearRing></outerBoundaryIs>
+              </Polygon>
+            </MultiGeometry>
+          </Placemark>
+          <Location><longitude>1</longitude><latitude>2</latitude><altitude>3</altitude></Location>
+        </Document>"#
+            .parse()
+            .unwrap();
+        let shift = |c: Coord| Coord::new(c.x + 10., c.y + 20., None);
+        assert_eq!(kml.transform_coords(shift), 7);
+
+        let expected: Kml = r#"<Document>
+          <Placemark>
+            <LookAt><longitude>11</longitude><latitude>22</latitude><altitude>3</altitude></LookAt>
+            <MultiGeometry>
+              <Point><coordinates>11,22</coordinates></Point>
+              <Polygon>
+                <outerBoundaryIs><LinearRing><coordinates>10,20 11,20 11,21 10,20</coordinates></LinearRing></outerBoundaryIs>
+              </Polygon>
+            </MultiGeometry>
+          </Placemark>
+          <Location><longitude>11</longitude><latitude>22</latitude><altitude>3</altitude></Location>
+        </Document>"#
+            .parse()
+            .unwrap();
+        assert_eq!(kml, expected);
+    }
+
+    #[test]
+    fn test_transform_bounds() {
+        let mut kml: Kml = r#"<Document>
+          <NetworkLink>
+            <Region><LatLonAltBox><north>2</north><south>1</south><east>4</east><west>3</west></LatLonAltBox></Region>
+          </NetworkLink>
+          <GroundOverlay>
+            <LatLonBox><north>2</north><south>1</south><east>4</east><west>3</west><rotation>10</rotation></LatLonBox>
+          </GroundOverlay>
+        </Document>"#
+            .parse()
+            .unwrap();
+        // Mirrors across both axes, so the edges swap
+        assert_eq!(
+            kml.transform_coords(|c: Coord| Coord::new(-c.x, -c.y, c.z)),
+            8
+        );
+
+        let expected: Kml = r#"<Document>
+          <NetworkLink>
+            <Region><LatLonAltBox><north>-1</north><south>-2</south><east>-3</east><west>-4</west></LatLonAltBox></Region>
+          </NetworkLink>
+          <GroundOverlay>
+            <LatLonBox><north>-1</north><south>-2</south><east>-3</east><west>-4</west><rotation>10</rotation></LatLonBox>
+          </GroundOverlay>
+        </Document>"#
+            .parse()
+            .unwrap();
+        assert_eq!(kml, expected);
+    }
+
+    #[cfg(feature = "proj")]
+    #[test]
+    fn test_reproject() {
+        let utm = Proj::from_proj_string("+proj=utm +zone=33 +datum=WGS84").unwrap();
+        let wgs84 = Proj::from_proj_string("+proj=longlat +datum=WGS84").unwrap();
+        let kml_str = "<Placemark><LineString><coordinates>500000,0,10 500000,1000000</coordinates></LineString></Placemark>";
+        let coords = |kml: &Kml| match &kml.placemarks().next().unwrap().geometry {
+            Some(Geometry::LineString(l)) => l.coords.clone(),
+            g => panic!("expected LineString, found {:?}", g),
+        };
+        let original: Kml = kml_str.parse().unwrap();
+        let mut kml = original.clone();
+        assert_eq!(kml.reproject(&utm, &wgs84).unwrap(), 2);
+        let lon_lat = coords(&kml);
+        assert!(lon_lat[0].approx_eq(&Coord::new(15., 0., Some(10.)), 1e-9));
+        assert_eq!(lon_lat[1].z, None);
+        assert!((lon_lat[1].x - 15.).abs() < 1e-9 && (lon_lat[1].y - 9.0466).abs() < 1e-4);
+
+        assert_eq!(kml.reproject(&wgs84, &utm).unwrap(), 2);
+        for (a, b) in coords(&kml).iter().zip(&coords(&original)) {
+            assert!(a.approx_eq(b, 1e-6));
+        }
+
+        // Latitudes past the poles can't be projected, which leaves the tree as it was
+        let mut kml: Kml = "<LineString><coordinates>15,0 15,100</coordinates></LineString>"
+            .parse()
+            .unwrap();
+        let before = kml.clone();
+        assert!(kml.reproject(&wgs84, &utm).is_err());
+        assert_eq!(kml, before);
+    }
+}
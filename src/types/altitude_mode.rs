@@ -1,23 +1,63 @@
 use std::fmt;
 use std::str::FromStr;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::errors::Error;
+use crate::types::element::Element;
 
 /// `kml:altitudeMode`, [9.20](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#322) in the
 /// KML specification
-#[derive(Copy, Clone, Debug, PartialEq)]
+///
+/// The sea floor modes are part of `kml:altitudeMode` in KML 2.3, and are only available as
+/// `gx:altitudeMode` in KML 2.2.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AltitudeMode {
+    #[default]
     ClampToGround,
     RelativeToGround,
     Absolute,
+    ClampToSeaFloor,
+    RelativeToSeaFloor,
 }
 
-impl Default for AltitudeMode {
-    fn default() -> AltitudeMode {
-        AltitudeMode::ClampToGround
+impl AltitudeMode {
+    /// Returns `true` for the modes that ignore altitudes and clamp to the ground or sea floor
+    pub fn is_clamped(&self) -> bool {
+        matches!(self, Self::ClampToGround | Self::ClampToSeaFloor)
+    }
+
+    /// Returns `true` for the sea floor modes
+    pub fn is_sea_floor(&self) -> bool {
+        matches!(self, Self::ClampToSeaFloor | Self::RelativeToSeaFloor)
+    }
+
+    /// Returns the equivalent mode relative to the ground for the sea floor modes, for versions of
+    /// KML without them
+    pub fn to_ground(self) -> Self {
+        match self {
+            Self::ClampToSeaFloor => Self::ClampToGround,
+            Self::RelativeToSeaFloor => Self::RelativeToGround,
+            mode => mode,
+        }
     }
 }
 
+// Element for writing a typed extension's altitude mode, omitted when it's the default
+pub(crate) fn altitude_mode_element(altitude_mode: &AltitudeMode) -> Option<Element> {
+    if *altitude_mode == AltitudeMode::default() {
+        return None;
+    }
+    let name = if altitude_mode.is_sea_floor() {
+        "gx:altitudeMode"
+    } else {
+        "altitudeMode"
+    };
+    Some(Element::new(name).with_content(altitude_mode.to_string()))
+}
+
 impl FromStr for AltitudeMode {
     type Err = Error;
 
@@ -26,6 +66,8 @@ impl FromStr for AltitudeMode {
             "clampToGround" => Ok(Self::ClampToGround),
             "relativeToGround" => Ok(Self::RelativeToGround),
             "absolute" => Ok(Self::Absolute),
+            "clampToSeaFloor" => Ok(Self::ClampToSeaFloor),
+            "relativeToSeaFloor" => Ok(Self::RelativeToSeaFloor),
             v => Err(Error::InvalidAltitudeMode(v.to_string())),
         }
     }
@@ -40,7 +82,28 @@ impl fmt::Display for AltitudeMode {
                 Self::ClampToGround => "clampToGround",
                 Self::RelativeToGround => "relativeToGround",
                 Self::Absolute => "absolute",
+                Self::ClampToSeaFloor => "clampToSeaFloor",
+                Self::RelativeToSeaFloor => "relativeToSeaFloor",
             }
         )
     }
 }
+
+/// Unit of altitudes, for converting data recorded in feet to the meters required by KML. See
+/// [`Kml::convert_altitudes`](../enum.Kml.html#method.convert_altitudes)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AltitudeUnit {
+    Meters,
+    Feet,
+}
+
+impl AltitudeUnit {
+    /// Returns the length of the unit in meters
+    pub fn meters(&self) -> f64 {
+        match self {
+            Self::Meters => 1.,
+            Self::Feet => 0.3048,
+        }
+    }
+}
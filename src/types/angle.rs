@@ -0,0 +1,101 @@
+//! Ranges of angles used by `kml:Orientation`, `kml:IconStyle`, and views like `kml:Camera`, along
+//! with normalization of values outside of them
+use num_traits::Float;
+
+use crate::types::coord::lit;
+
+pub(crate) const HEADING: (f64, f64) = (0., 360.);
+pub(crate) const ROLL: (f64, f64) = (-180., 180.);
+pub(crate) const CAMERA_TILT: (f64, f64) = (0., 180.);
+pub(crate) const LOOK_AT_TILT: (f64, f64) = (0., 90.);
+pub(crate) const LATITUDE: (f64, f64) = (-90., 90.);
+pub(crate) const LONGITUDE: (f64, f64) = (-180., 180.);
+
+fn in_range<T: Float>(value: T, (min, max): (f64, f64)) -> bool {
+    value >= lit(min) && value <= lit(max)
+}
+
+// Wraps the value around the range, which must span 360 degrees
+fn wrap<T: Float>(value: T, range: (f64, f64)) -> T {
+    if in_range(value, range) {
+        return value;
+    }
+    let wrapped = (value - lit(range.0)) % lit(360.);
+    let wrapped = if wrapped < T::zero() {
+        wrapped + lit(360.)
+    } else {
+        wrapped
+    };
+    wrapped + lit(range.0)
+}
+
+fn clamp<T: Float>(value: T, (min, max): (f64, f64)) -> T {
+    value.max(lit(min)).min(lit(max))
+}
+
+/// Field of an orientation or view that has a restricted range of angles
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Angle {
+    Heading,
+    Roll,
+    Tilt((f64, f64)),
+    Latitude,
+    Longitude,
+}
+
+impl Angle {
+    /// Returns the field's angle for a child element of a view or `kml:Orientation`
+    pub(crate) fn for_field(parent: &str, field: &str) -> Option<Self> {
+        match (parent, field) {
+            ("Camera" | "LookAt" | "Orientation", "heading") => Some(Self::Heading),
+            ("Camera" | "Orientation", "tilt") => Some(Self::Tilt(CAMERA_TILT)),
+            ("LookAt", "tilt") => Some(Self::Tilt(LOOK_AT_TILT)),
+            ("Camera" | "Orientation", "roll") => Some(Self::Roll),
+            ("Camera" | "LookAt", "latitude") => Some(Self::Latitude),
+            ("Camera" | "LookAt", "longitude") => Some(Self::Longitude),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn range(self) -> (f64, f64) {
+        match self {
+            Self::Heading => HEADING,
+            Self::Roll => ROLL,
+            Self::Tilt(range) => range,
+            Self::Latitude => LATITUDE,
+            Self::Longitude => LONGITUDE,
+        }
+    }
+
+    /// Wraps headings, rolls, and longitudes around their range and clamps tilts and latitudes,
+    /// leaving values within the range as is
+    pub(crate) fn normalize<T: Float>(self, value: T) -> T {
+        match self {
+            Self::Heading | Self::Roll | Self::Longitude => wrap(value, self.range()),
+            Self::Tilt(_) | Self::Latitude => clamp(value, self.range()),
+        }
+    }
+
+    /// Normalizes the value in place, returning `true` if it changed
+    pub(crate) fn normalize_in_place<T: Float>(self, value: &mut T) -> bool {
+        let normalized = self.normalize(*value);
+        let changed = normalized != *value;
+        *value = normalized;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(Angle::Heading.normalize(-90.), 270.);
+        assert_eq!(Angle::Heading.normalize(720.5), 0.5);
+        assert_eq!(Angle::Heading.normalize(360.), 360.);
+        assert_eq!(Angle::Roll.normalize(190.), -170.);
+        assert_eq!(Angle::Tilt(LOOK_AT_TILT).normalize(120.), 90.);
+        assert_eq!(Angle::Latitude.normalize(-95.), -90.);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::types::coord::CoordType;
+use crate::types::placemark::Placemark;
+use crate::types::style::BalloonStyle;
+
+impl BalloonStyle {
+    /// Renders `text` for a placemark by replacing its entities, producing the HTML shown in the
+    /// balloon
+    ///
+    /// Entities follow the [balloon text](https://developers.google.com/kml/documentation/kmlreference#balloonstyle)
+    /// rules of Google Earth:
+    ///
+    /// - `$[name]`, `$[description]`, `$[address]`, `$[Snippet]`, and `$[id]` are replaced with
+    ///   the placemark's values
+    /// - `$[dataName]` is replaced with the value of the `kml:Data` with that name in the
+    ///   placemark's `kml:ExtendedData`, and `$[dataName/displayName]` with its display name
+    /// - `$[schemaName/fieldName]` is replaced with the value of the `kml:SimpleData` with that
+    ///   name in a `kml:SchemaData`
+    ///
+    /// Entities without a value, including `$[geDirections]`, are replaced with an empty string.
+    /// Without `text`, the name is rendered followed by the description. Values are inserted as is,
+    /// since descriptions are commonly HTML already.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::Kml;
+    /// use kml::types::BalloonStyle;
+    ///
+    /// let kml: Kml = r#"<Placemark>
+    ///   <name>Trailhead</name>
+    ///   <ExtendedData>
+    ///     <Data name="elevation"><displayName>Elevation</displayName><value>1200</value></Data>
+    ///   </ExtendedData>
+    /// </Placemark>"#.parse().unwrap();
+    ///
+    /// let style = BalloonStyle::default()
+    ///     .with_text("<b>$[name]</b> $[elevation/displayName]: $[elevation] m");
+    /// if let Kml::Placemark(placemark) = kml {
+    ///     assert_eq!(style.render(&placemark), "<b>Trailhead</b> Elevation: 1200 m");
+    /// }
+    /// ```
+    pub fn render<T: CoordType>(&self, placemark: &Placemark<T>) -> String {
+        let template = self
+            .text
+            .as_deref()
+            .unwrap_or("<h3>$[name]</h3>$[description]");
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("$[") {
+            let end = match rest[start..].find(']') {
+                Some(end) => start + end,
+                None => break,
+            };
+            rendered.push_str(&rest[..start]);
+            rendered.push_str(&entity_value(placemark, &rest[start + 2..end]).unwrap_or_default());
+            rest = &rest[end + 1..];
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+}
+
+fn entity_value<T: CoordType>(placemark: &Placemark<T>, entity: &str) -> Option<String> {
+    match entity {
+        "name" => return placemark.name.clone(),
+        "description" => return placemark.description.clone(),
+        "id" => return placemark.attrs.get("id").cloned(),
+        "address" => return placemark.address.clone(),
+        "Snippet" => return placemark.snippet.as_ref().map(|s| s.text.clone()),
+        _ => {}
+    }
+    let extended_data = placemark.extended_data.as_ref()?;
+    let data = |name: &str| extended_data.data.iter().find(|d| d.name == name);
+    match entity.split_once('/') {
+        None => extended_data.data_value(entity).map(String::from),
+        Some((name, "displayName")) if data(name).is_some() => {
+            let data = data(name)?;
+            Some(data.display_name.as_deref().unwrap_or(name).to_string())
+        }
+        Some((_, field)) => extended_data.simple_data_value(field).map(String::from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Kml;
+
+    #[test]
+    fn test_render_balloon() {
+        let kml: Kml = r##"<Placemark id="p1">
+          <name>Trailhead</name>
+          <description><![CDATA[<i>Parking</i>]]></description>
+          <address>1 Trail Rd</address>
+          <ExtendedData>
+            <Data name="open"><value>yes</value></Data>
+            <SchemaData schemaUrl="#trail"><SimpleData name="length">4.2</SimpleData></SchemaData>
+          </ExtendedData>
+        </Placemark>"##
+            .parse()
+            .unwrap();
+        let placemark = match kml {
+            Kml::Placemark(p) => p,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            BalloonStyle::default().render(&placemark),
+            "<h3>Trailhead</h3><i>Parking</i>"
+        );
+        let style = BalloonStyle::default().with_text(
+            "$[id] $[address] $[open/displayName]=$[open] $[Trail/length] km$[geDirections] $[missing",
+        );
+        assert_eq!(
+            style.render(&placemark),
+            "p1 1 Trail Rd open=yes 4.2 km $[missing"
+        );
+    }
+}
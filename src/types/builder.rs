@@ -0,0 +1,489 @@
+use std::collections::HashMap;
+
+use crate::types::altitude_mode::AltitudeMode;
+use crate::types::coord::CoordType;
+use crate::types::element::Element;
+use crate::types::extended_data::ExtendedData;
+use crate::types::feature::FeatureProps;
+use crate::types::geometry::Geometry;
+use crate::types::kml::Kml;
+use crate::types::linear_ring::LinearRing;
+use crate::types::placemark::Placemark;
+use crate::types::polygon::Polygon;
+use crate::types::style::{
+    BalloonStyle, IconStyle, LabelStyle, LineStyle, ListStyle, PolyStyle, Style,
+};
+use crate::types::time_primitive::TimePrimitive;
+use crate::types::view::AbstractView;
+
+/// Builder for a [`Placemark`](struct.Placemark.html), created with
+/// [`Placemark::builder`](struct.Placemark.html#method.builder)
+///
+/// # Example
+///
+/// ```
+/// use kml::types::{Placemark, Point};
+///
+/// let placemark: Placemark = Placemark::builder()
+///     .id("home")
+///     .name("Home")
+///     .style_url("#house")
+///     .geometry(Point::new(-105.2, 39.5, None))
+///     .build();
+/// assert_eq!(placemark.attrs["id"], "home");
+/// assert_eq!(placemark.style_url.as_deref(), Some("#house"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PlacemarkBuilder<T: CoordType = f64> {
+    placemark: Placemark<T>,
+}
+
+impl<T> Placemark<T>
+where
+    T: CoordType + Default,
+{
+    /// Returns a builder for a placemark
+    pub fn builder() -> PlacemarkBuilder<T> {
+        PlacemarkBuilder::default()
+    }
+}
+
+impl<T> PlacemarkBuilder<T>
+where
+    T: CoordType,
+{
+    pub fn id(self, id: impl Into<String>) -> Self {
+        self.attr("id", id)
+    }
+
+    pub fn attr(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.placemark.attrs.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.placemark.name = Some(name.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.placemark.description = Some(description.into());
+        self
+    }
+
+    pub fn view(mut self, view: impl Into<AbstractView<T>>) -> Self {
+        self.placemark.view = Some(view.into());
+        self
+    }
+
+    pub fn time_primitive(mut self, time_primitive: impl Into<TimePrimitive>) -> Self {
+        self.placemark.time_primitive = Some(time_primitive.into());
+        self
+    }
+
+    /// Sets the inline style, which overrides the shared style of the `styleUrl`
+    pub fn style(mut self, style: Style) -> Self {
+        self.placemark.style = Some(style);
+        self
+    }
+
+    pub fn extended_data(mut self, extended_data: ExtendedData) -> Self {
+        self.placemark.extended_data = Some(extended_data);
+        self
+    }
+
+    /// Sets the `kml:styleUrl`, like `#id` for a style in the same document
+    pub fn style_url(mut self, style_url: impl Into<String>) -> Self {
+        self.placemark.style_url = Some(style_url.into());
+        self
+    }
+
+    pub fn geometry(mut self, geometry: impl Into<Geometry<T>>) -> Self {
+        self.placemark.geometry = Some(geometry.into());
+        self
+    }
+
+    /// Adds an element without a typed representation, like `kml:Region`
+    pub fn child(mut self, child: Element) -> Self {
+        self.placemark.children.push(child);
+        self
+    }
+
+    pub fn build(self) -> Placemark<T> {
+        self.placemark
+    }
+}
+
+/// Builder for a [`Style`](struct.Style.html), created with
+/// [`Style::builder`](struct.Style.html#method.builder)
+///
+/// # Example
+///
+/// ```
+/// use kml::types::{Color, LineStyle, PolyStyle, Style};
+///
+/// let blue = Color::from_rgb(0, 0, 0xff);
+/// let style = Style::builder()
+///     .id("lake")
+///     .line(LineStyle { color: blue, ..Default::default() })
+///     .poly(PolyStyle { color: blue.with_alpha(0x7f), ..Default::default() })
+///     .build();
+/// assert_eq!(style.line.unwrap().color, "ffff0000");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StyleBuilder {
+    style: Style,
+}
+
+impl Style {
+    /// Returns a builder for a style
+    pub fn builder() -> StyleBuilder {
+        StyleBuilder::default()
+    }
+}
+
+impl StyleBuilder {
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.style.id = id.into();
+        self
+    }
+
+    pub fn balloon(mut self, balloon: BalloonStyle) -> Self {
+        self.style.balloon = Some(balloon);
+        self
+    }
+
+    pub fn icon(mut self, icon: IconStyle) -> Self {
+        self.style.icon = Some(icon);
+        self
+    }
+
+    pub fn label(mut self, label: LabelStyle) -> Self {
+        self.style.label = Some(label);
+        self
+    }
+
+    pub fn line(mut self, line: LineStyle) -> Self {
+        self.style.line = Some(line);
+        self
+    }
+
+    pub fn poly(mut self, poly: PolyStyle) -> Self {
+        self.style.poly = Some(poly);
+        self
+    }
+
+    pub fn list(mut self, list: ListStyle) -> Self {
+        self.style.list = Some(list);
+        self
+    }
+
+    pub fn build(self) -> Style {
+        self.style
+    }
+}
+
+/// Builder for a [`Polygon`](struct.Polygon.html), created with
+/// [`Polygon::builder`](struct.Polygon.html#method.builder)
+///
+/// # Example
+///
+/// ```
+/// use kml::types::{AltitudeMode, LinearRing, Polygon};
+///
+/// let polygon: Polygon = Polygon::builder()
+///     .outer(LinearRing::from_coords(vec![(0., 0.), (4., 0.), (4., 4.), (0., 0.)]))
+///     .inner(LinearRing::from_coords(vec![(1., 1.), (2., 2.), (2., 1.), (1., 1.)]))
+///     .altitude_mode(AltitudeMode::RelativeToGround)
+///     .extrude(true)
+///     .build();
+/// assert_eq!(polygon.inner.len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PolygonBuilder<T: CoordType = f64> {
+    polygon: Polygon<T>,
+}
+
+impl<T> Polygon<T>
+where
+    T: CoordType + Default,
+{
+    /// Returns a builder for a polygon
+    pub fn builder() -> PolygonBuilder<T> {
+        PolygonBuilder::default()
+    }
+}
+
+impl<T> PolygonBuilder<T>
+where
+    T: CoordType,
+{
+    pub fn id(self, id: impl Into<String>) -> Self {
+        self.attr("id", id)
+    }
+
+    pub fn attr(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.polygon.attrs.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn outer(mut self, outer: impl Into<LinearRing<T>>) -> Self {
+        self.polygon.outer = outer.into();
+        self
+    }
+
+    /// Adds an inner boundary
+    pub fn inner(mut self, inner: impl Into<LinearRing<T>>) -> Self {
+        self.polygon.inner.push(inner.into());
+        self
+    }
+
+    pub fn extrude(mut self, extrude: bool) -> Self {
+        self.polygon.extrude = extrude;
+        self
+    }
+
+    pub fn tessellate(mut self, tessellate: bool) -> Self {
+        self.polygon.tessellate = tessellate;
+        self
+    }
+
+    pub fn altitude_mode(mut self, altitude_mode: AltitudeMode) -> Self {
+        self.polygon.altitude_mode = altitude_mode;
+        self
+    }
+
+    pub fn build(self) -> Polygon<T> {
+        self.polygon
+    }
+}
+
+/// Builder for a [`Kml::Document`](enum.Kml.html#variant.Document) or
+/// [`Kml::Folder`](enum.Kml.html#variant.Folder), created with
+/// [`Kml::document_builder`](enum.Kml.html#method.document_builder) or
+/// [`Kml::folder_builder`](enum.Kml.html#method.folder_builder)
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, types::{Placemark, Point, Style}};
+///
+/// let kml: Kml = Kml::document_builder()
+///     .name("Trip")
+///     .element(Style::builder().id("stop").build())
+///     .element(
+///         Kml::folder_builder()
+///             .name("Day 1")
+///             .elements((0..3).map(|i| {
+///                 Placemark::builder()
+///                     .style_url("#stop")
+///                     .geometry(Point::new(i as f64, 0., None))
+///                     .build()
+///             }))
+///             .build(),
+///     )
+///     .build();
+/// assert!(kml.to_string().contains("<Folder><name>Day 1</name><Placemark>"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DocumentBuilder<T: CoordType = f64> {
+    folder: bool,
+    attrs: HashMap<String, String>,
+    props: FeatureProps,
+    elements: Vec<Kml<T>>,
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Returns a builder for a `kml:Document`
+    pub fn document_builder() -> DocumentBuilder<T> {
+        DocumentBuilder {
+            folder: false,
+            attrs: HashMap::new(),
+            props: FeatureProps::default(),
+            elements: Vec::new(),
+        }
+    }
+
+    /// Returns a builder for a `kml:Folder`
+    pub fn folder_builder() -> DocumentBuilder<T> {
+        DocumentBuilder {
+            folder: true,
+            ..Kml::document_builder()
+        }
+    }
+}
+
+impl<T> DocumentBuilder<T>
+where
+    T: CoordType,
+{
+    pub fn id(self, id: impl Into<String>) -> Self {
+        self.attr("id", id)
+    }
+
+    pub fn attr(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attrs.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.props.name = Some(name.into());
+        self
+    }
+
+    /// Sets the fields shared by features, replacing any `name` that was set
+    pub fn props(mut self, props: FeatureProps) -> Self {
+        self.props = props;
+        self
+    }
+
+    /// Adds a child like a placemark, style, or nested folder
+    pub fn element(mut self, element: impl Into<Kml<T>>) -> Self {
+        self.elements.push(element.into());
+        self
+    }
+
+    /// Adds each of the children
+    pub fn elements<K: Into<Kml<T>>>(mut self, elements: impl IntoIterator<Item = K>) -> Self {
+        self.elements.extend(elements.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn build(self) -> Kml<T> {
+        let DocumentBuilder {
+            folder,
+            attrs,
+            props,
+            elements,
+        } = self;
+        if folder {
+            Kml::Folder {
+                attrs,
+                props,
+                elements,
+            }
+        } else {
+            Kml::Document {
+                attrs,
+                props,
+                elements,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Data, LookAt, Point, TimeStamp};
+
+    #[test]
+    fn test_placemark_builder() {
+        let placemark: Placemark = Placemark::builder()
+            .id("p1")
+            .attr("targetId", "p0")
+            .name("Home")
+            .description("Where we live")
+            .view(LookAt::new(1., 2., 100.))
+            .time_primitive(TimeStamp::default())
+            .style(Style::builder().id("inline").build())
+            .extended_data(ExtendedData::default().with_data(Data::new("rooms", "3")))
+            .style_url("#house")
+            .geometry(Point::new(1., 2., None))
+            .child(Element::new("Region"))
+            .build();
+        assert_eq!(placemark.attrs.len(), 2);
+        assert_eq!(placemark.attrs["id"], "p1");
+        assert_eq!(placemark.description.as_deref(), Some("Where we live"));
+        assert!(matches!(placemark.view, Some(AbstractView::LookAt(_))));
+        assert!(matches!(
+            placemark.time_primitive,
+            Some(TimePrimitive::TimeStamp(_))
+        ));
+        assert!(matches!(placemark.geometry, Some(Geometry::Point(_))));
+        assert_eq!(placemark.children, vec![Element::new("Region")]);
+
+        // Setting a value again replaces it, and an empty builder builds the default
+        let placemark: Placemark = Placemark::builder().id("a").id("b").name("x").build();
+        assert_eq!(placemark.attrs["id"], "b");
+        assert_eq!(Placemark::<f64>::builder().build(), Placemark::default());
+    }
+
+    #[test]
+    fn test_style_builder() {
+        let style = Style::builder()
+            .id("s")
+            .balloon(BalloonStyle::default())
+            .icon(IconStyle::default())
+            .label(LabelStyle::default())
+            .line(LineStyle::default())
+            .poly(PolyStyle::default())
+            .list(ListStyle::default())
+            .build();
+        assert_eq!(style.id, "s");
+        assert!(style.balloon.is_some() && style.icon.is_some() && style.label.is_some());
+        assert!(style.line.is_some() && style.poly.is_some() && style.list.is_some());
+        assert_eq!(Style::builder().build(), Style::default());
+    }
+
+    #[test]
+    fn test_polygon_builder() {
+        let ring = || LinearRing::from_coords(vec![(0., 0.), (1., 0.), (1., 1.), (0., 0.)]);
+        let polygon: Polygon = Polygon::builder()
+            .id("lake")
+            .outer(ring())
+            .inner(ring())
+            .inner(ring())
+            .tessellate(true)
+            .build();
+        assert_eq!(polygon.outer, ring());
+        assert_eq!(polygon.inner.len(), 2);
+        assert!(polygon.tessellate && !polygon.extrude);
+        assert_eq!(polygon.altitude_mode, AltitudeMode::ClampToGround);
+        assert_eq!(Polygon::<f64>::builder().build(), Polygon::default());
+    }
+
+    #[test]
+    fn test_document_builder() {
+        let folder: Kml = Kml::folder_builder()
+            .id("f")
+            .props(FeatureProps::default().with_name("Stops"))
+            .elements(vec![Placemark::default(), Placemark::default()])
+            .build();
+        match &folder {
+            Kml::Folder {
+                attrs,
+                props,
+                elements,
+            } => {
+                assert_eq!(attrs["id"], "f");
+                assert_eq!(props.name.as_deref(), Some("Stops"));
+                assert_eq!(elements.len(), 2);
+            }
+            k => panic!("expected Folder, found {:?}", k),
+        }
+
+        // Props replace the name set before them
+        let document: Kml = Kml::document_builder()
+            .name("Trip")
+            .props(FeatureProps::default())
+            .element(folder)
+            .build();
+        match document {
+            Kml::Document {
+                props, elements, ..
+            } => {
+                assert_eq!(props.name, None);
+                assert!(matches!(elements[0], Kml::Folder { .. }));
+            }
+            k => panic!("expected Document, found {:?}", k),
+        }
+        assert!(matches!(
+            Kml::<f64>::document_builder().build(),
+            Kml::Document { elements, .. } if elements.is_empty()
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+
+/// `kml:color`, [16.9](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#1180) in the KML
+/// specification
+///
+/// KML writes colors as 8 hex digits in `aabbggrr` order, the reverse of the `rrggbbaa` order used
+/// by HTML and CSS. `Color` parses and formats the KML order, with
+/// [`from_hex_rgb`](#method.from_hex_rgb) and [`to_hex_rgb`](#method.to_hex_rgb) for the HTML
+/// order. Colors compare equal to strings they parse from, so style colors can be checked
+/// against `aabbggrr` values directly.
+///
+/// # Example
+///
+/// ```
+/// use kml::types::{Color, LineStyle};
+///
+/// let orange = Color::from_hex_rgb("#ff8000").unwrap();
+/// assert_eq!(orange.to_string(), "ff0080ff");
+/// assert_eq!("7f0080FF".parse::<Color>().unwrap(), orange.with_alpha(0x7f));
+///
+/// let line = LineStyle::default().with_color(orange);
+/// assert_eq!(line.color, "ff0080ff");
+/// assert_eq!(line.color.to_rgba(), [0xff, 0x80, 0x00, 0xff]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Color {
+    pub a: u8,
+    pub b: u8,
+    pub g: u8,
+    pub r: u8,
+}
+
+impl Color {
+    /// Opaque white, the default color of styles, which leaves icons and lines unchanged
+    pub const WHITE: Color = Color::from_rgb(0xff, 0xff, 0xff);
+    pub const BLACK: Color = Color::from_rgb(0, 0, 0);
+
+    pub const fn from_rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { a, b, g, r }
+    }
+
+    /// Creates an opaque color
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Color::from_rgba(r, g, b, 0xff)
+    }
+
+    /// Parses a color in the `rrggbb` or `rrggbbaa` order used by HTML and CSS, optionally
+    /// prefixed with `#`, with full opacity if alpha isn't included
+    pub fn from_hex_rgb(s: &str) -> Result<Self, Error> {
+        let hex = s.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        let [r, g, b, a] = match (hex.len(), parse_hex(hex)) {
+            (6, Some(v)) => [(v >> 16) as u8, (v >> 8) as u8, v as u8, 0xff],
+            (8, Some(v)) => v.to_be_bytes(),
+            _ => return Err(Error::InvalidColor(s.to_string())),
+        };
+        Ok(Color::from_rgba(r, g, b, a))
+    }
+
+    // Parses a color in `aabbggrr` order, or converts one in the `rrggbb` or `#`-prefixed order of
+    // HTML that KML files often use by mistake
+    pub(crate) fn parse_normalized(s: &str) -> Result<Self, Error> {
+        let color = s.trim();
+        if color.starts_with('#') || color.len() == 6 {
+            Color::from_hex_rgb(s)
+        } else {
+            s.parse()
+        }
+    }
+
+    /// Returns the color with the alpha replaced, where 0 is transparent and 255 is opaque
+    pub fn with_alpha(mut self, a: u8) -> Self {
+        self.a = a;
+        self
+    }
+
+    pub fn to_rgba(&self) -> [u8; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    /// Formats the color in the `#rrggbb` order used by HTML and CSS, or `#rrggbbaa` if it isn't
+    /// opaque
+    pub fn to_hex_rgb(&self) -> String {
+        if self.a == 0xff {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<u32> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Color::WHITE
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    /// Parses 8 hex digits in `aabbggrr` order, ignoring case and surrounding whitespace
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.trim();
+        match (hex.len(), parse_hex(hex)) {
+            (8, Some(v)) => {
+                let [a, b, g, r] = v.to_be_bytes();
+                Ok(Color { a, b, g, r })
+            }
+            _ => Err(Error::InvalidColor(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    /// Formats the color as 8 lowercase hex digits in `aabbggrr` order
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02x}{:02x}{:02x}{:02x}",
+            self.a, self.b, self.g, self.r
+        )
+    }
+}
+
+impl PartialEq<str> for Color {
+    fn eq(&self, other: &str) -> bool {
+        matches!(other.parse::<Color>(), Ok(other) if *self == other)
+    }
+}
+
+impl PartialEq<&str> for Color {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq<String> for Color {
+    fn eq(&self, other: &String) -> bool {
+        *self == *other.as_str()
+    }
+}
+
+impl PartialEq<Color> for str {
+    fn eq(&self, other: &Color) -> bool {
+        *other == *self
+    }
+}
+
+impl PartialEq<Color> for &str {
+    fn eq(&self, other: &Color) -> bool {
+        *other == **self
+    }
+}
+
+impl PartialEq<Color> for String {
+    fn eq(&self, other: &Color) -> bool {
+        *other == *self.as_str()
+    }
+}
+
+impl From<Color> for String {
+    fn from(color: Color) -> String {
+        color.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color() {
+        let color = Color::from_rgba(0x12, 0x34, 0x56, 0x78);
+        assert_eq!(color.to_string(), "78563412");
+        assert_eq!(" 78563412 ".parse::<Color>().unwrap(), color);
+        assert_eq!(color.to_hex_rgb(), "#12345678");
+        assert_eq!(Color::from_hex_rgb("12345678").unwrap(), color);
+        assert_eq!(Color::BLACK.to_hex_rgb(), "#000000");
+        assert_eq!(String::from(Color::default()), "ffffffff");
+        assert_eq!(color, "78563412");
+        assert_eq!(color, "78563412".to_uppercase().as_str());
+        assert_ne!(color, "12345678");
+        assert_eq!("78563412", color);
+        assert_eq!(*"78563412", color);
+        assert_eq!("78563412".to_string(), color);
+        assert_eq!(color, "78563412".to_string());
+        assert_ne!("12345678", color);
+
+        for invalid in [
+            "",
+            "ff0000",
+            "#ff0000ff",
+            "gg0000ff",
+            "+f0000ff",
+            "ff0000ff0",
+        ] {
+            assert!(
+                matches!(invalid.parse::<Color>(), Err(Error::InvalidColor(_))),
+                "{}",
+                invalid
+            );
+        }
+        assert!(Color::from_hex_rgb("#ff00").is_err());
+    }
+}
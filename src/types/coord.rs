@@ -3,6 +3,9 @@ use std::str::FromStr;
 
 use num_traits::Float;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::errors::Error;
 
 /// Coordinate type compatible with `geo-types`
@@ -13,8 +16,10 @@ impl<T: Float + Debug> CoordType for T {}
 /// in the KML specification
 ///
 /// Coordinates are tuples with the third Z value for altitude being optional. Coordinate tuples are
-/// separated by any whitespace character
+/// separated by any whitespace character, and tuples with fewer than 2 or more than 3 components
+/// are an error rather than being truncated.
 #[derive(Copy, Clone, Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Coord<T: CoordType = f64> {
     pub x: T,
     pub y: T,
@@ -28,6 +33,29 @@ where
     pub fn new(x: T, y: T, z: Option<T>) -> Self {
         Coord { x, y, z }
     }
+
+    /// Returns `true` if the coordinates are within `tolerance` of each other in each dimension,
+    /// with altitudes only compared if both coordinates have one
+    pub fn approx_eq(&self, other: &Coord<T>, tolerance: T) -> bool {
+        (self.x - other.x).abs() <= tolerance
+            && (self.y - other.y).abs() <= tolerance
+            && match (self.z, other.z) {
+                (Some(a), Some(b)) => (a - b).abs() <= tolerance,
+                _ => true,
+            }
+    }
+}
+
+// Converts a literal to the coordinate type, which can represent every value the crate uses
+pub(crate) fn lit<T: Float>(v: f64) -> T {
+    T::from(v).unwrap()
+}
+
+// Removes coordinates within the tolerance of the previous one, returning the number removed
+pub(crate) fn dedup_coords<T: CoordType>(coords: &mut Vec<Coord<T>>, tolerance: T) -> usize {
+    let len = coords.len();
+    coords.dedup_by(|c, prev| c.approx_eq(prev, tolerance));
+    len - coords.len()
 }
 
 impl<T> From<(T, T)> for Coord<T>
@@ -82,21 +110,30 @@ where
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.trim().split(',');
-        let x_str = parts.next().ok_or(Error::CoordEmpty)?;
-        let x: T = x_str
-            .parse()
-            .map_err(|_| Error::NumParse(x_str.to_string()))?;
-        let y_str = parts.next().ok_or(Error::CoordEmpty)?;
-        let y: T = y_str
-            .parse()
-            .map_err(|_| Error::NumParse(y_str.to_string()))?;
-        let z = if let Some(z) = parts.next() {
-            Some(z.parse::<T>().map_err(|_| Error::NumParse(z.to_string()))?)
-        } else {
-            None
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(Error::CoordEmpty);
+        }
+        // Parts are only counted for the error, so valid tuples are parsed without allocating
+        let arity_error = || Error::InvalidCoordArity {
+            tuple: s.to_string(),
+            found: s.split(',').count(),
+        };
+        let mut parts = s.split(',');
+        let (x, y, z) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(x), Some(y), z) if parts.next().is_none() => (x, y, z),
+            _ => return Err(arity_error()),
+        };
+        let parse = |part: &str| {
+            let part = part.trim();
+            part.parse::<T>()
+                .map_err(|_| Error::NumParse(part.to_string()))
         };
-        Ok(Coord { x, y, z })
+        Ok(Coord {
+            x: parse(x)?,
+            y: parse(y)?,
+            z: z.map(parse).transpose()?,
+        })
     }
 }
 
@@ -115,6 +152,9 @@ where
 
 /// Utility method for parsing multiple coordinates according to the spec
 ///
+/// Whitespace next to the commas within a tuple (i.e. `1, 2, 3`) isn't allowed by the spec, but is
+/// common enough in exported KML that it's accepted.
+///
 /// # Example
 ///
 /// ```
@@ -122,14 +162,31 @@ where
 ///
 /// let coords_str = "1,1,0\n\n1,2,0  2,2,0";
 /// let coords: Vec<Coord> = coords_from_str(coords_str).unwrap();
+/// assert_eq!(coords_from_str::<f64>("1, 2 ,0 3,4").unwrap().len(), 2);
 /// ```
 pub fn coords_from_str<T: CoordType + FromStr>(s: &str) -> Result<Vec<Coord<T>>, Error> {
-    s.split_whitespace().map(Coord::from_str).collect()
+    split_tuples(s).map(Coord::from_str).collect()
+}
+
+// Splits coordinates into tuples, joining tuples that were split by whitespace next to commas into
+// one slice that spans them
+pub(crate) fn split_tuples(s: &str) -> impl Iterator<Item = &str> {
+    let offset = move |part: &str| part.as_ptr() as usize - s.as_ptr() as usize;
+    let mut parts = s.split_whitespace().peekable();
+    std::iter::from_fn(move || {
+        let mut last = parts.next()?;
+        let start = offset(last);
+        while let Some(part) = parts.next_if(|part| last.ends_with(',') || part.starts_with(',')) {
+            last = part;
+        }
+        Some(&s[start..offset(last) + last.len()])
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{coords_from_str, Coord};
+    use super::{coords_from_str, dedup_coords, split_tuples, Coord};
+    use crate::errors::Error;
     use std::str::FromStr;
 
     #[test]
@@ -152,6 +209,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_coord_from_str_arity() {
+        assert!(matches!(
+            Coord::<f64>::from_str("1"),
+            Err(Error::InvalidCoordArity { found: 1, .. })
+        ));
+        assert!(matches!(
+            Coord::<f64>::from_str("1,2,3,4"),
+            Err(Error::InvalidCoordArity { found: 4, .. })
+        ));
+        assert!(matches!(
+            Coord::<f64>::from_str(" "),
+            Err(Error::CoordEmpty)
+        ));
+    }
+
+    #[test]
+    fn test_split_tuples() {
+        let tuples: Vec<&str> = split_tuples(" 1,2,3\n4 ,5, 6\t7,8 , 9 ,\n10 ").collect();
+        assert_eq!(tuples, ["1,2,3", "4 ,5, 6", "7,8 , 9 ,\n10"]);
+        assert_eq!(split_tuples(" \n ").count(), 0);
+    }
+
     #[test]
     fn test_coords_from_str() {
         assert_eq!(
@@ -170,4 +250,23 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn test_dedup_coords() {
+        let mut coords = vec![
+            Coord::new(0., 0., Some(10.)),
+            Coord::new(0., 0., Some(20.)),
+            Coord::new(0.0001, 0., None),
+            Coord::new(1., 1., None),
+        ];
+        assert_eq!(dedup_coords(&mut coords, 0.001), 1);
+        assert_eq!(
+            coords,
+            vec![
+                Coord::new(0., 0., Some(10.)),
+                Coord::new(0., 0., Some(20.)),
+                Coord::new(1., 1., None),
+            ]
+        );
+    }
 }
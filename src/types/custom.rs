@@ -0,0 +1,86 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::types::element::{CustomElement, Element};
+
+/// Typed value of an extension element read with a parser registered through
+/// [`KmlReader::register`](../struct.KmlReader.html#method.register), kept along with the element
+/// it was read from so it can be written back
+///
+/// Values are compared by their elements.
+#[derive(Clone)]
+pub struct Custom {
+    element: Element,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+impl Custom {
+    /// Creates a custom value, converting it to an element for writing
+    pub fn new<C>(value: C) -> Self
+    where
+        C: CustomElement + Any + Send + Sync,
+    {
+        Custom {
+            element: value.to_element(),
+            value: Arc::new(value),
+        }
+    }
+
+    // Keeps the element as it was read rather than converting the value back
+    pub(crate) fn from_parts<C>(element: Element, value: C) -> Self
+    where
+        C: Any + Send + Sync,
+    {
+        Custom {
+            element,
+            value: Arc::new(value),
+        }
+    }
+
+    /// Returns the element the value was read from or converted to
+    pub fn element(&self) -> &Element {
+        &self.element
+    }
+
+    // Allows renaming elements when converting between versions without changing the value
+    pub(crate) fn element_mut(&mut self) -> &mut Element {
+        &mut self.element
+    }
+
+    /// Returns the value if it's of type `C`
+    pub fn downcast_ref<C: Any>(&self) -> Option<&C> {
+        self.value.downcast_ref()
+    }
+}
+
+impl fmt::Debug for Custom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Custom")
+            .field("element", &self.element)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Custom {
+    fn eq(&self, other: &Self) -> bool {
+        self.element == other.element
+    }
+}
+
+// Only the element is serialized, so deserialized values can be downcast to `Element` rather than
+// the type they were read as
+#[cfg(feature = "serde")]
+impl serde::Serialize for Custom {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.element, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Custom {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let element = Element::deserialize(deserializer)?;
+        Ok(Custom::from_parts(element.clone(), element))
+    }
+}
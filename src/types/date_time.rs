@@ -0,0 +1,407 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+
+/// `kml:dateTimeType` in the KML specification, used by `kml:when` in `kml:TimeStamp`, and by
+/// `kml:begin` and `kml:end` in `kml:TimeSpan`
+///
+/// Values can be an XML Schema `gYear`, `gYearMonth`, `date`, or `dateTime`, with the precision of
+/// the value indicating the precision of the time period. A `dateTime` can have a UTC offset
+/// (`Z` or `+hh:mm`) or be in local time without one.
+///
+/// # Example
+///
+/// ```
+/// use kml::types::KmlDateTime;
+///
+/// let dt: KmlDateTime = "1997-07-16T07:30:15.5+03:00".parse().unwrap();
+/// assert_eq!(
+///     dt,
+///     KmlDateTime::DateTime {
+///         year: 1997,
+///         month: 7,
+///         day: 16,
+///         hour: 7,
+///         minute: 30,
+///         second: 15,
+///         nanosecond: 500_000_000,
+///         offset: Some(180),
+///     }
+/// );
+/// assert_eq!(dt.to_string(), "1997-07-16T07:30:15.5+03:00");
+/// assert_eq!("1997-07".parse::<KmlDateTime>().unwrap(), KmlDateTime::YearMonth { year: 1997, month: 7 });
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum KmlDateTime {
+    /// `gYear` like `1997`
+    Year(i32),
+    /// `gYearMonth` like `1997-07`
+    YearMonth { year: i32, month: u8 },
+    /// `date` like `1997-07-16`
+    Date { year: i32, month: u8, day: u8 },
+    /// `dateTime` like `1997-07-16T07:30:15Z`, with the UTC offset in minutes if present
+    DateTime {
+        year: i32,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+        nanosecond: u32,
+        offset: Option<i16>,
+    },
+}
+
+impl KmlDateTime {
+    /// Returns the year, which is included at every precision
+    pub fn year(&self) -> i32 {
+        match *self {
+            Self::Year(year)
+            | Self::YearMonth { year, .. }
+            | Self::Date { year, .. }
+            | Self::DateTime { year, .. } => year,
+        }
+    }
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Parses a fixed number of ASCII digits within the inclusive range
+fn parse_field(s: &str, digits: usize, min: u8, max: u8) -> Option<u8> {
+    if s.len() != digits || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok().filter(|v| (min..=max).contains(v))
+}
+
+fn parse_year(s: &str) -> Option<i32> {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    // Years have at least four digits, and more than four can't have leading zeros
+    if digits.len() < 4
+        || (digits.len() > 4 && digits.starts_with('0'))
+        || !digits.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn parse_offset(s: &str) -> Option<Option<i16>> {
+    if s.is_empty() {
+        return Some(None);
+    }
+    if s == "Z" {
+        return Some(Some(0));
+    }
+    let sign = match s.as_bytes()[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let (hours, minutes) = s[1..].split_once(':')?;
+    let hours = parse_field(hours, 2, 0, 14)?;
+    let minutes = parse_field(minutes, 2, 0, 59)?;
+    if hours == 14 && minutes != 0 {
+        return None;
+    }
+    Some(Some(sign * (hours as i16 * 60 + minutes as i16)))
+}
+
+fn parse_time(s: &str) -> Option<(u8, u8, u8, u32, Option<i16>)> {
+    let offset_start = s.find(['Z', '+', '-']).unwrap_or(s.len());
+    let (time, offset) = s.split_at(offset_start);
+    let (time, fraction) = match time.split_once('.') {
+        Some((time, fraction)) => (time, Some(fraction)),
+        None => (time, None),
+    };
+    let mut fields = time.split(':');
+    let hour = parse_field(fields.next()?, 2, 0, 23)?;
+    let minute = parse_field(fields.next()?, 2, 0, 59)?;
+    let second = parse_field(fields.next()?, 2, 0, 59)?;
+    if fields.next().is_some() {
+        return None;
+    }
+    let nanosecond = match fraction {
+        Some(f) if !f.is_empty() && f.bytes().all(|b| b.is_ascii_digit()) => {
+            // Digits beyond nanosecond precision are truncated
+            let digits = &f[..f.len().min(9)];
+            digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32)
+        }
+        Some(_) => return None,
+        None => 0,
+    };
+    Some((hour, minute, second, nanosecond, parse_offset(offset)?))
+}
+
+impl FromStr for KmlDateTime {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || Error::InvalidDateTime(s.to_string());
+        let trimmed = s.trim();
+        let (date, time) = match trimmed.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (trimmed, None),
+        };
+
+        // The year can be negative, so look for the month separator after the sign
+        let year_end = date
+            .get(1..)
+            .and_then(|d| d.find('-'))
+            .map_or(date.len(), |i| i + 1);
+        let year = parse_year(&date[..year_end]).ok_or_else(err)?;
+        let mut rest = date[year_end..].split('-').skip(1);
+        let month = match rest.next() {
+            Some(month) => parse_field(month, 2, 1, 12).ok_or_else(err)?,
+            None if time.is_none() => return Ok(Self::Year(year)),
+            None => return Err(err()),
+        };
+        let day = match rest.next() {
+            Some(day) => parse_field(day, 2, 1, days_in_month(year, month)).ok_or_else(err)?,
+            None if time.is_none() => return Ok(Self::YearMonth { year, month }),
+            None => return Err(err()),
+        };
+        if rest.next().is_some() {
+            return Err(err());
+        }
+        let time = match time {
+            Some(time) => time,
+            None => return Ok(Self::Date { year, month, day }),
+        };
+        let (hour, minute, second, nanosecond, offset) = parse_time(time).ok_or_else(err)?;
+        Ok(Self::DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+            offset,
+        })
+    }
+}
+
+fn fmt_year(f: &mut fmt::Formatter, year: i32) -> fmt::Result {
+    if year < 0 {
+        write!(f, "-")?;
+    }
+    write!(f, "{:04}", year.unsigned_abs())
+}
+
+impl fmt::Display for KmlDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_year(f, self.year())?;
+        match *self {
+            Self::Year(_) => Ok(()),
+            Self::YearMonth { month, .. } => write!(f, "-{:02}", month),
+            Self::Date { month, day, .. } => write!(f, "-{:02}-{:02}", month, day),
+            Self::DateTime {
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                nanosecond,
+                offset,
+                ..
+            } => {
+                write!(
+                    f,
+                    "-{:02}-{:02}T{:02}:{:02}:{:02}",
+                    month, day, hour, minute, second
+                )?;
+                if nanosecond > 0 {
+                    let fraction = format!("{:09}", nanosecond);
+                    write!(f, ".{}", fraction.trim_end_matches('0'))?;
+                }
+                match offset {
+                    None => Ok(()),
+                    Some(0) => write!(f, "Z"),
+                    Some(offset) => write!(
+                        f,
+                        "{}{:02}:{:02}",
+                        if offset < 0 { '-' } else { '+' },
+                        offset.unsigned_abs() / 60,
+                        offset.unsigned_abs() % 60
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for KmlDateTime {
+    fn from(val: chrono::DateTime<Tz>) -> KmlDateTime {
+        use chrono::{Datelike, Offset, Timelike};
+
+        let local = val.naive_local();
+        KmlDateTime::DateTime {
+            year: local.year(),
+            month: local.month() as u8,
+            day: local.day() as u8,
+            hour: local.hour() as u8,
+            minute: local.minute() as u8,
+            second: local.second() as u8,
+            // Leap seconds are represented by chrono as nanoseconds past the last second
+            nanosecond: local.nanosecond().min(999_999_999),
+            offset: Some((val.offset().fix().local_minus_utc() / 60) as i16),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl std::convert::TryFrom<KmlDateTime> for chrono::DateTime<chrono::FixedOffset> {
+    type Error = Error;
+
+    /// Converts a `dateTime` with a UTC offset, returning an error for local times and values
+    /// with lower precision
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use chrono::{DateTime, FixedOffset};
+    /// use kml::types::KmlDateTime;
+    ///
+    /// let dt: KmlDateTime = "2021-03-04T05:06:07-05:00".parse().unwrap();
+    /// let chrono_dt = DateTime::<FixedOffset>::try_from(dt).unwrap();
+    /// assert_eq!(chrono_dt.timestamp(), 1_614_852_367);
+    /// assert_eq!(chrono_dt.offset().local_minus_utc(), -5 * 3600);
+    /// assert_eq!(KmlDateTime::from(chrono_dt), dt);
+    /// assert!(DateTime::<FixedOffset>::try_from(KmlDateTime::Year(2021)).is_err());
+    /// ```
+    fn try_from(val: KmlDateTime) -> Result<chrono::DateTime<chrono::FixedOffset>, Self::Error> {
+        use chrono::TimeZone;
+
+        let offset = match val {
+            KmlDateTime::DateTime {
+                offset: Some(offset),
+                ..
+            } => chrono::FixedOffset::east_opt(offset as i32 * 60),
+            _ => None,
+        };
+        let naive = chrono::NaiveDateTime::try_from(val)?;
+        offset
+            .and_then(|offset| offset.from_local_datetime(&naive).single())
+            .ok_or_else(|| Error::InvalidDateTime(val.to_string()))
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl std::convert::TryFrom<KmlDateTime> for chrono::NaiveDateTime {
+    type Error = Error;
+
+    /// Converts to the start of the period in local time, ignoring any UTC offset, returning an
+    /// error for years outside of chrono's supported range
+    fn try_from(val: KmlDateTime) -> Result<chrono::NaiveDateTime, Self::Error> {
+        let (month, day, hour, minute, second, nanosecond) = match val {
+            KmlDateTime::Year(_) => (1, 1, 0, 0, 0, 0),
+            KmlDateTime::YearMonth { month, .. } => (month, 1, 0, 0, 0, 0),
+            KmlDateTime::Date { month, day, .. } => (month, day, 0, 0, 0, 0),
+            KmlDateTime::DateTime {
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                nanosecond,
+                ..
+            } => (month, day, hour, minute, second, nanosecond),
+        };
+        chrono::NaiveDate::from_ymd_opt(val.year(), month as u32, day as u32)
+            .and_then(|d| d.and_hms_nano_opt(hour as u32, minute as u32, second as u32, nanosecond))
+            .ok_or_else(|| Error::InvalidDateTime(val.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date_time() {
+        assert_eq!(
+            "1997".parse::<KmlDateTime>().unwrap(),
+            KmlDateTime::Year(1997)
+        );
+        assert_eq!(
+            "-0044-03-15".parse::<KmlDateTime>().unwrap(),
+            KmlDateTime::Date {
+                year: -44,
+                month: 3,
+                day: 15
+            }
+        );
+        assert_eq!(
+            "2000-02-29T23:59:59-05:30".parse::<KmlDateTime>().unwrap(),
+            KmlDateTime::DateTime {
+                year: 2000,
+                month: 2,
+                day: 29,
+                hour: 23,
+                minute: 59,
+                second: 59,
+                nanosecond: 0,
+                offset: Some(-330),
+            }
+        );
+        assert_eq!(
+            "2007-01-14T21:05:02"
+                .parse::<KmlDateTime>()
+                .unwrap()
+                .to_string(),
+            "2007-01-14T21:05:02"
+        );
+    }
+
+    #[test]
+    fn test_parse_date_time_invalid() {
+        for s in &[
+            "",
+            "97",
+            "1997-7",
+            "1997-13",
+            "1900-02-29",
+            "1997-07-16T",
+            "1997-07T07:30:15Z",
+            "1997-07-16T24:00:00Z",
+            "1997-07-16T07:30Z",
+            "1997-07-16T07:30:15.Z",
+            "1997-07-16T07:30:15+15:00",
+            "1997-07-16 07:30:15",
+        ] {
+            assert!(s.parse::<KmlDateTime>().is_err(), "{} should be invalid", s);
+        }
+    }
+
+    #[test]
+    fn test_format_date_time() {
+        for s in &[
+            "1997",
+            "-0044",
+            "12345-01",
+            "1997-07-16",
+            "1997-07-16T07:30:15Z",
+            "1997-07-16T07:30:15.125-03:00",
+        ] {
+            assert_eq!(s.parse::<KmlDateTime>().unwrap().to_string(), *s);
+        }
+    }
+}
@@ -1,10 +1,145 @@
 use std::collections::HashMap;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::coord::CoordType;
+use crate::types::geometry::Geometry;
+use crate::types::kml::Kml;
+
 /// Generic type used for supporting elements that are extensions or not currently implemented
+///
+/// Elements are also the escape hatch for carrying vendor-specific types through the typed tree
+/// as [`Kml::Element`](../enum.Kml.html#variant.Element) or [`Geometry::Element`], see
+/// [`CustomElement`].
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Element {
     pub name: String,
     pub attrs: HashMap<String, String>,
     pub content: Option<String>,
     pub children: Vec<Element>,
 }
+
+impl Element {
+    pub fn new(name: impl Into<String>) -> Self {
+        Element {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the name without any namespace prefix, which is only kept when reading with
+    /// [`KmlReader::lossless`](../struct.KmlReader.html#method.lossless)
+    pub fn local_name(&self) -> &str {
+        self.name
+            .split_once(':')
+            .map_or(&self.name, |(_, local)| local)
+    }
+
+    /// Returns the first child with the local name
+    pub fn child(&self, local_name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.local_name() == local_name)
+    }
+
+    /// Adds an attribute, returning `self` for chaining
+    pub fn with_attr(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attrs.insert(key.into(), value.into());
+        self
+    }
+
+    /// Adds a child element, returning `self` for chaining
+    pub fn with_child(mut self, child: Element) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+impl_with!(Element {
+    with_content(some content: String),
+});
+
+/// Conversion between application types and [`Element`], so vendor-specific extensions without a
+/// type in this crate can be read from and added to the typed tree
+///
+/// # Example
+///
+/// ```
+/// use kml::Kml;
+/// use kml::types::{CustomElement, Element};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Speed(f64);
+///
+/// impl CustomElement for Speed {
+///     fn from_element(element: &Element) -> Option<Self> {
+///         if element.local_name() != "speed" {
+///             return None;
+///         }
+///         element.content.as_deref()?.parse().ok().map(Speed)
+///     }
+///
+///     fn to_element(&self) -> Element {
+///         Element::new("acme:speed").with_content(self.0.to_string())
+///     }
+/// }
+///
+/// let kml: Kml = Kml::from_custom(&Speed(12.5));
+/// assert_eq!(kml.to_string(), "<acme:speed>12.5</acme:speed>");
+/// assert_eq!(kml.as_custom::<Speed>(), Some(Speed(12.5)));
+/// ```
+pub trait CustomElement: Sized {
+    /// Converts from an element, returning `None` if it doesn't represent this type
+    fn from_element(element: &Element) -> Option<Self>;
+
+    /// Converts into an element for writing
+    fn to_element(&self) -> Element;
+}
+
+impl<T> From<Element> for Geometry<T>
+where
+    T: CoordType,
+{
+    fn from(element: Element) -> Self {
+        Geometry::Element(element)
+    }
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Wraps a custom type in [`Kml::Element`](enum.Kml.html#variant.Element)
+    pub fn from_custom<C: CustomElement>(custom: &C) -> Self {
+        Kml::Element(custom.to_element())
+    }
+
+    /// Converts an element into a custom type, returning `None` for other variants or elements that
+    /// don't represent the type
+    pub fn as_custom<C: CustomElement>(&self) -> Option<C> {
+        match self {
+            Kml::Element(e) => C::from_element(e),
+            Kml::Custom(c) => C::from_element(c.element()),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Geometry<T>
+where
+    T: CoordType,
+{
+    /// Wraps a custom type in [`Geometry::Element`]
+    pub fn from_custom<C: CustomElement>(custom: &C) -> Self {
+        Geometry::Element(custom.to_element())
+    }
+
+    /// Converts an element into a custom type, returning `None` for other variants or elements that
+    /// don't represent the type
+    pub fn as_custom<C: CustomElement>(&self) -> Option<C> {
+        match self {
+            Geometry::Element(e) => C::from_element(e),
+            _ => None,
+        }
+    }
+}
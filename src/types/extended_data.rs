@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::element::{CustomElement, Element};
+
+/// `kml:Data`, [9.4](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#159) in the KML
+/// specification, an untyped name and value pair in [`ExtendedData`](struct.ExtendedData.html)
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Data {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub value: String,
+    pub attrs: HashMap<String, String>,
+}
+
+impl Data {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Data {
+            name: name.into(),
+            value: value.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl_with!(Data {
+    with_display_name(some display_name: String),
+});
+
+/// `kml:SimpleData`, a value in [`SchemaData`](struct.SchemaData.html) for the
+/// [`SimpleField`](struct.SimpleField.html) with the same name
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SimpleData {
+    pub name: String,
+    pub value: String,
+}
+
+impl SimpleData {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        SimpleData {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// `kml:SchemaData`, [9.5](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#165) in the
+/// KML specification, values for the fields of the [`Schema`](struct.Schema.html) that
+/// `schema_url` references
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SchemaData {
+    pub schema_url: Option<String>,
+    pub data: Vec<SimpleData>,
+    pub attrs: HashMap<String, String>,
+}
+
+impl SchemaData {
+    pub fn new(schema_url: impl Into<String>) -> Self {
+        SchemaData {
+            schema_url: Some(schema_url.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a value, returning `self` for chaining
+    pub fn with_data(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.data.push(SimpleData::new(name, value));
+        self
+    }
+}
+
+/// `kml:ExtendedData`, [9.3](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#153) in the
+/// KML specification, custom data attached to a feature
+///
+/// Elements from other namespaces are kept in `children`, and are written after `data` and
+/// `schema_data`. The `ExtendedData` of features like [`Placemark`](struct.Placemark.html) is read
+/// into their `extended_data`, while that of documents and folders is left as an element that can
+/// be converted with [`Kml::as_custom`](enum.Kml.html#method.as_custom).
+///
+/// # Example
+///
+/// ```
+/// use kml::types::{Data, ExtendedData, Placemark, SchemaData};
+///
+/// let placemark: Placemark = Placemark::default().with_extended_data(
+///     ExtendedData::default()
+///         .with_data(Data::new("elevation", "1200").with_display_name("Elevation (m)"))
+///         .with_schema_data(SchemaData::new("#trail").with_data("length", "4.2")),
+/// );
+/// let extended_data = placemark.extended_data.unwrap();
+/// assert_eq!(extended_data.data_value("elevation"), Some("1200"));
+/// assert_eq!(extended_data.simple_data_value("length"), Some("4.2"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExtendedData {
+    pub data: Vec<Data>,
+    pub schema_data: Vec<SchemaData>,
+    pub attrs: HashMap<String, String>,
+    pub children: Vec<Element>,
+}
+
+impl ExtendedData {
+    /// Adds a `kml:Data`, returning `self` for chaining
+    pub fn with_data(mut self, data: Data) -> Self {
+        self.data.push(data);
+        self
+    }
+
+    /// Adds a `kml:SchemaData`, returning `self` for chaining
+    pub fn with_schema_data(mut self, schema_data: SchemaData) -> Self {
+        self.schema_data.push(schema_data);
+        self
+    }
+
+    /// Returns the value of the first `kml:Data` with the name
+    pub fn data_value(&self, name: &str) -> Option<&str> {
+        self.data
+            .iter()
+            .find(|d| d.name == name)
+            .map(|d| d.value.as_str())
+    }
+
+    /// Returns the value of the first `kml:SimpleData` with the name in any `kml:SchemaData`
+    pub fn simple_data_value(&self, name: &str) -> Option<&str> {
+        self.schema_data
+            .iter()
+            .flat_map(|s| s.data.iter())
+            .find(|d| d.name == name)
+            .map(|d| d.value.as_str())
+    }
+}
+
+/// `kml:SimpleField`, a field of a [`Schema`](struct.Schema.html)
+///
+/// `field_type` is one of the XML schema types `string`, `int`, `uint`, `short`, `ushort`,
+/// `float`, `double`, or `bool`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SimpleField {
+    pub name: String,
+    pub field_type: String,
+    pub display_name: Option<String>,
+    pub attrs: HashMap<String, String>,
+}
+
+impl SimpleField {
+    pub fn new(name: impl Into<String>, field_type: impl Into<String>) -> Self {
+        SimpleField {
+            name: name.into(),
+            field_type: field_type.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl_with!(SimpleField {
+    with_display_name(some display_name: String),
+});
+
+/// `kml:Schema`, [9.6](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#172) in the KML
+/// specification, the typed fields of [`SchemaData`](struct.SchemaData.html) referencing its id
+///
+/// Other fields like `gx:SimpleArrayField` are kept in `children`, and are written after `fields`.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, types::{Schema, SimpleField}};
+///
+/// let schema = Schema::new("trail")
+///     .with_name("Trail")
+///     .with_field(SimpleField::new("length", "double").with_display_name("Length (km)"));
+///
+/// let kml: Kml = Kml::Schema(schema.clone());
+/// let kml_str = kml.to_string();
+/// assert!(kml_str.contains(r#"<SimpleField name="length" type="double">"#));
+/// assert_eq!(kml_str.parse::<Kml>().unwrap(), kml);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Schema {
+    pub name: Option<String>,
+    pub fields: Vec<SimpleField>,
+    pub attrs: HashMap<String, String>,
+    pub children: Vec<Element>,
+}
+
+impl Schema {
+    /// Creates a schema with the id referenced by `schemaUrl`, without the leading `#`
+    pub fn new(id: impl Into<String>) -> Self {
+        let mut schema = Schema::default();
+        schema.attrs.insert("id".to_string(), id.into());
+        schema
+    }
+
+    /// Adds a field, returning `self` for chaining
+    pub fn with_field(mut self, field: SimpleField) -> Self {
+        self.fields.push(field);
+        self
+    }
+}
+
+impl_with!(Schema {
+    with_name(some name: String),
+});
+
+// Removes the attribute from a copy of the element's attributes, so it's only written once
+fn take_attr(element: &Element, name: &str) -> (Option<String>, HashMap<String, String>) {
+    let mut attrs = element.attrs.clone();
+    (attrs.remove(name), attrs)
+}
+
+fn child_content(element: &Element, local_name: &str) -> Option<String> {
+    element.child(local_name).and_then(|c| c.content.clone())
+}
+
+fn element_with_attrs(name: &str, attrs: &HashMap<String, String>) -> Element {
+    let mut element = Element::new(name);
+    element.attrs = attrs.clone();
+    element
+}
+
+impl CustomElement for Data {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "Data" {
+            return None;
+        }
+        let (name, attrs) = take_attr(element, "name");
+        Some(Data {
+            name: name?,
+            display_name: child_content(element, "displayName"),
+            value: child_content(element, "value").unwrap_or_default(),
+            attrs,
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = element_with_attrs("Data", &self.attrs).with_attr("name", &self.name);
+        if let Some(display_name) = &self.display_name {
+            element =
+                element.with_child(Element::new("displayName").with_content(display_name.clone()));
+        }
+        element.with_child(Element::new("value").with_content(self.value.clone()))
+    }
+}
+
+impl CustomElement for SchemaData {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "SchemaData" {
+            return None;
+        }
+        let (schema_url, attrs) = take_attr(element, "schemaUrl");
+        let data = element
+            .children
+            .iter()
+            .filter(|c| c.local_name() == "SimpleData")
+            .map(|c| {
+                Some(SimpleData {
+                    name: c.attrs.get("name")?.clone(),
+                    value: c.content.clone().unwrap_or_default(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(SchemaData {
+            schema_url,
+            data,
+            attrs,
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = element_with_attrs("SchemaData", &self.attrs);
+        if let Some(schema_url) = &self.schema_url {
+            element = element.with_attr("schemaUrl", schema_url);
+        }
+        for data in self.data.iter() {
+            element = element.with_child(
+                Element::new("SimpleData")
+                    .with_attr("name", &data.name)
+                    .with_content(data.value.clone()),
+            );
+        }
+        element
+    }
+}
+
+impl CustomElement for ExtendedData {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "ExtendedData" {
+            return None;
+        }
+        let mut extended_data = ExtendedData {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "Data" => extended_data.data.push(Data::from_element(child)?),
+                "SchemaData" => extended_data
+                    .schema_data
+                    .push(SchemaData::from_element(child)?),
+                _ => extended_data.children.push(child.clone()),
+            }
+        }
+        Some(extended_data)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = element_with_attrs("ExtendedData", &self.attrs);
+        for data in self.data.iter() {
+            element = element.with_child(data.to_element());
+        }
+        for schema_data in self.schema_data.iter() {
+            element = element.with_child(schema_data.to_element());
+        }
+        for child in self.children.iter() {
+            element = element.with_child(child.clone());
+        }
+        element
+    }
+}
+
+impl CustomElement for SimpleField {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "SimpleField" {
+            return None;
+        }
+        let (name, mut attrs) = take_attr(element, "name");
+        Some(SimpleField {
+            name: name?,
+            field_type: attrs.remove("type").unwrap_or_default(),
+            display_name: child_content(element, "displayName"),
+            attrs,
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = element_with_attrs("SimpleField", &self.attrs)
+            .with_attr("name", &self.name)
+            .with_attr("type", &self.field_type);
+        if let Some(display_name) = &self.display_name {
+            element =
+                element.with_child(Element::new("displayName").with_content(display_name.clone()));
+        }
+        element
+    }
+}
+
+impl CustomElement for Schema {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "Schema" {
+            return None;
+        }
+        let (name, attrs) = take_attr(element, "name");
+        let mut schema = Schema {
+            name,
+            attrs,
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "SimpleField" => schema.fields.push(SimpleField::from_element(child)?),
+                _ => schema.children.push(child.clone()),
+            }
+        }
+        Some(schema)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = element_with_attrs("Schema", &self.attrs);
+        if let Some(name) = &self.name {
+            element = element.with_attr("name", name);
+        }
+        for field in self.fields.iter() {
+            element = element.with_child(field.to_element());
+        }
+        for child in self.children.iter() {
+            element = element.with_child(child.clone());
+        }
+        element
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_data_element_round_trip() {
+        let mut extended_data = ExtendedData::default()
+            .with_data(Data::new("elevation", "1200").with_display_name("Elevation"))
+            .with_schema_data(SchemaData::new("#trail").with_data("length", "4.2"));
+        extended_data
+            .children
+            .push(Element::new("camp:site").with_content("7"));
+        let element = extended_data.to_element();
+        assert_eq!(ExtendedData::from_element(&element), Some(extended_data));
+
+        // The name and schemaUrl are written once, as attributes
+        let element = Data::new("a", "1").to_element();
+        assert_eq!(element.attrs.len(), 1);
+        let element = SchemaData::new("#s").to_element();
+        assert_eq!(
+            element.attrs.get("schemaUrl").map(String::as_str),
+            Some("#s")
+        );
+    }
+
+    #[test]
+    fn test_read_partial_data() {
+        // A missing value is empty, and SimpleData values aren't trimmed
+        let element = Element::new("Data").with_attr("name", "empty");
+        assert_eq!(Data::from_element(&element), Some(Data::new("empty", "")));
+        let element = Element::new("SchemaData").with_child(
+            Element::new("SimpleData")
+                .with_attr("name", "note")
+                .with_content(" spaced "),
+        );
+        let schema_data = SchemaData::from_element(&element).unwrap();
+        assert_eq!(schema_data.schema_url, None);
+        assert_eq!(schema_data.data, vec![SimpleData::new("note", " spaced ")]);
+    }
+
+    #[test]
+    fn test_read_invalid_data() {
+        // Names are required
+        assert_eq!(Data::from_element(&Element::new("Data")), None);
+        let element = Element::new("SchemaData").with_child(Element::new("SimpleData"));
+        assert_eq!(SchemaData::from_element(&element), None);
+        let element = Element::new("ExtendedData").with_child(Element::new("Data"));
+        assert_eq!(ExtendedData::from_element(&element), None);
+        assert_eq!(
+            SimpleField::from_element(&Element::new("SimpleField")),
+            None
+        );
+        assert_eq!(ExtendedData::from_element(&Element::new("Data")), None);
+    }
+
+    #[test]
+    fn test_lookup_values() {
+        let extended_data = ExtendedData::default()
+            .with_data(Data::new("kind", "trail"))
+            .with_data(Data::new("kind", "road"))
+            .with_schema_data(SchemaData::new("#a"))
+            .with_schema_data(SchemaData::new("#b").with_data("lanes", "2"));
+        // The first value with the name is returned
+        assert_eq!(extended_data.data_value("kind"), Some("trail"));
+        assert_eq!(extended_data.simple_data_value("lanes"), Some("2"));
+        assert_eq!(extended_data.data_value("lanes"), None);
+        assert_eq!(ExtendedData::default().simple_data_value("kind"), None);
+    }
+
+    #[test]
+    fn test_schema_element_round_trip() {
+        let mut schema = Schema::new("trail")
+            .with_name("Trail")
+            .with_field(SimpleField::new("length", "double").with_display_name("Length"))
+            .with_field(SimpleField::new("surface", "string"));
+        schema.children.push(Element::new("gx:SimpleArrayField"));
+        let element = schema.to_element();
+        assert_eq!(Schema::from_element(&element), Some(schema));
+
+        // A field without a type is read with an empty one
+        let element = Element::new("SimpleField").with_attr("name", "untyped");
+        let field = SimpleField::from_element(&element).unwrap();
+        assert_eq!(field, SimpleField::new("untyped", ""));
+    }
+}
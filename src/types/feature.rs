@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::element::{CustomElement, Element};
+
+/// Fields of `kml:AbstractFeatureGroup` on `kml:Document` and `kml:Folder`,
+/// [9.1](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#128) in the KML specification
+///
+/// [`Placemark`](struct.Placemark.html) has the same fields directly. The `atom:author` and
+/// `atom:link` are written with the `atom` prefix, which should be declared on the document with
+/// [`KmlDocument::with_atom_namespace`](../struct.KmlDocument.html#method.with_atom_namespace).
+///
+/// # Example
+///
+/// ```
+/// use kml::Kml;
+///
+/// let kml_str = r##"<Folder>
+///   <name>Trails</name>
+///   <open>1</open>
+///   <styleUrl>#trail</styleUrl>
+/// </Folder>"##;
+/// let kml: Kml = kml_str.parse().unwrap();
+///
+/// if let Kml::Folder { props, elements, .. } = kml {
+///     assert_eq!(props.name.as_deref(), Some("Trails"));
+///     assert_eq!(props.open, Some(true));
+///     assert_eq!(props.style_url.as_deref(), Some("#trail"));
+///     assert!(elements.is_empty());
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FeatureProps {
+    pub name: Option<String>,
+    pub visibility: Option<bool>,
+    /// Whether the feature is expanded in the places panel
+    pub open: Option<bool>,
+    /// `atom:name` of the `atom:author`
+    pub author: Option<String>,
+    /// `href` of the `atom:link`
+    pub link: Option<String>,
+    pub address: Option<String>,
+    pub phone_number: Option<String>,
+    pub snippet: Option<Snippet>,
+    pub description: Option<String>,
+    /// Reference to a shared `kml:Style` or `kml:StyleMap`, like `#id` for one in the same
+    /// document
+    pub style_url: Option<String>,
+}
+
+impl_with!(FeatureProps {
+    with_name(some name: String),
+    with_visibility(some visibility: bool),
+    with_open(some open: bool),
+    with_author(some author: String),
+    with_link(some link: String),
+    with_address(some address: String),
+    with_phone_number(some phone_number: String),
+    with_snippet(some snippet: Snippet),
+    with_description(some description: String),
+    with_style_url(some style_url: String),
+});
+
+impl FeatureProps {
+    // Whether the local name is one of the fields, which are read as elements and then set
+    pub(crate) fn is_field(local_name: &[u8]) -> bool {
+        matches!(
+            local_name,
+            b"name"
+                | b"visibility"
+                | b"open"
+                | b"author"
+                | b"link"
+                | b"address"
+                | b"phoneNumber"
+                | b"Snippet"
+                | b"description"
+                | b"styleUrl"
+        )
+    }
+
+    // Sets the field of the element, returning `false` if it's invalid, has attributes the field
+    // can't keep, or the field is already set, so it can be kept as an element instead
+    pub(crate) fn set_field(&mut self, element: &Element) -> bool {
+        fn set<V>(field: &mut Option<V>, value: Option<V>) -> bool {
+            match value {
+                Some(value) if field.is_none() => {
+                    *field = Some(value);
+                    true
+                }
+                _ => false,
+            }
+        }
+        let content = || Some(element.content.clone().unwrap_or_default());
+        match element.local_name() {
+            "name" => set(&mut self.name, content()),
+            "visibility" => set(&mut self.visibility, parse_bool(element)),
+            "open" => set(&mut self.open, parse_bool(element)),
+            "author" if element.attrs.is_empty() && element.children.len() == 1 => set(
+                &mut self.author,
+                element
+                    .child("name")
+                    .map(|n| n.content.clone().unwrap_or_default()),
+            ),
+            "link" if element.attrs.len() == 1 => {
+                set(&mut self.link, element.attrs.get("href").cloned())
+            }
+            "address" => set(&mut self.address, content()),
+            "phoneNumber" => set(&mut self.phone_number, content()),
+            "Snippet" => set(&mut self.snippet, Snippet::from_element(element)),
+            "description" => set(&mut self.description, content()),
+            "styleUrl" => set(&mut self.style_url, content()),
+            _ => false,
+        }
+    }
+}
+
+fn parse_bool(element: &Element) -> Option<bool> {
+    match element.content.as_deref().unwrap_or_default().trim() {
+        "1" | "true" => Some(true),
+        "0" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// `kml:Snippet`, a short description of a feature shown in the places panel instead of its
+/// `description`
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Snippet {
+    pub text: String,
+    /// Number of lines to show, which defaults to 2 when missing
+    pub max_lines: Option<u32>,
+    pub attrs: HashMap<String, String>,
+}
+
+impl Snippet {
+    pub fn new(text: impl Into<String>) -> Self {
+        Snippet {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl_with!(Snippet {
+    with_max_lines(some max_lines: u32),
+});
+
+impl CustomElement for Snippet {
+    /// Returns `None` for the deprecated lowercase `kml:snippet`, or an invalid `maxLines`, which
+    /// [`Kml::repair`](../enum.Kml.html#method.repair) fixes
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "Snippet" {
+            return None;
+        }
+        let mut attrs = element.attrs.clone();
+        let max_lines = match attrs.remove("maxLines") {
+            Some(max_lines) => Some(max_lines.trim().parse().ok()?),
+            None => None,
+        };
+        Some(Snippet {
+            text: element.content.clone().unwrap_or_default(),
+            max_lines,
+            attrs,
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("Snippet").with_content(self.text.clone());
+        element.attrs = self.attrs.clone();
+        if let Some(max_lines) = self.max_lines {
+            element
+                .attrs
+                .insert("maxLines".to_string(), max_lines.to_string());
+        }
+        element
+    }
+}
@@ -1,22 +1,119 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::types::coord::CoordType;
 use crate::types::element::Element;
 use crate::types::line_string::LineString;
 use crate::types::linear_ring::LinearRing;
+use crate::types::model::Model;
 use crate::types::multi_geometry::MultiGeometry;
 use crate::types::point::Point;
 use crate::types::polygon::Polygon;
+use crate::types::track::{MultiTrack, Track};
+use crate::visit::KmlVisitor;
 
 /// Enum for elements in `kml:AbstractGeometryGroup`, [10.1](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#432)
 /// in the KML specification
 ///
-/// `kml:Model` is currently represented by a placeholder element
+/// Geometries from other namespaces can be kept as an [`Element`](struct.Element.html)
 #[non_exhaustive]
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Geometry<T: CoordType = f64> {
     Point(Point<T>),
     LineString(LineString<T>),
     LinearRing(LinearRing<T>),
     Polygon(Polygon<T>),
     MultiGeometry(MultiGeometry<T>),
-    Element(Element), // Currently just a stand-in for Model
+    Track(Track<T>),
+    MultiTrack(MultiTrack<T>),
+    Model(Model<T>),
+    Element(Element),
+}
+
+impl<T> Geometry<T>
+where
+    T: CoordType,
+{
+    /// Orients the rings of every `kml:Polygon` in the geometry, returning the number of rings
+    /// changed. See [`Polygon::orient`](struct.Polygon.html#method.orient)
+    pub fn orient_polygons(&mut self) -> usize {
+        let mut visitor = OrientPolygons(0);
+        self.walk(&mut visitor);
+        visitor.0
+    }
+
+    /// Removes consecutive coordinates within `tolerance` of each other from every line and ring in
+    /// the geometry, returning the number of coordinates removed
+    pub fn dedup_coords(&mut self, tolerance: T) -> usize {
+        let mut visitor = DedupCoords(tolerance, 0);
+        self.walk(&mut visitor);
+        visitor.1
+    }
+
+    /// Closes every `kml:LinearRing` in the geometry, returning the number of rings changed
+    pub fn close_rings(&mut self) -> usize {
+        let mut visitor = CloseRings(0);
+        self.walk(&mut visitor);
+        visitor.0
+    }
+}
+
+// Orients the rings of polygons, counting the rings changed
+pub(crate) struct OrientPolygons(pub usize);
+
+impl<T: CoordType> KmlVisitor<T> for OrientPolygons {
+    fn visit_polygon(&mut self, polygon: &mut Polygon<T>) {
+        self.0 += polygon.orient();
+    }
+}
+
+// Removes consecutive coordinates within the tolerance from lines and rings, counting the
+// coordinates removed
+pub(crate) struct DedupCoords<T>(pub T, pub usize);
+
+impl<T: CoordType> KmlVisitor<T> for DedupCoords<T> {
+    fn visit_line_string(&mut self, line_string: &mut LineString<T>) {
+        self.1 += line_string.dedup_coords(self.0);
+    }
+
+    fn visit_linear_ring(&mut self, linear_ring: &mut LinearRing<T>) {
+        self.1 += linear_ring.dedup_coords(self.0);
+    }
 }
+
+// Closes rings, counting the rings changed
+pub(crate) struct CloseRings(pub usize);
+
+impl<T: CoordType> KmlVisitor<T> for CloseRings {
+    fn visit_linear_ring(&mut self, linear_ring: &mut LinearRing<T>) {
+        self.0 += linear_ring.close() as usize;
+    }
+}
+
+macro_rules! impl_geometry_from {
+    ($($variant:ident => $ty:ty),* $(,)?) => {
+        $(
+            impl<T> From<$ty> for Geometry<T>
+            where
+                T: CoordType,
+            {
+                fn from(geometry: $ty) -> Self {
+                    Geometry::$variant(geometry)
+                }
+            }
+        )*
+    };
+}
+
+impl_geometry_from!(
+    Point => Point<T>,
+    LineString => LineString<T>,
+    LinearRing => LinearRing<T>,
+    Polygon => Polygon<T>,
+    MultiGeometry => MultiGeometry<T>,
+    Track => Track<T>,
+    MultiTrack => MultiTrack<T>,
+    Model => Model<T>,
+);
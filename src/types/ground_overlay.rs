@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::altitude_mode::{altitude_mode_element, AltitudeMode};
+use crate::types::color::Color;
+use crate::types::coord::{lit, CoordType};
+use crate::types::element::{CustomElement, Element};
+use crate::types::extended_data::ExtendedData;
+use crate::types::style::Icon;
+
+/// `kml:LatLonBox`, the bounds of a [`GroundOverlay`](struct.GroundOverlay.html) image, see
+/// [Google's LatLonBox reference](https://developers.google.com/kml/documentation/kmlreference#latlonbox)
+///
+/// `rotation` is the counter-clockwise rotation of the image about its center in degrees.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LatLonBox<T: CoordType = f64> {
+    pub north: T,
+    pub south: T,
+    pub east: T,
+    pub west: T,
+    pub rotation: f64,
+    pub attrs: HashMap<String, String>,
+}
+
+impl<T> LatLonBox<T>
+where
+    T: CoordType,
+{
+    pub fn new(north: T, south: T, east: T, west: T) -> Self {
+        LatLonBox {
+            north,
+            south,
+            east,
+            west,
+            rotation: 0.,
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Default for LatLonBox<T>
+where
+    T: CoordType,
+{
+    /// Returns the schema's defaults, which cover the whole globe
+    fn default() -> Self {
+        let max = lit(180.);
+        LatLonBox::new(max, -max, max, -max)
+    }
+}
+
+impl_with!(LatLonBox<T> {
+    with_rotation(rotation: f64),
+});
+
+/// `kml:GroundOverlay`, [11.2](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#360) in
+/// the KML specification, an image draped over the terrain
+///
+/// Elements of the feature without a typed representation like `kml:description` and
+/// `gx:LatLonQuad` are kept in `children`. `gx:LatLonQuad` is written last, and the rest are
+/// written after the name.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, types::{GroundOverlay, Icon, LatLonBox}};
+///
+/// let overlay = GroundOverlay::new(Icon::new("files/map.png"))
+///     .with_name("Map")
+///     .with_lat_lon_box(LatLonBox::new(40., 39., -104., -105.).with_rotation(10.));
+///
+/// let kml_str = Kml::GroundOverlay(overlay.clone()).to_string();
+/// assert!(kml_str.contains("<rotation>10</rotation>"));
+/// assert_eq!(kml_str.parse::<Kml>().unwrap(), Kml::GroundOverlay(overlay));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GroundOverlay<T: CoordType = f64> {
+    pub name: Option<String>,
+    pub extended_data: Option<ExtendedData>,
+    /// Colors in the `rrggbb` order of HTML are converted when reading
+    pub color: Option<Color>,
+    pub draw_order: i32,
+    pub icon: Option<Icon>,
+    pub altitude: T,
+    pub altitude_mode: AltitudeMode,
+    pub lat_lon_box: Option<LatLonBox<T>>,
+    pub attrs: HashMap<String, String>,
+    pub children: Vec<Element>,
+}
+
+impl<T> GroundOverlay<T>
+where
+    T: CoordType,
+{
+    pub fn new(icon: Icon) -> Self {
+        GroundOverlay {
+            icon: Some(icon),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T> Default for GroundOverlay<T>
+where
+    T: CoordType,
+{
+    fn default() -> Self {
+        GroundOverlay {
+            name: None,
+            extended_data: None,
+            color: None,
+            draw_order: 0,
+            icon: None,
+            altitude: T::zero(),
+            altitude_mode: AltitudeMode::default(),
+            lat_lon_box: None,
+            attrs: HashMap::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl_with!(GroundOverlay<T> {
+    with_name(some name: String),
+    with_extended_data(some extended_data: ExtendedData),
+    with_color(some color: Color),
+    with_draw_order(draw_order: i32),
+    with_icon(some icon: Icon),
+    with_altitude(altitude: T),
+    with_altitude_mode(altitude_mode: AltitudeMode),
+    with_lat_lon_box(some lat_lon_box: LatLonBox<T>),
+});
+
+fn content(element: &Element) -> &str {
+    element.content.as_deref().unwrap_or_default().trim()
+}
+
+fn text_element(name: &str, content: impl ToString) -> Element {
+    Element::new(name).with_content(content.to_string())
+}
+
+impl<T> LatLonBox<T>
+where
+    T: CoordType + FromStr,
+{
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "LatLonBox" {
+            return None;
+        }
+        let mut b = LatLonBox {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "north" => b.north = content(child).parse().ok()?,
+                "south" => b.south = content(child).parse().ok()?,
+                "east" => b.east = content(child).parse().ok()?,
+                "west" => b.west = content(child).parse().ok()?,
+                "rotation" => b.rotation = content(child).parse().ok()?,
+                _ => {}
+            }
+        }
+        Some(b)
+    }
+}
+
+impl<T> CustomElement for LatLonBox<T>
+where
+    T: CoordType + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        LatLonBox::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("LatLonBox")
+            .with_child(text_element("north", self.north))
+            .with_child(text_element("south", self.south))
+            .with_child(text_element("east", self.east))
+            .with_child(text_element("west", self.west));
+        element.attrs = self.attrs.clone();
+        if self.rotation != 0. {
+            element = element.with_child(text_element("rotation", self.rotation));
+        }
+        element
+    }
+}
+
+impl<T> GroundOverlay<T>
+where
+    T: CoordType + FromStr,
+{
+    // Reads an overlay from an element, which the reader needs without the `Display` bound of
+    // `CustomElement`
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "GroundOverlay" {
+            return None;
+        }
+        let mut overlay = GroundOverlay {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "name" => overlay.name = child.content.clone(),
+                "ExtendedData" if overlay.extended_data.is_none() => {
+                    match ExtendedData::from_element(child) {
+                        Some(d) => overlay.extended_data = Some(d),
+                        None => overlay.children.push(child.clone()),
+                    }
+                }
+                "color" => overlay.color = Some(Color::parse_normalized(content(child)).ok()?),
+                "drawOrder" => overlay.draw_order = content(child).parse().ok()?,
+                "Icon" => overlay.icon = Some(Icon::from_element(child)?),
+                "altitude" => overlay.altitude = content(child).parse().ok()?,
+                "altitudeMode" => overlay.altitude_mode = content(child).parse().ok()?,
+                "LatLonBox" => overlay.lat_lon_box = Some(LatLonBox::parse_element(child)?),
+                _ => overlay.children.push(child.clone()),
+            }
+        }
+        Some(overlay)
+    }
+}
+
+impl<T> CustomElement for GroundOverlay<T>
+where
+    T: CoordType + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        GroundOverlay::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("GroundOverlay");
+        element.attrs = self.attrs.clone();
+        if let Some(name) = &self.name {
+            element = element.with_child(text_element("name", name));
+        }
+        let (lat_lon_quads, children): (Vec<&Element>, Vec<&Element>) = self
+            .children
+            .iter()
+            .partition(|c| c.local_name() == "LatLonQuad");
+        for child in children {
+            element = element.with_child(child.clone());
+        }
+        if let Some(extended_data) = &self.extended_data {
+            element = element.with_child(extended_data.to_element());
+        }
+        if let Some(color) = &self.color {
+            element = element.with_child(text_element("color", color));
+        }
+        if self.draw_order != 0 {
+            element = element.with_child(text_element("drawOrder", self.draw_order));
+        }
+        if let Some(icon) = &self.icon {
+            element = element.with_child(icon.to_element());
+        }
+        if !self.altitude.is_zero() {
+            element = element.with_child(text_element("altitude", self.altitude));
+        }
+        if let Some(altitude_mode) = altitude_mode_element(&self.altitude_mode) {
+            element = element.with_child(altitude_mode);
+        }
+        if let Some(lat_lon_box) = &self.lat_lon_box {
+            element = element.with_child(lat_lon_box.to_element());
+        }
+        for child in lat_lon_quads {
+            element = element.with_child(child.clone());
+        }
+        element
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Data;
+
+    #[test]
+    fn test_ground_overlay_element_round_trip() {
+        let overlay: GroundOverlay = GroundOverlay::new(Icon::new("map.png"))
+            .with_name("Map")
+            .with_extended_data(ExtendedData::default().with_data(Data::new("source", "scan")))
+            .with_color(Color::WHITE.with_alpha(0x7f))
+            .with_draw_order(2)
+            .with_altitude(10.)
+            .with_altitude_mode(AltitudeMode::Absolute)
+            .with_lat_lon_box(LatLonBox::new(40., 39., -104., -105.).with_rotation(12.5));
+        let element = overlay.to_element();
+        assert_eq!(
+            element.child("color").unwrap().content.as_deref(),
+            Some("7fffffff")
+        );
+        assert_eq!(GroundOverlay::from_element(&element), Some(overlay));
+
+        // Defaults aren't written
+        let element = GroundOverlay::<f64>::new(Icon::new("map.png")).to_element();
+        assert_eq!(element.children.len(), 1);
+    }
+
+    #[test]
+    fn test_ground_overlay_children() {
+        let quad = Element::new("gx:LatLonQuad")
+            .with_child(text_element("coordinates", "0,0 1,0 1,1 0,1"));
+        let element = Element::new("GroundOverlay")
+            .with_child(quad.clone())
+            .with_child(text_element("visibility", "0"))
+            .with_child(text_element("color", "#ff0000"));
+        let overlay: GroundOverlay = GroundOverlay::from_element(&element).unwrap();
+        assert_eq!(overlay.color, Some(Color::from_rgb(0xff, 0, 0)));
+        assert_eq!(overlay.lat_lon_box, None);
+        assert_eq!(overlay.children.len(), 2);
+
+        // `gx:LatLonQuad` is written last, after the typed fields
+        let written = overlay.to_element();
+        assert_eq!(written.children.last(), Some(&quad));
+        assert_eq!(written.children[0].local_name(), "visibility");
+    }
+
+    #[test]
+    fn test_read_invalid_ground_overlay() {
+        let invalid = |child: Element| {
+            let element = Element::new("GroundOverlay").with_child(child);
+            GroundOverlay::<f64>::from_element(&element)
+        };
+        assert_eq!(invalid(text_element("color", "red")), None);
+        assert_eq!(invalid(text_element("drawOrder", "1.5")), None);
+        assert_eq!(invalid(text_element("altitude", "")), None);
+        assert_eq!(
+            invalid(Element::new("LatLonBox").with_child(text_element("rotation", "x"))),
+            None
+        );
+        assert_eq!(
+            GroundOverlay::<f64>::from_element(&Element::new("Overlay")),
+            None
+        );
+        assert_eq!(
+            LatLonBox::<f64>::from_element(&Element::new("LatLonAltBox")),
+            None
+        );
+    }
+}
@@ -1,31 +1,41 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::str::FromStr;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::errors::Error;
+use crate::types::geometry::{CloseRings, DedupCoords, OrientPolygons};
 use crate::types::{
-    BalloonStyle, CoordType, Element, Icon, IconStyle, LabelStyle, LineString, LineStyle,
-    LinearRing, ListStyle, Location, MultiGeometry, Orientation, Pair, Placemark, Point, PolyStyle,
-    Polygon, Scale, Style, StyleMap,
+    angle::Angle, normalize_color, BalloonStyle, Camera, CoordType, Custom, Element, FeatureProps,
+    GroundOverlay, Icon, IconStyle, ImagePyramid, LabelStyle, LatLonAltBox, LatLonBox, LineString,
+    LineStyle, LinearRing, ListStyle, Location, Lod, LookAt, Model, MultiGeometry, MultiTrack,
+    NetworkLink, Orientation, Pair, PhotoOverlay, Placemark, Point, PolyStyle, Polygon, Region,
+    Scale, Schema, ScreenOverlay, Style, StyleMap, TimeSpan, TimeStamp, Tour, Track, ViewVolume,
 };
+use crate::visit::KmlVisitor;
 
 /// Enum for representing the KML version being parsed
 ///
 /// According to <http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#7> namespace for 2.3
 /// is unchanged since it should be backwards-compatible
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Versions 2.0 and 2.1 predate the OGC standard and use Google's `http://earth.google.com/kml`
+/// namespaces.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[non_exhaustive]
 pub enum KmlVersion {
+    #[default]
     Unknown,
+    V20,
+    V21,
     V22,
     V23,
 }
 
-impl Default for KmlVersion {
-    fn default() -> KmlVersion {
-        KmlVersion::Unknown
-    }
-}
-
 // TODO: According to http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#7 namespace for 2.3
 // is unchanged since it should be backwards-compatible
 impl FromStr for KmlVersion {
@@ -34,24 +44,162 @@ impl FromStr for KmlVersion {
     // TODO: Support different Google Earth implementations? Only check end?
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "http://www.opengis.net/kml/2.2" => Ok(Self::V22),
+            "http://earth.google.com/kml/2.0" => Ok(Self::V20),
+            "http://earth.google.com/kml/2.1" => Ok(Self::V21),
+            "http://www.opengis.net/kml/2.2" | "http://earth.google.com/kml/2.2" => Ok(Self::V22),
             "http://www.opengis.net/kml/2.3" => Ok(Self::V23),
             v => Err(Error::InvalidKmlVersion(v.to_string())),
         }
     }
 }
 
+impl KmlVersion {
+    /// Returns the namespace URI of the version, or `None` if it's unknown
+    pub fn namespace(&self) -> Option<&'static str> {
+        match self {
+            Self::Unknown => None,
+            Self::V20 => Some("http://earth.google.com/kml/2.0"),
+            Self::V21 => Some("http://earth.google.com/kml/2.1"),
+            Self::V22 => Some("http://www.opengis.net/kml/2.2"),
+            Self::V23 => Some("http://www.opengis.net/kml/2.3"),
+        }
+    }
+
+    /// Returns `true` if the element from Google's extensions, without its `gx` prefix, is part of
+    /// this version's own namespace. KML 2.3 adopted `Track`, `MultiTrack`, `LatLonQuad`, and
+    /// `Tour` along with their children, which are only available as `gx` elements in 2.2, as well
+    /// as the sea floor modes of `gx:altitudeMode`.
+    pub fn is_native(&self, local_name: &str) -> bool {
+        *self == Self::V23 && GX_NATIVE_IN_V23.contains(&local_name)
+    }
+
+    // Adds or removes the gx prefix of an element name for the version, leaving names that are
+    // the same in every version as is
+    pub(crate) fn element_name<'e>(&self, name: &'e str) -> Cow<'e, str> {
+        match name.strip_prefix("gx:") {
+            Some(local_name) if self.is_native(local_name) => Cow::Borrowed(local_name),
+            // altitudeMode is in every version, but only has the sea floor modes from 2.3
+            None if *self == Self::V22 && Self::V23.is_native(name) && name != "altitudeMode" => {
+                Cow::Owned(format!("gx:{}", name))
+            }
+            _ => Cow::Borrowed(name),
+        }
+    }
+}
+
+// Extension elements adopted into the KML 2.3 namespace
+const GX_NATIVE_IN_V23: &[&str] = &[
+    "Track",
+    "MultiTrack",
+    "coord",
+    "angles",
+    "interpolate",
+    "LatLonQuad",
+    "Tour",
+    "Playlist",
+    "FlyTo",
+    "flyToMode",
+    "Wait",
+    "AnimatedUpdate",
+    "TourControl",
+    "playMode",
+    "SoundCue",
+    "duration",
+    "delayedStart",
+    "altitudeMode",
+];
+
+/// Namespace of Google's extensions, conventionally with the `gx` prefix
+pub const GX_NAMESPACE: &str = "http://www.google.com/kml/ext/2.2";
+/// Namespace of Atom elements like `atom:author`, conventionally with the `atom` prefix
+pub const ATOM_NAMESPACE: &str = "http://www.w3.org/2005/Atom";
+/// Namespace of `xal:AddressDetails`, conventionally with the `xal` prefix
+pub const XAL_NAMESPACE: &str = "urn:oasis:names:tc:ciq:xsdschema:xAL:2.0";
+
 /// Container for KML root element
 #[derive(Clone, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct KmlDocument<T: CoordType = f64> {
     pub version: KmlVersion,
     pub attrs: HashMap<String, String>,
     pub elements: Vec<Kml<T>>,
 }
 
+impl<T> KmlDocument<T>
+where
+    T: CoordType,
+{
+    /// Creates an empty document declaring the namespace of the version as the default namespace
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlDocument, KmlVersion};
+    ///
+    /// let doc: KmlDocument = KmlDocument::new(KmlVersion::V22).with_gx_namespace();
+    /// assert_eq!(
+    ///     Kml::KmlDocument(doc).to_string(),
+    ///     r#"<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2"></kml>"#
+    /// );
+    /// ```
+    pub fn new(version: KmlVersion) -> Self {
+        let mut attrs = HashMap::new();
+        if let Some(namespace) = version.namespace() {
+            attrs.insert("xmlns".to_string(), namespace.to_string());
+        }
+        KmlDocument {
+            version,
+            attrs,
+            elements: Vec::new(),
+        }
+    }
+
+    /// Declares a namespace with the prefix, returning `self` for chaining
+    pub fn with_namespace(mut self, prefix: &str, namespace: &str) -> Self {
+        self.attrs
+            .insert(format!("xmlns:{}", prefix), namespace.to_string());
+        self
+    }
+
+    /// Declares [`GX_NAMESPACE`](types/constant.GX_NAMESPACE.html) with the `gx` prefix
+    pub fn with_gx_namespace(self) -> Self {
+        self.with_namespace("gx", GX_NAMESPACE)
+    }
+
+    /// Declares [`ATOM_NAMESPACE`](types/constant.ATOM_NAMESPACE.html) with the `atom` prefix
+    pub fn with_atom_namespace(self) -> Self {
+        self.with_namespace("atom", ATOM_NAMESPACE)
+    }
+
+    /// Declares [`XAL_NAMESPACE`](types/constant.XAL_NAMESPACE.html) with the `xal` prefix
+    pub fn with_xal_namespace(self) -> Self {
+        self.with_namespace("xal", XAL_NAMESPACE)
+    }
+
+    /// Returns the namespace declared for the prefix, or the default namespace for an empty prefix
+    pub fn namespace(&self, prefix: &str) -> Option<&str> {
+        let key = if prefix.is_empty() {
+            "xmlns".to_string()
+        } else {
+            format!("xmlns:{}", prefix)
+        };
+        self.attrs.get(&key).map(String::as_str)
+    }
+
+    /// Returns the version of KML declared by the default namespace, which is
+    /// [`KmlVersion::Unknown`](enum.KmlVersion.html#variant.Unknown) if it's missing or isn't a
+    /// KML namespace
+    pub fn declared_version(&self) -> KmlVersion {
+        self.namespace("")
+            .and_then(|ns| ns.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
 /// Enum for representing any KML element
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[non_exhaustive]
 pub enum Kml<T: CoordType = f64> {
     KmlDocument(KmlDocument<T>),
@@ -63,13 +211,34 @@ pub enum Kml<T: CoordType = f64> {
     LinearRing(LinearRing<T>),
     Polygon(Polygon<T>),
     MultiGeometry(MultiGeometry<T>),
+    Track(Track<T>),
+    MultiTrack(MultiTrack<T>),
+    Model(Model<T>),
     Placemark(Placemark<T>),
+    Tour(Tour),
+    TimeStamp(TimeStamp),
+    TimeSpan(TimeSpan),
+    NetworkLink(NetworkLink<T>),
+    Region(Region<T>),
+    Lod(Lod),
+    LatLonAltBox(LatLonAltBox<T>),
+    GroundOverlay(GroundOverlay<T>),
+    LatLonBox(LatLonBox<T>),
+    ScreenOverlay(ScreenOverlay),
+    PhotoOverlay(PhotoOverlay<T>),
+    ViewVolume(ViewVolume),
+    ImagePyramid(ImagePyramid),
+    Schema(Schema),
+    Camera(Camera<T>),
+    LookAt(LookAt<T>),
     Document {
         attrs: HashMap<String, String>,
+        props: FeatureProps,
         elements: Vec<Kml<T>>,
     },
     Folder {
         attrs: HashMap<String, String>,
+        props: FeatureProps,
         elements: Vec<Kml<T>>,
     },
     Style(Style),
@@ -83,4 +252,404 @@ pub enum Kml<T: CoordType = f64> {
     PolyStyle(PolyStyle),
     ListStyle(ListStyle),
     Element(Element),
+    Custom(Custom),
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Orients the rings of every `kml:Polygon` in the tree so outer rings wind counter-clockwise and
+    /// inner rings wind clockwise, returning the number of rings changed. See [`Polygon::orient`](types/struct.Polygon.html#method.orient)
+    pub fn orient_polygons(&mut self) -> usize {
+        let mut visitor = OrientPolygons(0);
+        self.walk(&mut visitor);
+        visitor.0
+    }
+
+    /// Closes every `kml:LinearRing` in the tree by appending its first coordinate where the first
+    /// and last coordinates don't match, returning the number of rings changed
+    ///
+    /// Unclosed rings render incorrectly in many viewers, so this can be used to repair input
+    /// before writing it back out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::Kml;
+    ///
+    /// let mut kml: Kml = "<LinearRing><coordinates>0,0 1,0 1,1</coordinates></LinearRing>"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(kml.close_rings(), 1);
+    /// assert!(kml.validate().is_empty());
+    /// ```
+    pub fn close_rings(&mut self) -> usize {
+        let mut visitor = CloseRings(0);
+        self.walk(&mut visitor);
+        visitor.0
+    }
+
+    /// Removes consecutive coordinates within `tolerance` of each other from every line and ring in
+    /// the tree, returning the number of coordinates removed
+    ///
+    /// Duplicate vertices are common in GPS exports, and inflate files and break algorithms that
+    /// expect segments to have a length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::Kml;
+    ///
+    /// let mut kml: Kml = "<LineString><coordinates>0,0 0,0 1,1</coordinates></LineString>"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(kml.dedup_coords(0.), 1);
+    /// ```
+    pub fn dedup_coords(&mut self, tolerance: T) -> usize {
+        let mut visitor = DedupCoords(tolerance, 0);
+        self.walk(&mut visitor);
+        visitor.1
+    }
+
+    /// Normalizes the colors of elements in the tree that were kept as
+    /// [`Element`](types/struct.Element.html) to `aabbggrr` order, returning the number of colors
+    /// changed. See [`normalize_color`](types/fn.normalize_color.html)
+    ///
+    /// Style and overlay colors are [`Color`](types/struct.Color.html)s, which are always in
+    /// `aabbggrr` order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlReader};
+    ///
+    /// // The invalid `drawOrder` keeps the overlay as an element
+    /// let mut kml: Kml = KmlReader::<_, f64>::from_string(
+    ///     "<GroundOverlay><color>#ff0000</color><drawOrder>top</drawOrder></GroundOverlay>",
+    /// )
+    /// .lenient(true)
+    /// .read()
+    /// .unwrap();
+    /// assert_eq!(kml.normalize_colors(), 1);
+    /// if let Kml::Element(overlay) = kml {
+    ///     assert_eq!(overlay.children[0].content.as_deref(), Some("ff0000ff"));
+    /// }
+    /// ```
+    pub fn normalize_colors(&mut self) -> usize {
+        let mut visitor = NormalizeColors(0);
+        self.walk(&mut visitor);
+        visitor.0
+    }
+
+    /// Normalizes headings, tilts, rolls, and view coordinates in the tree that are outside of the
+    /// ranges in the specification, returning the number of values changed
+    ///
+    /// Headings and rolls are wrapped around their ranges, and tilts and latitudes are clamped, as
+    /// Google Earth otherwise does silently. This covers `kml:Orientation`, the heading of
+    /// `kml:IconStyle`, `kml:Location`, and views like `kml:Camera` and `kml:LookAt`, including
+    /// those read as [`Element`](types/struct.Element.html).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, types::AbstractView};
+    ///
+    /// let mut kml: Kml = r#"<Placemark>
+    ///   <LookAt><heading>-45</heading><tilt>120</tilt></LookAt>
+    /// </Placemark>"#.parse().unwrap();
+    /// assert_eq!(kml.normalize_angles(), 2);
+    /// if let Kml::Placemark(p) = kml {
+    ///     let look_at = match p.view {
+    ///         Some(AbstractView::LookAt(l)) => l,
+    ///         v => panic!("expected LookAt, found {:?}", v),
+    ///     };
+    ///     assert_eq!((look_at.heading, look_at.tilt), (315., 90.));
+    /// }
+    /// ```
+    pub fn normalize_angles(&mut self) -> usize {
+        let mut visitor = NormalizeAngles(0);
+        self.walk(&mut visitor);
+        visitor.0
+    }
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    // Name of the variant used in conversion errors and tracing
+    pub(crate) fn variant_name(&self) -> &'static str {
+        match self {
+            Kml::KmlDocument(_) => "KmlDocument",
+            Kml::Scale(_) => "Scale",
+            Kml::Orientation(_) => "Orientation",
+            Kml::Point(_) => "Point",
+            Kml::Location(_) => "Location",
+            Kml::LineString(_) => "LineString",
+            Kml::LinearRing(_) => "LinearRing",
+            Kml::Polygon(_) => "Polygon",
+            Kml::MultiGeometry(_) => "MultiGeometry",
+            Kml::Track(_) => "Track",
+            Kml::MultiTrack(_) => "MultiTrack",
+            Kml::Model(_) => "Model",
+            Kml::Placemark(_) => "Placemark",
+            Kml::Tour(_) => "Tour",
+            Kml::TimeStamp(_) => "TimeStamp",
+            Kml::TimeSpan(_) => "TimeSpan",
+            Kml::NetworkLink(_) => "NetworkLink",
+            Kml::Region(_) => "Region",
+            Kml::Lod(_) => "Lod",
+            Kml::LatLonAltBox(_) => "LatLonAltBox",
+            Kml::GroundOverlay(_) => "GroundOverlay",
+            Kml::LatLonBox(_) => "LatLonBox",
+            Kml::ScreenOverlay(_) => "ScreenOverlay",
+            Kml::PhotoOverlay(_) => "PhotoOverlay",
+            Kml::ViewVolume(_) => "ViewVolume",
+            Kml::ImagePyramid(_) => "ImagePyramid",
+            Kml::Schema(_) => "Schema",
+            Kml::Camera(_) => "Camera",
+            Kml::LookAt(_) => "LookAt",
+            Kml::Document { .. } => "Document",
+            Kml::Folder { .. } => "Folder",
+            Kml::Style(_) => "Style",
+            Kml::StyleMap(_) => "StyleMap",
+            Kml::Pair(_) => "Pair",
+            Kml::BalloonStyle(_) => "BalloonStyle",
+            Kml::IconStyle(_) => "IconStyle",
+            Kml::Icon(_) => "Icon",
+            Kml::LabelStyle(_) => "LabelStyle",
+            Kml::LineStyle(_) => "LineStyle",
+            Kml::PolyStyle(_) => "PolyStyle",
+            Kml::ListStyle(_) => "ListStyle",
+            Kml::Element(_) => "Element",
+            Kml::Custom(_) => "Custom",
+        }
+    }
+
+    // Number of elements in the tree, counting containers and their contents
+    #[cfg(feature = "tracing")]
+    pub(crate) fn count_elements(&self) -> usize {
+        let children = match self {
+            Kml::KmlDocument(d) => &d.elements,
+            Kml::Document { elements, .. } | Kml::Folder { elements, .. } => elements,
+            _ => return 1,
+        };
+        1 + children.iter().map(Kml::count_elements).sum::<usize>()
+    }
+}
+
+// Implements `TryFrom` for extracting the type from its `Kml` variant by value and by reference,
+// returning `Error::UnexpectedKml` for any other variant
+macro_rules! impl_try_from_kml {
+    ($($variant:ident => $ty:ty),* $(,)?) => {
+        $(
+            impl<T: CoordType> TryFrom<Kml<T>> for $ty {
+                type Error = Error;
+
+                fn try_from(kml: Kml<T>) -> Result<Self, Self::Error> {
+                    match kml {
+                        Kml::$variant(v) => Ok(v),
+                        k => Err(Error::UnexpectedKml {
+                            expected: stringify!($variant),
+                            found: k.variant_name(),
+                        }),
+                    }
+                }
+            }
+
+            impl<T: CoordType> From<$ty> for Kml<T> {
+                fn from(v: $ty) -> Self {
+                    Kml::$variant(v)
+                }
+            }
+
+            impl<'a, T: CoordType> TryFrom<&'a Kml<T>> for &'a $ty {
+                type Error = Error;
+
+                fn try_from(kml: &'a Kml<T>) -> Result<Self, Self::Error> {
+                    match kml {
+                        Kml::$variant(v) => Ok(v),
+                        k => Err(Error::UnexpectedKml {
+                            expected: stringify!($variant),
+                            found: k.variant_name(),
+                        }),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_kml!(
+    KmlDocument => KmlDocument<T>,
+    Scale => Scale<T>,
+    Orientation => Orientation<T>,
+    Point => Point<T>,
+    Location => Location<T>,
+    LineString => LineString<T>,
+    LinearRing => LinearRing<T>,
+    Polygon => Polygon<T>,
+    MultiGeometry => MultiGeometry<T>,
+    Track => Track<T>,
+    MultiTrack => MultiTrack<T>,
+    Model => Model<T>,
+    Placemark => Placemark<T>,
+    Tour => Tour,
+    TimeStamp => TimeStamp,
+    TimeSpan => TimeSpan,
+    NetworkLink => NetworkLink<T>,
+    Region => Region<T>,
+    Lod => Lod,
+    LatLonAltBox => LatLonAltBox<T>,
+    GroundOverlay => GroundOverlay<T>,
+    LatLonBox => LatLonBox<T>,
+    ScreenOverlay => ScreenOverlay,
+    PhotoOverlay => PhotoOverlay<T>,
+    ViewVolume => ViewVolume,
+    ImagePyramid => ImagePyramid,
+    Schema => Schema,
+    Camera => Camera<T>,
+    LookAt => LookAt<T>,
+    Style => Style,
+    StyleMap => StyleMap,
+    Pair => Pair,
+    BalloonStyle => BalloonStyle,
+    IconStyle => IconStyle,
+    Icon => Icon,
+    LabelStyle => LabelStyle,
+    LineStyle => LineStyle,
+    PolyStyle => PolyStyle,
+    ListStyle => ListStyle,
+    Element => Element,
+    Custom => Custom,
+);
+
+// Normalizes colors of elements without a typed representation, counting the colors changed
+struct NormalizeColors(usize);
+
+impl<T: CoordType> KmlVisitor<T> for NormalizeColors {
+    fn visit_element(&mut self, element: &mut Element) {
+        self.0 += normalize_element_colors(element);
+    }
+}
+
+// Normalizes angles of orientations, locations, views, and icon styles, including those of elements
+// without a typed representation, counting the values changed
+struct NormalizeAngles(usize);
+
+impl<T: CoordType> KmlVisitor<T> for NormalizeAngles {
+    fn visit_icon_style(&mut self, icon_style: &mut IconStyle) {
+        self.0 += icon_style.normalize_heading() as usize;
+    }
+
+    fn visit_camera(&mut self, camera: &mut Camera<T>) {
+        self.0 += camera.normalize();
+    }
+
+    fn visit_look_at(&mut self, look_at: &mut LookAt<T>) {
+        self.0 += look_at.normalize();
+    }
+
+    fn visit_location(&mut self, location: &mut Location<T>) {
+        self.0 += Angle::Latitude.normalize_in_place(&mut location.latitude) as usize
+            + Angle::Longitude.normalize_in_place(&mut location.longitude) as usize;
+    }
+
+    fn visit_orientation(&mut self, orientation: &mut Orientation<T>) {
+        self.0 += orientation.normalize();
+    }
+
+    fn visit_element(&mut self, element: &mut Element) {
+        self.0 += normalize_element_angles(element);
+    }
+}
+
+// Normalizes `color`, `bgColor`, and `textColor` children of elements without a typed
+// representation, leaving values that can't be interpreted as colors as is
+fn normalize_element_colors(element: &mut Element) -> usize {
+    let mut changed = 0;
+    for child in element.children.iter_mut() {
+        if !matches!(child.local_name(), "color" | "bgColor" | "textColor") {
+            changed += normalize_element_colors(child);
+            continue;
+        }
+        let content = child.content.as_deref().unwrap_or_default();
+        match normalize_color(content) {
+            Some(normalized) if normalized != content => {
+                child.content = Some(normalized);
+                changed += 1;
+            }
+            _ => {}
+        }
+    }
+    changed
+}
+
+// Normalizes angles in the children of views and orientations without a typed representation,
+// leaving values that can't be parsed as is
+fn normalize_element_angles(element: &mut Element) -> usize {
+    let mut changed = 0;
+    let parent = element.local_name().to_string();
+    for child in element.children.iter_mut() {
+        let angle = match Angle::for_field(&parent, child.local_name()) {
+            Some(angle) => angle,
+            None => {
+                changed += normalize_element_angles(child);
+                continue;
+            }
+        };
+        let value = child
+            .content
+            .as_deref()
+            .and_then(|c| c.trim().parse::<f64>().ok());
+        if let Some(mut value) = value {
+            if angle.normalize_in_place(&mut value) {
+                child.content = Some(value.to_string());
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_declared_version() {
+        let kml: Kml = r#"<kml xmlns="http://www.opengis.net/kml/2.3" xmlns:gx="http://www.google.com/kml/ext/2.2"></kml>"#
+            .parse()
+            .unwrap();
+        let doc = KmlDocument::try_from(kml).unwrap();
+        assert_eq!(doc.version, KmlVersion::V23);
+        assert_eq!(doc.namespace("gx"), Some(GX_NAMESPACE));
+        assert_eq!(doc, KmlDocument::new(KmlVersion::V23).with_gx_namespace());
+
+        let kml: Kml = r#"<kml xmlns="http://earth.google.com/kml/2.1"></kml>"#
+            .parse()
+            .unwrap();
+        assert_eq!(KmlDocument::try_from(kml).unwrap().version, KmlVersion::V21);
+    }
+
+    #[test]
+    fn test_try_from_kml() {
+        let kml: Kml = "<Point><coordinates>1,1</coordinates></Point>"
+            .parse()
+            .unwrap();
+        let point = <&Point>::try_from(&kml).unwrap();
+        assert_eq!(point.coord, (1., 1.).into());
+        assert!(matches!(
+            Polygon::try_from(kml.clone()),
+            Err(Error::UnexpectedKml {
+                expected: "Polygon",
+                found: "Point"
+            })
+        ));
+        assert_eq!(Point::try_from(kml).unwrap().coord, (1., 1.).into());
+
+        let kml: Kml = "<Style id=\"s\"></Style>".parse().unwrap();
+        assert_eq!(Style::try_from(kml).unwrap().id, "s");
+    }
 }
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::types::altitude_mode::AltitudeMode;
-use crate::types::coord::{Coord, CoordType};
+use crate::types::coord::{dedup_coords, Coord, CoordType};
 
 /// `kml:LineString`, [10.7](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#488) in the
 /// KML specification
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LineString<T: CoordType = f64> {
     pub coords: Vec<Coord<T>>,
     pub extrude: bool,
@@ -14,6 +18,55 @@ pub struct LineString<T: CoordType = f64> {
     pub attrs: HashMap<String, String>,
 }
 
+impl<T> LineString<T>
+where
+    T: CoordType,
+{
+    /// Removes consecutive coordinates within `tolerance` of each other, which also removes
+    /// zero-length segments, returning the number of coordinates removed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Coord, LineString};
+    ///
+    /// let mut line = LineString::from(vec![
+    ///     Coord::from((0., 0.)),
+    ///     Coord::from((0., 0.)),
+    ///     Coord::from((1., 1.)),
+    ///     Coord::from((1.000001, 1.)),
+    /// ]);
+    /// assert_eq!(line.dedup_coords(1e-5), 2);
+    /// assert_eq!(line.coords, vec![Coord::from((0., 0.)), Coord::from((1., 1.))]);
+    /// ```
+    pub fn dedup_coords(&mut self, tolerance: T) -> usize {
+        dedup_coords(&mut self.coords, tolerance)
+    }
+}
+
+impl<T> LineString<T>
+where
+    T: CoordType + Default,
+{
+    /// Creates a line from anything that converts into coordinates, like tuples or arrays
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Coord, LineString};
+    ///
+    /// let line = LineString::from_coords(vec![(0., 0.), (1., 1.)]);
+    /// assert_eq!(line.coords, vec![Coord::from((0., 0.)), Coord::from((1., 1.))]);
+    /// ```
+    pub fn from_coords<I, C>(coords: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<Coord<T>>,
+    {
+        LineString::from(coords.into_iter().map(Into::into).collect::<Vec<_>>())
+    }
+}
+
 impl<T> From<Vec<Coord<T>>> for LineString<T>
 where
     T: CoordType + Default,
@@ -25,3 +78,9 @@ where
         }
     }
 }
+
+impl_with!(LineString<T> {
+    with_extrude(extrude: bool),
+    with_tessellate(tessellate: bool),
+    with_altitude_mode(altitude_mode: AltitudeMode),
+});
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::types::altitude_mode::AltitudeMode;
-use crate::types::coord::{Coord, CoordType};
+use crate::types::coord::{dedup_coords, Coord, CoordType};
 
 /// `kml:LinearRing`, [10.5](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#465) in the
 /// KML specification
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LinearRing<T: CoordType = f64> {
     pub coords: Vec<Coord<T>>,
     pub extrude: bool,
@@ -14,6 +18,128 @@ pub struct LinearRing<T: CoordType = f64> {
     pub attrs: HashMap<String, String>,
 }
 
+impl<T> LinearRing<T>
+where
+    T: CoordType,
+{
+    /// Returns `true` if the first and last coordinates are the same, as required by
+    /// [ATC-104](https://docs.opengeospatial.org/ts/14-068r2/14-068r2.html#atc-104)
+    pub fn is_closed(&self) -> bool {
+        self.coords.first() == self.coords.last()
+    }
+
+    /// Appends the first coordinate to the end of the ring if it isn't already closed, returning
+    /// `true` if the ring was changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Coord, LinearRing};
+    ///
+    /// let mut ring = LinearRing::from(vec![
+    ///     Coord::from((0., 0.)),
+    ///     Coord::from((1., 0.)),
+    ///     Coord::from((1., 1.)),
+    /// ]);
+    /// assert!(!ring.is_closed());
+    /// assert!(ring.close());
+    /// assert_eq!(ring.coords.last(), Some(&Coord::from((0., 0.))));
+    /// ```
+    pub fn close(&mut self) -> bool {
+        if self.is_closed() {
+            return false;
+        }
+        let first = self.coords[0];
+        self.coords.push(first);
+        true
+    }
+
+    /// Removes consecutive coordinates within `tolerance` of each other, returning the number of
+    /// coordinates removed
+    ///
+    /// Closed rings stay closed with the first coordinate repeated at the end, and coordinates just
+    /// before the end that are within `tolerance` of the first coordinate are removed too.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Coord, LinearRing};
+    ///
+    /// let mut ring = LinearRing::from(vec![
+    ///     Coord::from((0., 0.)),
+    ///     Coord::from((1., 0.)),
+    ///     Coord::from((1., 0.)),
+    ///     Coord::from((1., 1.)),
+    ///     Coord::from((0., 0.)),
+    ///     Coord::from((0., 0.)),
+    /// ]);
+    /// assert_eq!(ring.dedup_coords(0.), 2);
+    /// assert_eq!(ring.coords.len(), 4);
+    /// assert!(ring.is_closed());
+    /// ```
+    pub fn dedup_coords(&mut self, tolerance: T) -> usize {
+        if !self.is_closed() || self.coords.len() < 2 {
+            return dedup_coords(&mut self.coords, tolerance);
+        }
+        let len = self.coords.len();
+        let first = self.coords[0];
+        self.coords.pop();
+        dedup_coords(&mut self.coords, tolerance);
+        while self.coords.len() > 1
+            && self.coords[self.coords.len() - 1].approx_eq(&first, tolerance)
+        {
+            self.coords.pop();
+        }
+        self.coords.push(first);
+        len - self.coords.len()
+    }
+
+    /// Returns `true` if the coordinates wind counter-clockwise, based on the sign of the area
+    /// enclosed by the ring. Rings without any area are neither clockwise or counter-clockwise.
+    pub fn is_ccw(&self) -> bool {
+        self.signed_area() > T::zero()
+    }
+
+    /// Returns `true` if the coordinates wind clockwise, based on the sign of the area enclosed by
+    /// the ring
+    pub fn is_cw(&self) -> bool {
+        self.signed_area() < T::zero()
+    }
+
+    // Shoelace formula, treating the ring as closed even if the last coordinate is missing
+    fn signed_area(&self) -> T {
+        let n = self.coords.len();
+        let twice_area = (0..n).fold(T::zero(), |acc, i| {
+            let (a, b) = (self.coords[i], self.coords[(i + 1) % n]);
+            acc + (a.x * b.y - b.x * a.y)
+        });
+        twice_area / (T::one() + T::one())
+    }
+}
+
+impl<T> LinearRing<T>
+where
+    T: CoordType + Default,
+{
+    /// Creates a ring from anything that converts into coordinates, like tuples or arrays
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::LinearRing;
+    ///
+    /// let ring = LinearRing::from_coords(vec![[0., 0.], [1., 0.], [1., 1.], [0., 0.]]);
+    /// assert!(ring.is_closed());
+    /// ```
+    pub fn from_coords<I, C>(coords: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<Coord<T>>,
+    {
+        LinearRing::from(coords.into_iter().map(Into::into).collect::<Vec<_>>())
+    }
+}
+
 impl<T> From<Vec<Coord<T>>> for LinearRing<T>
 where
     T: CoordType + Default,
@@ -25,3 +151,9 @@ where
         }
     }
 }
+
+impl_with!(LinearRing<T> {
+    with_extrude(extrude: bool),
+    with_tessellate(tessellate: bool),
+    with_altitude_mode(altitude_mode: AltitudeMode),
+});
@@ -0,0 +1,31 @@
+// Implements chainable `with_*` setters that consume and return `self`. Fields prefixed with
+// `some` are `Option`s that are set to `Some` of the value.
+macro_rules! impl_with {
+    (@setter $method:ident, some $field:ident: $ty:ty) => {
+        #[doc = concat!("Sets `", stringify!($field), "` to `Some` of the value, returning `self` for chaining")]
+        pub fn $method(mut self, $field: impl Into<$ty>) -> Self {
+            self.$field = Some($field.into());
+            self
+        }
+    };
+    (@setter $method:ident, $field:ident: $ty:ty) => {
+        #[doc = concat!("Sets `", stringify!($field), "`, returning `self` for chaining")]
+        pub fn $method(mut self, $field: impl Into<$ty>) -> Self {
+            self.$field = $field.into();
+            self
+        }
+    };
+    ($name:ident<T> { $($method:ident($($field:tt)+)),* $(,)? }) => {
+        impl<T> $name<T>
+        where
+            T: CoordType,
+        {
+            $(impl_with!(@setter $method, $($field)+);)*
+        }
+    };
+    ($name:ident { $($method:ident($($field:tt)+)),* $(,)? }) => {
+        impl $name {
+            $(impl_with!(@setter $method, $($field)+);)*
+        }
+    };
+}
@@ -1,48 +1,88 @@
 //! Module containing types for KML elements
+#[macro_use]
+mod macros;
+
 mod altitude_mode;
+pub(crate) mod angle;
+mod color;
 mod coord;
+mod date_time;
 
-pub use altitude_mode::AltitudeMode;
+pub use altitude_mode::{AltitudeMode, AltitudeUnit};
+pub use color::Color;
 pub use coord::{coords_from_str, Coord, CoordType};
+pub(crate) use coord::{lit, split_tuples};
+pub use date_time::KmlDateTime;
 
+mod extended_data;
+mod ground_overlay;
 mod line_string;
 mod linear_ring;
 mod location;
+mod model;
 mod multi_geometry;
+mod network_link;
 mod orientation;
+mod photo_overlay;
 mod point;
 mod polygon;
+mod region;
 mod scale;
+mod time_primitive;
+mod tour;
+mod track;
 mod vec2;
+mod view;
 
+pub use extended_data::{Data, ExtendedData, Schema, SchemaData, SimpleData, SimpleField};
+pub use ground_overlay::{GroundOverlay, LatLonBox};
 pub use line_string::LineString;
 pub use linear_ring::LinearRing;
 pub use location::Location;
+pub use model::{Alias, Model, ResourceMap};
 pub use multi_geometry::MultiGeometry;
+pub use network_link::{Link, NetworkLink, RefreshMode, ViewRefreshMode};
 pub use orientation::Orientation;
+pub use photo_overlay::{GridOrigin, ImagePyramid, PhotoOverlay, PhotoShape, ViewVolume};
 pub use point::Point;
 pub use polygon::Polygon;
+pub use region::{LatLonAltBox, Lod, Region};
 pub use scale::Scale;
+pub use time_primitive::{TimePrimitive, TimeSpan, TimeStamp};
+pub use tour::{AnimatedUpdate, FlyTo, FlyToMode, SoundCue, Tour, TourPrimitive};
+pub use track::{GxMultiTrack, GxTrack, MultiTrack, SimpleArrayData, Track, TrackSample};
 pub use vec2::{Units, Vec2};
+pub use view::{AbstractView, Camera, LookAt};
 
+mod balloon;
+mod builder;
+mod custom;
 mod element;
+mod feature;
 pub(crate) mod geom_props;
 mod placemark;
 
-pub use element::Element;
+pub use builder::{DocumentBuilder, PlacemarkBuilder, PolygonBuilder, StyleBuilder};
+pub use custom::Custom;
+pub use element::{CustomElement, Element};
+pub use feature::{FeatureProps, Snippet};
 pub use placemark::Placemark;
 
 mod geometry;
 
 pub use geometry::Geometry;
 
+mod screen_overlay;
+mod standard_icon;
 mod style;
 
+pub use screen_overlay::{ScreenAnchor, ScreenOverlay};
+pub use standard_icon::{IconColor, PaddleSymbol, Shape, StandardIcon, STANDARD_ICON_BASE};
 pub use style::{
-    BalloonStyle, ColorMode, Icon, IconStyle, LabelStyle, LineStyle, ListStyle, Pair, PolyStyle,
-    Style, StyleMap,
+    normalize_color, BalloonStyle, ColorMode, Icon, IconStyle, LabelStyle, LineStyle, ListStyle,
+    Pair, PolyStyle, Style, StyleMap,
 };
 
 mod kml;
 
-pub use self::kml::{Kml, KmlDocument, KmlVersion};
+pub use self::kml::{Kml, KmlDocument, KmlVersion, ATOM_NAMESPACE, GX_NAMESPACE, XAL_NAMESPACE};
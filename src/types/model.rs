@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::altitude_mode::AltitudeMode;
+use crate::types::coord::CoordType;
+use crate::types::element::{CustomElement, Element};
+use crate::types::location::Location;
+use crate::types::network_link::Link;
+use crate::types::orientation::Orientation;
+use crate::types::scale::Scale;
+
+/// `kml:Alias`, [10.14](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#595) in the KML
+/// specification, maps a texture path in a model file to the path of the texture in the KML or KMZ
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Alias {
+    /// Path of the texture in the KML or KMZ
+    pub target_href: String,
+    /// Path of the texture referenced by the model file
+    pub source_href: String,
+    pub attrs: HashMap<String, String>,
+}
+
+impl Alias {
+    pub fn new(target_href: impl Into<String>, source_href: impl Into<String>) -> Self {
+        Alias {
+            target_href: target_href.into(),
+            source_href: source_href.into(),
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+/// `kml:ResourceMap`, [10.13](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#586) in
+/// the KML specification, the texture aliases of a [`Model`](struct.Model.html)
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ResourceMap {
+    pub aliases: Vec<Alias>,
+    pub attrs: HashMap<String, String>,
+}
+
+impl ResourceMap {
+    /// Adds an alias, returning `self` for chaining
+    pub fn with_alias(mut self, alias: Alias) -> Self {
+        self.aliases.push(alias);
+        self
+    }
+}
+
+/// `kml:Model`, [10.9](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#520) in the KML
+/// specification, a 3D model in a COLLADA file placed at `location`
+///
+/// Unknown children are kept in `children`, and are written last.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, types::{Alias, Link, Location, Model, Orientation, Placemark, ResourceMap}};
+///
+/// let model = Model::new(Link::new("models/house.dae"))
+///     .with_location(Location::new(39.5, -105.2, 0.))
+///     .with_orientation(Orientation::new(0., 0., 90.))
+///     .with_resource_map(
+///         ResourceMap::default().with_alias(Alias::new("textures/roof.png", "roof.png")),
+///     );
+/// let kml = Kml::Placemark(Placemark::default().with_geometry(model));
+///
+/// let kml_str = kml.to_string();
+/// assert!(kml_str.contains("<sourceHref>roof.png</sourceHref>"));
+/// assert_eq!(kml_str.parse::<Kml>().unwrap(), kml);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Model<T: CoordType = f64> {
+    pub altitude_mode: AltitudeMode,
+    pub location: Option<Location<T>>,
+    pub orientation: Option<Orientation<T>>,
+    pub scale: Option<Scale<T>>,
+    pub link: Option<Link>,
+    pub resource_map: Option<ResourceMap>,
+    pub attrs: HashMap<String, String>,
+    pub children: Vec<Element>,
+}
+
+impl<T> Model<T>
+where
+    T: CoordType,
+{
+    pub fn new(link: Link) -> Self {
+        Model {
+            altitude_mode: AltitudeMode::default(),
+            location: None,
+            orientation: None,
+            scale: None,
+            link: Some(link),
+            resource_map: None,
+            attrs: HashMap::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl_with!(Model<T> {
+    with_altitude_mode(altitude_mode: AltitudeMode),
+    with_location(some location: Location<T>),
+    with_orientation(some orientation: Orientation<T>),
+    with_scale(some scale: Scale<T>),
+    with_link(some link: Link),
+    with_resource_map(some resource_map: ResourceMap),
+});
+
+fn child_content(element: &Element, local_name: &str) -> Option<String> {
+    element
+        .child(local_name)
+        .map(|c| c.content.as_deref().unwrap_or_default().trim().to_string())
+}
+
+impl CustomElement for Alias {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "Alias" {
+            return None;
+        }
+        Some(Alias {
+            target_href: child_content(element, "targetHref")?,
+            source_href: child_content(element, "sourceHref")?,
+            attrs: element.attrs.clone(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("Alias")
+            .with_child(Element::new("targetHref").with_content(self.target_href.clone()))
+            .with_child(Element::new("sourceHref").with_content(self.source_href.clone()));
+        element.attrs = self.attrs.clone();
+        element
+    }
+}
+
+impl CustomElement for ResourceMap {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "ResourceMap" {
+            return None;
+        }
+        Some(ResourceMap {
+            aliases: element
+                .children
+                .iter()
+                .filter(|c| c.local_name() == "Alias")
+                .map(Alias::from_element)
+                .collect::<Option<Vec<_>>>()?,
+            attrs: element.attrs.clone(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("ResourceMap");
+        element.attrs = self.attrs.clone();
+        for alias in self.aliases.iter() {
+            element = element.with_child(alias.to_element());
+        }
+        element
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_map_element_round_trip() {
+        let resource_map = ResourceMap::default()
+            .with_alias(Alias::new("textures/roof.png", "roof.png"))
+            .with_alias(Alias::new("textures/wall.png", "wall.png"));
+        let element = resource_map.to_element();
+        assert_eq!(ResourceMap::from_element(&element), Some(resource_map));
+
+        // Children other than aliases are skipped, and hrefs are trimmed
+        let alias = Element::new("Alias")
+            .with_child(Element::new("targetHref").with_content(" a.png "))
+            .with_child(Element::new("sourceHref").with_content("b.png"));
+        let element = Element::new("ResourceMap")
+            .with_child(Element::new("Note"))
+            .with_child(alias);
+        assert_eq!(
+            ResourceMap::from_element(&element),
+            Some(ResourceMap::default().with_alias(Alias::new("a.png", "b.png")))
+        );
+        assert_eq!(
+            ResourceMap::from_element(&Element::new("ResourceMap")),
+            Some(ResourceMap::default())
+        );
+    }
+
+    #[test]
+    fn test_read_invalid_resource_map() {
+        // Both hrefs of an alias are required
+        let alias = Element::new("Alias").with_child(Element::new("targetHref"));
+        assert_eq!(Alias::from_element(&alias), None);
+        let element = Element::new("ResourceMap").with_child(alias);
+        assert_eq!(ResourceMap::from_element(&element), None);
+        assert_eq!(ResourceMap::from_element(&Element::new("Alias")), None);
+        assert_eq!(Alias::from_element(&Element::new("ResourceMap")), None);
+    }
+
+    #[test]
+    fn test_model_new() {
+        let model: Model = Model::new(Link::new("house.dae"))
+            .with_location(Location::new(39.5, -105.2, 2.))
+            .with_scale(Scale::new(2., 2., 1.));
+        assert_eq!(model.link, Some(Link::new("house.dae")));
+        assert_eq!(model.altitude_mode, AltitudeMode::ClampToGround);
+        assert_eq!(model.orientation, None);
+        assert!(model.children.is_empty());
+    }
+}
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::types::coord::CoordType;
 use crate::types::geometry::Geometry;
 
 /// `kml:MultiGeometry`, [10.2](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#438) in the
 /// KML specification
 #[derive(Clone, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MultiGeometry<T: CoordType = f64> {
     pub geometries: Vec<Geometry<T>>,
     pub attrs: HashMap<String, String>,
@@ -21,4 +25,26 @@ where
             ..Default::default()
         }
     }
+
+    /// Creates a `MultiGeometry` from anything that converts into [`Geometry`], so a collection
+    /// of a single geometry type doesn't need to be wrapped first
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{MultiGeometry, Point};
+    ///
+    /// let points = MultiGeometry::from_geometries(vec![
+    ///     Point::new(0., 0., None),
+    ///     Point::new(1., 1., None),
+    /// ]);
+    /// assert_eq!(points.geometries.len(), 2);
+    /// ```
+    pub fn from_geometries<I, G>(geometries: I) -> Self
+    where
+        I: IntoIterator<Item = G>,
+        G: Into<Geometry<T>>,
+    {
+        MultiGeometry::new(geometries.into_iter().map(Into::into).collect())
+    }
 }
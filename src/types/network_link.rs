@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::types::coord::CoordType;
+use crate::types::element::{CustomElement, Element};
+use crate::types::extended_data::ExtendedData;
+use crate::types::region::Region;
+use crate::types::view::AbstractView;
+
+/// `kml:refreshMode` of a [`Link`](struct.Link.html)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RefreshMode {
+    #[default]
+    OnChange,
+    OnInterval,
+    OnExpire,
+}
+
+impl FromStr for RefreshMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "onChange" => Ok(Self::OnChange),
+            "onInterval" => Ok(Self::OnInterval),
+            "onExpire" => Ok(Self::OnExpire),
+            v => Err(Error::InvalidRefreshMode(v.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for RefreshMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::OnChange => "onChange",
+                Self::OnInterval => "onInterval",
+                Self::OnExpire => "onExpire",
+            }
+        )
+    }
+}
+
+/// `kml:viewRefreshMode` of a [`Link`](struct.Link.html)
+///
+/// `OnRegion` refreshes the link when its network link's [`Region`](struct.Region.html) becomes
+/// active.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ViewRefreshMode {
+    #[default]
+    Never,
+    OnRequest,
+    OnStop,
+    OnRegion,
+}
+
+impl FromStr for ViewRefreshMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(Self::Never),
+            "onRequest" => Ok(Self::OnRequest),
+            "onStop" => Ok(Self::OnStop),
+            "onRegion" => Ok(Self::OnRegion),
+            v => Err(Error::InvalidViewRefreshMode(v.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ViewRefreshMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Never => "never",
+                Self::OnRequest => "onRequest",
+                Self::OnStop => "onStop",
+                Self::OnRegion => "onRegion",
+            }
+        )
+    }
+}
+
+/// `kml:Link`, the file a [`NetworkLink`](struct.NetworkLink.html) loads and when it's
+/// refreshed, see [Google's Link reference](https://developers.google.com/kml/documentation/kmlreference#link)
+///
+/// Fields with their default values aren't written.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Link {
+    pub href: String,
+    pub refresh_mode: RefreshMode,
+    /// Seconds between refreshes with `RefreshMode::OnInterval`
+    pub refresh_interval: f64,
+    pub view_refresh_mode: ViewRefreshMode,
+    /// Seconds after the view stops before refreshing with `ViewRefreshMode::OnStop`
+    pub view_refresh_time: f64,
+    pub view_bound_scale: f64,
+    pub view_format: Option<String>,
+    pub http_query: Option<String>,
+    pub attrs: HashMap<String, String>,
+}
+
+impl Link {
+    pub fn new(href: impl Into<String>) -> Self {
+        Link {
+            href: href.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Link {
+    fn default() -> Self {
+        Link {
+            href: String::new(),
+            refresh_mode: RefreshMode::default(),
+            refresh_interval: 4.,
+            view_refresh_mode: ViewRefreshMode::default(),
+            view_refresh_time: 4.,
+            view_bound_scale: 1.,
+            view_format: None,
+            http_query: None,
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+impl_with!(Link {
+    with_refresh_mode(refresh_mode: RefreshMode),
+    with_refresh_interval(refresh_interval: f64),
+    with_view_refresh_mode(view_refresh_mode: ViewRefreshMode),
+    with_view_refresh_time(view_refresh_time: f64),
+    with_view_bound_scale(view_bound_scale: f64),
+    with_view_format(some view_format: String),
+    with_http_query(some http_query: String),
+});
+
+/// `kml:NetworkLink`, a feature loading another KML file, see
+/// [Google's NetworkLink reference](https://developers.google.com/kml/documentation/kmlreference#networklink)
+///
+/// Network links are read into [`Kml::NetworkLink`](../enum.Kml.html#variant.NetworkLink). Elements
+/// of the feature without a typed representation like `kml:description` and `kml:styleUrl` are
+/// kept in `children`, and are written before the view, region, and extended data.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, types::{LatLonAltBox, Link, Lod, NetworkLink, Region, ViewRefreshMode}};
+///
+/// // One tile of a super-overlay, loaded once it's at least 256 pixels across
+/// let tile = NetworkLink::new(
+///     Link::new("tiles/1/0/0.kml").with_view_refresh_mode(ViewRefreshMode::OnRegion),
+/// )
+/// .with_name("1/0/0")
+/// .with_region(Region::new(LatLonAltBox::new(90., 0., 0., -180.)).with_lod(Lod::new(256., -1.)));
+///
+/// let kml_str = Kml::NetworkLink(tile.clone()).to_string();
+/// assert!(kml_str.contains("<viewRefreshMode>onRegion</viewRefreshMode>"));
+/// assert_eq!(kml_str.parse::<Kml>().unwrap(), Kml::NetworkLink(tile));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NetworkLink<T: CoordType = f64> {
+    pub name: Option<String>,
+    pub view: Option<AbstractView<T>>,
+    pub region: Option<Region<T>>,
+    pub extended_data: Option<ExtendedData>,
+    /// Whether refreshing the link resets the visibility of its features to their `visibility`
+    pub refresh_visibility: bool,
+    /// Whether viewers fly to the view of the linked file when it's loaded
+    pub fly_to_view: bool,
+    pub link: Option<Link>,
+    pub attrs: HashMap<String, String>,
+    pub children: Vec<Element>,
+}
+
+impl<T> NetworkLink<T>
+where
+    T: CoordType,
+{
+    pub fn new(link: Link) -> Self {
+        NetworkLink {
+            link: Some(link),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T> Default for NetworkLink<T>
+where
+    T: CoordType,
+{
+    fn default() -> Self {
+        NetworkLink {
+            name: None,
+            view: None,
+            region: None,
+            extended_data: None,
+            refresh_visibility: false,
+            fly_to_view: false,
+            link: None,
+            attrs: HashMap::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl_with!(NetworkLink<T> {
+    with_name(some name: String),
+    with_view(some view: AbstractView<T>),
+    with_region(some region: Region<T>),
+    with_extended_data(some extended_data: ExtendedData),
+    with_refresh_visibility(refresh_visibility: bool),
+    with_fly_to_view(fly_to_view: bool),
+    with_link(some link: Link),
+});
+
+fn content(element: &Element) -> &str {
+    element.content.as_deref().unwrap_or_default().trim()
+}
+
+fn parse_bool(element: &Element) -> bool {
+    matches!(content(element), "1" | "true")
+}
+
+fn text_element(name: &str, content: impl ToString) -> Element {
+    Element::new(name).with_content(content.to_string())
+}
+
+impl CustomElement for Link {
+    fn from_element(element: &Element) -> Option<Self> {
+        // `kml:Url` is the name of links in KML 2.0
+        if !matches!(element.local_name(), "Link" | "Url") {
+            return None;
+        }
+        let mut link = Link {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "href" => link.href = content(child).to_string(),
+                "refreshMode" => link.refresh_mode = content(child).parse().ok()?,
+                "refreshInterval" => link.refresh_interval = content(child).parse().ok()?,
+                "viewRefreshMode" => link.view_refresh_mode = content(child).parse().ok()?,
+                "viewRefreshTime" => link.view_refresh_time = content(child).parse().ok()?,
+                "viewBoundScale" => link.view_bound_scale = content(child).parse().ok()?,
+                "viewFormat" => link.view_format = child.content.clone(),
+                "httpQuery" => link.http_query = child.content.clone(),
+                _ => {}
+            }
+        }
+        Some(link)
+    }
+
+    fn to_element(&self) -> Element {
+        let defaults = Link::default();
+        let mut element = Element::new("Link").with_child(text_element("href", &self.href));
+        element.attrs = self.attrs.clone();
+        if self.refresh_mode != defaults.refresh_mode {
+            element = element.with_child(text_element("refreshMode", self.refresh_mode));
+        }
+        if self.refresh_interval != defaults.refresh_interval {
+            element = element.with_child(text_element("refreshInterval", self.refresh_interval));
+        }
+        if self.view_refresh_mode != defaults.view_refresh_mode {
+            element = element.with_child(text_element("viewRefreshMode", self.view_refresh_mode));
+        }
+        if self.view_refresh_time != defaults.view_refresh_time {
+            element = element.with_child(text_element("viewRefreshTime", self.view_refresh_time));
+        }
+        if self.view_bound_scale != defaults.view_bound_scale {
+            element = element.with_child(text_element("viewBoundScale", self.view_bound_scale));
+        }
+        if let Some(view_format) = &self.view_format {
+            element = element.with_child(text_element("viewFormat", view_format));
+        }
+        if let Some(http_query) = &self.http_query {
+            element = element.with_child(text_element("httpQuery", http_query));
+        }
+        element
+    }
+}
+
+impl<T> NetworkLink<T>
+where
+    T: CoordType + FromStr,
+{
+    // Reads a network link from an element, which the reader needs without the `Display` bound of
+    // `CustomElement`
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "NetworkLink" {
+            return None;
+        }
+        let mut network_link = NetworkLink {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "name" => network_link.name = child.content.clone(),
+                "Camera" | "LookAt" if network_link.view.is_none() => {
+                    match AbstractView::parse_element(child) {
+                        Some(v) => network_link.view = Some(v),
+                        None => network_link.children.push(child.clone()),
+                    }
+                }
+                "Region" => network_link.region = Some(Region::parse_element(child)?),
+                "ExtendedData" if network_link.extended_data.is_none() => {
+                    match ExtendedData::from_element(child) {
+                        Some(d) => network_link.extended_data = Some(d),
+                        None => network_link.children.push(child.clone()),
+                    }
+                }
+                "refreshVisibility" => network_link.refresh_visibility = parse_bool(child),
+                "flyToView" => network_link.fly_to_view = parse_bool(child),
+                "Link" | "Url" => network_link.link = Some(Link::from_element(child)?),
+                _ => network_link.children.push(child.clone()),
+            }
+        }
+        Some(network_link)
+    }
+}
+
+impl<T> CustomElement for NetworkLink<T>
+where
+    T: CoordType + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        NetworkLink::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("NetworkLink");
+        element.attrs = self.attrs.clone();
+        if let Some(name) = &self.name {
+            element = element.with_child(text_element("name", name));
+        }
+        for child in self.children.iter() {
+            element = element.with_child(child.clone());
+        }
+        if let Some(view) = &self.view {
+            element = element.with_child(view.to_element());
+        }
+        if let Some(region) = &self.region {
+            element = element.with_child(region.to_element());
+        }
+        if let Some(extended_data) = &self.extended_data {
+            element = element.with_child(extended_data.to_element());
+        }
+        if self.refresh_visibility {
+            element = element.with_child(text_element("refreshVisibility", 1));
+        }
+        if self.fly_to_view {
+            element = element.with_child(text_element("flyToView", 1));
+        }
+        match &self.link {
+            Some(link) => element.with_child(link.to_element()),
+            None => element,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Data, LatLonAltBox, LookAt};
+
+    #[test]
+    fn test_refresh_modes() {
+        for mode in [
+            RefreshMode::OnChange,
+            RefreshMode::OnInterval,
+            RefreshMode::OnExpire,
+        ] {
+            assert_eq!(mode.to_string().parse::<RefreshMode>().unwrap(), mode);
+        }
+        for mode in [
+            ViewRefreshMode::Never,
+            ViewRefreshMode::OnRequest,
+            ViewRefreshMode::OnStop,
+            ViewRefreshMode::OnRegion,
+        ] {
+            assert_eq!(mode.to_string().parse::<ViewRefreshMode>().unwrap(), mode);
+        }
+        assert!(matches!(
+            "onchange".parse::<RefreshMode>(),
+            Err(Error::InvalidRefreshMode(m)) if m == "onchange"
+        ));
+        assert!(matches!(
+            "".parse::<ViewRefreshMode>(),
+            Err(Error::InvalidViewRefreshMode(_))
+        ));
+    }
+
+    #[test]
+    fn test_link_element() {
+        // Links are named `kml:Url` in KML 2.0
+        let element = Element::new("Url")
+            .with_child(text_element("href", " tiles.kml "))
+            .with_child(text_element("refreshMode", "onInterval"))
+            .with_child(text_element("refreshInterval", "60"))
+            .with_child(text_element("httpQuery", "client=[clientName]"));
+        let link = Link::from_element(&element).unwrap();
+        assert_eq!(
+            link,
+            Link::new("tiles.kml")
+                .with_refresh_mode(RefreshMode::OnInterval)
+                .with_refresh_interval(60.)
+                .with_http_query("client=[clientName]")
+        );
+
+        // Only values other than the defaults are written
+        let element = link.to_element();
+        assert_eq!(element.name, "Link");
+        assert!(element.child("viewRefreshTime").is_none());
+        assert_eq!(Link::from_element(&element), Some(link));
+        assert_eq!(Link::new("a.kml").to_element().children.len(), 1);
+
+        let invalid = Element::new("Link").with_child(text_element("refreshInterval", "often"));
+        assert_eq!(Link::from_element(&invalid), None);
+        assert_eq!(Link::from_element(&Element::new("Icon")), None);
+    }
+
+    #[test]
+    fn test_network_link_element_round_trip() {
+        let network_link: NetworkLink = NetworkLink::new(Link::new("more.kml"))
+            .with_name("More")
+            .with_view(AbstractView::LookAt(LookAt::new(1., 2., 300.)))
+            .with_region(Region::new(LatLonAltBox::new(10., 0., 10., 0.)))
+            .with_extended_data(ExtendedData::default().with_data(Data::new("page", "2")))
+            .with_refresh_visibility(true)
+            .with_fly_to_view(true);
+        let element = network_link.to_element();
+        assert_eq!(NetworkLink::from_element(&element), Some(network_link));
+
+        // Unknown children are kept, and a region without a box makes the element invalid
+        let element = Element::new("NetworkLink")
+            .with_child(text_element("open", "1"))
+            .with_child(text_element("flyToView", "true"));
+        let network_link: NetworkLink = NetworkLink::from_element(&element).unwrap();
+        assert!(network_link.fly_to_view);
+        assert_eq!(network_link.link, None);
+        assert_eq!(network_link.children, vec![text_element("open", "1")]);
+        let element = element.with_child(Element::new("Region"));
+        assert_eq!(NetworkLink::<f64>::from_element(&element), None);
+    }
+}
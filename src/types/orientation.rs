@@ -1,9 +1,14 @@
 use std::collections::HashMap;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::angle::{Angle, CAMERA_TILT};
 use crate::types::coord::CoordType;
 
 /// `kml:Orientation`, [10.11](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#558) in the KML
 #[derive(Clone, Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Orientation<T: CoordType = f64> {
     pub roll: T,
     pub tilt: T,
@@ -24,3 +29,26 @@ where
         }
     }
 }
+
+impl<T> Orientation<T>
+where
+    T: CoordType,
+{
+    /// Wraps `heading` into 0 to 360 and `roll` into -180 to 180, and clamps `tilt` to 0 to 180,
+    /// returning the number of fields changed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::Orientation;
+    ///
+    /// let mut orientation = Orientation::new(190., 45., -90.);
+    /// assert_eq!(orientation.normalize(), 2);
+    /// assert_eq!(orientation, Orientation::new(-170., 45., 270.));
+    /// ```
+    pub fn normalize(&mut self) -> usize {
+        Angle::Heading.normalize_in_place(&mut self.heading) as usize
+            + Angle::Tilt(CAMERA_TILT).normalize_in_place(&mut self.tilt) as usize
+            + Angle::Roll.normalize_in_place(&mut self.roll) as usize
+    }
+}
@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::types::altitude_mode::{altitude_mode_element, AltitudeMode};
+use crate::types::color::Color;
+use crate::types::coord::{Coord, CoordType};
+use crate::types::element::{CustomElement, Element};
+use crate::types::extended_data::ExtendedData;
+use crate::types::point::Point;
+use crate::types::style::Icon;
+
+/// `kml:shape` of a [`PhotoOverlay`](struct.PhotoOverlay.html), the surface the photo is
+/// projected onto
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PhotoShape {
+    #[default]
+    Rectangle,
+    Cylinder,
+    Sphere,
+}
+
+impl FromStr for PhotoShape {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rectangle" => Ok(Self::Rectangle),
+            "cylinder" => Ok(Self::Cylinder),
+            "sphere" => Ok(Self::Sphere),
+            v => Err(Error::InvalidShape(v.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for PhotoShape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Rectangle => "rectangle",
+                Self::Cylinder => "cylinder",
+                Self::Sphere => "sphere",
+            }
+        )
+    }
+}
+
+/// `kml:gridOrigin` of an [`ImagePyramid`](struct.ImagePyramid.html), the corner where tile
+/// numbering starts
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GridOrigin {
+    #[default]
+    LowerLeft,
+    UpperLeft,
+}
+
+impl FromStr for GridOrigin {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lowerLeft" => Ok(Self::LowerLeft),
+            "upperLeft" => Ok(Self::UpperLeft),
+            v => Err(Error::InvalidGridOrigin(v.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for GridOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::LowerLeft => "lowerLeft",
+                Self::UpperLeft => "upperLeft",
+            }
+        )
+    }
+}
+
+/// `kml:ViewVolume`, the extent of a [`PhotoOverlay`](struct.PhotoOverlay.html) in degrees from
+/// the camera's view direction, see
+/// [Google's ViewVolume reference](https://developers.google.com/kml/documentation/kmlreference#viewvolume)
+///
+/// `near` is the distance in meters from the camera to the photo.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ViewVolume {
+    pub left_fov: f64,
+    pub right_fov: f64,
+    pub bottom_fov: f64,
+    pub top_fov: f64,
+    pub near: f64,
+    pub attrs: HashMap<String, String>,
+}
+
+impl ViewVolume {
+    pub fn new(left_fov: f64, right_fov: f64, bottom_fov: f64, top_fov: f64, near: f64) -> Self {
+        ViewVolume {
+            left_fov,
+            right_fov,
+            bottom_fov,
+            top_fov,
+            near,
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+/// `kml:ImagePyramid`, the tiling of a large [`PhotoOverlay`](struct.PhotoOverlay.html) image,
+/// see [Google's ImagePyramid reference](https://developers.google.com/kml/documentation/kmlreference#imagepyramid)
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ImagePyramid {
+    /// Width and height of the square tiles in pixels, which should be a power of two
+    pub tile_size: i32,
+    pub max_width: i32,
+    pub max_height: i32,
+    pub grid_origin: GridOrigin,
+    pub attrs: HashMap<String, String>,
+}
+
+impl ImagePyramid {
+    pub fn new(max_width: i32, max_height: i32) -> Self {
+        ImagePyramid {
+            max_width,
+            max_height,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ImagePyramid {
+    fn default() -> Self {
+        ImagePyramid {
+            tile_size: 256,
+            max_width: 0,
+            max_height: 0,
+            grid_origin: GridOrigin::default(),
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+impl_with!(ImagePyramid {
+    with_tile_size(tile_size: i32),
+    with_grid_origin(grid_origin: GridOrigin),
+});
+
+/// `kml:PhotoOverlay`, [11.5](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#372) in
+/// the KML specification, a photo placed in the scene at the position of a camera
+///
+/// The photo's `$[level]`, `$[x]`, and `$[y]` placeholders in the icon's `href` are filled in
+/// from the `image_pyramid` by viewers. Elements of the feature without a typed representation like
+/// `kml:Camera` are kept in `children`, and are written after the name.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, types::{Icon, ImagePyramid, PhotoOverlay, PhotoShape, Point, ViewVolume}};
+///
+/// let overlay = PhotoOverlay::new(Icon::new("tiles/$[level]/$[x]_$[y].jpg"))
+///     .with_view_volume(ViewVolume::new(-60., 60., -30., 30., 100.))
+///     .with_image_pyramid(ImagePyramid::new(8192, 4096))
+///     .with_point(Point::new(-105., 40., None))
+///     .with_shape(PhotoShape::Cylinder);
+///
+/// let kml_str = Kml::PhotoOverlay(overlay.clone()).to_string();
+/// assert!(kml_str.contains("<shape>cylinder</shape>"));
+/// assert_eq!(kml_str.parse::<Kml>().unwrap(), Kml::PhotoOverlay(overlay));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PhotoOverlay<T: CoordType = f64> {
+    pub name: Option<String>,
+    pub extended_data: Option<ExtendedData>,
+    /// Colors in the `rrggbb` order of HTML are converted when reading
+    pub color: Option<Color>,
+    pub draw_order: i32,
+    pub icon: Option<Icon>,
+    pub rotation: f64,
+    pub view_volume: Option<ViewVolume>,
+    pub image_pyramid: Option<ImagePyramid>,
+    pub point: Option<Point<T>>,
+    pub shape: PhotoShape,
+    pub attrs: HashMap<String, String>,
+    pub children: Vec<Element>,
+}
+
+impl<T> PhotoOverlay<T>
+where
+    T: CoordType,
+{
+    pub fn new(icon: Icon) -> Self {
+        PhotoOverlay {
+            icon: Some(icon),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T> Default for PhotoOverlay<T>
+where
+    T: CoordType,
+{
+    fn default() -> Self {
+        PhotoOverlay {
+            name: None,
+            extended_data: None,
+            color: None,
+            draw_order: 0,
+            icon: None,
+            rotation: 0.,
+            view_volume: None,
+            image_pyramid: None,
+            point: None,
+            shape: PhotoShape::default(),
+            attrs: HashMap::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl_with!(PhotoOverlay<T> {
+    with_name(some name: String),
+    with_extended_data(some extended_data: ExtendedData),
+    with_color(some color: Color),
+    with_draw_order(draw_order: i32),
+    with_icon(some icon: Icon),
+    with_rotation(rotation: f64),
+    with_view_volume(some view_volume: ViewVolume),
+    with_image_pyramid(some image_pyramid: ImagePyramid),
+    with_point(some point: Point<T>),
+    with_shape(shape: PhotoShape),
+});
+
+fn content(element: &Element) -> &str {
+    element.content.as_deref().unwrap_or_default().trim()
+}
+
+fn text_element(name: &str, content: impl ToString) -> Element {
+    Element::new(name).with_content(content.to_string())
+}
+
+impl CustomElement for ViewVolume {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "ViewVolume" {
+            return None;
+        }
+        let mut view_volume = ViewVolume {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "leftFov" => view_volume.left_fov = content(child).parse().ok()?,
+                "rightFov" => view_volume.right_fov = content(child).parse().ok()?,
+                "bottomFov" => view_volume.bottom_fov = content(child).parse().ok()?,
+                "topFov" => view_volume.top_fov = content(child).parse().ok()?,
+                "near" => view_volume.near = content(child).parse().ok()?,
+                _ => {}
+            }
+        }
+        Some(view_volume)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("ViewVolume")
+            .with_child(text_element("leftFov", self.left_fov))
+            .with_child(text_element("rightFov", self.right_fov))
+            .with_child(text_element("bottomFov", self.bottom_fov))
+            .with_child(text_element("topFov", self.top_fov))
+            .with_child(text_element("near", self.near));
+        element.attrs = self.attrs.clone();
+        element
+    }
+}
+
+impl CustomElement for ImagePyramid {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "ImagePyramid" {
+            return None;
+        }
+        let mut image_pyramid = ImagePyramid {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "tileSize" => image_pyramid.tile_size = content(child).parse().ok()?,
+                "maxWidth" => image_pyramid.max_width = content(child).parse().ok()?,
+                "maxHeight" => image_pyramid.max_height = content(child).parse().ok()?,
+                "gridOrigin" => image_pyramid.grid_origin = content(child).parse().ok()?,
+                _ => {}
+            }
+        }
+        Some(image_pyramid)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("ImagePyramid");
+        element.attrs = self.attrs.clone();
+        if self.tile_size != 256 {
+            element = element.with_child(text_element("tileSize", self.tile_size));
+        }
+        element = element
+            .with_child(text_element("maxWidth", self.max_width))
+            .with_child(text_element("maxHeight", self.max_height));
+        if self.grid_origin != GridOrigin::default() {
+            element = element.with_child(text_element("gridOrigin", self.grid_origin));
+        }
+        element
+    }
+}
+
+// Points in overlays are read as elements along with the rest of the overlay, so they're converted
+// here rather than by the reader's geometry parsing
+fn point_from_element<T>(element: &Element) -> Option<Point<T>>
+where
+    T: CoordType + FromStr,
+{
+    let mut point = Point {
+        coord: Coord::new(T::zero(), T::zero(), None),
+        extrude: false,
+        altitude_mode: AltitudeMode::default(),
+        attrs: element.attrs.clone(),
+    };
+    for child in element.children.iter() {
+        match child.local_name() {
+            "coordinates" => point.coord = content(child).parse().ok()?,
+            "extrude" => point.extrude = matches!(content(child), "1" | "true"),
+            "altitudeMode" => point.altitude_mode = content(child).parse().ok()?,
+            _ => {}
+        }
+    }
+    Some(point)
+}
+
+fn point_element<T>(point: &Point<T>) -> Element
+where
+    T: CoordType + fmt::Display,
+{
+    let mut element = Element::new("Point");
+    element.attrs = point.attrs.clone();
+    if point.extrude {
+        element = element.with_child(text_element("extrude", 1));
+    }
+    if let Some(altitude_mode) = altitude_mode_element(&point.altitude_mode) {
+        element = element.with_child(altitude_mode);
+    }
+    element.with_child(text_element("coordinates", point.coord))
+}
+
+impl<T> PhotoOverlay<T>
+where
+    T: CoordType + FromStr,
+{
+    // Reads an overlay from an element, which the reader needs without the `Display` bound of
+    // `CustomElement`
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "PhotoOverlay" {
+            return None;
+        }
+        let mut overlay = PhotoOverlay {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "name" => overlay.name = child.content.clone(),
+                "ExtendedData" if overlay.extended_data.is_none() => {
+                    match ExtendedData::from_element(child) {
+                        Some(d) => overlay.extended_data = Some(d),
+                        None => overlay.children.push(child.clone()),
+                    }
+                }
+                "color" => overlay.color = Some(Color::parse_normalized(content(child)).ok()?),
+                "drawOrder" => overlay.draw_order = content(child).parse().ok()?,
+                "Icon" => overlay.icon = Some(Icon::from_element(child)?),
+                "rotation" => overlay.rotation = content(child).parse().ok()?,
+                "ViewVolume" => overlay.view_volume = Some(ViewVolume::from_element(child)?),
+                "ImagePyramid" => overlay.image_pyramid = Some(ImagePyramid::from_element(child)?),
+                "Point" => overlay.point = Some(point_from_element(child)?),
+                "shape" => overlay.shape = content(child).parse().ok()?,
+                _ => overlay.children.push(child.clone()),
+            }
+        }
+        Some(overlay)
+    }
+}
+
+impl<T> CustomElement for PhotoOverlay<T>
+where
+    T: CoordType + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        PhotoOverlay::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("PhotoOverlay");
+        element.attrs = self.attrs.clone();
+        if let Some(name) = &self.name {
+            element = element.with_child(text_element("name", name));
+        }
+        for child in self.children.iter() {
+            element = element.with_child(child.clone());
+        }
+        if let Some(extended_data) = &self.extended_data {
+            element = element.with_child(extended_data.to_element());
+        }
+        if let Some(color) = &self.color {
+            element = element.with_child(text_element("color", color));
+        }
+        if self.draw_order != 0 {
+            element = element.with_child(text_element("drawOrder", self.draw_order));
+        }
+        if let Some(icon) = &self.icon {
+            element = element.with_child(icon.to_element());
+        }
+        if self.rotation != 0. {
+            element = element.with_child(text_element("rotation", self.rotation));
+        }
+        if let Some(view_volume) = &self.view_volume {
+            element = element.with_child(view_volume.to_element());
+        }
+        if let Some(image_pyramid) = &self.image_pyramid {
+            element = element.with_child(image_pyramid.to_element());
+        }
+        if let Some(point) = &self.point {
+            element = element.with_child(point_element(point));
+        }
+        if self.shape != PhotoShape::default() {
+            element = element.with_child(text_element("shape", self.shape));
+        }
+        element
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_photo_enums() {
+        for shape in [
+            PhotoShape::Rectangle,
+            PhotoShape::Cylinder,
+            PhotoShape::Sphere,
+        ] {
+            assert_eq!(shape.to_string().parse::<PhotoShape>().unwrap(), shape);
+        }
+        for origin in [GridOrigin::LowerLeft, GridOrigin::UpperLeft] {
+            assert_eq!(origin.to_string().parse::<GridOrigin>().unwrap(), origin);
+        }
+        assert!(matches!(
+            "Sphere".parse::<PhotoShape>(),
+            Err(Error::InvalidShape(s)) if s == "Sphere"
+        ));
+        assert!(matches!(
+            "upper left".parse::<GridOrigin>(),
+            Err(Error::InvalidGridOrigin(_))
+        ));
+    }
+
+    #[test]
+    fn test_photo_overlay_element_round_trip() {
+        let overlay: PhotoOverlay = PhotoOverlay::new(Icon::new("photo_$[level]_$[x]_$[y].jpg"))
+            .with_name("Pano")
+            .with_color(Color::WHITE)
+            .with_draw_order(1)
+            .with_rotation(-10.)
+            .with_view_volume(ViewVolume::new(-60., 60., -30., 30., 100.))
+            .with_image_pyramid(
+                ImagePyramid::new(8192, 4096)
+                    .with_tile_size(512)
+                    .with_grid_origin(GridOrigin::UpperLeft),
+            )
+            .with_point(Point::new(1., 2., Some(3.)).with_extrude(true))
+            .with_shape(PhotoShape::Cylinder);
+        let element = overlay.to_element();
+        assert_eq!(PhotoOverlay::from_element(&element), Some(overlay));
+
+        // Defaults of the image pyramid and shape aren't written
+        let element = PhotoOverlay::<f64>::default()
+            .with_image_pyramid(ImagePyramid::new(1024, 1024))
+            .to_element();
+        assert_eq!(element.children.len(), 1);
+        assert_eq!(element.children[0].children.len(), 2);
+    }
+
+    #[test]
+    fn test_read_invalid_photo_overlay() {
+        let invalid = |child: Element| {
+            let element = Element::new("PhotoOverlay").with_child(child);
+            PhotoOverlay::<f64>::from_element(&element)
+        };
+        assert_eq!(invalid(text_element("shape", "cube")), None);
+        assert_eq!(invalid(text_element("rotation", "")), None);
+        assert_eq!(
+            invalid(Element::new("ViewVolume").with_child(text_element("near", "far"))),
+            None
+        );
+        assert_eq!(
+            invalid(Element::new("ImagePyramid").with_child(text_element("maxWidth", "1.5"))),
+            None
+        );
+        assert_eq!(
+            invalid(Element::new("Point").with_child(text_element("coordinates", "1"))),
+            None
+        );
+        assert_eq!(
+            PhotoOverlay::<f64>::from_element(&Element::new("Photo")),
+            None
+        );
+
+        // Unknown children are kept
+        let overlay = invalid(text_element("visibility", "0")).unwrap();
+        assert_eq!(overlay.children, vec![text_element("visibility", "0")]);
+    }
+}
@@ -1,8 +1,16 @@
 use std::collections::HashMap;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::types::coord::CoordType;
 use crate::types::element::Element;
+use crate::types::extended_data::ExtendedData;
+use crate::types::feature::Snippet;
 use crate::types::geometry::Geometry;
+use crate::types::style::Style;
+use crate::types::time_primitive::TimePrimitive;
+use crate::types::view::AbstractView;
 
 /// `kml:Placemark`, [9.14](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#249) in the KML
 /// specification
@@ -11,11 +19,66 @@ use crate::types::geometry::Geometry;
 /// but Google's  reference says it's optional [Google Placemark reference](https://developers.google.com/kml/documentation/kmlreference#placemark).
 ///
 /// Currently leaving optional.
+///
+/// # Example
+///
+/// ```
+/// use kml::types::{AltitudeMode, LineString, Placemark};
+///
+/// let placemark: Placemark = Placemark::default()
+///     .with_name("Route")
+///     .with_geometry(
+///         LineString::from_coords(vec![(0., 0., 10.), (1., 1., 20.)])
+///             .with_altitude_mode(AltitudeMode::RelativeToGround)
+///             .with_extrude(true),
+///     );
+/// assert_eq!(placemark.name.as_deref(), Some("Route"));
+/// ```
 #[derive(Clone, Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Placemark<T: CoordType = f64> {
     pub name: Option<String>,
+    pub visibility: Option<bool>,
+    /// Whether the placemark is expanded in the places panel
+    pub open: Option<bool>,
+    /// `atom:name` of the `atom:author`
+    pub author: Option<String>,
+    /// `href` of the `atom:link`
+    pub link: Option<String>,
+    pub address: Option<String>,
+    pub phone_number: Option<String>,
+    pub snippet: Option<Snippet>,
     pub description: Option<String>,
+    /// `kml:Camera` or `kml:LookAt` viewers fly to when the placemark is selected
+    pub view: Option<AbstractView<T>>,
+    /// `kml:TimeStamp` or `kml:TimeSpan` of the placemark
+    pub time_primitive: Option<TimePrimitive>,
+    /// Reference to a shared `kml:Style` or `kml:StyleMap`, like `#id` for one in the same
+    /// document
+    pub style_url: Option<String>,
+    /// Inline `kml:Style`, which overrides the shared style referenced by `style_url`. See
+    /// [`StyleResolver`](../struct.StyleResolver.html)
+    pub style: Option<Style>,
+    pub extended_data: Option<ExtendedData>,
     pub geometry: Option<Geometry<T>>,
     pub attrs: HashMap<String, String>,
     pub children: Vec<Element>,
 }
+
+impl_with!(Placemark<T> {
+    with_name(some name: String),
+    with_visibility(some visibility: bool),
+    with_open(some open: bool),
+    with_author(some author: String),
+    with_link(some link: String),
+    with_address(some address: String),
+    with_phone_number(some phone_number: String),
+    with_snippet(some snippet: Snippet),
+    with_description(some description: String),
+    with_view(some view: AbstractView<T>),
+    with_time_primitive(some time_primitive: TimePrimitive),
+    with_style_url(some style_url: String),
+    with_style(some style: Style),
+    with_extended_data(some extended_data: ExtendedData),
+    with_geometry(some geometry: Geometry<T>),
+});
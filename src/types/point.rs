@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::types::altitude_mode::AltitudeMode;
 use crate::types::coord::{Coord, CoordType};
 
@@ -8,6 +11,7 @@ use crate::types::coord::{Coord, CoordType};
 ///
 /// Coord is required as of <https://docs.opengeospatial.org/ts/14-068r2/14-068r2.html#atc-114>
 #[derive(Clone, Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Point<T: CoordType = f64> {
     pub coord: Coord<T>,
     pub extrude: bool,
@@ -34,4 +38,24 @@ where
     pub fn new(x: T, y: T, z: Option<T>) -> Self {
         Point::from(Coord::new(x, y, z))
     }
+
+    /// Sets how the altitude of the point is interpreted, typically used right after
+    /// [`Point::new`] since a `z` value is ignored with the default `clampToGround`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{AltitudeMode, Point};
+    ///
+    /// let point = Point::new(1., 1., Some(100.)).with_altitude_mode(AltitudeMode::Absolute);
+    /// assert_eq!(point.altitude_mode, AltitudeMode::Absolute);
+    /// ```
+    pub fn with_altitude_mode(mut self, altitude_mode: AltitudeMode) -> Self {
+        self.altitude_mode = altitude_mode;
+        self
+    }
 }
+
+impl_with!(Point<T> {
+    with_extrude(extrude: bool),
+});
@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::types::altitude_mode::AltitudeMode;
 use crate::types::coord::CoordType;
 use crate::types::linear_ring::LinearRing;
@@ -7,6 +10,7 @@ use crate::types::linear_ring::LinearRing;
 /// `kml:Polygon`, [10.8](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#505) in the KML
 /// specification
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Polygon<T: CoordType = f64> {
     pub outer: LinearRing<T>,
     pub inner: Vec<LinearRing<T>>,
@@ -20,6 +24,19 @@ impl<T> Polygon<T>
 where
     T: CoordType + Default,
 {
+    /// Creates a polygon from its outer boundary and any inner boundaries
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{LinearRing, Polygon};
+    ///
+    /// let polygon = Polygon::new(
+    ///     LinearRing::from_coords(vec![(0., 0.), (4., 0.), (4., 4.), (0., 0.)]),
+    ///     vec![LinearRing::from_coords(vec![(1., 1.), (2., 2.), (2., 1.), (1., 1.)])],
+    /// );
+    /// assert_eq!(polygon.inner.len(), 1);
+    /// ```
     pub fn new(outer: LinearRing<T>, inner: Vec<LinearRing<T>>) -> Self {
         Polygon {
             outer,
@@ -28,3 +45,66 @@ where
         }
     }
 }
+
+impl<T> Polygon<T>
+where
+    T: CoordType,
+{
+    /// Reverses rings where needed so that the outer ring winds counter-clockwise and inner rings
+    /// wind clockwise, returning the number of rings changed
+    ///
+    /// The KML specification requires this order, and Google Earth commonly renders polygons
+    /// with the wrong winding without a fill.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Coord, LinearRing, Polygon};
+    ///
+    /// let outer = LinearRing::from(vec![
+    ///     Coord::from((0., 0.)),
+    ///     Coord::from((0., 1.)),
+    ///     Coord::from((1., 1.)),
+    ///     Coord::from((0., 0.)),
+    /// ]);
+    /// let mut polygon = Polygon::new(outer, vec![]);
+    /// assert!(polygon.outer.is_cw());
+    /// assert_eq!(polygon.orient(), 1);
+    /// assert!(polygon.outer.is_ccw());
+    /// ```
+    pub fn orient(&mut self) -> usize {
+        let mut changed = 0;
+        if self.outer.is_cw() {
+            self.outer.coords.reverse();
+            changed += 1;
+        }
+        for ring in self.inner.iter_mut().filter(|r| r.is_ccw()) {
+            ring.coords.reverse();
+            changed += 1;
+        }
+        changed
+    }
+
+    /// Removes consecutive coordinates within `tolerance` of each other from the outer and inner
+    /// rings, returning the number of coordinates removed. See [`LinearRing::dedup_coords`](struct.LinearRing.html#method.dedup_coords)
+    pub fn dedup_coords(&mut self, tolerance: T) -> usize {
+        std::iter::once(&mut self.outer)
+            .chain(self.inner.iter_mut())
+            .map(|r| r.dedup_coords(tolerance))
+            .sum()
+    }
+
+    /// Closes the outer and inner rings, returning the number of rings changed
+    pub fn close_rings(&mut self) -> usize {
+        std::iter::once(&mut self.outer)
+            .chain(self.inner.iter_mut())
+            .map(|r| r.close() as usize)
+            .sum()
+    }
+}
+
+impl_with!(Polygon<T> {
+    with_extrude(extrude: bool),
+    with_tessellate(tessellate: bool),
+    with_altitude_mode(altitude_mode: AltitudeMode),
+});
@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::altitude_mode::{altitude_mode_element, AltitudeMode};
+use crate::types::coord::{lit, CoordType};
+use crate::types::element::{CustomElement, Element};
+
+/// `kml:LatLonAltBox`, the bounding box of a [`Region`](struct.Region.html), see
+/// [Google's LatLonAltBox reference](https://developers.google.com/kml/documentation/kmlreference#latlonaltbox)
+///
+/// `min_altitude` and `max_altitude` are only used when the altitude mode isn't clamped to the
+/// ground or sea floor.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LatLonAltBox<T: CoordType = f64> {
+    pub north: T,
+    pub south: T,
+    pub east: T,
+    pub west: T,
+    pub min_altitude: T,
+    pub max_altitude: T,
+    pub altitude_mode: AltitudeMode,
+    pub attrs: HashMap<String, String>,
+}
+
+impl<T> LatLonAltBox<T>
+where
+    T: CoordType,
+{
+    pub fn new(north: T, south: T, east: T, west: T) -> Self {
+        LatLonAltBox {
+            north,
+            south,
+            east,
+            west,
+            min_altitude: T::zero(),
+            max_altitude: T::zero(),
+            altitude_mode: AltitudeMode::default(),
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Default for LatLonAltBox<T>
+where
+    T: CoordType,
+{
+    /// Returns the schema's defaults, which cover the whole globe
+    fn default() -> Self {
+        let max = lit(180.);
+        LatLonAltBox::new(max, -max, max, -max)
+    }
+}
+
+impl_with!(LatLonAltBox<T> {
+    with_min_altitude(min_altitude: T),
+    with_max_altitude(max_altitude: T),
+    with_altitude_mode(altitude_mode: AltitudeMode),
+});
+
+/// `kml:Lod`, the range of sizes in screen pixels a [`Region`](struct.Region.html) is active at,
+/// see [Google's Lod reference](https://developers.google.com/kml/documentation/kmlreference#lod)
+///
+/// A `max_lod_pixels` of -1 means the region stays active however large it is on screen.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Lod {
+    pub min_lod_pixels: f64,
+    pub max_lod_pixels: f64,
+    pub min_fade_extent: f64,
+    pub max_fade_extent: f64,
+    pub attrs: HashMap<String, String>,
+}
+
+impl Lod {
+    pub fn new(min_lod_pixels: f64, max_lod_pixels: f64) -> Self {
+        Lod {
+            min_lod_pixels,
+            max_lod_pixels,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Lod {
+    fn default() -> Self {
+        Lod {
+            min_lod_pixels: 0.,
+            max_lod_pixels: -1.,
+            min_fade_extent: 0.,
+            max_fade_extent: 0.,
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+impl_with!(Lod {
+    with_min_fade_extent(min_fade_extent: f64),
+    with_max_fade_extent(max_fade_extent: f64),
+});
+
+/// `kml:Region`, the area and level of detail a feature is active in, see
+/// [Google's Region reference](https://developers.google.com/kml/documentation/kmlreference#region)
+///
+/// Regions on [`NetworkLink`](struct.NetworkLink.html)s load the linked file only once the region
+/// is active, which is how super-overlays load tiles at increasing detail.
+///
+/// # Example
+///
+/// ```
+/// use kml::types::{LatLonAltBox, Lod, Region};
+///
+/// let region = Region::new(LatLonAltBox::new(40., 39., -104., -105.))
+///     .with_lod(Lod::new(128., 512.));
+/// assert_eq!(region.lod.unwrap().max_lod_pixels, 512.);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Region<T: CoordType = f64> {
+    pub lat_lon_alt_box: LatLonAltBox<T>,
+    pub lod: Option<Lod>,
+    pub attrs: HashMap<String, String>,
+}
+
+impl<T> Region<T>
+where
+    T: CoordType,
+{
+    pub fn new(lat_lon_alt_box: LatLonAltBox<T>) -> Self {
+        Region {
+            lat_lon_alt_box,
+            lod: None,
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Default for Region<T>
+where
+    T: CoordType,
+{
+    fn default() -> Self {
+        Region::new(LatLonAltBox::default())
+    }
+}
+
+impl_with!(Region<T> {
+    with_lod(some lod: Lod),
+});
+
+// Parses the trimmed content of each child with a name in `fields`, calling `set` with the index
+// of the name, and returning `None` if any value is invalid
+fn parse_fields<F: FromStr>(
+    element: &Element,
+    fields: &[&str],
+    mut set: impl FnMut(usize, F),
+) -> Option<()> {
+    for child in element.children.iter() {
+        if let Some(i) = fields.iter().position(|f| *f == child.local_name()) {
+            set(
+                i,
+                child
+                    .content
+                    .as_deref()
+                    .unwrap_or_default()
+                    .trim()
+                    .parse()
+                    .ok()?,
+            );
+        }
+    }
+    Some(())
+}
+
+fn text_element(name: &str, content: impl ToString) -> Element {
+    Element::new(name).with_content(content.to_string())
+}
+
+impl<T> LatLonAltBox<T>
+where
+    T: CoordType + FromStr,
+{
+    // Reads a box from an element, which the reader needs without the `Display` bound of
+    // `CustomElement`
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "LatLonAltBox" {
+            return None;
+        }
+        let mut b = LatLonAltBox {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        let fields = [
+            "north",
+            "south",
+            "east",
+            "west",
+            "minAltitude",
+            "maxAltitude",
+        ];
+        parse_fields(element, &fields, |i, value| match i {
+            0 => b.north = value,
+            1 => b.south = value,
+            2 => b.east = value,
+            3 => b.west = value,
+            4 => b.min_altitude = value,
+            _ => b.max_altitude = value,
+        })?;
+        parse_fields(element, &["altitudeMode"], |_, value| {
+            b.altitude_mode = value
+        })?;
+        Some(b)
+    }
+}
+
+impl<T> CustomElement for LatLonAltBox<T>
+where
+    T: CoordType + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        LatLonAltBox::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("LatLonAltBox")
+            .with_child(text_element("north", self.north))
+            .with_child(text_element("south", self.south))
+            .with_child(text_element("east", self.east))
+            .with_child(text_element("west", self.west));
+        element.attrs = self.attrs.clone();
+        if !self.min_altitude.is_zero() {
+            element = element.with_child(text_element("minAltitude", self.min_altitude));
+        }
+        if !self.max_altitude.is_zero() {
+            element = element.with_child(text_element("maxAltitude", self.max_altitude));
+        }
+        match altitude_mode_element(&self.altitude_mode) {
+            Some(altitude_mode) => element.with_child(altitude_mode),
+            None => element,
+        }
+    }
+}
+
+impl CustomElement for Lod {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "Lod" {
+            return None;
+        }
+        let mut lod = Lod {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        let fields = [
+            "minLodPixels",
+            "maxLodPixels",
+            "minFadeExtent",
+            "maxFadeExtent",
+        ];
+        parse_fields(element, &fields, |i, value| match i {
+            0 => lod.min_lod_pixels = value,
+            1 => lod.max_lod_pixels = value,
+            2 => lod.min_fade_extent = value,
+            _ => lod.max_fade_extent = value,
+        })?;
+        Some(lod)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("Lod")
+            .with_child(text_element("minLodPixels", self.min_lod_pixels))
+            .with_child(text_element("maxLodPixels", self.max_lod_pixels));
+        element.attrs = self.attrs.clone();
+        if self.min_fade_extent != 0. {
+            element = element.with_child(text_element("minFadeExtent", self.min_fade_extent));
+        }
+        if self.max_fade_extent != 0. {
+            element = element.with_child(text_element("maxFadeExtent", self.max_fade_extent));
+        }
+        element
+    }
+}
+
+impl<T> Region<T>
+where
+    T: CoordType + FromStr,
+{
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "Region" {
+            return None;
+        }
+        Some(Region {
+            lat_lon_alt_box: LatLonAltBox::parse_element(element.child("LatLonAltBox")?)?,
+            lod: match element.child("Lod") {
+                Some(lod) => Some(Lod::from_element(lod)?),
+                None => None,
+            },
+            attrs: element.attrs.clone(),
+        })
+    }
+}
+
+impl<T> CustomElement for Region<T>
+where
+    T: CoordType + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        Region::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("Region").with_child(self.lat_lon_alt_box.to_element());
+        element.attrs = self.attrs.clone();
+        match &self.lod {
+            Some(lod) => element.with_child(lod.to_element()),
+            None => element,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_element_round_trip() {
+        let region: Region = Region::new(
+            LatLonAltBox::new(40., 39., -104., -105.)
+                .with_min_altitude(100.)
+                .with_max_altitude(200.)
+                .with_altitude_mode(AltitudeMode::Absolute),
+        )
+        .with_lod(Lod::new(128., 1024.).with_max_fade_extent(64.));
+        // Altitudes and the altitude mode are only written when they aren't the defaults
+        let element = region.to_element();
+        assert_eq!(element.child("LatLonAltBox").unwrap().children.len(), 7);
+        assert_eq!(Region::from_element(&element), Some(region));
+
+        let region: Region = Region::new(LatLonAltBox::new(1., 0., 1., 0.));
+        let element = region.to_element();
+        assert_eq!(element.child("LatLonAltBox").unwrap().children.len(), 4);
+        assert!(element.child("Lod").is_none());
+        assert_eq!(Region::from_element(&element), Some(region));
+    }
+
+    #[test]
+    fn test_read_invalid_region() {
+        // The box is required, and every value must be a number
+        assert_eq!(Region::<f64>::from_element(&Element::new("Region")), None);
+        let lat_lon_alt_box =
+            Element::new("LatLonAltBox").with_child(text_element("north", "north"));
+        let element = Element::new("Region").with_child(lat_lon_alt_box);
+        assert_eq!(Region::<f64>::from_element(&element), None);
+
+        let lod = Element::new("Lod").with_child(text_element("minLodPixels", ""));
+        let element = Region::<f64>::default().to_element().with_child(lod);
+        assert_eq!(Region::<f64>::from_element(&element), None);
+        assert_eq!(Lod::from_element(&Element::new("Region")), None);
+    }
+
+    #[test]
+    fn test_read_partial_box() {
+        // Missing edges keep the default, and values are trimmed
+        let element = Element::new("LatLonAltBox")
+            .with_child(text_element("north", " 45 "))
+            .with_child(text_element("altitudeMode", "clampToGround"));
+        let lat_lon_alt_box = LatLonAltBox::<f64>::from_element(&element).unwrap();
+        assert_eq!(lat_lon_alt_box.north, 45.);
+        assert_eq!(lat_lon_alt_box.west, LatLonAltBox::<f64>::default().west);
+        assert_eq!(lat_lon_alt_box.altitude_mode, AltitudeMode::ClampToGround);
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::color::Color;
+use crate::types::element::{CustomElement, Element};
+use crate::types::extended_data::ExtendedData;
+use crate::types::style::Icon;
+use crate::types::vec2::{Units, Vec2};
+
+/// `kml:ScreenOverlay`, [11.7](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#385) in
+/// the KML specification
+///
+/// Screen overlays are read into [`Kml::ScreenOverlay`](../enum.Kml.html#variant.ScreenOverlay).
+/// Elements of the feature without a typed representation like `kml:description` are kept in
+/// `children`, and are written after the name.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScreenOverlay {
+    pub name: Option<String>,
+    pub extended_data: Option<ExtendedData>,
+    /// Colors in the `rrggbb` order of HTML are converted when reading
+    pub color: Option<Color>,
+    pub draw_order: i32,
+    pub icon: Icon,
+    /// Point on the image that's placed at `screen_xy`
+    pub overlay_xy: Vec2,
+    /// Point on the screen where `overlay_xy` is placed
+    pub screen_xy: Vec2,
+    pub rotation_xy: Option<Vec2>,
+    /// Size of the image on the screen, where `-1` keeps the native size of the image and `0`
+    /// keeps its aspect ratio
+    pub size: Vec2,
+    pub rotation: f64,
+    pub attrs: HashMap<String, String>,
+    pub children: Vec<Element>,
+}
+
+impl Default for ScreenOverlay {
+    fn default() -> ScreenOverlay {
+        ScreenOverlay {
+            name: None,
+            extended_data: None,
+            color: None,
+            draw_order: 0,
+            icon: Icon::default(),
+            overlay_xy: Vec2::new(0., 0., Units::Fraction, Units::Fraction),
+            screen_xy: Vec2::new(0., 0., Units::Fraction, Units::Fraction),
+            rotation_xy: None,
+            size: Vec2::new(-1., -1., Units::Fraction, Units::Fraction),
+            rotation: 0.,
+            attrs: HashMap::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Position on the screen for [`ScreenOverlay::anchored`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScreenAnchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl ScreenAnchor {
+    // Fractions of the width and height from the bottom left corner
+    fn fractions(&self) -> (f64, f64) {
+        match self {
+            Self::TopLeft => (0., 1.),
+            Self::Top => (0.5, 1.),
+            Self::TopRight => (1., 1.),
+            Self::Left => (0., 0.5),
+            Self::Center => (0.5, 0.5),
+            Self::Right => (1., 0.5),
+            Self::BottomLeft => (0., 0.),
+            Self::Bottom => (0.5, 0.),
+            Self::BottomRight => (1., 0.),
+        }
+    }
+}
+
+// Offset from the edge at the fraction, measured from the nearest edge so it holds when the screen
+// is resized
+fn screen_offset(fraction: f64, margin: f64) -> (f64, Units) {
+    if fraction == 0. {
+        (margin, Units::Pixels)
+    } else if fraction == 1. {
+        (margin, Units::InsetPixels)
+    } else {
+        (fraction, Units::Fraction)
+    }
+}
+
+impl ScreenOverlay {
+    /// Creates an overlay of the image with the corner or edge of the image at the same corner or
+    /// edge of the screen, `margin` pixels in from the edges
+    ///
+    /// Offsets from the right and top are in `insetPixels` so the image stays in place when the
+    /// screen is resized, and centered positions ignore the margin. The image is shown at its
+    /// native size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{ScreenAnchor, ScreenOverlay, Units, Vec2};
+    ///
+    /// let overlay = ScreenOverlay::anchored("legend.png", ScreenAnchor::BottomRight, 10.);
+    /// assert_eq!(overlay.overlay_xy, Vec2::new(1., 0., Units::Fraction, Units::Fraction));
+    /// assert_eq!(overlay.screen_xy, Vec2::new(10., 10., Units::InsetPixels, Units::Pixels));
+    /// ```
+    pub fn anchored(href: impl Into<String>, anchor: ScreenAnchor, margin: f64) -> Self {
+        let (x, y) = anchor.fractions();
+        let (screen_x, xunits) = screen_offset(x, margin);
+        let (screen_y, yunits) = screen_offset(y, margin);
+        ScreenOverlay {
+            icon: Icon::new(href),
+            overlay_xy: Vec2::new(x, y, Units::Fraction, Units::Fraction),
+            screen_xy: Vec2::new(screen_x, screen_y, xunits, yunits),
+            ..Default::default()
+        }
+    }
+
+    /// Creates an overlay for a logo in the top left corner of the screen, 10 pixels in from the
+    /// edges
+    pub fn logo(href: impl Into<String>) -> Self {
+        Self::anchored(href, ScreenAnchor::TopLeft, 10.)
+    }
+
+    /// Creates an overlay for a legend in the bottom right corner of the screen, 10 pixels in from
+    /// the edges
+    pub fn legend(href: impl Into<String>) -> Self {
+        Self::anchored(href, ScreenAnchor::BottomRight, 10.)
+    }
+
+    /// Creates an overlay for a title centered at the top of the screen, 10 pixels down from the
+    /// edge
+    pub fn title(href: impl Into<String>) -> Self {
+        Self::anchored(href, ScreenAnchor::Top, 10.)
+    }
+
+    /// Sets the size of the image in pixels, returning `self` for chaining. A width or height of
+    /// `0` keeps the aspect ratio of the image.
+    pub fn with_size_pixels(mut self, width: f64, height: f64) -> Self {
+        self.size = Vec2::new(width, height, Units::Pixels, Units::Pixels);
+        self
+    }
+
+    /// Sets the size of the image as a fraction of the screen size, returning `self` for chaining.
+    /// A width or height of `0` keeps the aspect ratio of the image.
+    pub fn with_size_fraction(mut self, width: f64, height: f64) -> Self {
+        self.size = Vec2::new(width, height, Units::Fraction, Units::Fraction);
+        self
+    }
+}
+
+impl_with!(ScreenOverlay {
+    with_name(some name: String),
+    with_extended_data(some extended_data: ExtendedData),
+    with_color(some color: Color),
+    with_draw_order(draw_order: i32),
+    with_rotation_xy(some rotation_xy: Vec2),
+    with_rotation(rotation: f64),
+});
+
+fn vec2_element(name: &str, vec2: &Vec2) -> Element {
+    Element::new(name)
+        .with_attr("x", vec2.x.to_string())
+        .with_attr("y", vec2.y.to_string())
+        .with_attr("xunits", vec2.xunits.to_string())
+        .with_attr("yunits", vec2.yunits.to_string())
+}
+
+fn vec2_from_element(element: &Element) -> Option<Vec2> {
+    let attr = |name: &str| element.attrs.get(name).map(String::as_str);
+    Some(Vec2 {
+        x: attr("x").map_or(Ok(1.), str::parse).ok()?,
+        y: attr("y").map_or(Ok(1.), str::parse).ok()?,
+        xunits: attr("xunits")
+            .map_or(Ok(Units::Fraction), str::parse)
+            .ok()?,
+        yunits: attr("yunits")
+            .map_or(Ok(Units::Fraction), str::parse)
+            .ok()?,
+    })
+}
+
+impl CustomElement for ScreenOverlay {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "ScreenOverlay" {
+            return None;
+        }
+        let mut overlay = ScreenOverlay {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "name" => overlay.name = child.content.clone(),
+                "ExtendedData" if overlay.extended_data.is_none() => {
+                    match ExtendedData::from_element(child) {
+                        Some(d) => overlay.extended_data = Some(d),
+                        None => overlay.children.push(child.clone()),
+                    }
+                }
+                "color" => {
+                    overlay.color = Some(Color::parse_normalized(child.content.as_deref()?).ok()?)
+                }
+                "drawOrder" => {
+                    overlay.draw_order = child.content.as_deref()?.trim().parse().ok()?
+                }
+                "Icon" => overlay.icon = Icon::from_element(child)?,
+                "overlayXY" => overlay.overlay_xy = vec2_from_element(child)?,
+                "screenXY" => overlay.screen_xy = vec2_from_element(child)?,
+                "rotationXY" => overlay.rotation_xy = Some(vec2_from_element(child)?),
+                "size" => overlay.size = vec2_from_element(child)?,
+                "rotation" => overlay.rotation = child.content.as_deref()?.trim().parse().ok()?,
+                _ => overlay.children.push(child.clone()),
+            }
+        }
+        Some(overlay)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("ScreenOverlay");
+        element.attrs = self.attrs.clone();
+        if let Some(name) = &self.name {
+            element = element.with_child(Element::new("name").with_content(name.clone()));
+        }
+        for child in self.children.iter() {
+            element = element.with_child(child.clone());
+        }
+        if let Some(extended_data) = &self.extended_data {
+            element = element.with_child(extended_data.to_element());
+        }
+        if let Some(color) = &self.color {
+            element = element.with_child(Element::new("color").with_content(color.to_string()));
+        }
+        if self.draw_order != 0 {
+            element = element
+                .with_child(Element::new("drawOrder").with_content(self.draw_order.to_string()));
+        }
+        element = element
+            .with_child(self.icon.to_element())
+            .with_child(vec2_element("overlayXY", &self.overlay_xy))
+            .with_child(vec2_element("screenXY", &self.screen_xy));
+        if let Some(rotation_xy) = &self.rotation_xy {
+            element = element.with_child(vec2_element("rotationXY", rotation_xy));
+        }
+        element
+            .with_child(vec2_element("size", &self.size))
+            .with_child(Element::new("rotation").with_content(self.rotation.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Kml;
+
+    #[test]
+    fn test_screen_overlay_presets() {
+        let logo = ScreenOverlay::logo("logo.png").with_size_pixels(200., 0.);
+        assert_eq!(
+            logo.overlay_xy,
+            Vec2::new(0., 1., Units::Fraction, Units::Fraction)
+        );
+        assert_eq!(
+            logo.screen_xy,
+            Vec2::new(10., 10., Units::Pixels, Units::InsetPixels)
+        );
+        assert_eq!(logo.size, Vec2::new(200., 0., Units::Pixels, Units::Pixels));
+
+        let title = ScreenOverlay::title("title.png");
+        assert_eq!(
+            title.screen_xy,
+            Vec2::new(0.5, 10., Units::Fraction, Units::InsetPixels)
+        );
+
+        let center = ScreenOverlay::anchored("splash.png", ScreenAnchor::Center, 10.);
+        assert_eq!(center.overlay_xy, center.screen_xy);
+    }
+
+    #[test]
+    fn test_screen_overlay_element_round_trip() {
+        let overlay = ScreenOverlay::legend("legend.png")
+            .with_name("Legend")
+            .with_rotation(15.);
+        let kml: Kml = Kml::from_custom(&overlay);
+        let read: Kml = kml.to_string().parse().unwrap();
+        assert_eq!(read, Kml::ScreenOverlay(overlay));
+    }
+}
@@ -0,0 +1,203 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::color::Color;
+use crate::types::style::{Icon, IconStyle};
+use crate::types::vec2::{Units, Vec2};
+
+/// Base URL of the icons built into Google Earth
+pub const STANDARD_ICON_BASE: &str = "http://maps.google.com/mapfiles/kml/";
+
+/// Colors available for pushpin and paddle icons
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum IconColor {
+    Blue,
+    Green,
+    LightBlue,
+    Pink,
+    Purple,
+    Red,
+    White,
+    Yellow,
+}
+
+/// Symbols drawn on paddle icons
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PaddleSymbol {
+    #[default]
+    Blank,
+    Circle,
+    Diamond,
+    Square,
+    Stars,
+}
+
+/// Icons from the `shapes` directory of the Google Earth icon palette
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum Shape {
+    PlacemarkCircle,
+    PlacemarkSquare,
+    ShadedDot,
+    OpenDiamond,
+    Donut,
+    Star,
+    Triangle,
+    Polygon,
+    Target,
+    CrossHairs,
+    Arrow,
+    Flag,
+    Info,
+    Caution,
+    Forbidden,
+    Airports,
+}
+
+impl Shape {
+    fn file_name(&self) -> &'static str {
+        match self {
+            Self::PlacemarkCircle => "placemark_circle",
+            Self::PlacemarkSquare => "placemark_square",
+            Self::ShadedDot => "shaded_dot",
+            Self::OpenDiamond => "open-diamond",
+            Self::Donut => "donut",
+            Self::Star => "star",
+            Self::Triangle => "triangle",
+            Self::Polygon => "polygon",
+            Self::Target => "target",
+            Self::CrossHairs => "cross-hairs",
+            Self::Arrow => "arrow",
+            Self::Flag => "flag",
+            Self::Info => "info-i",
+            Self::Caution => "caution",
+            Self::Forbidden => "forbidden",
+            Self::Airports => "airports",
+        }
+    }
+}
+
+/// Icon from the palette built into Google Earth, for use as the `href` of a `kml:Icon` without
+/// hard-coding URLs
+///
+/// # Example
+///
+/// ```
+/// use kml::types::{IconColor, PaddleSymbol, Shape, StandardIcon};
+///
+/// assert_eq!(
+///     StandardIcon::Pushpin(IconColor::Yellow).href(),
+///     "http://maps.google.com/mapfiles/kml/pushpin/ylw-pushpin.png"
+/// );
+/// assert_eq!(
+///     StandardIcon::Paddle(IconColor::Blue, PaddleSymbol::Stars).href(),
+///     "http://maps.google.com/mapfiles/kml/paddle/blu-stars.png"
+/// );
+/// assert_eq!(
+///     StandardIcon::Shape(Shape::PlacemarkCircle).href(),
+///     "http://maps.google.com/mapfiles/kml/shapes/placemark_circle.png"
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum StandardIcon {
+    Pushpin(IconColor),
+    Paddle(IconColor, PaddleSymbol),
+    Shape(Shape),
+}
+
+impl StandardIcon {
+    /// Returns the URL of the icon
+    pub fn href(&self) -> String {
+        let path = match self {
+            Self::Pushpin(color) => {
+                let color = match color {
+                    IconColor::Blue => "blue",
+                    IconColor::Green => "grn",
+                    IconColor::LightBlue => "ltblu",
+                    IconColor::Pink => "pink",
+                    IconColor::Purple => "purple",
+                    IconColor::Red => "red",
+                    IconColor::White => "wht",
+                    IconColor::Yellow => "ylw",
+                };
+                format!("pushpin/{}-pushpin.png", color)
+            }
+            Self::Paddle(color, symbol) => {
+                let color = match color {
+                    IconColor::Blue => "blu",
+                    IconColor::Green => "grn",
+                    IconColor::LightBlue => "ltblu",
+                    IconColor::Pink => "pink",
+                    IconColor::Purple => "purple",
+                    IconColor::Red => "red",
+                    IconColor::White => "wht",
+                    IconColor::Yellow => "ylw",
+                };
+                let symbol = match symbol {
+                    PaddleSymbol::Blank => "blank",
+                    PaddleSymbol::Circle => "circle",
+                    PaddleSymbol::Diamond => "diamond",
+                    PaddleSymbol::Square => "square",
+                    PaddleSymbol::Stars => "stars",
+                };
+                format!("paddle/{}-{}.png", color, symbol)
+            }
+            Self::Shape(shape) => format!("shapes/{}.png", shape.file_name()),
+        };
+        format!("{}{}", STANDARD_ICON_BASE, path)
+    }
+
+    /// Returns the point of the icon anchored to the placemark, which is the tip of pushpins and
+    /// paddles and `None` for shapes, which are centered
+    pub fn hot_spot(&self) -> Option<Vec2> {
+        let (x, y) = match self {
+            Self::Pushpin(_) => (20., 2.),
+            Self::Paddle(..) => (32., 1.),
+            Self::Shape(_) => return None,
+        };
+        Some(Vec2 {
+            x,
+            y,
+            xunits: Units::Pixels,
+            yunits: Units::Pixels,
+        })
+    }
+}
+
+impl From<StandardIcon> for Icon {
+    fn from(icon: StandardIcon) -> Self {
+        Icon::new(icon.href())
+    }
+}
+
+impl IconStyle {
+    /// Creates a style for a standard icon tinted with the color, with the icon's hot spot
+    ///
+    /// Pushpins and paddles are already colored, so they're usually tinted with
+    /// [`Color::WHITE`](struct.Color.html#associatedconstant.WHITE), while the white shapes take on
+    /// the color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{Color, IconStyle, Shape, StandardIcon};
+    ///
+    /// let red = Color::from_rgb(0xff, 0, 0);
+    /// let style = IconStyle::from_standard(StandardIcon::Shape(Shape::ShadedDot), red);
+    /// assert_eq!(style.icon.href, "http://maps.google.com/mapfiles/kml/shapes/shaded_dot.png");
+    /// assert_eq!(style.color, "ff0000ff");
+    /// ```
+    pub fn from_standard(icon: StandardIcon, color: Color) -> Self {
+        IconStyle {
+            icon: icon.into(),
+            hot_spot: icon.hot_spot(),
+            color,
+            ..Default::default()
+        }
+    }
+}
@@ -2,13 +2,20 @@ use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::errors::Error;
 
+use crate::types::angle::Angle;
+use crate::types::color::Color;
+use crate::types::element::{CustomElement, Element};
 use crate::types::Vec2;
 
 /// `kml:Style`, [12.2](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#798) in the KML
 /// specification
 #[derive(Clone, Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Style {
     pub id: String,
     pub balloon: Option<BalloonStyle>,
@@ -17,34 +24,81 @@ pub struct Style {
     pub line: Option<LineStyle>,
     pub poly: Option<PolyStyle>,
     pub list: Option<ListStyle>,
+    /// Attributes other than `id`, like `targetId`
+    pub attrs: HashMap<String, String>,
+}
+
+impl_with!(Style {
+    with_id(id: String),
+    with_balloon(some balloon: BalloonStyle),
+    with_icon(some icon: IconStyle),
+    with_label(some label: LabelStyle),
+    with_line(some line: LineStyle),
+    with_poly(some poly: PolyStyle),
+    with_list(some list: ListStyle),
+});
+
+/// Normalizes a `kml:color` value to 8 lowercase hex digits in `aabbggrr` order, returning `None`
+/// if it can't be interpreted as a color
+///
+/// Values of 6 hex digits or prefixed with `#` are assumed to be in the `rrggbb` or `rrggbbaa`
+/// order used by HTML and CSS, and are reordered with full opacity if alpha isn't included.
+///
+/// # Example
+///
+/// ```
+/// use kml::types::normalize_color;
+///
+/// assert_eq!(normalize_color("7F00FF00"), Some("7f00ff00".to_string()));
+/// assert_eq!(normalize_color("#ff8000"), Some("ff0080ff".to_string()));
+/// assert_eq!(normalize_color("ff800040"), Some("ff800040".to_string()));
+/// assert_eq!(normalize_color("#ff800040"), Some("400080ff".to_string()));
+/// assert_eq!(normalize_color("red"), None);
+/// ```
+pub fn normalize_color(color: &str) -> Option<String> {
+    Color::parse_normalized(color).ok().map(|c| c.to_string())
 }
 
 /// `kml:StyleMap`, [12.3](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#811) in the KML
 /// specification
 #[derive(Clone, Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct StyleMap {
     pub id: String,
     pub pairs: Vec<Pair>,
+    /// Attributes other than `id`, like `targetId`
+    pub attrs: HashMap<String, String>,
 }
 
+impl_with!(StyleMap { with_id(id: String) });
+
 /// `kml:Pair`, [12.4](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#819) in the KML
 /// specification
 #[derive(Clone, Default, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Pair {
     pub key: String,
     pub style_url: String,
     pub attrs: HashMap<String, String>,
 }
 
+impl_with!(Pair {
+    with_key(key: String),
+    with_style_url(style_url: String),
+});
+
 /// `kml:BalloonStyle`, [12.7](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#841) in the
 /// KML specification
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BalloonStyle {
     pub id: String,
-    pub bg_color: Option<String>,
-    pub text_color: String,
+    pub bg_color: Option<Color>,
+    pub text_color: Color,
     pub text: Option<String>,
     pub display: bool,
+    /// Attributes other than `id`, like `targetId`
+    pub attrs: HashMap<String, String>,
 }
 
 impl Default for BalloonStyle {
@@ -52,27 +106,32 @@ impl Default for BalloonStyle {
         BalloonStyle {
             id: "".to_string(),
             bg_color: None,
-            text_color: "ffffffff".to_string(),
+            text_color: Color::WHITE,
             text: None,
             display: true,
+            attrs: HashMap::new(),
         }
     }
 }
 
+impl_with!(BalloonStyle {
+    with_id(id: String),
+    with_bg_color(some bg_color: Color),
+    with_text_color(text_color: Color),
+    with_text(some text: String),
+    with_display(display: bool),
+});
+
 /// `kml:colorMode`, [12.11](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#879) in the
 /// KML specification
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ColorMode {
+    #[default]
     Default,
     Random,
 }
 
-impl Default for ColorMode {
-    fn default() -> ColorMode {
-        ColorMode::Default
-    }
-}
-
 impl FromStr for ColorMode {
     type Err = Error;
 
@@ -101,14 +160,24 @@ impl fmt::Display for ColorMode {
 /// `kml:IconStyle`, [12.12](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#883) in the
 /// KML specification
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct IconStyle {
     pub id: String,
     pub scale: f64,
     pub heading: f64,
     pub hot_spot: Option<Vec2>,
     pub icon: Icon,
-    pub color: String,
+    pub color: Color,
     pub color_mode: ColorMode,
+    /// Attributes other than `id`, like `targetId`
+    pub attrs: HashMap<String, String>,
+}
+
+impl IconStyle {
+    /// Wraps `heading` into 0 to 360, returning `true` if it changed
+    pub fn normalize_heading(&mut self) -> bool {
+        Angle::Heading.normalize_in_place(&mut self.heading)
+    }
 }
 
 impl Default for IconStyle {
@@ -119,102 +188,174 @@ impl Default for IconStyle {
             heading: 0.0,
             hot_spot: None,
             icon: Icon::default(),
-            color: "ffffffff".to_string(),
+            color: Color::WHITE,
             color_mode: ColorMode::default(),
+            attrs: HashMap::new(),
         }
     }
 }
 
+impl_with!(IconStyle {
+    with_id(id: String),
+    with_scale(scale: f64),
+    with_heading(heading: f64),
+    with_hot_spot(some hot_spot: Vec2),
+    with_icon(icon: Icon),
+    with_color(color: Color),
+    with_color_mode(color_mode: ColorMode),
+});
+
 /// `kml:Icon`, [12.13](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#900) in the KML
 /// specification.
 ///
 /// Implements on `kml:BasicLinkType`
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Icon {
     pub href: String,
+    pub attrs: HashMap<String, String>,
+}
+
+impl Icon {
+    pub fn new(href: impl Into<String>) -> Self {
+        Icon {
+            href: href.into(),
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+impl CustomElement for Icon {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "Icon" {
+            return None;
+        }
+        let href = element.child("href").and_then(|h| h.content.clone());
+        Some(Icon {
+            href: href.unwrap_or_default(),
+            attrs: element.attrs.clone(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element =
+            Element::new("Icon").with_child(Element::new("href").with_content(self.href.clone()));
+        element.attrs = self.attrs.clone();
+        element
+    }
 }
 
 /// `kml:LabelStyle`, [12.14](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#909) in the
 /// KML specification.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LabelStyle {
     pub id: String,
-    pub color: String,
+    pub color: Color,
     pub color_mode: ColorMode,
     pub scale: f64,
+    /// Attributes other than `id`, like `targetId`
+    pub attrs: HashMap<String, String>,
 }
 
 impl Default for LabelStyle {
     fn default() -> LabelStyle {
         LabelStyle {
             id: "".to_string(),
-            color: "ffffffff".to_string(),
+            color: Color::WHITE,
             color_mode: ColorMode::default(),
             scale: 1.0,
+            attrs: HashMap::new(),
         }
     }
 }
 
+impl_with!(LabelStyle {
+    with_id(id: String),
+    with_color(color: Color),
+    with_color_mode(color_mode: ColorMode),
+    with_scale(scale: f64),
+});
+
 /// `kml:LineStyle`, [12.15](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#917) in the
 /// KML specification.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LineStyle {
     pub id: String,
-    pub color: String,
+    pub color: Color,
     pub color_mode: ColorMode,
     pub width: f64,
+    /// Attributes other than `id`, like `targetId`
+    pub attrs: HashMap<String, String>,
 }
 
 impl Default for LineStyle {
     fn default() -> LineStyle {
         LineStyle {
             id: "".to_string(),
-            color: "ffffffff".to_string(),
+            color: Color::WHITE,
             color_mode: ColorMode::default(),
             width: 1.0,
+            attrs: HashMap::new(),
         }
     }
 }
 
+impl_with!(LineStyle {
+    with_id(id: String),
+    with_color(color: Color),
+    with_color_mode(color_mode: ColorMode),
+    with_width(width: f64),
+});
+
 /// `kml:PolyStyle`, [12.16](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#927) in the
 /// KML specification.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PolyStyle {
     pub id: String,
-    pub color: String,
+    pub color: Color,
     pub color_mode: ColorMode,
     pub fill: bool,
     pub outline: bool,
+    /// Attributes other than `id`, like `targetId`
+    pub attrs: HashMap<String, String>,
 }
 
 impl Default for PolyStyle {
     fn default() -> PolyStyle {
         PolyStyle {
             id: "".to_string(),
-            color: "ffffffff".to_string(),
+            color: Color::WHITE,
             color_mode: ColorMode::default(),
             fill: true,
             outline: true,
+            attrs: HashMap::new(),
         }
     }
 }
 
+impl_with!(PolyStyle {
+    with_id(id: String),
+    with_color(color: Color),
+    with_color_mode(color_mode: ColorMode),
+    with_fill(fill: bool),
+    with_outline(outline: bool),
+});
+
 /// `kml:listItemType`, [12.18](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#955) in the
 /// KML specification.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ListItemType {
+    #[default]
     Check,
     CheckOffOnly,
     CheckHideChildren,
     RadioFolder,
 }
 
-impl Default for ListItemType {
-    fn default() -> ListItemType {
-        ListItemType::Check
-    }
-}
-
 impl FromStr for ListItemType {
     type Err = Error;
 
@@ -247,20 +388,31 @@ impl fmt::Display for ListItemType {
 /// `kml:ListStyle`, [12.17](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#940) in the
 /// KML specification.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ListStyle {
     pub id: String,
-    pub bg_color: String,
+    pub bg_color: Color,
     pub max_snippet_lines: u32,
     pub list_item_type: ListItemType,
+    /// Attributes other than `id`, like `targetId`
+    pub attrs: HashMap<String, String>,
 }
 
 impl Default for ListStyle {
     fn default() -> ListStyle {
         ListStyle {
             id: "".to_string(),
-            bg_color: "ffffffff".to_string(),
+            bg_color: Color::WHITE,
             max_snippet_lines: 2,
             list_item_type: ListItemType::default(),
+            attrs: HashMap::new(),
         }
     }
 }
+
+impl_with!(ListStyle {
+    with_id(id: String),
+    with_bg_color(bg_color: Color),
+    with_max_snippet_lines(max_snippet_lines: u32),
+    with_list_item_type(list_item_type: ListItemType),
+});
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::date_time::KmlDateTime;
+use crate::types::element::{CustomElement, Element};
+
+/// `kml:TimeStamp`, [15.3](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html) in the KML
+/// specification, a single moment in time at the precision of `when`
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeStamp {
+    pub when: Option<KmlDateTime>,
+    pub attrs: HashMap<String, String>,
+}
+
+impl TimeStamp {
+    pub fn new(when: KmlDateTime) -> Self {
+        TimeStamp {
+            when: Some(when),
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+/// `kml:TimeSpan`, [15.2](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html) in the KML
+/// specification, a period of time that's unbounded when `begin` or `end` is missing
+///
+/// # Example
+///
+/// ```
+/// use kml::types::{KmlDateTime, TimeSpan};
+///
+/// let span = TimeSpan::default().with_begin("2020-01".parse::<KmlDateTime>().unwrap());
+/// assert_eq!(span.begin.unwrap().year(), 2020);
+/// assert!(span.end.is_none());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeSpan {
+    pub begin: Option<KmlDateTime>,
+    pub end: Option<KmlDateTime>,
+    pub attrs: HashMap<String, String>,
+}
+
+impl TimeSpan {
+    pub fn new(begin: Option<KmlDateTime>, end: Option<KmlDateTime>) -> Self {
+        TimeSpan {
+            begin,
+            end,
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+impl_with!(TimeSpan {
+    with_begin(some begin: KmlDateTime),
+    with_end(some end: KmlDateTime),
+});
+
+/// `kml:AbstractTimePrimitiveGroup`, the time associated with a feature
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TimePrimitive {
+    TimeStamp(TimeStamp),
+    TimeSpan(TimeSpan),
+}
+
+impl From<TimeStamp> for TimePrimitive {
+    fn from(val: TimeStamp) -> TimePrimitive {
+        TimePrimitive::TimeStamp(val)
+    }
+}
+
+impl From<TimeSpan> for TimePrimitive {
+    fn from(val: TimeSpan) -> TimePrimitive {
+        TimePrimitive::TimeSpan(val)
+    }
+}
+
+// Parses the child's content, returning `None` if it's invalid and `Some(None)` if it's missing
+fn child_date_time(element: &Element, local_name: &str) -> Option<Option<KmlDateTime>> {
+    match element.child(local_name) {
+        Some(child) => child
+            .content
+            .as_deref()
+            .unwrap_or_default()
+            .parse()
+            .ok()
+            .map(Some),
+        None => Some(None),
+    }
+}
+
+fn with_date_time(element: Element, name: &str, value: &Option<KmlDateTime>) -> Element {
+    match value {
+        Some(value) => element.with_child(Element::new(name).with_content(value.to_string())),
+        None => element,
+    }
+}
+
+impl CustomElement for TimeStamp {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "TimeStamp" {
+            return None;
+        }
+        Some(TimeStamp {
+            when: child_date_time(element, "when")?,
+            attrs: element.attrs.clone(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("TimeStamp");
+        element.attrs = self.attrs.clone();
+        with_date_time(element, "when", &self.when)
+    }
+}
+
+impl CustomElement for TimeSpan {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "TimeSpan" {
+            return None;
+        }
+        Some(TimeSpan {
+            begin: child_date_time(element, "begin")?,
+            end: child_date_time(element, "end")?,
+            attrs: element.attrs.clone(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("TimeSpan");
+        element.attrs = self.attrs.clone();
+        let element = with_date_time(element, "begin", &self.begin);
+        with_date_time(element, "end", &self.end)
+    }
+}
+
+impl CustomElement for TimePrimitive {
+    fn from_element(element: &Element) -> Option<Self> {
+        match element.local_name() {
+            "TimeStamp" => TimeStamp::from_element(element).map(TimePrimitive::TimeStamp),
+            "TimeSpan" => TimeSpan::from_element(element).map(TimePrimitive::TimeSpan),
+            _ => None,
+        }
+    }
+
+    fn to_element(&self) -> Element {
+        match self {
+            TimePrimitive::TimeStamp(t) => t.to_element(),
+            TimePrimitive::TimeSpan(t) => t.to_element(),
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+use crate::types::element::{CustomElement, Element};
+
+/// `gx:Tour` from Google's extensions, which plays the primitives in its `gx:Playlist` in order
+///
+/// Tours are read into [`Kml::Tour`](../enum.Kml.html#variant.Tour), and are written with the
+/// `gx` prefix unless writing KML 2.3.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Tour {
+    pub name: Option<String>,
+    pub playlist: Vec<TourPrimitive>,
+    pub attrs: HashMap<String, String>,
+}
+
+/// Entries of a `gx:Playlist`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum TourPrimitive {
+    FlyTo(FlyTo),
+    /// `gx:Wait`, pausing for the duration in seconds
+    Wait(f64),
+    AnimatedUpdate(AnimatedUpdate),
+    /// `gx:TourControl`, pausing the tour until the user resumes it
+    TourControl,
+    SoundCue(SoundCue),
+}
+
+/// `gx:FlyTo`, moving to a view over `duration` seconds
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FlyTo {
+    pub duration: f64,
+    pub mode: FlyToMode,
+    /// `kml:Camera` or `kml:LookAt` to fly to
+    pub view: Option<Element>,
+}
+
+/// `gx:flyToMode`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FlyToMode {
+    #[default]
+    Bounce,
+    Smooth,
+}
+
+impl FromStr for FlyToMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bounce" => Ok(Self::Bounce),
+            "smooth" => Ok(Self::Smooth),
+            v => Err(Error::InvalidFlyToMode(v.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for FlyToMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Bounce => "bounce",
+                Self::Smooth => "smooth",
+            }
+        )
+    }
+}
+
+/// `gx:AnimatedUpdate`, applying a `kml:Update` over `duration` seconds after `delayed_start`
+/// seconds while the tour continues
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnimatedUpdate {
+    pub duration: f64,
+    pub delayed_start: f64,
+    pub update: Option<Element>,
+}
+
+/// `gx:SoundCue`, playing audio after `delayed_start` seconds while the tour continues
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SoundCue {
+    pub href: String,
+    pub delayed_start: f64,
+}
+
+impl TourPrimitive {
+    // Seconds the tour waits for the primitive before moving to the next one
+    fn blocking_duration(&self) -> f64 {
+        match self {
+            Self::FlyTo(f) => f.duration,
+            Self::Wait(duration) => *duration,
+            _ => 0.,
+        }
+    }
+
+    // Seconds after the tour reaches the primitive until it starts and finishes
+    fn span(&self) -> (f64, f64) {
+        match self {
+            Self::AnimatedUpdate(u) => (u.delayed_start, u.delayed_start + u.duration),
+            Self::SoundCue(s) => (s.delayed_start, s.delayed_start),
+            p => (0., p.blocking_duration()),
+        }
+    }
+}
+
+impl Tour {
+    /// Returns the time in seconds until the last primitive finishes
+    ///
+    /// `gx:FlyTo` and `gx:Wait` are played one after another, while `gx:AnimatedUpdate` and
+    /// `gx:SoundCue` run alongside them, so an update can finish after the rest of the playlist.
+    /// The length of sounds and the time paused by `gx:TourControl` aren't known, so they don't
+    /// add to the duration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::types::{AnimatedUpdate, FlyTo, Tour, TourPrimitive};
+    ///
+    /// let tour = Tour {
+    ///     playlist: vec![
+    ///         TourPrimitive::FlyTo(FlyTo { duration: 5., ..Default::default() }),
+    ///         TourPrimitive::AnimatedUpdate(AnimatedUpdate {
+    ///             duration: 10.,
+    ///             delayed_start: 1.,
+    ///             update: None,
+    ///         }),
+    ///         TourPrimitive::Wait(2.),
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(tour.duration(), 16.);
+    /// let starts: Vec<f64> = tour.timeline().map(|(start, _)| start).collect();
+    /// assert_eq!(starts, vec![0., 6., 5.]);
+    /// ```
+    pub fn duration(&self) -> f64 {
+        self.timeline()
+            .map(|(start, p)| start - p.span().0 + p.span().1)
+            .fold(0., f64::max)
+    }
+
+    /// Returns an iterator over the primitives in playlist order along with the time in seconds
+    /// each starts, accounting for the delayed start of `gx:AnimatedUpdate` and `gx:SoundCue`
+    pub fn timeline(&self) -> impl Iterator<Item = (f64, &TourPrimitive)> {
+        self.playlist.iter().scan(0., |time, primitive| {
+            let start = *time + primitive.span().0;
+            *time += primitive.blocking_duration();
+            Some((start, primitive))
+        })
+    }
+}
+
+impl_with!(Tour {
+    with_name(some name: String),
+});
+
+fn child_f64(element: &Element, local_name: &str) -> Option<f64> {
+    match element.child(local_name) {
+        Some(child) => child.content.as_deref()?.trim().parse().ok(),
+        None => Some(0.),
+    }
+}
+
+fn text_element(name: &str, content: impl ToString) -> Element {
+    Element::new(name).with_content(content.to_string())
+}
+
+impl CustomElement for TourPrimitive {
+    fn from_element(element: &Element) -> Option<Self> {
+        Some(match element.local_name() {
+            "FlyTo" => TourPrimitive::FlyTo(FlyTo {
+                duration: child_f64(element, "duration")?,
+                mode: match element.child("flyToMode") {
+                    Some(m) => m.content.as_deref()?.trim().parse().ok()?,
+                    None => FlyToMode::default(),
+                },
+                view: element
+                    .children
+                    .iter()
+                    .find(|c| matches!(c.local_name(), "Camera" | "LookAt"))
+                    .cloned(),
+            }),
+            "Wait" => TourPrimitive::Wait(child_f64(element, "duration")?),
+            "AnimatedUpdate" => TourPrimitive::AnimatedUpdate(AnimatedUpdate {
+                duration: child_f64(element, "duration")?,
+                delayed_start: child_f64(element, "delayedStart")?,
+                update: element.child("Update").cloned(),
+            }),
+            "TourControl" => TourPrimitive::TourControl,
+            "SoundCue" => TourPrimitive::SoundCue(SoundCue {
+                href: element.child("href")?.content.clone().unwrap_or_default(),
+                delayed_start: child_f64(element, "delayedStart")?,
+            }),
+            _ => return None,
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        match self {
+            TourPrimitive::FlyTo(f) => {
+                let mut element =
+                    Element::new("gx:FlyTo").with_child(text_element("gx:duration", f.duration));
+                if f.mode != FlyToMode::default() {
+                    element = element.with_child(text_element("gx:flyToMode", f.mode));
+                }
+                match &f.view {
+                    Some(view) => element.with_child(view.clone()),
+                    None => element,
+                }
+            }
+            TourPrimitive::Wait(duration) => {
+                Element::new("gx:Wait").with_child(text_element("gx:duration", duration))
+            }
+            TourPrimitive::AnimatedUpdate(u) => {
+                let element = Element::new("gx:AnimatedUpdate")
+                    .with_child(text_element("gx:duration", u.duration))
+                    .with_child(text_element("gx:delayedStart", u.delayed_start));
+                match &u.update {
+                    Some(update) => element.with_child(update.clone()),
+                    None => element,
+                }
+            }
+            TourPrimitive::TourControl => {
+                Element::new("gx:TourControl").with_child(text_element("gx:playMode", "pause"))
+            }
+            TourPrimitive::SoundCue(s) => Element::new("gx:SoundCue")
+                .with_child(text_element("href", &s.href))
+                .with_child(text_element("gx:delayedStart", s.delayed_start)),
+        }
+    }
+}
+
+impl CustomElement for Tour {
+    fn from_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "Tour" {
+            return None;
+        }
+        let playlist = match element.child("Playlist") {
+            Some(playlist) => playlist
+                .children
+                .iter()
+                .map(TourPrimitive::from_element)
+                .collect::<Option<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        Some(Tour {
+            name: element.child("name").and_then(|n| n.content.clone()),
+            playlist,
+            attrs: element.attrs.clone(),
+        })
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("gx:Tour");
+        element.attrs = self.attrs.clone();
+        if let Some(name) = &self.name {
+            element = element.with_child(text_element("name", name));
+        }
+        let playlist = self
+            .playlist
+            .iter()
+            .fold(Element::new("gx:Playlist"), |playlist, p| {
+                playlist.with_child(p.to_element())
+            });
+        element.with_child(playlist)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Kml;
+
+    #[test]
+    fn test_tour_timeline() {
+        let kml: Kml = r#"<gx:Tour xmlns:gx="http://www.google.com/kml/ext/2.2">
+          <name>Tour</name>
+          <gx:Playlist>
+            <gx:SoundCue><href>intro.mp3</href><gx:delayedStart>1</gx:delayedStart></gx:SoundCue>
+            <gx:FlyTo><gx:duration>4</gx:duration><gx:flyToMode>smooth</gx:flyToMode>
+              <LookAt><range>100</range></LookAt>
+            </gx:FlyTo>
+            <gx:TourControl><gx:playMode>pause</gx:playMode></gx:TourControl>
+            <gx:AnimatedUpdate><gx:duration>3</gx:duration><Update/></gx:AnimatedUpdate>
+            <gx:Wait><gx:duration>1.5</gx:duration></gx:Wait>
+          </gx:Playlist>
+        </gx:Tour>"#
+            .parse()
+            .unwrap();
+        let tour = match kml {
+            Kml::Tour(tour) => tour,
+            k => panic!("expected Tour, found {:?}", k),
+        };
+
+        assert_eq!(tour.name.as_deref(), Some("Tour"));
+        assert!(matches!(
+            &tour.playlist[1],
+            TourPrimitive::FlyTo(FlyTo {
+                mode: FlyToMode::Smooth,
+                view: Some(_),
+                ..
+            })
+        ));
+        assert_eq!(
+            tour.timeline()
+                .map(|(start, _)| start)
+                .collect::<Vec<f64>>(),
+            vec![1., 0., 4., 4., 4.]
+        );
+        assert_eq!(tour.duration(), 7.);
+        assert_eq!(Tour::from_element(&tour.to_element()), Some(tour));
+    }
+}
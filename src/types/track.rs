@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::altitude_mode::{altitude_mode_element, AltitudeMode};
+use crate::types::coord::{Coord, CoordType};
+use crate::types::date_time::KmlDateTime;
+use crate::types::element::{CustomElement, Element};
+use crate::types::geometry::Geometry;
+use crate::types::line_string::LineString;
+use crate::types::multi_geometry::MultiGeometry;
+use crate::types::orientation::Orientation;
+
+/// `gx:Track` from Google's extensions, which is `kml:Track` in KML 2.3
+///
+/// Each time in `when` has the coordinate at the same index in `coords`, and if there are any
+/// `angles` or values in `arrays` there's one for each time as well. Use
+/// [`Track::from_samples`](#method.from_samples) to build tracks without keeping the lists in sync.
+///
+/// Tracks are read into [`Geometry::Track`](enum.Geometry.html#variant.Track) in placemarks and
+/// multi-geometries and [`Kml::Track`](../enum.Kml.html#variant.Track) elsewhere, and are written
+/// with the `gx` prefix unless writing KML 2.3.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Track<T: CoordType = f64> {
+    pub altitude_mode: AltitudeMode,
+    pub when: Vec<KmlDateTime>,
+    pub coords: Vec<Coord<T>>,
+    /// Heading, tilt, and roll of a model at each time, written as `gx:angles`
+    pub angles: Vec<Orientation<T>>,
+    /// `schemaUrl` of the `kml:SchemaData` containing `arrays`
+    pub schema_url: Option<String>,
+    pub arrays: Vec<SimpleArrayData>,
+    pub attrs: HashMap<String, String>,
+}
+
+/// [`Track`] by the name of its element in Google's extension namespace
+pub type GxTrack<T = f64> = Track<T>;
+
+/// `gx:MultiTrack` from Google's extensions, which is `kml:MultiTrack` in KML 2.3
+///
+/// With `interpolate`, viewers connect the end of each track to the start of the next one.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MultiTrack<T: CoordType = f64> {
+    pub altitude_mode: AltitudeMode,
+    pub interpolate: bool,
+    pub tracks: Vec<Track<T>>,
+    pub attrs: HashMap<String, String>,
+}
+
+/// [`MultiTrack`] by the name of its element in Google's extension namespace
+pub type GxMultiTrack<T = f64> = MultiTrack<T>;
+
+/// `gx:SimpleArrayData`, with a value for each time in a [`Track`]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SimpleArrayData {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+/// Time and coordinate of a [`Track`], along with optional angles and named values for
+/// `gx:SimpleArrayData`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrackSample<T: CoordType = f64> {
+    pub when: KmlDateTime,
+    pub coord: Coord<T>,
+    pub angles: Option<Orientation<T>>,
+    pub data: Vec<(String, String)>,
+}
+
+impl<T> TrackSample<T>
+where
+    T: CoordType,
+{
+    pub fn new(when: KmlDateTime, coord: Coord<T>) -> Self {
+        TrackSample {
+            when,
+            coord,
+            angles: None,
+            data: Vec::new(),
+        }
+    }
+
+    /// Adds a value for the `gx:SimpleArrayData` with the name, returning `self` for chaining
+    pub fn with_data(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.data.push((name.into(), value.to_string()));
+        self
+    }
+}
+
+impl_with!(TrackSample<T> {
+    with_angles(some angles: Orientation<T>),
+});
+
+impl<T> From<(KmlDateTime, Coord<T>)> for TrackSample<T>
+where
+    T: CoordType,
+{
+    fn from((when, coord): (KmlDateTime, Coord<T>)) -> Self {
+        TrackSample::new(when, coord)
+    }
+}
+
+impl<T> Track<T>
+where
+    T: CoordType + Default,
+{
+    /// Creates a track from samples in order, like `(KmlDateTime, Coord)` tuples or
+    /// [`TrackSample`] values with angles and data
+    ///
+    /// If any sample has angles, samples without them get zero angles, and samples missing a value
+    /// for a `gx:SimpleArrayData` get an empty value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::Kml;
+    /// use kml::types::{Coord, KmlDateTime, Orientation, Track, TrackSample};
+    ///
+    /// let start: KmlDateTime = "2010-05-28T02:02:09Z".parse().unwrap();
+    /// let end: KmlDateTime = "2010-05-28T02:02:35Z".parse().unwrap();
+    /// let track = Track::from_samples(vec![
+    ///     TrackSample::new(start, Coord::new(-122.207881, 37.371915, Some(156.)))
+    ///         .with_angles(Orientation::new(0., 0., 45.))
+    ///         .with_data("heartrate", 181),
+    ///     TrackSample::new(end, Coord::new(-122.205712, 37.373288, Some(152.)))
+    ///         .with_data("heartrate", 177),
+    /// ]);
+    /// assert_eq!(track.when, vec![start, end]);
+    /// assert_eq!(track.angles[1], Orientation::default());
+    /// assert_eq!(track.arrays[0].values, vec!["181", "177"]);
+    ///
+    /// let kml: Kml = Kml::Track(track);
+    /// assert!(kml.to_string().contains("<gx:coord>-122.207881 37.371915 156</gx:coord>"));
+    /// ```
+    pub fn from_samples<I, S>(samples: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<TrackSample<T>>,
+    {
+        let samples: Vec<TrackSample<T>> = samples.into_iter().map(Into::into).collect();
+        let mut track = Track {
+            when: samples.iter().map(|s| s.when).collect(),
+            coords: samples.iter().map(|s| s.coord).collect(),
+            ..Default::default()
+        };
+        if samples.iter().any(|s| s.angles.is_some()) {
+            track.angles = samples
+                .iter()
+                .map(|s| s.angles.clone().unwrap_or_default())
+                .collect();
+        }
+        for (i, sample) in samples.iter().enumerate() {
+            for (name, value) in sample.data.iter() {
+                let array = match track.arrays.iter().position(|a| &a.name == name) {
+                    Some(pos) => &mut track.arrays[pos],
+                    None => {
+                        track.arrays.push(SimpleArrayData {
+                            name: name.clone(),
+                            values: Vec::new(),
+                        });
+                        track.arrays.last_mut().unwrap()
+                    }
+                };
+                array.values.resize(i, String::new());
+                array.values.push(value.clone());
+            }
+        }
+        for array in track.arrays.iter_mut() {
+            array.values.resize(samples.len(), String::new());
+        }
+        track
+    }
+}
+
+impl<T> Track<T>
+where
+    T: CoordType + Default,
+{
+    /// Returns a `kml:LineString` through the coordinates of the track, with the ground
+    /// equivalent of sea floor altitude modes
+    pub fn to_line_string(&self) -> LineString<T> {
+        LineString {
+            coords: self.coords.clone(),
+            altitude_mode: self.altitude_mode.to_ground(),
+            ..Default::default()
+        }
+    }
+}
+
+impl_with!(Track<T> {
+    with_altitude_mode(altitude_mode: AltitudeMode),
+    with_schema_url(some schema_url: String),
+});
+
+impl<T> MultiTrack<T>
+where
+    T: CoordType,
+{
+    pub fn new(tracks: Vec<Track<T>>) -> Self {
+        MultiTrack {
+            altitude_mode: AltitudeMode::default(),
+            interpolate: false,
+            tracks,
+            attrs: HashMap::new(),
+        }
+    }
+}
+
+impl<T> MultiTrack<T>
+where
+    T: CoordType + Default,
+{
+    /// Returns a `kml:MultiGeometry` with a `kml:LineString` for each track, see
+    /// [`Track::to_line_string`]
+    pub fn to_multi_geometry(&self) -> MultiGeometry<T> {
+        MultiGeometry::new(
+            self.tracks
+                .iter()
+                .map(|t| Geometry::LineString(t.to_line_string()))
+                .collect(),
+        )
+    }
+}
+
+impl_with!(MultiTrack<T> {
+    with_altitude_mode(altitude_mode: AltitudeMode),
+    with_interpolate(interpolate: bool),
+});
+
+// Parses space-separated values like the contents of `gx:coord` and `gx:angles`
+fn parse_values<T: FromStr>(s: &str) -> Option<Vec<T>> {
+    s.split_whitespace().map(|v| v.parse().ok()).collect()
+}
+
+impl<T> Track<T>
+where
+    T: CoordType + Default + FromStr,
+{
+    // Reads a track from an element, which the reader needs without the `Display` bound of
+    // `CustomElement`
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "Track" {
+            return None;
+        }
+        let mut track = Track {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            let content = child.content.as_deref().unwrap_or_default();
+            match child.local_name() {
+                "altitudeMode" => track.altitude_mode = content.parse().ok()?,
+                "when" => track.when.push(content.parse().ok()?),
+                "coord" => match parse_values(content)?.as_slice() {
+                    [x, y] => track.coords.push(Coord::new(*x, *y, None)),
+                    [x, y, z] => track.coords.push(Coord::new(*x, *y, Some(*z))),
+                    _ => return None,
+                },
+                "angles" => match parse_values(content)?.as_slice() {
+                    [heading, tilt, roll] => {
+                        track.angles.push(Orientation::new(*roll, *tilt, *heading))
+                    }
+                    _ => return None,
+                },
+                "ExtendedData" => {
+                    let schema_data = child.child("SchemaData")?;
+                    track.schema_url = schema_data.attrs.get("schemaUrl").cloned();
+                    for array in schema_data.children.iter() {
+                        if array.local_name() != "SimpleArrayData" {
+                            continue;
+                        }
+                        track.arrays.push(SimpleArrayData {
+                            name: array.attrs.get("name").cloned().unwrap_or_default(),
+                            values: array
+                                .children
+                                .iter()
+                                .filter(|v| v.local_name() == "value")
+                                .map(|v| v.content.clone().unwrap_or_default())
+                                .collect(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(track)
+    }
+}
+
+impl<T> MultiTrack<T>
+where
+    T: CoordType + Default + FromStr,
+{
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "MultiTrack" {
+            return None;
+        }
+        let mut multi_track = MultiTrack::new(Vec::new());
+        multi_track.attrs = element.attrs.clone();
+        for child in element.children.iter() {
+            let content = child.content.as_deref().unwrap_or_default().trim();
+            match child.local_name() {
+                "altitudeMode" => multi_track.altitude_mode = content.parse().ok()?,
+                "interpolate" => multi_track.interpolate = content == "1" || content == "true",
+                "Track" => multi_track.tracks.push(Track::parse_element(child)?),
+                _ => {}
+            }
+        }
+        Some(multi_track)
+    }
+}
+
+impl<T> CustomElement for Track<T>
+where
+    T: CoordType + Default + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        Track::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("gx:Track");
+        element.attrs = self.attrs.clone();
+        if let Some(altitude_mode) = altitude_mode_element(&self.altitude_mode) {
+            element = element.with_child(altitude_mode);
+        }
+        for when in self.when.iter() {
+            element = element.with_child(Element::new("when").with_content(when.to_string()));
+        }
+        for coord in self.coords.iter() {
+            let z = coord.z.unwrap_or_else(T::zero);
+            element = element.with_child(
+                Element::new("gx:coord").with_content(format!("{} {} {}", coord.x, coord.y, z)),
+            );
+        }
+        for angles in self.angles.iter() {
+            element = element.with_child(Element::new("gx:angles").with_content(format!(
+                "{} {} {}",
+                angles.heading, angles.tilt, angles.roll
+            )));
+        }
+        if !self.arrays.is_empty() {
+            let mut schema_data = Element::new("SchemaData");
+            if let Some(schema_url) = &self.schema_url {
+                schema_data = schema_data.with_attr("schemaUrl", schema_url.clone());
+            }
+            for array in self.arrays.iter() {
+                let mut array_element =
+                    Element::new("gx:SimpleArrayData").with_attr("name", array.name.clone());
+                for value in array.values.iter() {
+                    array_element = array_element
+                        .with_child(Element::new("gx:value").with_content(value.clone()));
+                }
+                schema_data = schema_data.with_child(array_element);
+            }
+            element = element.with_child(Element::new("ExtendedData").with_child(schema_data));
+        }
+        element
+    }
+}
+
+impl<T> CustomElement for MultiTrack<T>
+where
+    T: CoordType + Default + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        MultiTrack::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("gx:MultiTrack");
+        element.attrs = self.attrs.clone();
+        if let Some(altitude_mode) = altitude_mode_element(&self.altitude_mode) {
+            element = element.with_child(altitude_mode);
+        }
+        if self.interpolate {
+            element = element.with_child(Element::new("gx:interpolate").with_content("1"));
+        }
+        self.tracks.iter().fold(element, |element, track| {
+            element.with_child(track.to_element())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_element_round_trip() {
+        let when = |s: &str| s.parse::<KmlDateTime>().unwrap();
+        let track: Track = Track::from_samples(vec![
+            TrackSample::new(when("2010-05-28T02:02:09Z"), Coord::new(1., 2., Some(3.)))
+                .with_data("cadence", 86),
+            TrackSample::new(when("2010-05-28T02:02:35Z"), Coord::new(4., 5., Some(6.)))
+                .with_data("heartrate", 181),
+            (when("2010-05-28T02:02:44Z"), Coord::new(7., 8., Some(9.))).into(),
+        ])
+        .with_schema_url("#schema");
+
+        assert!(track.angles.is_empty());
+        assert_eq!(
+            track.arrays,
+            vec![
+                SimpleArrayData {
+                    name: "cadence".to_string(),
+                    values: vec!["86".to_string(), String::new(), String::new()],
+                },
+                SimpleArrayData {
+                    name: "heartrate".to_string(),
+                    values: vec![String::new(), "181".to_string(), String::new()],
+                },
+            ]
+        );
+        assert_eq!(Track::from_element(&track.to_element()), Some(track));
+    }
+}
@@ -1,9 +1,13 @@
 use core::fmt;
 use std::str::FromStr;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::Error;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vec2 {
     pub x: f64,
     pub y: f64,
@@ -11,6 +15,17 @@ pub struct Vec2 {
     pub yunits: Units,
 }
 
+impl Vec2 {
+    pub fn new(x: f64, y: f64, xunits: Units, yunits: Units) -> Self {
+        Vec2 {
+            x,
+            y,
+            xunits,
+            yunits,
+        }
+    }
+}
+
 impl Default for Vec2 {
     fn default() -> Self {
         Self {
@@ -22,19 +37,15 @@ impl Default for Vec2 {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Units {
+    #[default]
     Fraction,
     Pixels,
     InsetPixels,
 }
 
-impl Default for Units {
-    fn default() -> Self {
-        Self::Fraction
-    }
-}
-
 impl FromStr for Units {
     type Err = Error;
 
@@ -0,0 +1,503 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::altitude_mode::{altitude_mode_element, AltitudeMode};
+use crate::types::angle::{Angle, CAMERA_TILT, LOOK_AT_TILT};
+use crate::types::coord::CoordType;
+use crate::types::element::{CustomElement, Element};
+use crate::types::time_primitive::TimePrimitive;
+
+/// `kml:Camera`, [10.3](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#400) in the KML
+/// specification, the position and orientation of the viewer
+///
+/// `time_primitive` is the `gx:TimeStamp` or `gx:TimeSpan` of the view, and other extensions like
+/// `gx:ViewerOptions` are kept in `children`.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, types::{AltitudeMode, Camera}};
+///
+/// let camera = Camera::new(-122.08, 37.42, 500.)
+///     .with_heading(45.)
+///     .with_tilt(60.)
+///     .with_altitude_mode(AltitudeMode::RelativeToGround);
+///
+/// let kml_str = Kml::Camera(camera.clone()).to_string();
+/// assert!(kml_str.contains("<tilt>60</tilt>"));
+/// assert_eq!(kml_str.parse::<Kml>().unwrap(), Kml::Camera(camera));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Camera<T: CoordType = f64> {
+    pub longitude: T,
+    pub latitude: T,
+    pub altitude: T,
+    pub heading: T,
+    pub tilt: T,
+    pub roll: T,
+    pub altitude_mode: AltitudeMode,
+    pub time_primitive: Option<TimePrimitive>,
+    pub attrs: HashMap<String, String>,
+    pub children: Vec<Element>,
+}
+
+impl<T> Camera<T>
+where
+    T: CoordType,
+{
+    pub fn new(longitude: T, latitude: T, altitude: T) -> Self {
+        Camera {
+            longitude,
+            latitude,
+            altitude,
+            ..Default::default()
+        }
+    }
+
+    /// Wraps `heading`, `roll`, and `longitude` around their ranges and clamps `tilt` and
+    /// `latitude`, returning the number of fields changed
+    pub fn normalize(&mut self) -> usize {
+        Angle::Longitude.normalize_in_place(&mut self.longitude) as usize
+            + Angle::Latitude.normalize_in_place(&mut self.latitude) as usize
+            + Angle::Heading.normalize_in_place(&mut self.heading) as usize
+            + Angle::Tilt(CAMERA_TILT).normalize_in_place(&mut self.tilt) as usize
+            + Angle::Roll.normalize_in_place(&mut self.roll) as usize
+    }
+}
+
+impl<T> Default for Camera<T>
+where
+    T: CoordType,
+{
+    fn default() -> Self {
+        Camera {
+            longitude: T::zero(),
+            latitude: T::zero(),
+            altitude: T::zero(),
+            heading: T::zero(),
+            tilt: T::zero(),
+            roll: T::zero(),
+            altitude_mode: AltitudeMode::default(),
+            time_primitive: None,
+            attrs: HashMap::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl_with!(Camera<T> {
+    with_heading(heading: T),
+    with_tilt(tilt: T),
+    with_roll(roll: T),
+    with_altitude_mode(altitude_mode: AltitudeMode),
+    with_time_primitive(some time_primitive: TimePrimitive),
+});
+
+/// `kml:LookAt`, [10.4](http://docs.opengeospatial.org/is/12-007r2/12-007r2.html#415) in the KML
+/// specification, a view of a point from `range` meters away
+///
+/// `time_primitive` is the `gx:TimeStamp` or `gx:TimeSpan` of the view, and other extensions like
+/// `gx:ViewerOptions` are kept in `children`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LookAt<T: CoordType = f64> {
+    pub longitude: T,
+    pub latitude: T,
+    pub altitude: T,
+    pub heading: T,
+    pub tilt: T,
+    pub range: T,
+    pub altitude_mode: AltitudeMode,
+    pub time_primitive: Option<TimePrimitive>,
+    pub attrs: HashMap<String, String>,
+    pub children: Vec<Element>,
+}
+
+impl<T> LookAt<T>
+where
+    T: CoordType,
+{
+    pub fn new(longitude: T, latitude: T, range: T) -> Self {
+        LookAt {
+            longitude,
+            latitude,
+            range,
+            ..Default::default()
+        }
+    }
+
+    /// Wraps `heading` and `longitude` around their ranges and clamps `tilt` and `latitude`,
+    /// returning the number of fields changed
+    pub fn normalize(&mut self) -> usize {
+        Angle::Longitude.normalize_in_place(&mut self.longitude) as usize
+            + Angle::Latitude.normalize_in_place(&mut self.latitude) as usize
+            + Angle::Heading.normalize_in_place(&mut self.heading) as usize
+            + Angle::Tilt(LOOK_AT_TILT).normalize_in_place(&mut self.tilt) as usize
+    }
+}
+
+impl<T> Default for LookAt<T>
+where
+    T: CoordType,
+{
+    fn default() -> Self {
+        LookAt {
+            longitude: T::zero(),
+            latitude: T::zero(),
+            altitude: T::zero(),
+            heading: T::zero(),
+            tilt: T::zero(),
+            range: T::zero(),
+            altitude_mode: AltitudeMode::default(),
+            time_primitive: None,
+            attrs: HashMap::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl_with!(LookAt<T> {
+    with_altitude(altitude: T),
+    with_heading(heading: T),
+    with_tilt(tilt: T),
+    with_altitude_mode(altitude_mode: AltitudeMode),
+    with_time_primitive(some time_primitive: TimePrimitive),
+});
+
+/// `kml:AbstractViewGroup`, the view of a feature that viewers fly to when it's selected
+///
+/// # Example
+///
+/// ```
+/// use kml::types::{AbstractView, LookAt, Placemark};
+///
+/// let placemark: Placemark = Placemark::default()
+///     .with_name("Summit")
+///     .with_view(LookAt::new(-105.6, 40.25, 2000.).with_tilt(45.));
+/// assert!(matches!(placemark.view, Some(AbstractView::LookAt(_))));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AbstractView<T: CoordType = f64> {
+    Camera(Camera<T>),
+    LookAt(LookAt<T>),
+}
+
+impl<T> AbstractView<T>
+where
+    T: CoordType,
+{
+    /// Normalizes the angles of the view, returning the number of fields changed
+    pub fn normalize(&mut self) -> usize {
+        match self {
+            AbstractView::Camera(c) => c.normalize(),
+            AbstractView::LookAt(l) => l.normalize(),
+        }
+    }
+}
+
+impl<T> From<Camera<T>> for AbstractView<T>
+where
+    T: CoordType,
+{
+    fn from(val: Camera<T>) -> AbstractView<T> {
+        AbstractView::Camera(val)
+    }
+}
+
+impl<T> From<LookAt<T>> for AbstractView<T>
+where
+    T: CoordType,
+{
+    fn from(val: LookAt<T>) -> AbstractView<T> {
+        AbstractView::LookAt(val)
+    }
+}
+
+fn content(element: &Element) -> &str {
+    element.content.as_deref().unwrap_or_default().trim()
+}
+
+fn text_element(name: &str, content: impl ToString) -> Element {
+    Element::new(name).with_content(content.to_string())
+}
+
+// Time primitives of views are Google extensions in KML 2.2
+fn time_primitive_element(time_primitive: &TimePrimitive) -> Element {
+    let mut element = time_primitive.to_element();
+    element.name = format!("gx:{}", element.name);
+    element
+}
+
+impl<T> Camera<T>
+where
+    T: CoordType + FromStr,
+{
+    // Reads a camera from an element, which the reader needs without the `Display` bound of
+    // `CustomElement`
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "Camera" {
+            return None;
+        }
+        let mut camera = Camera {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "longitude" => camera.longitude = content(child).parse().ok()?,
+                "latitude" => camera.latitude = content(child).parse().ok()?,
+                "altitude" => camera.altitude = content(child).parse().ok()?,
+                "heading" => camera.heading = content(child).parse().ok()?,
+                "tilt" => camera.tilt = content(child).parse().ok()?,
+                "roll" => camera.roll = content(child).parse().ok()?,
+                "altitudeMode" => camera.altitude_mode = content(child).parse().ok()?,
+                "TimeStamp" | "TimeSpan" if camera.time_primitive.is_none() => {
+                    camera.time_primitive = Some(TimePrimitive::from_element(child)?)
+                }
+                _ => camera.children.push(child.clone()),
+            }
+        }
+        Some(camera)
+    }
+}
+
+impl<T> CustomElement for Camera<T>
+where
+    T: CoordType + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        Camera::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("Camera");
+        element.attrs = self.attrs.clone();
+        if let Some(time_primitive) = &self.time_primitive {
+            element = element.with_child(time_primitive_element(time_primitive));
+        }
+        for child in self.children.iter() {
+            element = element.with_child(child.clone());
+        }
+        element = element
+            .with_child(text_element("longitude", self.longitude))
+            .with_child(text_element("latitude", self.latitude))
+            .with_child(text_element("altitude", self.altitude));
+        for (name, value) in [
+            ("heading", self.heading),
+            ("tilt", self.tilt),
+            ("roll", self.roll),
+        ] {
+            if !value.is_zero() {
+                element = element.with_child(text_element(name, value));
+            }
+        }
+        match altitude_mode_element(&self.altitude_mode) {
+            Some(altitude_mode) => element.with_child(altitude_mode),
+            None => element,
+        }
+    }
+}
+
+impl<T> LookAt<T>
+where
+    T: CoordType + FromStr,
+{
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        if element.local_name() != "LookAt" {
+            return None;
+        }
+        let mut look_at = LookAt {
+            attrs: element.attrs.clone(),
+            ..Default::default()
+        };
+        for child in element.children.iter() {
+            match child.local_name() {
+                "longitude" => look_at.longitude = content(child).parse().ok()?,
+                "latitude" => look_at.latitude = content(child).parse().ok()?,
+                "altitude" => look_at.altitude = content(child).parse().ok()?,
+                "heading" => look_at.heading = content(child).parse().ok()?,
+                "tilt" => look_at.tilt = content(child).parse().ok()?,
+                "range" => look_at.range = content(child).parse().ok()?,
+                "altitudeMode" => look_at.altitude_mode = content(child).parse().ok()?,
+                "TimeStamp" | "TimeSpan" if look_at.time_primitive.is_none() => {
+                    look_at.time_primitive = Some(TimePrimitive::from_element(child)?)
+                }
+                _ => look_at.children.push(child.clone()),
+            }
+        }
+        Some(look_at)
+    }
+}
+
+impl<T> CustomElement for LookAt<T>
+where
+    T: CoordType + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        LookAt::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        let mut element = Element::new("LookAt");
+        element.attrs = self.attrs.clone();
+        if let Some(time_primitive) = &self.time_primitive {
+            element = element.with_child(time_primitive_element(time_primitive));
+        }
+        for child in self.children.iter() {
+            element = element.with_child(child.clone());
+        }
+        element = element
+            .with_child(text_element("longitude", self.longitude))
+            .with_child(text_element("latitude", self.latitude));
+        for (name, value) in [
+            ("altitude", self.altitude),
+            ("heading", self.heading),
+            ("tilt", self.tilt),
+        ] {
+            if !value.is_zero() {
+                element = element.with_child(text_element(name, value));
+            }
+        }
+        element = element.with_child(text_element("range", self.range));
+        match altitude_mode_element(&self.altitude_mode) {
+            Some(altitude_mode) => element.with_child(altitude_mode),
+            None => element,
+        }
+    }
+}
+
+impl<T> AbstractView<T>
+where
+    T: CoordType + FromStr,
+{
+    pub(crate) fn parse_element(element: &Element) -> Option<Self> {
+        match element.local_name() {
+            "Camera" => Camera::parse_element(element).map(AbstractView::Camera),
+            "LookAt" => LookAt::parse_element(element).map(AbstractView::LookAt),
+            _ => None,
+        }
+    }
+}
+
+impl<T> CustomElement for AbstractView<T>
+where
+    T: CoordType + FromStr + fmt::Display,
+{
+    fn from_element(element: &Element) -> Option<Self> {
+        AbstractView::parse_element(element)
+    }
+
+    fn to_element(&self) -> Element {
+        match self {
+            AbstractView::Camera(c) => c.to_element(),
+            AbstractView::LookAt(l) => l.to_element(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{KmlDateTime, TimeStamp};
+
+    #[test]
+    fn test_camera_element_round_trip() {
+        let when: KmlDateTime = "2020-01-02T03:04:05Z".parse().unwrap();
+        let camera: Camera = Camera::new(-122.08, 37.42, 500.)
+            .with_heading(45.)
+            .with_tilt(60.)
+            .with_roll(-5.)
+            .with_altitude_mode(AltitudeMode::Absolute)
+            .with_time_primitive(TimeStamp::new(when));
+        let element = camera.to_element();
+        // Time primitives of views are written in Google's extension namespace
+        assert_eq!(element.children[0].name, "gx:TimeStamp");
+        assert_eq!(Camera::from_element(&element), Some(camera.clone()));
+        assert_eq!(
+            AbstractView::from_element(&element),
+            Some(AbstractView::Camera(camera))
+        );
+
+        // Zero angles aren't written, but the position always is
+        let element = Camera::<f64>::default().to_element();
+        let names: Vec<&str> = element.children.iter().map(|c| c.local_name()).collect();
+        assert_eq!(names, ["longitude", "latitude", "altitude"]);
+    }
+
+    #[test]
+    fn test_look_at_element_round_trip() {
+        let look_at: LookAt = LookAt::new(-105.6, 40.25, 2000.)
+            .with_altitude(100.)
+            .with_tilt(45.)
+            .with_altitude_mode(AltitudeMode::RelativeToSeaFloor);
+        let element = look_at.to_element();
+        assert_eq!(LookAt::from_element(&element), Some(look_at.clone()));
+        assert_eq!(
+            AbstractView::from_element(&element),
+            Some(AbstractView::LookAt(look_at))
+        );
+
+        let element = LookAt::<f64>::new(1., 2., 0.).to_element();
+        let names: Vec<&str> = element.children.iter().map(|c| c.local_name()).collect();
+        assert_eq!(names, ["longitude", "latitude", "range"]);
+    }
+
+    #[test]
+    fn test_read_invalid_views() {
+        let camera =
+            |child: Element| Camera::<f64>::from_element(&Element::new("Camera").with_child(child));
+        assert_eq!(camera(text_element("longitude", "west")), None);
+        assert_eq!(camera(text_element("altitudeMode", "floating")), None);
+        assert_eq!(
+            camera(Element::new("TimeStamp").with_child(text_element("when", "now"))),
+            None
+        );
+        let look_at = Element::new("LookAt").with_child(text_element("range", ""));
+        assert_eq!(LookAt::<f64>::from_element(&look_at), None);
+        assert_eq!(
+            AbstractView::<f64>::from_element(&Element::new("Region")),
+            None
+        );
+        assert_eq!(LookAt::<f64>::from_element(&Element::new("Camera")), None);
+
+        // Only the first time primitive is typed, the rest are kept as children
+        let stamp = Element::new("TimeStamp").with_child(text_element("when", "2020"));
+        let element = Element::new("Camera")
+            .with_child(stamp.clone())
+            .with_child(stamp.clone());
+        let camera = Camera::<f64>::from_element(&element).unwrap();
+        assert!(camera.time_primitive.is_some());
+        assert_eq!(camera.children, vec![stamp]);
+    }
+
+    #[test]
+    fn test_normalize_views() {
+        let mut camera: Camera = Camera::new(190., 95., 0.)
+            .with_heading(-90.)
+            .with_tilt(200.)
+            .with_roll(190.);
+        assert_eq!(camera.normalize(), 5);
+        assert_eq!(
+            (
+                camera.longitude,
+                camera.latitude,
+                camera.heading,
+                camera.tilt,
+                camera.roll
+            ),
+            (-170., 90., 270., 180., -170.)
+        );
+        assert_eq!(camera.normalize(), 0);
+
+        // The tilt of a look at is limited to 90 degrees
+        let mut view = AbstractView::LookAt(LookAt::new(0., 0., 10.).with_tilt(120.));
+        assert_eq!(view.normalize(), 1);
+        assert!(matches!(view, AbstractView::LookAt(l) if l.tilt == 90.));
+    }
+}
@@ -0,0 +1,2005 @@
+//! Module for validating parsed KML against constraints in the OGC KML 2.2 specification
+//!
+//! Constraints are based on the abstract test suite in the [KML 2.2 conformance test suite](https://docs.opengeospatial.org/ts/14-068r2/14-068r2.html)
+//! and only cover the elements currently modeled in [`types`](../types/index.html).
+//!
+//! # Example
+//!
+//! ```
+//! use kml::{Kml, validation::IssueKind};
+//!
+//! let kml_str = r#"
+//! <Placemark>
+//!   <Polygon>
+//!     <outerBoundaryIs>
+//!       <LinearRing>
+//!         <coordinates>-1,2,0 -1.5,3,0 -1.5,2,0 -1,1,0</coordinates>
+//!       </LinearRing>
+//!     </outerBoundaryIs>
+//!   </Polygon>
+//! </Placemark>"#;
+//! let kml: Kml = kml_str.parse().unwrap();
+//!
+//! let issues = kml.validate();
+//! assert_eq!(issues[0].path, "Placemark/Polygon/outerBoundaryIs/LinearRing");
+//! assert_eq!(issues[0].kind, IssueKind::UnclosedRing);
+//! ```
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use num_traits::NumCast;
+
+use crate::antimeridian;
+use crate::types::angle::{self, Angle};
+use crate::types::{
+    normalize_color, AbstractView, AltitudeMode, BalloonStyle, Camera, Coord, CoordType,
+    CustomElement, Element, ExtendedData, FeatureProps, Geometry, GroundOverlay, Icon, IconStyle,
+    Kml, KmlDateTime, LabelStyle, LatLonAltBox, LatLonBox, LineString, LineStyle, LinearRing,
+    ListStyle, Location, LookAt, Model, MultiGeometry, MultiTrack, NetworkLink, Orientation, Pair,
+    PhotoOverlay, Placemark, Point, PolyStyle, Polygon, Region, ScreenOverlay, Style, StyleMap,
+    TimePrimitive, Tour, TourPrimitive, Track, ViewVolume,
+};
+
+/// Severity of a [`ValidationIssue`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// Violates a requirement of the specification
+    Error,
+    /// Allowed by the specification, but likely to render incorrectly
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Error => "error",
+                Self::Warning => "warning",
+            }
+        )
+    }
+}
+
+/// Constraint violated by a KML element
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum IssueKind {
+    /// Required child element is missing or empty
+    MissingChild(String),
+    /// Value is outside of the range allowed for the field
+    OutOfRange { field: String, value: String },
+    /// First and last coordinates of a `kml:LinearRing` don't match
+    UnclosedRing,
+    /// `id` attribute is shared with an earlier element
+    DuplicateId(String),
+    /// `styleUrl` fragment doesn't match the id of any shared style in the document
+    DanglingStyleUrl(String),
+    /// `styleUrl` references a style outside of the document, so it can't be checked
+    ExternalStyleUrl(String),
+    /// Child element isn't allowed by the schema's content model for its parent
+    UnexpectedElement(String),
+    /// Child element is allowed by the schema, but not at its position in the parent
+    OutOfOrder(String),
+    /// `extrude` or `tessellate` is enabled, but has no effect with the geometry's `altitudeMode`
+    AltitudeModeConflict {
+        field: String,
+        altitude_mode: AltitudeMode,
+    },
+    /// Consecutive coordinates are more than 180 degrees of longitude apart, see
+    /// [`Kml::split_antimeridian`]
+    CrossesAntimeridian,
+    /// `kml:when`, `kml:begin`, or `kml:end` isn't a valid [`KmlDateTime`](../types/enum.KmlDateTime.html)
+    InvalidDateTime(String),
+    /// Geometry has fewer coordinates than the minimum for its type
+    TooFewCoordinates { min: usize, found: usize },
+    /// `href` isn't a valid relative reference or URI, like an invalid scheme or percent-encoding
+    MalformedHref(String),
+    /// `href` will likely break when the file is moved, like Windows paths or unencoded spaces
+    NonPortableHref(String),
+    /// Color isn't 8 hex digits in `aabbggrr` order, with the [`normalize_color`](../types/fn.normalize_color.html)
+    /// suggestion for common mistakes like `#rrggbb`
+    InvalidColor {
+        field: String,
+        value: String,
+        suggestion: Option<String>,
+    },
+    /// `schemaUrl` of a `kml:SchemaData` doesn't match the id of any `kml:Schema` in the document
+    UndeclaredSchema(String),
+    /// `kml:SimpleData` name doesn't match any `kml:SimpleField` of its schema
+    UnknownSimpleField(String),
+    /// `kml:SimpleData` value can't be parsed as the type of its `kml:SimpleField`
+    SimpleDataTypeMismatch {
+        name: String,
+        field_type: String,
+        value: String,
+    },
+    /// `gx:Track` doesn't have one of the field for each `kml:when`, like `gx:coord` or the values
+    /// of a `gx:SimpleArrayData`
+    TrackLengthMismatch {
+        field: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingChild(child) => write!(f, "missing required child {}", child),
+            Self::OutOfRange { field, value } => write!(f, "{} out of range: {}", field, value),
+            Self::UnclosedRing => write!(f, "first and last coordinates of ring don't match"),
+            Self::DuplicateId(id) => write!(f, "duplicate id: {}", id),
+            Self::DanglingStyleUrl(url) => write!(f, "styleUrl references missing style: {}", url),
+            Self::ExternalStyleUrl(url) => write!(f, "styleUrl is not a local reference: {}", url),
+            Self::UnexpectedElement(name) => write!(f, "unexpected element {}", name),
+            Self::OutOfOrder(name) => write!(f, "element {} is out of order", name),
+            Self::AltitudeModeConflict {
+                field,
+                altitude_mode,
+            } => write!(
+                f,
+                "{} has no effect with altitudeMode {}",
+                field, altitude_mode
+            ),
+            Self::CrossesAntimeridian => write!(f, "coordinates cross the antimeridian"),
+            Self::InvalidDateTime(value) => write!(f, "invalid dateTime: {}", value),
+            Self::TooFewCoordinates { min, found } => {
+                write!(f, "expected at least {} coordinates, found {}", min, found)
+            }
+            Self::MalformedHref(href) => write!(f, "malformed href: {}", href),
+            Self::NonPortableHref(href) => write!(f, "href is not portable: {}", href),
+            Self::TrackLengthMismatch {
+                field,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {} {} values for each when, found {}",
+                expected, field, found
+            ),
+            Self::InvalidColor {
+                field,
+                value,
+                suggestion,
+            } => {
+                write!(f, "{} is not an aabbggrr color: {}", field, value)?;
+                match suggestion {
+                    Some(suggestion) => write!(f, " (did you mean {}?)", suggestion),
+                    None => Ok(()),
+                }
+            }
+            Self::UndeclaredSchema(url) => {
+                write!(f, "schemaUrl references missing schema: {}", url)
+            }
+            Self::UnknownSimpleField(name) => write!(f, "schema has no SimpleField {}", name),
+            Self::SimpleDataTypeMismatch {
+                name,
+                field_type,
+                value,
+            } => write!(
+                f,
+                "SimpleData {} is not a valid {}: {}",
+                name, field_type, value
+            ),
+        }
+    }
+}
+
+/// Constraint violation found by [`validate`], located by the path of element names from the root
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationIssue {
+    pub path: String,
+    pub severity: Severity,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}: {}", self.severity, self.path, self.kind)
+    }
+}
+
+/// Checks a KML tree against the constraints of the specification, returning every violation found
+///
+/// Elements without a typed representation (i.e. [`Element`](../types/struct.Element.html)) are
+/// only checked for valid `kml:dateTimeType` values in time primitives, well-formed links, angles
+/// of views, `kml:SchemaData` matching its `kml:Schema`, and the values of feature fields like
+/// `kml:visibility` that couldn't be read into [`FeatureProps`](../types/struct.FeatureProps.html).
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, validation::validate};
+///
+/// let kml: Kml = "<Point><coordinates>1,1,1</coordinates></Point>".parse().unwrap();
+/// assert!(validate(&kml).is_empty());
+/// ```
+pub fn validate<T: CoordType>(kml: &Kml<T>) -> Vec<ValidationIssue> {
+    let mut validator = Validator::default();
+    validator.validate_kml(kml);
+    validator.resolve_style_urls();
+    validator.resolve_schema_data();
+    validator.issues
+}
+
+// Checks the value against the `kml:SimpleField` type, which can be any of the XML Schema types
+// listed in the specification. Empty values and unknown types aren't checked.
+fn simple_data_matches(field_type: &str, value: &str) -> bool {
+    if value.is_empty() {
+        return true;
+    }
+    match field_type.trim_start_matches("xsd:") {
+        "int" => value.parse::<i32>().is_ok(),
+        "uint" => value.parse::<u32>().is_ok(),
+        "short" => value.parse::<i16>().is_ok(),
+        "ushort" => value.parse::<u16>().is_ok(),
+        "float" => value.parse::<f32>().is_ok(),
+        "double" => value.parse::<f64>().is_ok(),
+        "bool" => matches!(value, "0" | "1" | "true" | "false"),
+        _ => true,
+    }
+}
+
+/// Checks only that each `styleUrl`, including those in `kml:Pair` elements of a `kml:StyleMap`,
+/// references a shared style in the same document
+///
+/// References to styles in other files (i.e. `other.kml#style` or `http://` URLs) are reported as
+/// warnings since they can't be resolved.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, validation::{check_style_urls, IssueKind}};
+///
+/// let kml_str = r#"
+/// <Document>
+///   <Style id="shared"></Style>
+///   <Placemark><styleUrl>#shared</styleUrl></Placemark>
+///   <Placemark><styleUrl>#missing</styleUrl></Placemark>
+/// </Document>"#;
+/// let kml: Kml = kml_str.parse().unwrap();
+///
+/// let issues = check_style_urls(&kml);
+/// assert_eq!(issues.len(), 1);
+/// assert_eq!(issues[0].path, "Document/Placemark[1]");
+/// assert_eq!(issues[0].kind, IssueKind::DanglingStyleUrl("#missing".to_string()));
+/// ```
+pub fn check_style_urls<T: CoordType>(kml: &Kml<T>) -> Vec<ValidationIssue> {
+    let mut validator = Validator::default();
+    validator.validate_kml(kml);
+    validator.issues.clear();
+    validator.resolve_style_urls();
+    validator.issues
+}
+
+/// Renames every `id` that duplicates an earlier one in the tree by suffixing it with the lowest
+/// number that makes it unique, returning the original and new id for each renamed element
+///
+/// The first element with an id keeps it, so existing `styleUrl` references continue to resolve
+/// to the same element as viewers that use the first match.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, validation::resolve_duplicate_ids};
+///
+/// let mut kml: Kml = r#"
+/// <Document>
+///   <Placemark id="a"></Placemark>
+///   <Placemark id="a"></Placemark>
+/// </Document>"#.parse().unwrap();
+///
+/// assert_eq!(resolve_duplicate_ids(&mut kml), vec![("a".to_string(), "a_2".to_string())]);
+/// assert!(kml.validate().is_empty());
+/// ```
+pub fn resolve_duplicate_ids<T: CoordType>(kml: &mut Kml<T>) -> Vec<(String, String)> {
+    let mut existing = HashSet::new();
+    for_each_id(kml, &mut |id| {
+        existing.insert(id.clone());
+    });
+
+    let mut seen = HashSet::new();
+    let mut renamed = Vec::new();
+    for_each_id(kml, &mut |id| {
+        if id.is_empty() || seen.insert(id.clone()) {
+            return;
+        }
+        let unique = (2..)
+            .map(|n| format!("{}_{}", id, n))
+            .find(|candidate| !existing.contains(candidate) && !seen.contains(candidate))
+            .unwrap();
+        seen.insert(unique.clone());
+        renamed.push((id.clone(), unique.clone()));
+        *id = unique;
+    });
+    renamed
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Checks the KML tree against the constraints of the specification, see [`validate`](validation/fn.validate.html)
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        validate(self)
+    }
+
+    /// Suffixes duplicate ids to make them unique, see [`resolve_duplicate_ids`](validation/fn.resolve_duplicate_ids.html)
+    pub fn resolve_duplicate_ids(&mut self) -> Vec<(String, String)> {
+        resolve_duplicate_ids(self)
+    }
+}
+
+// Visits each id checked by the validator in document order
+fn for_each_id<T: CoordType>(kml: &mut Kml<T>, f: &mut dyn FnMut(&mut String)) {
+    fn attr_id(attrs: &mut HashMap<String, String>, f: &mut dyn FnMut(&mut String)) {
+        if let Some(id) = attrs.get_mut("id") {
+            f(id);
+        }
+    }
+
+    fn time_primitive_ids(
+        time_primitive: Option<&mut TimePrimitive>,
+        f: &mut dyn FnMut(&mut String),
+    ) {
+        match time_primitive {
+            Some(TimePrimitive::TimeStamp(t)) => attr_id(&mut t.attrs, f),
+            Some(TimePrimitive::TimeSpan(t)) => attr_id(&mut t.attrs, f),
+            None => {}
+        }
+    }
+
+    fn camera_ids<T: CoordType>(camera: &mut Camera<T>, f: &mut dyn FnMut(&mut String)) {
+        attr_id(&mut camera.attrs, f);
+        time_primitive_ids(camera.time_primitive.as_mut(), f);
+    }
+
+    fn look_at_ids<T: CoordType>(look_at: &mut LookAt<T>, f: &mut dyn FnMut(&mut String)) {
+        attr_id(&mut look_at.attrs, f);
+        time_primitive_ids(look_at.time_primitive.as_mut(), f);
+    }
+
+    fn view_ids<T: CoordType>(view: Option<&mut AbstractView<T>>, f: &mut dyn FnMut(&mut String)) {
+        match view {
+            Some(AbstractView::Camera(c)) => camera_ids(c, f),
+            Some(AbstractView::LookAt(l)) => look_at_ids(l, f),
+            None => {}
+        }
+    }
+
+    fn polygon_ids<T: CoordType>(polygon: &mut Polygon<T>, f: &mut dyn FnMut(&mut String)) {
+        attr_id(&mut polygon.attrs, f);
+        attr_id(&mut polygon.outer.attrs, f);
+        for ring in polygon.inner.iter_mut() {
+            attr_id(&mut ring.attrs, f);
+        }
+    }
+
+    fn multi_geometry_ids<T: CoordType>(
+        multi_geometry: &mut MultiGeometry<T>,
+        f: &mut dyn FnMut(&mut String),
+    ) {
+        attr_id(&mut multi_geometry.attrs, f);
+        for g in multi_geometry.geometries.iter_mut() {
+            geometry_ids(g, f);
+        }
+    }
+
+    fn multi_track_ids<T: CoordType>(
+        multi_track: &mut MultiTrack<T>,
+        f: &mut dyn FnMut(&mut String),
+    ) {
+        attr_id(&mut multi_track.attrs, f);
+        for track in multi_track.tracks.iter_mut() {
+            attr_id(&mut track.attrs, f);
+        }
+    }
+
+    fn model_ids<T: CoordType>(model: &mut Model<T>, f: &mut dyn FnMut(&mut String)) {
+        attr_id(&mut model.attrs, f);
+        if let Some(location) = model.location.as_mut() {
+            attr_id(&mut location.attrs, f);
+        }
+        if let Some(orientation) = model.orientation.as_mut() {
+            attr_id(&mut orientation.attrs, f);
+        }
+        if let Some(scale) = model.scale.as_mut() {
+            attr_id(&mut scale.attrs, f);
+        }
+        if let Some(link) = model.link.as_mut() {
+            attr_id(&mut link.attrs, f);
+        }
+    }
+
+    fn geometry_ids<T: CoordType>(geometry: &mut Geometry<T>, f: &mut dyn FnMut(&mut String)) {
+        match geometry {
+            Geometry::Point(p) => attr_id(&mut p.attrs, f),
+            Geometry::LineString(l) => attr_id(&mut l.attrs, f),
+            Geometry::LinearRing(l) => attr_id(&mut l.attrs, f),
+            Geometry::Polygon(p) => polygon_ids(p, f),
+            Geometry::MultiGeometry(g) => multi_geometry_ids(g, f),
+            Geometry::Track(t) => attr_id(&mut t.attrs, f),
+            Geometry::MultiTrack(t) => multi_track_ids(t, f),
+            Geometry::Model(m) => model_ids(m, f),
+            Geometry::Element(_) => {}
+        }
+    }
+
+    fn region_ids<T: CoordType>(region: &mut Region<T>, f: &mut dyn FnMut(&mut String)) {
+        attr_id(&mut region.attrs, f);
+        attr_id(&mut region.lat_lon_alt_box.attrs, f);
+        if let Some(lod) = region.lod.as_mut() {
+            attr_id(&mut lod.attrs, f);
+        }
+    }
+
+    fn network_link_ids<T: CoordType>(
+        network_link: &mut NetworkLink<T>,
+        f: &mut dyn FnMut(&mut String),
+    ) {
+        attr_id(&mut network_link.attrs, f);
+        view_ids(network_link.view.as_mut(), f);
+        if let Some(region) = network_link.region.as_mut() {
+            region_ids(region, f);
+        }
+        if let Some(link) = network_link.link.as_mut() {
+            attr_id(&mut link.attrs, f);
+        }
+    }
+
+    fn ground_overlay_ids<T: CoordType>(
+        overlay: &mut GroundOverlay<T>,
+        f: &mut dyn FnMut(&mut String),
+    ) {
+        attr_id(&mut overlay.attrs, f);
+        if let Some(lat_lon_box) = overlay.lat_lon_box.as_mut() {
+            attr_id(&mut lat_lon_box.attrs, f);
+        }
+    }
+
+    fn photo_overlay_ids<T: CoordType>(
+        overlay: &mut PhotoOverlay<T>,
+        f: &mut dyn FnMut(&mut String),
+    ) {
+        attr_id(&mut overlay.attrs, f);
+        if let Some(view_volume) = overlay.view_volume.as_mut() {
+            attr_id(&mut view_volume.attrs, f);
+        }
+        if let Some(image_pyramid) = overlay.image_pyramid.as_mut() {
+            attr_id(&mut image_pyramid.attrs, f);
+        }
+        if let Some(point) = overlay.point.as_mut() {
+            attr_id(&mut point.attrs, f);
+        }
+    }
+
+    fn style_ids(style: &mut Style, f: &mut dyn FnMut(&mut String)) {
+        f(&mut style.id);
+        for id in vec![
+            style.line.as_mut().map(|s| &mut s.id),
+            style.label.as_mut().map(|s| &mut s.id),
+            style.icon.as_mut().map(|s| &mut s.id),
+            style.poly.as_mut().map(|s| &mut s.id),
+            style.balloon.as_mut().map(|s| &mut s.id),
+            style.list.as_mut().map(|s| &mut s.id),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            f(id);
+        }
+    }
+
+    match kml {
+        Kml::KmlDocument(d) => {
+            for e in d.elements.iter_mut() {
+                for_each_id(e, f);
+            }
+        }
+        Kml::Scale(s) => attr_id(&mut s.attrs, f),
+        Kml::Orientation(o) => attr_id(&mut o.attrs, f),
+        Kml::Point(p) => attr_id(&mut p.attrs, f),
+        Kml::Location(l) => attr_id(&mut l.attrs, f),
+        Kml::Model(m) => model_ids(m, f),
+        Kml::LineString(l) => attr_id(&mut l.attrs, f),
+        Kml::LinearRing(l) => attr_id(&mut l.attrs, f),
+        Kml::Polygon(p) => polygon_ids(p, f),
+        Kml::MultiGeometry(g) => multi_geometry_ids(g, f),
+        Kml::Track(t) => attr_id(&mut t.attrs, f),
+        Kml::MultiTrack(t) => multi_track_ids(t, f),
+        Kml::Placemark(p) => {
+            attr_id(&mut p.attrs, f);
+            view_ids(p.view.as_mut(), f);
+            time_primitive_ids(p.time_primitive.as_mut(), f);
+            if let Some(s) = p.style.as_mut() {
+                style_ids(s, f);
+            }
+            if let Some(g) = p.geometry.as_mut() {
+                geometry_ids(g, f);
+            }
+        }
+        Kml::Tour(t) => attr_id(&mut t.attrs, f),
+        Kml::TimeStamp(t) => attr_id(&mut t.attrs, f),
+        Kml::TimeSpan(t) => attr_id(&mut t.attrs, f),
+        Kml::NetworkLink(n) => network_link_ids(n, f),
+        Kml::Region(r) => region_ids(r, f),
+        Kml::Lod(l) => attr_id(&mut l.attrs, f),
+        Kml::LatLonAltBox(b) => attr_id(&mut b.attrs, f),
+        Kml::GroundOverlay(o) => ground_overlay_ids(o, f),
+        Kml::LatLonBox(b) => attr_id(&mut b.attrs, f),
+        Kml::ScreenOverlay(o) => attr_id(&mut o.attrs, f),
+        Kml::PhotoOverlay(o) => photo_overlay_ids(o, f),
+        Kml::ViewVolume(v) => attr_id(&mut v.attrs, f),
+        Kml::ImagePyramid(i) => attr_id(&mut i.attrs, f),
+        Kml::Camera(c) => camera_ids(c, f),
+        Kml::LookAt(l) => look_at_ids(l, f),
+        Kml::Document {
+            attrs, elements, ..
+        }
+        | Kml::Folder {
+            attrs, elements, ..
+        } => {
+            attr_id(attrs, f);
+            for e in elements.iter_mut() {
+                for_each_id(e, f);
+            }
+        }
+        Kml::Style(s) => style_ids(s, f),
+        Kml::StyleMap(s) => {
+            f(&mut s.id);
+            for pair in s.pairs.iter_mut() {
+                attr_id(&mut pair.attrs, f);
+            }
+        }
+        Kml::Pair(p) => attr_id(&mut p.attrs, f),
+        Kml::BalloonStyle(s) => f(&mut s.id),
+        Kml::IconStyle(s) => f(&mut s.id),
+        Kml::LabelStyle(s) => f(&mut s.id),
+        Kml::LineStyle(s) => f(&mut s.id),
+        Kml::PolyStyle(s) => f(&mut s.id),
+        Kml::ListStyle(s) => f(&mut s.id),
+        Kml::Icon(_) | Kml::Schema(_) | Kml::Element(_) | Kml::Custom(_) => {}
+    }
+}
+
+#[derive(Default)]
+struct Validator {
+    path: Vec<String>,
+    index: Option<usize>,
+    ids: HashSet<String>,
+    style_ids: HashSet<String>,
+    // Path of the referencing element and the styleUrl, resolved once all styles are collected
+    style_urls: Vec<(String, String)>,
+    // Types of each `kml:SimpleField` by name for each `kml:Schema` id
+    schemas: HashMap<String, HashMap<String, String>>,
+    // Path of each `kml:SchemaData`, resolved once all schemas are collected
+    schema_data: Vec<(String, Element)>,
+    issues: Vec<ValidationIssue>,
+}
+
+impl Validator {
+    fn validate_kml<T: CoordType>(&mut self, k: &Kml<T>) {
+        match k {
+            Kml::KmlDocument(d) => self.scoped("kml", |v| v.validate_elements(&d.elements)),
+            Kml::Scale(_) => {}
+            Kml::Orientation(o) => self.validate_orientation(o),
+            Kml::Point(p) => self.validate_point(p),
+            Kml::Location(l) => self.validate_location(l),
+            Kml::Model(m) => self.validate_model(m),
+            Kml::LineString(l) => self.validate_line_string(l),
+            Kml::LinearRing(l) => self.validate_linear_ring(l),
+            Kml::Polygon(p) => self.validate_polygon(p),
+            Kml::MultiGeometry(g) => self.validate_multi_geometry(g),
+            Kml::Track(t) => self.validate_track(t),
+            Kml::MultiTrack(t) => self.validate_multi_track(t),
+            Kml::Placemark(p) => self.validate_placemark(p),
+            Kml::Tour(t) => self.validate_tour(t),
+            Kml::TimeStamp(t) => self.scoped("TimeStamp", |v| v.check_id(t.attrs.get("id"))),
+            Kml::TimeSpan(t) => self.scoped("TimeSpan", |v| v.check_id(t.attrs.get("id"))),
+            Kml::NetworkLink(n) => self.validate_network_link(n),
+            Kml::Region(r) => self.validate_region(r),
+            Kml::Lod(l) => self.scoped("Lod", |v| v.check_id(l.attrs.get("id"))),
+            Kml::LatLonAltBox(b) => self.validate_lat_lon_alt_box(b),
+            Kml::GroundOverlay(o) => self.validate_ground_overlay(o),
+            Kml::LatLonBox(b) => self.validate_lat_lon_box(b),
+            Kml::ScreenOverlay(o) => self.validate_screen_overlay(o),
+            Kml::PhotoOverlay(o) => self.validate_photo_overlay(o),
+            Kml::ViewVolume(v) => self.validate_view_volume(v),
+            Kml::ImagePyramid(i) => self.scoped("ImagePyramid", |v| v.check_id(i.attrs.get("id"))),
+            Kml::Schema(s) => self.validate_element(&s.to_element()),
+            Kml::Camera(c) => self.validate_camera(c),
+            Kml::LookAt(l) => self.validate_look_at(l),
+            Kml::Document {
+                attrs,
+                props,
+                elements,
+            } => self.scoped("Document", |v| v.validate_container(attrs, props, elements)),
+            Kml::Folder {
+                attrs,
+                props,
+                elements,
+            } => self.scoped("Folder", |v| v.validate_container(attrs, props, elements)),
+            Kml::Style(s) => self.validate_style(s),
+            Kml::StyleMap(s) => self.validate_style_map(s),
+            Kml::Pair(p) => self.validate_pair(p),
+            Kml::BalloonStyle(s) => self.validate_balloon_style(s),
+            Kml::IconStyle(s) => self.validate_icon_style(s),
+            Kml::Icon(i) => self.scoped("Icon", |v| v.check_href(&i.href)),
+            Kml::LabelStyle(s) => self.validate_label_style(s),
+            Kml::LineStyle(s) => self.validate_line_style(s),
+            Kml::PolyStyle(s) => self.validate_poly_style(s),
+            Kml::ListStyle(s) => self.validate_list_style(s),
+            Kml::Element(e) if e.local_name() == "styleUrl" => {
+                self.add_style_url(e.content.as_deref().unwrap_or_default())
+            }
+            Kml::Element(e) => self.validate_element(e),
+            Kml::Custom(c) => self.validate_element(c.element()),
+        }
+    }
+
+    fn validate_elements<T: CoordType>(&mut self, elements: &[Kml<T>]) {
+        // Sibling containers and placemarks are indexed so paths stay unambiguous
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for e in elements {
+            let name = match e {
+                Kml::Document { .. } => "Document",
+                Kml::Folder { .. } => "Folder",
+                Kml::Placemark(_) => "Placemark",
+                _ => {
+                    self.validate_kml(e);
+                    continue;
+                }
+            };
+            let count = counts.entry(name).or_insert(0);
+            self.index = Some(*count);
+            *count += 1;
+            self.validate_kml(e);
+        }
+    }
+
+    fn validate_container<T: CoordType>(
+        &mut self,
+        attrs: &HashMap<String, String>,
+        props: &FeatureProps,
+        elements: &[Kml<T>],
+    ) {
+        self.check_id(attrs.get("id"));
+        if let Some(style_url) = &props.style_url {
+            self.add_style_url(style_url);
+        }
+        self.validate_elements(elements);
+    }
+
+    fn validate_placemark<T: CoordType>(&mut self, placemark: &Placemark<T>) {
+        self.scoped("Placemark", |v| {
+            v.check_id(placemark.attrs.get("id"));
+            if let Some(view) = &placemark.view {
+                v.validate_view(view);
+            }
+            v.validate_time_primitive(placemark.time_primitive.as_ref());
+            if let Some(style_url) = &placemark.style_url {
+                v.add_style_url(style_url);
+            }
+            if let Some(style) = &placemark.style {
+                v.validate_style(style);
+            }
+            for child in placemark.children.iter() {
+                match child.local_name() {
+                    "styleUrl" => v.add_style_url(child.content.as_deref().unwrap_or_default()),
+                    _ => v.validate_element(child),
+                }
+            }
+            v.validate_extended_data(placemark.extended_data.as_ref());
+            if let Some(geometry) = &placemark.geometry {
+                v.validate_geometry(geometry);
+            }
+        })
+    }
+
+    fn validate_time_primitive(&mut self, time_primitive: Option<&TimePrimitive>) {
+        match time_primitive {
+            Some(TimePrimitive::TimeStamp(t)) => {
+                self.scoped("TimeStamp", |v| v.check_id(t.attrs.get("id")))
+            }
+            Some(TimePrimitive::TimeSpan(t)) => {
+                self.scoped("TimeSpan", |v| v.check_id(t.attrs.get("id")))
+            }
+            None => {}
+        }
+    }
+
+    fn validate_view<T: CoordType>(&mut self, view: &AbstractView<T>) {
+        match view {
+            AbstractView::Camera(c) => self.validate_camera(c),
+            AbstractView::LookAt(l) => self.validate_look_at(l),
+        }
+    }
+
+    fn validate_camera<T: CoordType>(&mut self, camera: &Camera<T>) {
+        self.scoped("Camera", |v| {
+            v.check_id(camera.attrs.get("id"));
+            v.validate_time_primitive(camera.time_primitive.as_ref());
+            for child in camera.children.iter() {
+                v.validate_element(child);
+            }
+            v.check_angle("longitude", Angle::Longitude, camera.longitude);
+            v.check_angle("latitude", Angle::Latitude, camera.latitude);
+            v.check_angle("heading", Angle::Heading, camera.heading);
+            v.check_angle("tilt", Angle::Tilt(angle::CAMERA_TILT), camera.tilt);
+            v.check_angle("roll", Angle::Roll, camera.roll);
+        })
+    }
+
+    fn validate_look_at<T: CoordType>(&mut self, look_at: &LookAt<T>) {
+        self.scoped("LookAt", |v| {
+            v.check_id(look_at.attrs.get("id"));
+            v.validate_time_primitive(look_at.time_primitive.as_ref());
+            for child in look_at.children.iter() {
+                v.validate_element(child);
+            }
+            v.check_angle("longitude", Angle::Longitude, look_at.longitude);
+            v.check_angle("latitude", Angle::Latitude, look_at.latitude);
+            v.check_angle("heading", Angle::Heading, look_at.heading);
+            v.check_angle("tilt", Angle::Tilt(angle::LOOK_AT_TILT), look_at.tilt);
+        })
+    }
+
+    // Extended data is checked as an element, which collects its `kml:SchemaData` for checking
+    // against the document's schemas
+    fn validate_extended_data(&mut self, extended_data: Option<&ExtendedData>) {
+        if let Some(extended_data) = extended_data {
+            self.validate_element(&extended_data.to_element());
+        }
+    }
+
+    fn validate_geometry<T: CoordType>(&mut self, geometry: &Geometry<T>) {
+        match geometry {
+            Geometry::Point(p) => self.validate_point(p),
+            Geometry::LineString(l) => self.validate_line_string(l),
+            Geometry::LinearRing(l) => self.validate_linear_ring(l),
+            Geometry::Polygon(p) => self.validate_polygon(p),
+            Geometry::MultiGeometry(g) => self.validate_multi_geometry(g),
+            Geometry::Track(t) => self.validate_track(t),
+            Geometry::MultiTrack(t) => self.validate_multi_track(t),
+            Geometry::Model(m) => self.validate_model(m),
+            Geometry::Element(e) => self.validate_element(e),
+        }
+    }
+
+    fn validate_track<T: CoordType>(&mut self, track: &Track<T>) {
+        self.scoped("Track", |v| {
+            v.check_id(track.attrs.get("id"));
+            v.check_coords(&track.coords);
+            let expected = track.when.len();
+            let mut lengths = vec![("coord", track.coords.len())];
+            if !track.angles.is_empty() {
+                lengths.push(("angles", track.angles.len()));
+            }
+            lengths.extend(
+                track
+                    .arrays
+                    .iter()
+                    .map(|a| (a.name.as_str(), a.values.len())),
+            );
+            for (field, found) in lengths.into_iter().filter(|(_, found)| *found != expected) {
+                v.push(
+                    Severity::Error,
+                    IssueKind::TrackLengthMismatch {
+                        field: field.to_string(),
+                        expected,
+                        found,
+                    },
+                );
+            }
+        })
+    }
+
+    fn validate_multi_track<T: CoordType>(&mut self, multi_track: &MultiTrack<T>) {
+        self.scoped("MultiTrack", |v| {
+            v.check_id(multi_track.attrs.get("id"));
+            for track in multi_track.tracks.iter() {
+                v.validate_track(track);
+            }
+        })
+    }
+
+    fn validate_tour(&mut self, tour: &Tour) {
+        self.scoped("Tour", |v| {
+            v.check_id(tour.attrs.get("id"));
+            for primitive in tour.playlist.iter() {
+                let element = match primitive {
+                    TourPrimitive::FlyTo(f) => f.view.as_ref(),
+                    TourPrimitive::AnimatedUpdate(u) => u.update.as_ref(),
+                    _ => None,
+                };
+                if let Some(element) = element {
+                    v.validate_element(element);
+                }
+            }
+        })
+    }
+
+    fn validate_network_link<T: CoordType>(&mut self, network_link: &NetworkLink<T>) {
+        self.scoped("NetworkLink", |v| {
+            v.check_id(network_link.attrs.get("id"));
+            for child in network_link.children.iter() {
+                v.validate_element(child);
+            }
+            if let Some(view) = &network_link.view {
+                v.validate_view(view);
+            }
+            if let Some(region) = &network_link.region {
+                v.validate_region(region);
+            }
+            v.validate_extended_data(network_link.extended_data.as_ref());
+            if let Some(link) = &network_link.link {
+                v.scoped("Link", |v| {
+                    v.check_id(link.attrs.get("id"));
+                    v.check_href(&link.href);
+                });
+            }
+        })
+    }
+
+    fn validate_region<T: CoordType>(&mut self, region: &Region<T>) {
+        self.scoped("Region", |v| {
+            v.check_id(region.attrs.get("id"));
+            v.validate_lat_lon_alt_box(&region.lat_lon_alt_box);
+            if let Some(lod) = &region.lod {
+                v.scoped("Lod", |v| v.check_id(lod.attrs.get("id")));
+            }
+        })
+    }
+
+    fn validate_lat_lon_alt_box<T: CoordType>(&mut self, lat_lon_alt_box: &LatLonAltBox<T>) {
+        self.scoped("LatLonAltBox", |v| {
+            v.check_id(lat_lon_alt_box.attrs.get("id"));
+            v.check_angle("north", Angle::Latitude, lat_lon_alt_box.north);
+            v.check_angle("south", Angle::Latitude, lat_lon_alt_box.south);
+            v.check_angle("east", Angle::Longitude, lat_lon_alt_box.east);
+            v.check_angle("west", Angle::Longitude, lat_lon_alt_box.west);
+        })
+    }
+
+    // Checks the elements shared by every kind of overlay
+    fn validate_overlay(
+        &mut self,
+        icon: Option<&Icon>,
+        extended_data: Option<&ExtendedData>,
+        children: &[Element],
+    ) {
+        for child in children.iter() {
+            self.validate_element(child);
+        }
+        self.validate_extended_data(extended_data);
+        if let Some(icon) = icon {
+            self.scoped("Icon", |v| v.check_href(&icon.href));
+        }
+    }
+
+    fn validate_ground_overlay<T: CoordType>(&mut self, overlay: &GroundOverlay<T>) {
+        self.scoped("GroundOverlay", |v| {
+            v.check_id(overlay.attrs.get("id"));
+            v.validate_overlay(
+                overlay.icon.as_ref(),
+                overlay.extended_data.as_ref(),
+                &overlay.children,
+            );
+            if let Some(lat_lon_box) = &overlay.lat_lon_box {
+                v.validate_lat_lon_box(lat_lon_box);
+            }
+        })
+    }
+
+    fn validate_lat_lon_box<T: CoordType>(&mut self, lat_lon_box: &LatLonBox<T>) {
+        self.scoped("LatLonBox", |v| {
+            v.check_id(lat_lon_box.attrs.get("id"));
+            v.check_angle("north", Angle::Latitude, lat_lon_box.north);
+            v.check_angle("south", Angle::Latitude, lat_lon_box.south);
+            v.check_angle("east", Angle::Longitude, lat_lon_box.east);
+            v.check_angle("west", Angle::Longitude, lat_lon_box.west);
+            v.check_range("rotation", lat_lon_box.rotation, -180., 180.);
+        })
+    }
+
+    fn validate_screen_overlay(&mut self, overlay: &ScreenOverlay) {
+        self.scoped("ScreenOverlay", |v| {
+            v.check_id(overlay.attrs.get("id"));
+            v.validate_overlay(
+                Some(&overlay.icon),
+                overlay.extended_data.as_ref(),
+                &overlay.children,
+            );
+            v.check_range("rotation", overlay.rotation, -180., 180.);
+        })
+    }
+
+    fn validate_photo_overlay<T: CoordType>(&mut self, overlay: &PhotoOverlay<T>) {
+        self.scoped("PhotoOverlay", |v| {
+            v.check_id(overlay.attrs.get("id"));
+            v.validate_overlay(
+                overlay.icon.as_ref(),
+                overlay.extended_data.as_ref(),
+                &overlay.children,
+            );
+            v.check_range("rotation", overlay.rotation, -180., 180.);
+            if let Some(view_volume) = &overlay.view_volume {
+                v.validate_view_volume(view_volume);
+            }
+            if let Some(image_pyramid) = &overlay.image_pyramid {
+                v.scoped("ImagePyramid", |v| {
+                    v.check_id(image_pyramid.attrs.get("id"))
+                });
+            }
+            if let Some(point) = &overlay.point {
+                v.validate_point(point);
+            }
+        })
+    }
+
+    fn validate_view_volume(&mut self, view_volume: &ViewVolume) {
+        self.scoped("ViewVolume", |v| {
+            v.check_id(view_volume.attrs.get("id"));
+            v.check_range("leftFov", view_volume.left_fov, -180., 180.);
+            v.check_range("rightFov", view_volume.right_fov, -180., 180.);
+            v.check_range("bottomFov", view_volume.bottom_fov, -90., 90.);
+            v.check_range("topFov", view_volume.top_fov, -90., 90.);
+        })
+    }
+
+    fn validate_point<T: CoordType>(&mut self, point: &Point<T>) {
+        self.scoped("Point", |v| {
+            v.check_id(point.attrs.get("id"));
+            v.check_coords(std::slice::from_ref(&point.coord));
+            v.check_altitude_mode(point.altitude_mode, point.extrude, false);
+        })
+    }
+
+    fn validate_orientation<T: CoordType>(&mut self, orientation: &Orientation<T>) {
+        self.scoped("Orientation", |v| {
+            v.check_id(orientation.attrs.get("id"));
+            v.check_angle("heading", Angle::Heading, orientation.heading);
+            v.check_angle("tilt", Angle::Tilt(angle::CAMERA_TILT), orientation.tilt);
+            v.check_angle("roll", Angle::Roll, orientation.roll);
+        })
+    }
+
+    fn validate_location<T: CoordType>(&mut self, location: &Location<T>) {
+        self.scoped("Location", |v| {
+            v.check_id(location.attrs.get("id"));
+            v.check_range("longitude", location.longitude, -180., 180.);
+            v.check_range("latitude", location.latitude, -90., 90.);
+        })
+    }
+
+    fn validate_model<T: CoordType>(&mut self, model: &Model<T>) {
+        self.scoped("Model", |v| {
+            v.check_id(model.attrs.get("id"));
+            if let Some(location) = &model.location {
+                v.validate_location(location);
+            }
+            if let Some(orientation) = &model.orientation {
+                v.validate_orientation(orientation);
+            }
+            if let Some(scale) = &model.scale {
+                v.scoped("Scale", |v| v.check_id(scale.attrs.get("id")));
+            }
+            if let Some(link) = &model.link {
+                v.scoped("Link", |v| {
+                    v.check_id(link.attrs.get("id"));
+                    v.check_href(&link.href);
+                });
+            }
+            if let Some(resource_map) = &model.resource_map {
+                v.validate_element(&resource_map.to_element());
+            }
+            for child in model.children.iter() {
+                v.validate_element(child);
+            }
+        })
+    }
+
+    fn validate_line_string<T: CoordType>(&mut self, line_string: &LineString<T>) {
+        self.scoped("LineString", |v| {
+            v.check_id(line_string.attrs.get("id"));
+            v.check_coords(&line_string.coords);
+            v.check_coord_count(&line_string.coords, 2);
+            v.check_altitude_mode(
+                line_string.altitude_mode,
+                line_string.extrude,
+                line_string.tessellate,
+            );
+        })
+    }
+
+    fn validate_linear_ring<T: CoordType>(&mut self, linear_ring: &LinearRing<T>) {
+        self.scoped("LinearRing", |v| {
+            v.check_id(linear_ring.attrs.get("id"));
+            v.check_coords(&linear_ring.coords);
+            v.check_coord_count(&linear_ring.coords, 4);
+            v.check_altitude_mode(
+                linear_ring.altitude_mode,
+                linear_ring.extrude,
+                linear_ring.tessellate,
+            );
+            // ATC-104: first and last coordinates must be the same
+            if !linear_ring.is_closed() {
+                v.push(Severity::Error, IssueKind::UnclosedRing);
+            }
+        })
+    }
+
+    fn validate_polygon<T: CoordType>(&mut self, polygon: &Polygon<T>) {
+        self.scoped("Polygon", |v| {
+            v.check_id(polygon.attrs.get("id"));
+            v.check_altitude_mode(polygon.altitude_mode, polygon.extrude, polygon.tessellate);
+            v.scoped("outerBoundaryIs", |v| {
+                if polygon.outer.coords.is_empty() {
+                    v.push(
+                        Severity::Error,
+                        IssueKind::MissingChild("LinearRing".to_string()),
+                    );
+                } else {
+                    v.validate_linear_ring(&polygon.outer);
+                }
+            });
+            if !polygon.inner.is_empty() {
+                v.scoped("innerBoundaryIs", |v| {
+                    for (i, ring) in polygon.inner.iter().enumerate() {
+                        v.index = Some(i);
+                        v.validate_linear_ring(ring);
+                    }
+                });
+            }
+        })
+    }
+
+    fn validate_multi_geometry<T: CoordType>(&mut self, multi_geometry: &MultiGeometry<T>) {
+        self.scoped("MultiGeometry", |v| {
+            v.check_id(multi_geometry.attrs.get("id"));
+            for g in multi_geometry.geometries.iter() {
+                v.validate_geometry(g);
+            }
+        })
+    }
+
+    // Elements without a typed representation are only checked for time primitives, including the
+    // `when` children of `gx:Track` in custom elements, links like `kml:Link` in a `kml:NetworkLink`, and the angles of
+    // views like `kml:Camera`
+    fn validate_element(&mut self, element: &Element) {
+        let time_fields: &[&str] = match element.local_name() {
+            "TimeStamp" | "Track" => &["when"],
+            "TimeSpan" => &["begin", "end"],
+            _ => &[],
+        };
+        let is_link = matches!(element.local_name(), "Icon" | "Link" | "Url");
+        match element.local_name() {
+            "Schema" => self.add_schema(element),
+            "SchemaData" => self.scoped(&element.name, |v| {
+                v.schema_data.push((v.path.join("/"), element.clone()))
+            }),
+            // Feature fields that couldn't be read into their typed fields
+            "visibility" | "open" => {
+                let value = element.content.as_deref().unwrap_or_default().trim();
+                if !matches!(value, "0" | "1" | "true" | "false") {
+                    self.push_out_of_range(&element.name, value);
+                }
+            }
+            "Snippet" | "snippet" => {
+                if let Some(max_lines) = element.attrs.get("maxLines") {
+                    if max_lines.trim().parse::<u32>().is_err() {
+                        self.scoped(&element.name, |v| {
+                            v.push_out_of_range("maxLines", max_lines)
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.scoped(&element.name, |v| {
+            for child in element.children.iter() {
+                if is_link && child.local_name() == "href" {
+                    v.check_href(child.content.as_deref().unwrap_or_default());
+                } else if matches!(child.local_name(), "color" | "bgColor" | "textColor") {
+                    v.check_color(&child.name, child.content.as_deref().unwrap_or_default());
+                } else if let Some(angle) =
+                    Angle::for_field(element.local_name(), child.local_name())
+                {
+                    let value = child.content.as_deref().unwrap_or_default().trim();
+                    match value.parse::<f64>() {
+                        Ok(value) => v.check_angle(&child.name, angle, value),
+                        Err(_) => v.push_out_of_range(&child.name, value),
+                    }
+                } else if time_fields.contains(&child.local_name()) {
+                    let value = child.content.as_deref().unwrap_or_default();
+                    if value.parse::<KmlDateTime>().is_err() {
+                        v.scoped(&child.name, |v| {
+                            v.push(
+                                Severity::Error,
+                                IssueKind::InvalidDateTime(value.to_string()),
+                            )
+                        });
+                    }
+                } else {
+                    v.validate_element(child);
+                }
+            }
+        })
+    }
+
+    fn validate_style(&mut self, style: &Style) {
+        self.style_ids.insert(style.id.clone());
+        self.scoped("Style", |v| {
+            v.check_id(Some(&style.id));
+            if let Some(line) = &style.line {
+                v.validate_line_style(line);
+            }
+            if let Some(label) = &style.label {
+                v.validate_label_style(label);
+            }
+            if let Some(icon) = &style.icon {
+                v.validate_icon_style(icon);
+            }
+            if let Some(poly) = &style.poly {
+                v.validate_poly_style(poly);
+            }
+            if let Some(balloon) = &style.balloon {
+                v.validate_balloon_style(balloon);
+            }
+            if let Some(list) = &style.list {
+                v.validate_list_style(list);
+            }
+        })
+    }
+
+    fn validate_line_style(&mut self, line: &LineStyle) {
+        self.scoped("LineStyle", |v| {
+            v.check_id(Some(&line.id));
+            if line.width < 0. {
+                v.push_out_of_range("width", line.width);
+            }
+        })
+    }
+
+    fn validate_label_style(&mut self, label: &LabelStyle) {
+        self.scoped("LabelStyle", |v| {
+            v.check_id(Some(&label.id));
+        })
+    }
+
+    fn validate_icon_style(&mut self, icon: &IconStyle) {
+        self.scoped("IconStyle", |v| {
+            v.check_id(Some(&icon.id));
+            v.check_angle("heading", Angle::Heading, icon.heading);
+            v.scoped("Icon", |v| v.check_href(&icon.icon.href));
+        })
+    }
+
+    fn validate_poly_style(&mut self, poly: &PolyStyle) {
+        self.scoped("PolyStyle", |v| {
+            v.check_id(Some(&poly.id));
+        })
+    }
+
+    fn validate_balloon_style(&mut self, balloon: &BalloonStyle) {
+        self.scoped("BalloonStyle", |v| {
+            v.check_id(Some(&balloon.id));
+        })
+    }
+
+    fn validate_list_style(&mut self, list: &ListStyle) {
+        self.scoped("ListStyle", |v| {
+            v.check_id(Some(&list.id));
+        })
+    }
+
+    fn validate_style_map(&mut self, style_map: &StyleMap) {
+        self.style_ids.insert(style_map.id.clone());
+        self.scoped("StyleMap", |v| {
+            v.check_id(Some(&style_map.id));
+            for pair in style_map.pairs.iter() {
+                v.validate_pair(pair);
+            }
+        })
+    }
+
+    fn validate_pair(&mut self, pair: &Pair) {
+        self.scoped("Pair", |v| {
+            v.check_id(pair.attrs.get("id"));
+            match pair.key.as_str() {
+                "normal" | "highlight" => {}
+                "" => v.push(Severity::Error, IssueKind::MissingChild("key".to_string())),
+                key => v.push_out_of_range("key", key),
+            }
+            if pair.style_url.is_empty() {
+                v.push(
+                    Severity::Error,
+                    IssueKind::MissingChild("styleUrl".to_string()),
+                );
+            } else {
+                v.add_style_url(&pair.style_url);
+            }
+        })
+    }
+
+    fn add_style_url(&mut self, style_url: &str) {
+        self.style_urls
+            .push((self.path.join("/"), style_url.trim().to_string()));
+    }
+
+    fn resolve_style_urls(&mut self) {
+        for (path, style_url) in std::mem::take(&mut self.style_urls) {
+            let kind = match style_url.strip_prefix('#') {
+                Some(id) if self.style_ids.contains(id) => continue,
+                Some(_) => IssueKind::DanglingStyleUrl(style_url),
+                None => IssueKind::ExternalStyleUrl(style_url),
+            };
+            let severity = match kind {
+                IssueKind::ExternalStyleUrl(_) => Severity::Warning,
+                _ => Severity::Error,
+            };
+            self.issues.push(ValidationIssue {
+                path,
+                severity,
+                kind,
+            });
+        }
+    }
+
+    fn add_schema(&mut self, schema: &Element) {
+        let id = match schema.attrs.get("id") {
+            Some(id) => id.clone(),
+            None => return,
+        };
+        let fields = schema
+            .children
+            .iter()
+            .filter(|c| c.local_name() == "SimpleField")
+            .filter_map(|c| {
+                let name = c.attrs.get("name")?.clone();
+                let field_type = c.attrs.get("type").cloned().unwrap_or_default();
+                Some((name, field_type))
+            })
+            .collect();
+        self.schemas.insert(id, fields);
+    }
+
+    fn resolve_schema_data(&mut self) {
+        for (path, schema_data) in std::mem::take(&mut self.schema_data) {
+            let schema_url = schema_data
+                .attrs
+                .get("schemaUrl")
+                .map(|u| u.trim())
+                .unwrap_or_default();
+            // Schemas in other files can't be checked
+            let id = match schema_url.strip_prefix('#') {
+                Some(id) => id,
+                None => continue,
+            };
+            let fields = match self.schemas.get(id) {
+                Some(fields) => fields,
+                None => {
+                    self.issues.push(ValidationIssue {
+                        path,
+                        severity: Severity::Error,
+                        kind: IssueKind::UndeclaredSchema(schema_url.to_string()),
+                    });
+                    continue;
+                }
+            };
+            for simple_data in schema_data
+                .children
+                .iter()
+                .filter(|c| c.local_name() == "SimpleData")
+            {
+                let name = simple_data
+                    .attrs
+                    .get("name")
+                    .map(String::as_str)
+                    .unwrap_or_default();
+                let value = simple_data.content.as_deref().unwrap_or_default().trim();
+                let kind = match fields.get(name) {
+                    None => IssueKind::UnknownSimpleField(name.to_string()),
+                    Some(field_type) if !simple_data_matches(field_type, value) => {
+                        IssueKind::SimpleDataTypeMismatch {
+                            name: name.to_string(),
+                            field_type: field_type.to_string(),
+                            value: value.to_string(),
+                        }
+                    }
+                    Some(_) => continue,
+                };
+                self.issues.push(ValidationIssue {
+                    path: path.clone(),
+                    severity: Severity::Error,
+                    kind,
+                });
+            }
+        }
+    }
+
+    // ATC-112 and ATC-113 only allow extrude without clampToGround and tessellate with it
+    fn check_altitude_mode(
+        &mut self,
+        altitude_mode: AltitudeMode,
+        extrude: bool,
+        tessellate: bool,
+    ) {
+        let clamped = altitude_mode.is_clamped();
+        let conflicts = [
+            ("extrude", extrude && clamped),
+            ("tessellate", tessellate && !clamped),
+        ];
+        for (field, _) in conflicts.iter().filter(|(_, conflict)| *conflict) {
+            self.push(
+                Severity::Warning,
+                IssueKind::AltitudeModeConflict {
+                    field: field.to_string(),
+                    altitude_mode,
+                },
+            );
+        }
+    }
+
+    fn check_coords<T: CoordType>(&mut self, coords: &[Coord<T>]) {
+        for coord in coords {
+            self.check_range("longitude", coord.x, -180., 180.);
+            self.check_range("latitude", coord.y, -90., 90.);
+        }
+        if antimeridian::crosses(coords) {
+            self.push(Severity::Warning, IssueKind::CrossesAntimeridian);
+        }
+    }
+
+    // hrefs are RFC 3986 URI references, either relative or with a valid scheme
+    fn check_href(&mut self, href: &str) {
+        let href = href.trim();
+        let is_drive_path = href.len() >= 2
+            && href.as_bytes()[0].is_ascii_alphabetic()
+            && href.as_bytes()[1] == b':'
+            && matches!(href.as_bytes().get(2), None | Some(b'\\') | Some(b'/'));
+        if href.contains('\\') || href.contains(' ') || is_drive_path {
+            self.push(
+                Severity::Warning,
+                IssueKind::NonPortableHref(href.to_string()),
+            );
+            return;
+        }
+
+        // A colon before any path, query, or fragment delimiter separates the scheme
+        let scheme = href
+            .split(['/', '?', '#'])
+            .next()
+            .and_then(|s| s.split_once(':'))
+            .map(|(scheme, _)| scheme);
+        let valid_scheme = scheme.map_or(true, |scheme| {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        });
+        let valid_chars = !href
+            .chars()
+            .any(|c| c.is_control() || "<>\"{}|^`".contains(c));
+        let valid_escapes = href.match_indices('%').all(|(i, _)| {
+            href.get(i + 1..i + 3)
+                .is_some_and(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))
+        });
+        if !(valid_scheme && valid_chars && valid_escapes) {
+            self.push(Severity::Error, IssueKind::MalformedHref(href.to_string()));
+        }
+    }
+
+    fn check_color(&mut self, field: &str, color: &str) {
+        let valid = color.len() == 8 && color.chars().all(|c| c.is_ascii_hexdigit());
+        if !valid {
+            self.push(
+                Severity::Error,
+                IssueKind::InvalidColor {
+                    field: field.to_string(),
+                    value: color.to_string(),
+                    suggestion: normalize_color(color),
+                },
+            );
+        }
+    }
+
+    fn check_coord_count<T: CoordType>(&mut self, coords: &[Coord<T>], min: usize) {
+        if coords.len() < min {
+            self.push(
+                Severity::Error,
+                IssueKind::TooFewCoordinates {
+                    min,
+                    found: coords.len(),
+                },
+            );
+        }
+    }
+
+    fn check_angle<T: CoordType>(&mut self, field: &str, angle: Angle, value: T) {
+        let (min, max) = angle.range();
+        self.check_range(field, value, min, max);
+    }
+
+    fn check_range<T: CoordType>(&mut self, field: &str, value: T, min: f64, max: f64) {
+        match <f64 as NumCast>::from(value) {
+            Some(v) if (min..=max).contains(&v) => {}
+            Some(v) => self.push_out_of_range(field, v),
+            None => self.push_out_of_range(field, format!("{:?}", value)),
+        }
+    }
+
+    fn check_id(&mut self, id: Option<&String>) {
+        let id = match id {
+            Some(id) if !id.is_empty() => id,
+            _ => return,
+        };
+        if !self.ids.insert(id.to_string()) {
+            self.push(Severity::Error, IssueKind::DuplicateId(id.to_string()));
+        }
+    }
+
+    fn push_out_of_range<V: fmt::Display>(&mut self, field: &str, value: V) {
+        self.push(
+            Severity::Error,
+            IssueKind::OutOfRange {
+                field: field.to_string(),
+                value: value.to_string(),
+            },
+        );
+    }
+
+    fn push(&mut self, severity: Severity, kind: IssueKind) {
+        self.issues.push(ValidationIssue {
+            path: self.path.join("/"),
+            severity,
+            kind,
+        });
+    }
+
+    fn scoped<F: FnOnce(&mut Self)>(&mut self, name: &str, f: F) {
+        match self.index.take() {
+            Some(i) => self.path.push(format!("{}[{}]", name, i)),
+            None => self.path.push(name.to_string()),
+        }
+        f(self);
+        self.path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_valid() {
+        let kml: Kml = include_str!("../tests/fixtures/polygon.kml")
+            .parse()
+            .unwrap();
+        assert_eq!(validate(&kml), vec![]);
+    }
+
+    #[test]
+    fn test_validate_coord_range() {
+        let kml: Kml = Kml::LineString(LineString::from(vec![
+            Coord::from((1., 1.)),
+            Coord::from((181., -91.)),
+        ]));
+        assert_eq!(
+            validate(&kml),
+            vec![
+                ValidationIssue {
+                    path: "LineString".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::OutOfRange {
+                        field: "longitude".to_string(),
+                        value: "181".to_string()
+                    }
+                },
+                ValidationIssue {
+                    path: "LineString".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::OutOfRange {
+                        field: "latitude".to_string(),
+                        value: "-91".to_string()
+                    }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_duplicate_ids_and_paths() {
+        let kml_str = r#"
+        <Document>
+          <Style id="a"></Style>
+          <Folder>
+            <Placemark id="a"><Point><coordinates>1,1</coordinates></Point></Placemark>
+            <Placemark>
+              <Polygon>
+                <outerBoundaryIs><LinearRing><coordinates>0,0 1,0 1,1 0,1</coordinates></LinearRing></outerBoundaryIs>
+              </Polygon>
+            </Placemark>
+          </Folder>
+        </Document>"#;
+        let kml: Kml = kml_str.parse().unwrap();
+        let issues = validate(&kml);
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].path, "Document/Folder[0]/Placemark[0]");
+        assert_eq!(issues[0].kind, IssueKind::DuplicateId("a".to_string()));
+        assert_eq!(
+            issues[1].path,
+            "Document/Folder[0]/Placemark[1]/Polygon/outerBoundaryIs/LinearRing"
+        );
+        assert_eq!(issues[1].kind, IssueKind::UnclosedRing);
+    }
+
+    #[test]
+    fn test_validate_style_map_pairs() {
+        let kml: Kml = Kml::StyleMap(StyleMap {
+            id: "map".to_string(),
+            pairs: vec![Pair {
+                key: "hover".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let kinds: Vec<IssueKind> = validate(&kml).into_iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                IssueKind::OutOfRange {
+                    field: "key".to_string(),
+                    value: "hover".to_string()
+                },
+                IssueKind::MissingChild("styleUrl".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_altitude_mode_conflicts() {
+        let kml_str = r#"
+        <MultiGeometry>
+          <Point><extrude>1</extrude><coordinates>1,1,10</coordinates></Point>
+          <LineString>
+            <tessellate>1</tessellate>
+            <altitudeMode>absolute</altitudeMode>
+            <coordinates>1,1,10 2,2,10</coordinates>
+          </LineString>
+          <LineString>
+            <extrude>1</extrude>
+            <altitudeMode>relativeToGround</altitudeMode>
+            <coordinates>1,1,10 2,2,10</coordinates>
+          </LineString>
+        </MultiGeometry>"#;
+        let kml: Kml = kml_str.parse().unwrap();
+
+        assert_eq!(
+            validate(&kml),
+            vec![
+                ValidationIssue {
+                    path: "MultiGeometry/Point".to_string(),
+                    severity: Severity::Warning,
+                    kind: IssueKind::AltitudeModeConflict {
+                        field: "extrude".to_string(),
+                        altitude_mode: AltitudeMode::ClampToGround,
+                    },
+                },
+                ValidationIssue {
+                    path: "MultiGeometry/LineString".to_string(),
+                    severity: Severity::Warning,
+                    kind: IssueKind::AltitudeModeConflict {
+                        field: "tessellate".to_string(),
+                        altitude_mode: AltitudeMode::Absolute,
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_crosses_antimeridian() {
+        let kml: Kml = r#"<Placemark>
+          <LineString><coordinates>170,0 -170,10</coordinates></LineString>
+        </Placemark>"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            validate(&kml),
+            vec![ValidationIssue {
+                path: "Placemark/LineString".to_string(),
+                severity: Severity::Warning,
+                kind: IssueKind::CrossesAntimeridian,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_resolve_duplicate_ids() {
+        let mut kml: Kml = r#"
+        <Document id="a">
+          <Style id="a_2"><LineStyle id="a"></LineStyle></Style>
+          <Placemark id="a">
+            <MultiGeometry id="b"><Point id="b"><coordinates>1,1</coordinates></Point></MultiGeometry>
+          </Placemark>
+        </Document>"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            kml.resolve_duplicate_ids(),
+            vec![
+                ("a".to_string(), "a_3".to_string()),
+                ("a".to_string(), "a_4".to_string()),
+                ("b".to_string(), "b_2".to_string()),
+            ]
+        );
+        assert_eq!(validate(&kml), vec![]);
+    }
+
+    #[test]
+    fn test_resolve_duplicate_ids_in_every_element() {
+        let elements = [
+            r#"<NetworkLink id="a"/>"#,
+            r#"<NetworkLink><Link id="a"><href>a.kml</href></Link></NetworkLink>"#,
+            r#"<NetworkLink><LookAt id="a"/></NetworkLink>"#,
+            r#"<Region id="a"><LatLonAltBox><north>1</north><south>0</south><east>1</east><west>0</west></LatLonAltBox></Region>"#,
+            r#"<Region><LatLonAltBox id="a"><north>1</north><south>0</south><east>1</east><west>0</west></LatLonAltBox><Lod id="b"/></Region>"#,
+            r#"<Lod id="a"/>"#,
+            r#"<GroundOverlay id="a"/>"#,
+            r#"<GroundOverlay><LatLonBox id="a"/></GroundOverlay>"#,
+            r#"<ScreenOverlay id="a"/>"#,
+            r#"<PhotoOverlay id="a"/>"#,
+            r#"<PhotoOverlay><ViewVolume id="a"/><ImagePyramid id="b"/><Point id="c"><coordinates>1,1</coordinates></Point></PhotoOverlay>"#,
+            r#"<gx:Track id="a"/>"#,
+            r#"<gx:MultiTrack id="a"><gx:Track id="b"/></gx:MultiTrack>"#,
+            r#"<gx:Tour id="a"/>"#,
+            r#"<TimeStamp id="a"/>"#,
+            r#"<TimeSpan id="a"/>"#,
+            r#"<Model id="a"><Location id="b"/><Orientation id="c"/><Scale id="d"/><Link id="e"/></Model>"#,
+            r#"<Camera id="a"><TimeStamp id="b"/></Camera>"#,
+            r#"<LookAt id="a"><TimeSpan id="b"/></LookAt>"#,
+            r#"<Placemark><LookAt id="a"/><TimeStamp id="b"/><gx:Track id="c"/></Placemark>"#,
+            r#"<Placemark><Model id="a"><Link id="b"/></Model></Placemark>"#,
+            r#"<LineStyle id="a"/>"#,
+        ];
+        for element in elements {
+            let ids = ["a", "b", "c", "d", "e"];
+            let count = ids
+                .iter()
+                .filter(|id| element.contains(&format!(r#"id="{}""#, id)))
+                .count();
+            let duplicates: String = ids[..count]
+                .iter()
+                .map(|id| format!(r#"<Placemark id="{}"/>"#, id))
+                .collect();
+            let mut kml: Kml = format!("<Document>{}{}</Document>", duplicates, element)
+                .parse()
+                .unwrap();
+            let duplicate_ids = |kml: &Kml| {
+                validate(kml)
+                    .into_iter()
+                    .filter(|issue| matches!(issue.kind, IssueKind::DuplicateId(_)))
+                    .count()
+            };
+            assert_eq!(duplicate_ids(&kml), count, "{}", element);
+            assert_eq!(kml.resolve_duplicate_ids().len(), count, "{}", element);
+            assert_eq!(duplicate_ids(&kml), 0, "{}", element);
+        }
+    }
+
+    #[test]
+    fn test_validate_date_times() {
+        let kml: Kml = r#"
+        <Placemark>
+          <TimeStamp><when>2007-01-14T21:05:02Z</when></TimeStamp>
+          <TimeSpan><begin>2007-13</begin><end>2008</end></TimeSpan>
+        </Placemark>"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            validate(&kml),
+            vec![ValidationIssue {
+                path: "Placemark/TimeSpan/begin".to_string(),
+                severity: Severity::Error,
+                kind: IssueKind::InvalidDateTime("2007-13".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_coord_counts() {
+        let kml: Kml = r#"
+        <Folder>
+          <Placemark><LineString><coordinates>1,1</coordinates></LineString></Placemark>
+          <Placemark>
+            <Polygon>
+              <outerBoundaryIs><LinearRing><coordinates>1,1 2,2 1,1</coordinates></LinearRing></outerBoundaryIs>
+            </Polygon>
+          </Placemark>
+          <Placemark><Polygon></Polygon></Placemark>
+        </Folder>"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            validate(&kml),
+            vec![
+                ValidationIssue {
+                    path: "Folder/Placemark[0]/LineString".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::TooFewCoordinates { min: 2, found: 1 },
+                },
+                ValidationIssue {
+                    path: "Folder/Placemark[1]/Polygon/outerBoundaryIs/LinearRing".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::TooFewCoordinates { min: 4, found: 3 },
+                },
+                ValidationIssue {
+                    path: "Folder/Placemark[2]/Polygon/outerBoundaryIs".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::MissingChild("LinearRing".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_hrefs() {
+        let kml: Kml = r#"
+        <Document>
+          <Style><IconStyle><Icon><href>C:\icons\pin.png</href></Icon></IconStyle></Style>
+          <NetworkLink><Link><href>http://example.com/my data.kml</href></Link></NetworkLink>
+          <NetworkLink><Link><href>ht_tp://example.com/data.kml</href></Link></NetworkLink>
+          <GroundOverlay><Icon><href>files/overlay%2.png</href></Icon></GroundOverlay>
+          <GroundOverlay><Icon><href>files/overlay%20image.png?a=1:2</href></Icon></GroundOverlay>
+        </Document>"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            validate(&kml),
+            vec![
+                ValidationIssue {
+                    path: "Document/Style/IconStyle/Icon".to_string(),
+                    severity: Severity::Warning,
+                    kind: IssueKind::NonPortableHref("C:\\icons\\pin.png".to_string()),
+                },
+                ValidationIssue {
+                    path: "Document/NetworkLink/Link".to_string(),
+                    severity: Severity::Warning,
+                    kind: IssueKind::NonPortableHref("http://example.com/my data.kml".to_string()),
+                },
+                ValidationIssue {
+                    path: "Document/NetworkLink/Link".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::MalformedHref("ht_tp://example.com/data.kml".to_string()),
+                },
+                ValidationIssue {
+                    path: "Document/GroundOverlay/Icon".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::MalformedHref("files/overlay%2.png".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_feature_fields() {
+        let kml: Kml = r#"
+        <Folder>
+          <open>yes</open>
+          <Placemark>
+            <visibility>1</visibility>
+            <Snippet maxLines="two">Summary</Snippet>
+          </Placemark>
+        </Folder>"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            validate(&kml),
+            vec![
+                ValidationIssue {
+                    path: "Folder".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::OutOfRange {
+                        field: "open".to_string(),
+                        value: "yes".to_string(),
+                    },
+                },
+                ValidationIssue {
+                    path: "Folder/Placemark[0]/Snippet".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::OutOfRange {
+                        field: "maxLines".to_string(),
+                        value: "two".to_string(),
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_colors() {
+        // Overlays that can't be read into their types are kept as elements, whose colors are
+        // checked
+        let kml = crate::KmlReader::<_, f64>::from_string(
+            r#"
+        <Document>
+          <GroundOverlay><color>#ff0000</color><drawOrder>top</drawOrder></GroundOverlay>
+          <PhotoOverlay><color>#00ff00</color></PhotoOverlay>
+          <ScreenOverlay><color>white</color></ScreenOverlay>
+        </Document>"#,
+        )
+        .lenient(true)
+        .read()
+        .unwrap();
+
+        assert_eq!(
+            validate(&kml),
+            vec![
+                ValidationIssue {
+                    path: "Document/GroundOverlay".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::InvalidColor {
+                        field: "color".to_string(),
+                        value: "#ff0000".to_string(),
+                        suggestion: Some("ff0000ff".to_string()),
+                    },
+                },
+                ValidationIssue {
+                    path: "Document/ScreenOverlay".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::InvalidColor {
+                        field: "color".to_string(),
+                        value: "white".to_string(),
+                        suggestion: None,
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_angles() {
+        let mut kml: Kml = r#"
+        <Document>
+          <Style><IconStyle><heading>400</heading></IconStyle></Style>
+          <Placemark>
+            <Camera><heading>90</heading><tilt>120</tilt><roll>-190</roll></Camera>
+            <LookAt><tilt>120</tilt><latitude>91</latitude></LookAt>
+          </Placemark>
+        </Document>"#
+            .parse()
+            .unwrap();
+
+        let issues: Vec<(String, IssueKind)> = validate(&kml)
+            .into_iter()
+            .map(|i| (i.path, i.kind))
+            .collect();
+        let out_of_range = |field: &str, value: &str| IssueKind::OutOfRange {
+            field: field.to_string(),
+            value: value.to_string(),
+        };
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "Document/Style/IconStyle".to_string(),
+                    out_of_range("heading", "400")
+                ),
+                (
+                    "Document/Placemark[0]/Camera".to_string(),
+                    out_of_range("roll", "-190")
+                ),
+                (
+                    "Document/Placemark[0]/LookAt".to_string(),
+                    out_of_range("tilt", "120")
+                ),
+                (
+                    "Document/Placemark[0]/LookAt".to_string(),
+                    out_of_range("latitude", "91")
+                ),
+            ]
+        );
+
+        assert_eq!(kml.normalize_angles(), 4);
+        assert!(validate(&kml).is_empty());
+    }
+
+    #[test]
+    fn test_validate_schema_data() {
+        let kml: Kml = r##"
+        <Document>
+          <Placemark>
+            <ExtendedData>
+              <SchemaData schemaUrl="#trail">
+                <SimpleData name="name">Pi in the sky</SimpleData>
+                <SimpleData name="length">3.14</SimpleData>
+                <SimpleData name="elevation">high</SimpleData>
+                <SimpleData name="grade">7</SimpleData>
+              </SchemaData>
+            </ExtendedData>
+          </Placemark>
+          <Placemark>
+            <ExtendedData><SchemaData schemaUrl="#missing"></SchemaData></ExtendedData>
+          </Placemark>
+          <Schema name="Trail" id="trail">
+            <SimpleField type="string" name="name"></SimpleField>
+            <SimpleField type="double" name="length"></SimpleField>
+            <SimpleField type="int" name="elevation"></SimpleField>
+          </Schema>
+        </Document>"##
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            validate(&kml),
+            vec![
+                ValidationIssue {
+                    path: "Document/Placemark[0]/ExtendedData/SchemaData".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::SimpleDataTypeMismatch {
+                        name: "elevation".to_string(),
+                        field_type: "int".to_string(),
+                        value: "high".to_string(),
+                    },
+                },
+                ValidationIssue {
+                    path: "Document/Placemark[0]/ExtendedData/SchemaData".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::UnknownSimpleField("grade".to_string()),
+                },
+                ValidationIssue {
+                    path: "Document/Placemark[1]/ExtendedData/SchemaData".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::UndeclaredSchema("#missing".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_style_urls() {
+        let kml_str = r##"
+        <Document>
+          <Style id="normal"></Style>
+          <StyleMap id="map">
+            <Pair><key>normal</key><styleUrl>#normal</styleUrl></Pair>
+            <Pair><key>highlight</key><styleUrl>#highlight</styleUrl></Pair>
+          </StyleMap>
+          <Placemark><styleUrl>#map</styleUrl></Placemark>
+          <Placemark><styleUrl>styles.kml#map</styleUrl></Placemark>
+          <Folder><styleUrl>#missing</styleUrl></Folder>
+        </Document>"##;
+        let kml: Kml = kml_str.parse().unwrap();
+
+        assert_eq!(
+            check_style_urls(&kml),
+            vec![
+                ValidationIssue {
+                    path: "Document/StyleMap/Pair".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::DanglingStyleUrl("#highlight".to_string()),
+                },
+                ValidationIssue {
+                    path: "Document/Placemark[1]".to_string(),
+                    severity: Severity::Warning,
+                    kind: IssueKind::ExternalStyleUrl("styles.kml#map".to_string()),
+                },
+                ValidationIssue {
+                    path: "Document/Folder[0]".to_string(),
+                    severity: Severity::Error,
+                    kind: IssueKind::DanglingStyleUrl("#missing".to_string()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Conversion of documents between versions of KML
+//!
+//! KML 2.3 adopted many of Google's extensions into its own namespace, so the same document is
+//! written with `gx:Track` for KML 2.2 and `Track` for KML 2.3. Older versions don't support the
+//! extensions at all, so converting to them replaces tracks with lines where possible.
+use crate::types::{AbstractView, CoordType, Element, Geometry, Kml, KmlVersion, GX_NAMESPACE};
+
+impl<T> Kml<T>
+where
+    T: CoordType + Default,
+{
+    /// Rewrites the tree for a version of KML, returning the number of elements changed
+    ///
+    /// - Extension elements adopted by KML 2.3 like `gx:Track` and `gx:Tour` lose their `gx`
+    ///   prefix when converting to 2.3 and gain it when converting to 2.2, see
+    ///   [`KmlVersion::is_native`](enum.KmlVersion.html#method.is_native)
+    /// - Sea floor altitude modes are written as `altitudeMode` in 2.3 and `gx:altitudeMode` in
+    ///   2.2, and replaced with the ground altitude modes for 2.0 and 2.1
+    /// - Tracks are replaced with a `kml:LineString`, or a `kml:MultiGeometry` of them for
+    ///   multi-tracks, for 2.0 and 2.1
+    /// - The root `kml` element declares the version's namespace, along with the `gx` namespace
+    ///   for 2.2
+    ///
+    /// Typed extensions like [`Track`](types/struct.Track.html) and [`Tour`](types/struct.Tour.html)
+    /// aren't changed for 2.2 and 2.3 since they're written for the version of their document.
+    /// Elements that aren't part of any version like `gx:Tour` in 2.1 are left as is, since
+    /// consumers ignore elements from unknown namespaces.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlVersion};
+    ///
+    /// let mut kml: Kml = r#"<kml xmlns="http://www.opengis.net/kml/2.3"><Placemark>
+    ///   <Track><altitudeMode>clampToSeaFloor</altitudeMode><coord>1 2 3</coord></Track>
+    ///   <LatLonQuad><coordinates>0,0 1,0 1,1 0,1</coordinates></LatLonQuad>
+    /// </Placemark></kml>"#.parse().unwrap();
+    ///
+    /// assert_eq!(kml.convert_version(KmlVersion::V22), 1);
+    /// let kml_str = kml.to_string();
+    /// assert!(kml_str.contains(r#"xmlns="http://www.opengis.net/kml/2.2""#));
+    /// assert!(kml_str.contains("<gx:LatLonQuad>"));
+    /// assert!(kml_str.contains("<gx:Track><gx:altitudeMode>clampToSeaFloor</gx:altitudeMode>"));
+    /// ```
+    pub fn convert_version(&mut self, version: KmlVersion) -> usize {
+        match self {
+            Kml::KmlDocument(d) => {
+                d.version = version.clone();
+                if let Some(namespace) = version.namespace() {
+                    d.attrs.insert("xmlns".to_string(), namespace.to_string());
+                }
+                if version == KmlVersion::V22 {
+                    d.attrs
+                        .entry("xmlns:gx".to_string())
+                        .or_insert_with(|| GX_NAMESPACE.to_string());
+                }
+                d.elements
+                    .iter_mut()
+                    .map(|e| e.convert_version(version.clone()))
+                    .sum()
+            }
+            Kml::Document { elements, .. } | Kml::Folder { elements, .. } => elements
+                .iter_mut()
+                .map(|e| e.convert_version(version.clone()))
+                .sum(),
+            Kml::Placemark(p) => {
+                p.geometry
+                    .as_mut()
+                    .map_or(0, |g| convert_geometry(g, &version))
+                    + p.view.as_mut().map_or(0, |v| convert_view(v, &version))
+                    + p.children
+                        .iter_mut()
+                        .map(|e| convert_element(e, &version))
+                        .sum::<usize>()
+            }
+            Kml::Track(t) if is_before_tracks(&version) => {
+                *self = Kml::LineString(t.to_line_string());
+                1
+            }
+            Kml::MultiTrack(t) if is_before_tracks(&version) => {
+                *self = Kml::MultiGeometry(t.to_multi_geometry());
+                1
+            }
+            Kml::MultiGeometry(g) => g
+                .geometries
+                .iter_mut()
+                .map(|g| convert_geometry(g, &version))
+                .sum(),
+            Kml::NetworkLink(n) => {
+                n.view.as_mut().map_or(0, |v| convert_view(v, &version))
+                    + convert_elements(&mut n.children, &version)
+            }
+            Kml::Camera(c) => convert_elements(&mut c.children, &version),
+            Kml::LookAt(l) => convert_elements(&mut l.children, &version),
+            Kml::GroundOverlay(o) => convert_elements(&mut o.children, &version),
+            Kml::ScreenOverlay(o) => convert_elements(&mut o.children, &version),
+            Kml::PhotoOverlay(o) => convert_elements(&mut o.children, &version),
+            Kml::Element(e) => convert_element(e, &version),
+            Kml::Custom(c) => convert_element(c.element_mut(), &version),
+            _ => 0,
+        }
+    }
+}
+
+// Tracks were added by Google's extensions to KML 2.2
+fn is_before_tracks(version: &KmlVersion) -> bool {
+    matches!(version, KmlVersion::V20 | KmlVersion::V21)
+}
+
+fn convert_geometry<T>(geometry: &mut Geometry<T>, version: &KmlVersion) -> usize
+where
+    T: CoordType + Default,
+{
+    match geometry {
+        Geometry::Track(t) if is_before_tracks(version) => {
+            *geometry = Geometry::LineString(t.to_line_string());
+            1
+        }
+        Geometry::MultiTrack(t) if is_before_tracks(version) => {
+            *geometry = Geometry::MultiGeometry(t.to_multi_geometry());
+            1
+        }
+        Geometry::MultiGeometry(g) => g
+            .geometries
+            .iter_mut()
+            .map(|g| convert_geometry(g, version))
+            .sum(),
+        Geometry::Element(e) => convert_element(e, version),
+        _ => 0,
+    }
+}
+
+fn convert_elements(elements: &mut [Element], version: &KmlVersion) -> usize {
+    elements
+        .iter_mut()
+        .map(|e| convert_element(e, version))
+        .sum()
+}
+
+// Extensions of views like `gx:ViewerOptions` are kept as elements
+fn convert_view<T: CoordType>(view: &mut AbstractView<T>, version: &KmlVersion) -> usize {
+    match view {
+        AbstractView::Camera(c) => convert_elements(&mut c.children, version),
+        AbstractView::LookAt(l) => convert_elements(&mut l.children, version),
+    }
+}
+
+pub(crate) fn convert_element(element: &mut Element, version: &KmlVersion) -> usize {
+    let mut changed = 0;
+    let sea_floor = element.local_name() == "altitudeMode"
+        && matches!(
+            element.content.as_deref().map(str::trim),
+            Some("relativeToSeaFloor" | "clampToSeaFloor")
+        );
+    let name = if sea_floor {
+        match version {
+            KmlVersion::V22 => "gx:altitudeMode".to_string(),
+            KmlVersion::V20 | KmlVersion::V21 => {
+                element.content = element
+                    .content
+                    .as_deref()
+                    .map(|c| c.trim().replace("SeaFloor", "Ground"));
+                changed += 1;
+                "altitudeMode".to_string()
+            }
+            KmlVersion::V23 => "altitudeMode".to_string(),
+            _ => element.name.clone(),
+        }
+    } else {
+        version.element_name(&element.name).into_owned()
+    };
+    if name != element.name {
+        element.name = name;
+        changed = 1;
+    }
+    changed
+        + element
+            .children
+            .iter_mut()
+            .map(|c| convert_element(c, version))
+            .sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V22: &str = r#"<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+      <Placemark>
+        <gx:MultiTrack>
+          <gx:Track><gx:coord>1 2 3</gx:coord><gx:coord>4 5 6</gx:coord></gx:Track>
+          <gx:Track><gx:altitudeMode>relativeToSeaFloor</gx:altitudeMode><gx:coord>7 8 9</gx:coord></gx:Track>
+        </gx:MultiTrack>
+      </Placemark>
+      <GroundOverlay>
+        <gx:LatLonQuad><coordinates>0,0 1,0 1,1 0,1</coordinates></gx:LatLonQuad>
+      </GroundOverlay>
+    </kml>"#;
+
+    fn read(kml_str: &str) -> Kml {
+        crate::KmlReader::<_, f64>::from_string(kml_str)
+            .lossless(true)
+            .read()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_convert_version_round_trip() {
+        let mut kml = read(V22);
+        let original = kml.clone();
+
+        assert_eq!(kml.convert_version(KmlVersion::V23), 1);
+        let v23 = kml.to_string();
+        assert!(v23.contains(r#"xmlns="http://www.opengis.net/kml/2.3""#));
+        assert!(v23.contains("<MultiTrack><Track><coord>1 2 3</coord>"));
+        assert!(v23.contains("<altitudeMode>relativeToSeaFloor</altitudeMode>"));
+        assert!(v23.contains("<LatLonQuad>"));
+
+        assert_eq!(kml.convert_version(KmlVersion::V22), 1);
+        assert_eq!(kml, original);
+    }
+
+    #[test]
+    fn test_convert_version_downgrade() {
+        let mut kml = read(V22);
+        assert_eq!(kml.convert_version(KmlVersion::V21), 1);
+        let placemark = match kml {
+            Kml::KmlDocument(d) => match &d.elements[0] {
+                Kml::Placemark(p) => p.clone(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        assert!(placemark.children.is_empty());
+        match placemark.geometry {
+            Some(Geometry::MultiGeometry(g)) => {
+                assert_eq!(g.geometries.len(), 2);
+                assert!(matches!(
+                    &g.geometries[1],
+                    Geometry::LineString(l) if l.altitude_mode == crate::types::AltitudeMode::RelativeToGround
+                ));
+            }
+            g => panic!("expected MultiGeometry, found {:?}", g),
+        }
+    }
+}
@@ -0,0 +1,739 @@
+//! Module for traversing a KML tree
+//!
+//! [`Kml::iter`](../enum.Kml.html#method.iter) walks the elements of documents and folders in
+//! document order, and [`KmlVisitor`] can be implemented to visit and mutate features and
+//! geometries without matching on every container. [`KmlVisitorRef`](by_ref::KmlVisitorRef)
+//! visits the same elements without mutating them. The passes over whole trees in this crate, like
+//! [`Kml::close_rings`](../enum.Kml.html#method.close_rings) and
+//! [`Kml::transform_coords`](../enum.Kml.html#method.transform_coords), are built on them.
+//!
+//! # Example
+//!
+//! ```
+//! use kml::{Kml, KmlVisitor, types::Point};
+//!
+//! let kml_str = r#"
+//! <Document>
+//!   <Placemark id="a"><Point><coordinates>1,2</coordinates></Point></Placemark>
+//!   <Folder>
+//!     <Placemark id="b"><Point><coordinates>3,4</coordinates></Point></Placemark>
+//!   </Folder>
+//! </Document>"#;
+//! let mut kml: Kml = kml_str.parse().unwrap();
+//! assert_eq!(kml.placemarks().count(), 2);
+//! assert!(matches!(kml.find_by_id("b"), Some(Kml::Placemark(_))));
+//!
+//! struct Shift;
+//!
+//! impl KmlVisitor for Shift {
+//!     fn visit_point(&mut self, point: &mut Point) {
+//!         point.coord.x += 10.;
+//!     }
+//! }
+//!
+//! kml.walk(&mut Shift);
+//! let xs: Vec<f64> = kml
+//!     .placemarks()
+//!     .filter_map(|p| match &p.geometry {
+//!         Some(kml::types::Geometry::Point(p)) => Some(p.coord.x),
+//!         _ => None,
+//!     })
+//!     .collect();
+//! assert_eq!(xs, [11., 13.]);
+//! ```
+use crate::types::{
+    AbstractView, Camera, CoordType, Element, Geometry, GroundOverlay, IconStyle, Kml,
+    LatLonAltBox, LatLonBox, LineString, LinearRing, Location, LookAt, Model, MultiGeometry,
+    MultiTrack, NetworkLink, Orientation, PhotoOverlay, Placemark, Point, Polygon, Region,
+    ScreenOverlay, Style, Track,
+};
+use by_ref::KmlVisitorRef;
+
+/// Iterator over an element and everything nested in its documents and folders, in document
+/// order, created with [`Kml::iter`](../enum.Kml.html#method.iter)
+#[derive(Clone, Debug)]
+pub struct Iter<'a, T: CoordType = f64> {
+    stack: Vec<&'a Kml<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: CoordType,
+{
+    type Item = &'a Kml<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let kml = self.stack.pop()?;
+        if let Some(elements) = kml.elements() {
+            self.stack.extend(elements.iter().rev());
+        }
+        Some(kml)
+    }
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType,
+{
+    /// Returns an iterator over this element followed by everything nested in it, descending into
+    /// the elements of `kml:kml`, `kml:Document`, and `kml:Folder`
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { stack: vec![self] }
+    }
+
+    /// Returns an iterator over every `kml:Placemark` in the tree, in document order
+    pub fn placemarks(&self) -> impl Iterator<Item = &Placemark<T>> {
+        self.iter().filter_map(|k| match k {
+            Kml::Placemark(p) => Some(p),
+            _ => None,
+        })
+    }
+
+    /// Returns the first element in the tree with the `id`, see [`Kml::id`](#method.id)
+    pub fn find_by_id(&self, id: &str) -> Option<&Kml<T>> {
+        self.iter().find(|k| k.id() == Some(id))
+    }
+
+    /// Returns the `id` attribute of the element, or `None` if it doesn't have one
+    ///
+    /// Styles store their id in an `id` field, where an empty id is treated as missing.
+    pub fn id(&self) -> Option<&str> {
+        let id = match self {
+            Kml::KmlDocument(d) => d.attrs.get("id"),
+            Kml::Scale(s) => s.attrs.get("id"),
+            Kml::Orientation(o) => o.attrs.get("id"),
+            Kml::Point(p) => p.attrs.get("id"),
+            Kml::Location(l) => l.attrs.get("id"),
+            Kml::LineString(l) => l.attrs.get("id"),
+            Kml::LinearRing(l) => l.attrs.get("id"),
+            Kml::Polygon(p) => p.attrs.get("id"),
+            Kml::MultiGeometry(g) => g.attrs.get("id"),
+            Kml::Track(t) => t.attrs.get("id"),
+            Kml::MultiTrack(t) => t.attrs.get("id"),
+            Kml::Model(m) => m.attrs.get("id"),
+            Kml::Placemark(p) => p.attrs.get("id"),
+            Kml::Tour(t) => t.attrs.get("id"),
+            Kml::TimeStamp(t) => t.attrs.get("id"),
+            Kml::TimeSpan(t) => t.attrs.get("id"),
+            Kml::NetworkLink(n) => n.attrs.get("id"),
+            Kml::Region(r) => r.attrs.get("id"),
+            Kml::Lod(l) => l.attrs.get("id"),
+            Kml::LatLonAltBox(b) => b.attrs.get("id"),
+            Kml::GroundOverlay(o) => o.attrs.get("id"),
+            Kml::LatLonBox(b) => b.attrs.get("id"),
+            Kml::ScreenOverlay(o) => o.attrs.get("id"),
+            Kml::PhotoOverlay(o) => o.attrs.get("id"),
+            Kml::ViewVolume(v) => v.attrs.get("id"),
+            Kml::ImagePyramid(p) => p.attrs.get("id"),
+            Kml::Schema(s) => s.attrs.get("id"),
+            Kml::Camera(c) => c.attrs.get("id"),
+            Kml::LookAt(l) => l.attrs.get("id"),
+            Kml::Document { attrs, .. } | Kml::Folder { attrs, .. } => attrs.get("id"),
+            Kml::Style(s) => Some(&s.id),
+            Kml::StyleMap(s) => Some(&s.id),
+            Kml::BalloonStyle(s) => Some(&s.id),
+            Kml::IconStyle(s) => Some(&s.id),
+            Kml::LabelStyle(s) => Some(&s.id),
+            Kml::LineStyle(s) => Some(&s.id),
+            Kml::PolyStyle(s) => Some(&s.id),
+            Kml::ListStyle(s) => Some(&s.id),
+            Kml::Pair(_) | Kml::Icon(_) => None,
+            Kml::Element(e) => e.attrs.get("id"),
+            Kml::Custom(c) => c.element().attrs.get("id"),
+        };
+        id.map(String::as_str).filter(|id| !id.is_empty())
+    }
+
+    /// Visits the tree with the visitor, see [`KmlVisitor`](visit/trait.KmlVisitor.html)
+    pub fn walk<V>(&mut self, visitor: &mut V)
+    where
+        V: KmlVisitor<T> + ?Sized,
+    {
+        visitor.visit_kml(self);
+    }
+
+    /// Visits the tree with the read-only visitor, see
+    /// [`KmlVisitorRef`](visit/by_ref/trait.KmlVisitorRef.html)
+    pub fn walk_ref<V>(&self, visitor: &mut V)
+    where
+        V: KmlVisitorRef<T> + ?Sized,
+    {
+        visitor.visit_kml(self);
+    }
+
+    // Elements of containers, or `None` for elements that can't contain features
+    fn elements(&self) -> Option<&[Kml<T>]> {
+        match self {
+            Kml::KmlDocument(d) => Some(&d.elements),
+            Kml::Document { elements, .. } | Kml::Folder { elements, .. } => Some(elements),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Geometry<T>
+where
+    T: CoordType,
+{
+    /// Visits the geometry with the visitor, see [`KmlVisitor`](../visit/trait.KmlVisitor.html)
+    pub fn walk<V>(&mut self, visitor: &mut V)
+    where
+        V: KmlVisitor<T> + ?Sized,
+    {
+        visitor.visit_geometry(self);
+    }
+
+    /// Visits the geometry with the read-only visitor, see
+    /// [`KmlVisitorRef`](../visit/by_ref/trait.KmlVisitorRef.html)
+    pub fn walk_ref<V>(&self, visitor: &mut V)
+    where
+        V: KmlVisitorRef<T> + ?Sized,
+    {
+        visitor.visit_geometry(self);
+    }
+}
+
+// Defines a visitor trait with its `walk_` functions, taking mutable references with `mut` and
+// shared references without, so both visit the same elements
+macro_rules! define_visitor {
+    ($(#[$attr:meta])* $visitor:ident, $($m:tt)?) => {
+        $(#[$attr])*
+        pub trait $visitor<T: CoordType = f64> {
+            fn visit_kml(&mut self, kml: &$($m)? Kml<T>) {
+                walk_kml(self, kml);
+            }
+
+            fn visit_placemark(&mut self, placemark: &$($m)? Placemark<T>) {
+                walk_placemark(self, placemark);
+            }
+
+            fn visit_network_link(&mut self, network_link: &$($m)? NetworkLink<T>) {
+                walk_network_link(self, network_link);
+            }
+
+            fn visit_ground_overlay(&mut self, ground_overlay: &$($m)? GroundOverlay<T>) {
+                walk_ground_overlay(self, ground_overlay);
+            }
+
+            fn visit_screen_overlay(&mut self, screen_overlay: &$($m)? ScreenOverlay) {
+                walk_screen_overlay(self, screen_overlay);
+            }
+
+            fn visit_photo_overlay(&mut self, photo_overlay: &$($m)? PhotoOverlay<T>) {
+                walk_photo_overlay(self, photo_overlay);
+            }
+
+            fn visit_style(&mut self, style: &$($m)? Style) {
+                walk_style(self, style);
+            }
+
+            fn visit_icon_style(&mut self, _icon_style: &$($m)? IconStyle) {}
+
+            fn visit_view(&mut self, view: &$($m)? AbstractView<T>) {
+                walk_view(self, view);
+            }
+
+            fn visit_camera(&mut self, _camera: &$($m)? Camera<T>) {}
+
+            fn visit_look_at(&mut self, _look_at: &$($m)? LookAt<T>) {}
+
+            fn visit_region(&mut self, region: &$($m)? Region<T>) {
+                walk_region(self, region);
+            }
+
+            fn visit_lat_lon_alt_box(&mut self, _lat_lon_alt_box: &$($m)? LatLonAltBox<T>) {}
+
+            fn visit_lat_lon_box(&mut self, _lat_lon_box: &$($m)? LatLonBox<T>) {}
+
+            fn visit_geometry(&mut self, geometry: &$($m)? Geometry<T>) {
+                walk_geometry(self, geometry);
+            }
+
+            fn visit_point(&mut self, _point: &$($m)? Point<T>) {}
+
+            fn visit_line_string(&mut self, _line_string: &$($m)? LineString<T>) {}
+
+            fn visit_linear_ring(&mut self, _linear_ring: &$($m)? LinearRing<T>) {}
+
+            fn visit_polygon(&mut self, polygon: &$($m)? Polygon<T>) {
+                walk_polygon(self, polygon);
+            }
+
+            fn visit_multi_geometry(&mut self, multi_geometry: &$($m)? MultiGeometry<T>) {
+                walk_multi_geometry(self, multi_geometry);
+            }
+
+            fn visit_track(&mut self, _track: &$($m)? Track<T>) {}
+
+            fn visit_multi_track(&mut self, multi_track: &$($m)? MultiTrack<T>) {
+                walk_multi_track(self, multi_track);
+            }
+
+            fn visit_model(&mut self, model: &$($m)? Model<T>) {
+                walk_model(self, model);
+            }
+
+            fn visit_location(&mut self, _location: &$($m)? Location<T>) {}
+
+            fn visit_orientation(&mut self, _orientation: &$($m)? Orientation<T>) {}
+
+            /// Visits the elements kept in the `children` of a feature or `kml:Model`, which can
+            /// be overridden to add or remove elements
+            #[allow(clippy::ptr_arg)]
+            fn visit_children(&mut self, children: &$($m)? Vec<Element>) {
+                walk_children(self, children);
+            }
+
+            fn visit_element(&mut self, element: &$($m)? Element) {
+                walk_element(self, element);
+            }
+        }
+
+        /// Visits the elements of containers, and the typed features, styles, views, and
+        /// geometries
+        pub fn walk_kml<T, V>(visitor: &mut V, kml: &$($m)? Kml<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            match kml {
+                Kml::KmlDocument(d) => {
+                    for e in &$($m)? d.elements {
+                        visitor.visit_kml(e);
+                    }
+                }
+                Kml::Document { elements, .. } | Kml::Folder { elements, .. } => {
+                    for e in elements {
+                        visitor.visit_kml(e);
+                    }
+                }
+                Kml::Placemark(p) => visitor.visit_placemark(p),
+                Kml::NetworkLink(n) => visitor.visit_network_link(n),
+                Kml::GroundOverlay(o) => visitor.visit_ground_overlay(o),
+                Kml::ScreenOverlay(o) => visitor.visit_screen_overlay(o),
+                Kml::PhotoOverlay(o) => visitor.visit_photo_overlay(o),
+                Kml::Style(s) => visitor.visit_style(s),
+                Kml::IconStyle(s) => visitor.visit_icon_style(s),
+                Kml::Camera(c) => visitor.visit_camera(c),
+                Kml::LookAt(l) => visitor.visit_look_at(l),
+                Kml::Region(r) => visitor.visit_region(r),
+                Kml::LatLonAltBox(b) => visitor.visit_lat_lon_alt_box(b),
+                Kml::LatLonBox(b) => visitor.visit_lat_lon_box(b),
+                Kml::Point(p) => visitor.visit_point(p),
+                Kml::LineString(l) => visitor.visit_line_string(l),
+                Kml::LinearRing(l) => visitor.visit_linear_ring(l),
+                Kml::Polygon(p) => visitor.visit_polygon(p),
+                Kml::MultiGeometry(g) => visitor.visit_multi_geometry(g),
+                Kml::Track(t) => visitor.visit_track(t),
+                Kml::MultiTrack(t) => visitor.visit_multi_track(t),
+                Kml::Model(m) => visitor.visit_model(m),
+                Kml::Location(l) => visitor.visit_location(l),
+                Kml::Orientation(o) => visitor.visit_orientation(o),
+                Kml::Element(e) => visitor.visit_element(e),
+                _ => {}
+            }
+        }
+
+        /// Visits the view, inline style, geometry, and other children of the placemark
+        pub fn walk_placemark<T, V>(visitor: &mut V, placemark: &$($m)? Placemark<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            if let Some(view) = &$($m)? placemark.view {
+                visitor.visit_view(view);
+            }
+            if let Some(style) = &$($m)? placemark.style {
+                visitor.visit_style(style);
+            }
+            if let Some(geometry) = &$($m)? placemark.geometry {
+                visitor.visit_geometry(geometry);
+            }
+            visitor.visit_children(&$($m)? placemark.children);
+        }
+
+        /// Visits the view, region, and other children of the network link
+        pub fn walk_network_link<T, V>(visitor: &mut V, network_link: &$($m)? NetworkLink<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            if let Some(view) = &$($m)? network_link.view {
+                visitor.visit_view(view);
+            }
+            if let Some(region) = &$($m)? network_link.region {
+                visitor.visit_region(region);
+            }
+            visitor.visit_children(&$($m)? network_link.children);
+        }
+
+        /// Visits the `kml:LatLonBox` and other children of the overlay
+        pub fn walk_ground_overlay<T, V>(visitor: &mut V, ground_overlay: &$($m)? GroundOverlay<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            if let Some(lat_lon_box) = &$($m)? ground_overlay.lat_lon_box {
+                visitor.visit_lat_lon_box(lat_lon_box);
+            }
+            visitor.visit_children(&$($m)? ground_overlay.children);
+        }
+
+        /// Visits the point the photo of the overlay was taken from, and other children
+        pub fn walk_photo_overlay<T, V>(visitor: &mut V, photo_overlay: &$($m)? PhotoOverlay<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            if let Some(point) = &$($m)? photo_overlay.point {
+                visitor.visit_point(point);
+            }
+            visitor.visit_children(&$($m)? photo_overlay.children);
+        }
+
+        /// Visits the children of the overlay
+        pub fn walk_screen_overlay<T, V>(visitor: &mut V, screen_overlay: &$($m)? ScreenOverlay)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            visitor.visit_children(&$($m)? screen_overlay.children);
+        }
+
+        /// Visits the `kml:IconStyle` of the style
+        pub fn walk_style<T, V>(visitor: &mut V, style: &$($m)? Style)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            if let Some(icon_style) = &$($m)? style.icon {
+                visitor.visit_icon_style(icon_style);
+            }
+        }
+
+        /// Visits the camera or look at
+        pub fn walk_view<T, V>(visitor: &mut V, view: &$($m)? AbstractView<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            match view {
+                AbstractView::Camera(c) => visitor.visit_camera(c),
+                AbstractView::LookAt(l) => visitor.visit_look_at(l),
+            }
+        }
+
+        /// Visits the `kml:LatLonAltBox` of the region
+        pub fn walk_region<T, V>(visitor: &mut V, region: &$($m)? Region<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            visitor.visit_lat_lon_alt_box(&$($m)? region.lat_lon_alt_box);
+        }
+
+        /// Visits the geometry by its type
+        pub fn walk_geometry<T, V>(visitor: &mut V, geometry: &$($m)? Geometry<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            match geometry {
+                Geometry::Point(p) => visitor.visit_point(p),
+                Geometry::LineString(l) => visitor.visit_line_string(l),
+                Geometry::LinearRing(l) => visitor.visit_linear_ring(l),
+                Geometry::Polygon(p) => visitor.visit_polygon(p),
+                Geometry::MultiGeometry(g) => visitor.visit_multi_geometry(g),
+                Geometry::Track(t) => visitor.visit_track(t),
+                Geometry::MultiTrack(t) => visitor.visit_multi_track(t),
+                Geometry::Model(m) => visitor.visit_model(m),
+                Geometry::Element(e) => visitor.visit_element(e),
+            }
+        }
+
+        /// Visits the outer and inner rings of the polygon
+        pub fn walk_polygon<T, V>(visitor: &mut V, polygon: &$($m)? Polygon<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            visitor.visit_linear_ring(&$($m)? polygon.outer);
+            for ring in &$($m)? polygon.inner {
+                visitor.visit_linear_ring(ring);
+            }
+        }
+
+        /// Visits each geometry of the `kml:MultiGeometry`
+        pub fn walk_multi_geometry<T, V>(visitor: &mut V, multi_geometry: &$($m)? MultiGeometry<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            for geometry in &$($m)? multi_geometry.geometries {
+                visitor.visit_geometry(geometry);
+            }
+        }
+
+        /// Visits each track of the `gx:MultiTrack`
+        pub fn walk_multi_track<T, V>(visitor: &mut V, multi_track: &$($m)? MultiTrack<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            for track in &$($m)? multi_track.tracks {
+                visitor.visit_track(track);
+            }
+        }
+
+        /// Visits the location, orientation, and other children of the model
+        pub fn walk_model<T, V>(visitor: &mut V, model: &$($m)? Model<T>)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            if let Some(location) = &$($m)? model.location {
+                visitor.visit_location(location);
+            }
+            if let Some(orientation) = &$($m)? model.orientation {
+                visitor.visit_orientation(orientation);
+            }
+            visitor.visit_children(&$($m)? model.children);
+        }
+
+        /// Visits the children of the element
+        pub fn walk_element<T, V>(visitor: &mut V, element: &$($m)? Element)
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            walk_children(visitor, &$($m)? element.children);
+        }
+
+        /// Visits each element
+        pub fn walk_children<T, V>(visitor: &mut V, children: &$($m)? [Element])
+        where
+            T: CoordType,
+            V: $visitor<T> + ?Sized,
+        {
+            for child in children {
+                visitor.visit_element(child);
+            }
+        }
+    };
+}
+
+define_visitor!(
+    /// Visitor for mutating a KML tree, passed to [`Kml::walk`](../enum.Kml.html#method.walk)
+    ///
+    /// Every method has a default that continues into the children of the element with the `walk_`
+    /// function of the same name, so implementations only override what they need. An override can
+    /// call the `walk_` function itself to keep descending. Elements without a typed representation
+    /// are visited with [`visit_element`](#method.visit_element), including those kept in the
+    /// `children` of features.
+    KmlVisitor,
+    mut
+);
+
+/// Read-only counterpart of [`KmlVisitor`](../trait.KmlVisitor.html), for inspecting a tree
+/// without mutating it
+pub mod by_ref {
+    use crate::types::{
+        AbstractView, Camera, CoordType, Element, Geometry, GroundOverlay, IconStyle, Kml,
+        LatLonAltBox, LatLonBox, LineString, LinearRing, Location, LookAt, Model, MultiGeometry,
+        MultiTrack, NetworkLink, Orientation, PhotoOverlay, Placemark, Point, Polygon, Region,
+        ScreenOverlay, Style, Track,
+    };
+
+    define_visitor!(
+        /// Visitor for inspecting a KML tree, passed to
+        /// [`Kml::walk_ref`](../../enum.Kml.html#method.walk_ref)
+        ///
+        /// It visits the same elements in the same order as
+        /// [`KmlVisitor`](../trait.KmlVisitor.html), with shared references.
+        KmlVisitorRef,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter {
+        placemarks: usize,
+        rings: usize,
+    }
+
+    impl KmlVisitor for Counter {
+        fn visit_placemark(&mut self, placemark: &mut Placemark) {
+            self.placemarks += 1;
+            walk_placemark(self, placemark);
+        }
+
+        fn visit_linear_ring(&mut self, _linear_ring: &mut LinearRing) {
+            self.rings += 1;
+        }
+    }
+
+    impl KmlVisitorRef for Counter {
+        fn visit_placemark(&mut self, placemark: &Placemark) {
+            self.placemarks += 1;
+            by_ref::walk_placemark(self, placemark);
+        }
+
+        fn visit_linear_ring(&mut self, _linear_ring: &LinearRing) {
+            self.rings += 1;
+        }
+    }
+
+    #[derive(Default)]
+    struct Visits(Vec<&'static str>);
+
+    impl KmlVisitor for Visits {
+        fn visit_placemark(&mut self, placemark: &mut Placemark) {
+            self.0.push("Placemark");
+            walk_placemark(self, placemark);
+        }
+
+        fn visit_network_link(&mut self, _network_link: &mut NetworkLink) {
+            self.0.push("NetworkLink");
+        }
+
+        fn visit_ground_overlay(&mut self, _ground_overlay: &mut GroundOverlay) {
+            self.0.push("GroundOverlay");
+        }
+
+        fn visit_screen_overlay(&mut self, _screen_overlay: &mut ScreenOverlay) {
+            self.0.push("ScreenOverlay");
+        }
+
+        fn visit_photo_overlay(&mut self, photo_overlay: &mut PhotoOverlay) {
+            self.0.push("PhotoOverlay");
+            walk_photo_overlay(self, photo_overlay);
+        }
+
+        fn visit_style(&mut self, _style: &mut Style) {
+            self.0.push("Style");
+        }
+
+        fn visit_point(&mut self, _point: &mut Point) {
+            self.0.push("Point");
+        }
+
+        fn visit_track(&mut self, _track: &mut Track) {
+            self.0.push("Track");
+        }
+
+        fn visit_multi_track(&mut self, multi_track: &mut MultiTrack) {
+            self.0.push("MultiTrack");
+            walk_multi_track(self, multi_track);
+        }
+
+        fn visit_model(&mut self, _model: &mut Model) {
+            self.0.push("Model");
+        }
+    }
+
+    #[test]
+    fn test_iter_document_order() {
+        let kml: Kml = r#"<kml><Document id="d">
+          <Style id="s"></Style>
+          <Folder id="f"><Placemark id="p1"></Placemark></Folder>
+          <Placemark id="p2"></Placemark>
+        </Document></kml>"#
+            .parse()
+            .unwrap();
+        let ids: Vec<_> = kml.iter().filter_map(Kml::id).collect();
+        assert_eq!(ids, ["d", "s", "f", "p1", "p2"]);
+        assert!(matches!(kml.find_by_id("f"), Some(Kml::Folder { .. })));
+        assert_eq!(kml.find_by_id("missing"), None);
+    }
+
+    #[test]
+    fn test_walk_nested_geometries() {
+        let mut kml: Kml = r#"<Folder>
+          <Placemark><MultiGeometry>
+            <Polygon>
+              <outerBoundaryIs><LinearRing><coordinates>0,0 1,0 1,1 0,0</coordinates></LinearRing></outerBoundaryIs>
+              <innerBoundaryIs><LinearRing><coordinates>0,0 1,0 1,1 0,0</coordinates></LinearRing></innerBoundaryIs>
+            </Polygon>
+            <LinearRing><coordinates>0,0 1,0 1,1 0,0</coordinates></LinearRing>
+          </MultiGeometry></Placemark>
+          <Placemark></Placemark>
+        </Folder>"#
+            .parse()
+            .unwrap();
+        let mut counter = Counter::default();
+        kml.walk(&mut counter);
+        assert_eq!(counter.placemarks, 2);
+        assert_eq!(counter.rings, 3);
+    }
+
+    #[test]
+    fn test_walk_every_element() {
+        let mut kml: Kml = r#"<Document>
+          <Placemark>
+            <Style><LineStyle><width>2</width></LineStyle></Style>
+            <gx:Track><when>2020-01-01T00:00:00Z</when><gx:coord>1 2 3</gx:coord></gx:Track>
+          </Placemark>
+          <Placemark><gx:MultiTrack><gx:Track></gx:Track><gx:Track></gx:Track></gx:MultiTrack></Placemark>
+          <Placemark><Model><Link><href>house.dae</href></Link></Model></Placemark>
+          <NetworkLink><Link><href>more.kml</href></Link></NetworkLink>
+          <GroundOverlay><Icon><href>map.png</href></Icon></GroundOverlay>
+          <ScreenOverlay><Icon><href>legend.png</href></Icon></ScreenOverlay>
+          <PhotoOverlay><Point><coordinates>1,2</coordinates></Point></PhotoOverlay>
+        </Document>"#
+            .parse()
+            .unwrap();
+        let mut visits = Visits::default();
+        kml.walk(&mut visits);
+        assert_eq!(
+            visits.0,
+            [
+                "Placemark",
+                "Style",
+                "Track",
+                "Placemark",
+                "MultiTrack",
+                "Track",
+                "Track",
+                "Placemark",
+                "Model",
+                "NetworkLink",
+                "GroundOverlay",
+                "ScreenOverlay",
+                "PhotoOverlay",
+                "Point",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_passes_reach_same_geometries() {
+        use crate::types::AltitudeUnit;
+
+        let kml: Kml = r#"<Document>
+          <Placemark><MultiGeometry>
+            <Point><coordinates>190,0,1</coordinates></Point>
+            <LineString><coordinates>190,0,1 200,0,1</coordinates></LineString>
+          </MultiGeometry></Placemark>
+          <Folder>
+            <PhotoOverlay><Point><coordinates>190,0,1</coordinates></Point></PhotoOverlay>
+          </Folder>
+        </Document>"#
+            .parse()
+            .unwrap();
+        assert_eq!(kml.num_vertices(), 4);
+        assert_eq!(kml.clone().transform_coords(|c| c), 4);
+        assert_eq!(kml.clone().wrap_longitudes(), 4);
+        assert_eq!(
+            kml.clone()
+                .convert_altitudes(AltitudeUnit::Meters, AltitudeUnit::Feet),
+            4
+        );
+
+        let mut counter = Counter::default();
+        kml.walk_ref(&mut counter);
+        assert_eq!(counter.placemarks, 1);
+    }
+}
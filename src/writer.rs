@@ -1,24 +1,178 @@
 //! Module for writing KML types
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::fmt;
+use std::fmt::{self, Write as _};
 use std::io::Write;
 use std::marker::PhantomData;
 use std::str;
 use std::str::FromStr;
 
-use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
 
 use crate::errors::Error;
 use crate::types::geom_props::GeomProps;
 use crate::types::{
-    BalloonStyle, Coord, CoordType, Element, Geometry, Icon, IconStyle, Kml, LabelStyle,
-    LineString, LineStyle, LinearRing, ListStyle, Location, MultiGeometry, Orientation, Pair,
-    Placemark, Point, PolyStyle, Polygon, Scale, Style, StyleMap,
+    AltitudeMode, BalloonStyle, Coord, CoordType, CustomElement, Element, FeatureProps, Geometry,
+    Icon, IconStyle, Kml, KmlDocument, KmlVersion, LabelStyle, LineString, LineStyle, LinearRing,
+    ListStyle, Location, Model, MultiGeometry, Orientation, Pair, Placemark, Point, PolyStyle,
+    Polygon, Scale, Style, StyleMap, ATOM_NAMESPACE, GX_NAMESPACE, XAL_NAMESPACE,
 };
 
+// Prefixes the writer uses for the namespaces of extension elements
+const STANDARD_PREFIXES: &[(&str, &str)] = &[
+    ("gx", GX_NAMESPACE),
+    ("atom", ATOM_NAMESPACE),
+    ("xal", XAL_NAMESPACE),
+];
+
+/// Options for formatting the output of [`KmlWriter`](struct.KmlWriter.html)
+///
+/// By default everything is written on a single line without an XML declaration.
+///
+/// # Example
+///
+/// ```
+/// use kml::{Kml, KmlWriter, KmlWriterOptions};
+///
+/// let kml: Kml = "<Placemark><name>Home</name></Placemark>".parse().unwrap();
+///
+/// let mut buf = Vec::new();
+/// let options = KmlWriterOptions::default()
+///     .with_indent(b' ', 2)
+///     .with_xml_declaration(true);
+/// KmlWriter::from_writer_with_options(&mut buf, options)
+///     .write(&kml)
+///     .unwrap();
+/// assert_eq!(
+///     String::from_utf8(buf).unwrap(),
+///     "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Placemark>\n  <name>Home</name>\n</Placemark>"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KmlWriterOptions {
+    /// Character to indent nested elements with, like `b' '` or `b'\t'`
+    pub indent_char: u8,
+    /// Number of `indent_char` per level of nesting, or 0 to write everything on one line
+    pub indent_size: usize,
+    /// Write `<?xml version="1.0" encoding="UTF-8"?>` before the KML
+    pub xml_declaration: bool,
+    /// Maximum number of decimal places of coordinates, or `None` to write them in full
+    pub coord_precision: Option<usize>,
+    /// Declare the KML namespace as the default namespace and the `gx`, `atom`, and `xal`
+    /// namespaces on the root `kml` element, unless they're already declared
+    pub declare_namespaces: bool,
+    /// Prefixes to write elements of [`GX_NAMESPACE`](types/constant.GX_NAMESPACE.html),
+    /// [`ATOM_NAMESPACE`](types/constant.ATOM_NAMESPACE.html), and
+    /// [`XAL_NAMESPACE`](types/constant.XAL_NAMESPACE.html) with instead of `gx`, `atom`, and
+    /// `xal`, by namespace
+    pub prefixes: HashMap<String, String>,
+}
+
+impl KmlWriterOptions {
+    /// Indent each level of nesting with `size` of `indent_char`
+    pub fn with_indent(mut self, indent_char: u8, size: usize) -> Self {
+        self.indent_char = indent_char;
+        self.indent_size = size;
+        self
+    }
+
+    pub fn with_xml_declaration(mut self, xml_declaration: bool) -> Self {
+        self.xml_declaration = xml_declaration;
+        self
+    }
+
+    /// Round coordinates to `precision` decimal places, dropping trailing zeros
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlWriter, KmlWriterOptions, types::Point};
+    ///
+    /// let kml = Kml::Point(Point::new(-105.123456789, 39.5, Some(1600.)));
+    ///
+    /// let mut buf = Vec::new();
+    /// let options = KmlWriterOptions::default().with_coord_precision(6);
+    /// KmlWriter::from_writer_with_options(&mut buf, options)
+    ///     .write(&kml)
+    ///     .unwrap();
+    /// assert!(String::from_utf8(buf)
+    ///     .unwrap()
+    ///     .contains("<coordinates>-105.123457,39.5,1600</coordinates>"));
+    /// ```
+    pub fn with_coord_precision(mut self, precision: usize) -> Self {
+        self.coord_precision = Some(precision);
+        self
+    }
+
+    pub fn with_declare_namespaces(mut self, declare_namespaces: bool) -> Self {
+        self.declare_namespaces = declare_namespaces;
+        self
+    }
+
+    /// Write elements of the namespace with the prefix, which is declared on the root `kml`
+    /// element
+    ///
+    /// Only the namespaces the writer adds prefixes for are affected, which are
+    /// [`GX_NAMESPACE`](types/constant.GX_NAMESPACE.html),
+    /// [`ATOM_NAMESPACE`](types/constant.ATOM_NAMESPACE.html), and
+    /// [`XAL_NAMESPACE`](types/constant.XAL_NAMESPACE.html).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlDocument, KmlWriter, KmlWriterOptions, types::{Element, GX_NAMESPACE}};
+    ///
+    /// let mut doc = KmlDocument::default();
+    /// doc.elements.push(Kml::Element(Element::new("gx:Tour")));
+    ///
+    /// let mut buf = Vec::new();
+    /// let options = KmlWriterOptions::default()
+    ///     .with_declare_namespaces(true)
+    ///     .with_prefix(GX_NAMESPACE, "ext");
+    /// KmlWriter::<_, f64>::from_writer_with_options(&mut buf, options)
+    ///     .write(&Kml::KmlDocument(doc))
+    ///     .unwrap();
+    /// let kml_str = String::from_utf8(buf).unwrap();
+    /// assert!(kml_str.starts_with(r#"<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:atom="#));
+    /// assert!(kml_str.contains(r#"xmlns:ext="http://www.google.com/kml/ext/2.2""#));
+    /// assert!(kml_str.contains("<ext:Tour></ext:Tour>"));
+    /// ```
+    pub fn with_prefix(mut self, namespace: &str, prefix: &str) -> Self {
+        self.prefixes
+            .insert(namespace.to_string(), prefix.to_string());
+        self
+    }
+
+    // Returns the prefix to write in place of one the writer uses, if it's been changed
+    fn prefix(&self, prefix: &str) -> Option<&str> {
+        if self.prefixes.is_empty() {
+            return None;
+        }
+        let (_, namespace) = STANDARD_PREFIXES.iter().find(|(p, _)| *p == prefix)?;
+        self.prefixes.get(*namespace).map(String::as_str)
+    }
+
+    fn xml_writer<W: Write>(&self, w: W) -> quick_xml::Writer<W> {
+        if self.indent_size > 0 {
+            quick_xml::Writer::new_with_indent(w, self.indent_char, self.indent_size)
+        } else {
+            quick_xml::Writer::new(w)
+        }
+    }
+}
+
 /// Struct for managing writing KML
 pub struct KmlWriter<W: Write, T: CoordType + FromStr + Default = f64> {
-    writer: quick_xml::Writer<W>,
+    writer: quick_xml::Writer<ByteCount<W>>,
+    options: KmlWriterOptions,
+    orient_polygons: bool,
+    normalize_altitude_modes: bool,
+    version: Option<KmlVersion>,
+    // Version of the document being written, for writing typed extensions like tracks
+    document_version: Option<KmlVersion>,
+    #[cfg(feature = "zip")]
+    pub(crate) split_kmz_folders: bool,
     _phantom: PhantomData<T>,
 }
 
@@ -42,16 +196,142 @@ where
     /// let mut writer = KmlWriter::<_, f64>::from_writer(&mut buf);
     /// ```
     pub fn from_writer(w: W) -> KmlWriter<W, T> {
-        KmlWriter::new(quick_xml::Writer::new(w))
+        KmlWriter::from_writer_with_options(w, KmlWriterOptions::default())
+    }
+
+    /// Creates `KmlWriter` from an input that implements `Write`, formatting the output with
+    /// [`KmlWriterOptions`](struct.KmlWriterOptions.html)
+    pub fn from_writer_with_options(w: W, options: KmlWriterOptions) -> KmlWriter<W, T> {
+        KmlWriter {
+            writer: options.xml_writer(ByteCount::new(w)),
+            options,
+            orient_polygons: false,
+            normalize_altitude_modes: false,
+            version: None,
+            document_version: None,
+            #[cfg(feature = "zip")]
+            split_kmz_folders: false,
+            _phantom: PhantomData,
+        }
     }
 
+    /// Creates `KmlWriter` from the output of a `quick_xml::Writer`
+    ///
+    /// Indentation of the `quick_xml::Writer` isn't kept, see
+    /// [`from_writer_with_options`](#method.from_writer_with_options) for indenting the output.
     pub fn new(writer: quick_xml::Writer<W>) -> KmlWriter<W, T> {
+        KmlWriter::from_writer(writer.into_inner())
+    }
+
+    /// Write the outer rings of polygons counter-clockwise and inner rings clockwise, reversing
+    /// coordinates where needed without modifying the input
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlWriter};
+    ///
+    /// let kml: Kml = r#"<Polygon><outerBoundaryIs><LinearRing>
+    ///   <coordinates>0,0 0,1 1,1 0,0</coordinates>
+    /// </LinearRing></outerBoundaryIs></Polygon>"#.parse().unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// KmlWriter::from_writer(&mut buf)
+    ///     .orient_polygons(true)
+    ///     .write(&kml)
+    ///     .unwrap();
+    /// assert!(String::from_utf8(buf).unwrap().contains("0,0\n1,1\n0,1\n0,0"));
+    /// ```
+    pub fn orient_polygons(mut self, orient_polygons: bool) -> Self {
+        self.orient_polygons = orient_polygons;
+        self
+    }
+
+    /// Write `extrude` and `tessellate` as disabled when they have no effect with the geometry's
+    /// `altitudeMode`, which is required by [ATC-112](https://docs.opengeospatial.org/ts/14-068r2/14-068r2.html#atc-112)
+    /// and [ATC-113](https://docs.opengeospatial.org/ts/14-068r2/14-068r2.html#atc-113)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlWriter, types::{AltitudeMode, Coord, LineString}};
+    ///
+    /// let kml = Kml::LineString(LineString {
+    ///     coords: vec![Coord::from((1., 1., 10.)), Coord::from((2., 2., 10.))],
+    ///     tessellate: true,
+    ///     altitude_mode: AltitudeMode::Absolute,
+    ///     ..Default::default()
+    /// });
+    ///
+    /// let mut buf = Vec::new();
+    /// KmlWriter::from_writer(&mut buf)
+    ///     .normalize_altitude_modes(true)
+    ///     .write(&kml)
+    ///     .unwrap();
+    /// assert!(String::from_utf8(buf).unwrap().contains("<tessellate>0</tessellate>"));
+    /// ```
+    pub fn normalize_altitude_modes(mut self, normalize_altitude_modes: bool) -> Self {
+        self.normalize_altitude_modes = normalize_altitude_modes;
+        self
+    }
+
+    /// Write for a specific version of KML, replacing the default namespace of the root `kml`
+    /// element with the version's namespace
+    ///
+    /// Extension elements that are part of the version are written without their `gx` prefix, and
+    /// for KML 2.2 the same elements are written with a `gx` prefix when it's missing. Writing KML
+    /// 2.2 also declares the `gx` namespace on the root element if it isn't already. See
+    /// [`KmlVersion::is_native`](enum.KmlVersion.html#method.is_native).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, KmlDocument, KmlVersion, KmlWriter, types::Element};
+    ///
+    /// let mut doc = KmlDocument::new(KmlVersion::V22).with_gx_namespace();
+    /// doc.elements.push(Kml::Element(Element::new("gx:Track")));
+    ///
+    /// let mut buf = Vec::new();
+    /// KmlWriter::<_, f64>::from_writer(&mut buf)
+    ///     .version(KmlVersion::V23)
+    ///     .write(&Kml::KmlDocument(doc))
+    ///     .unwrap();
+    /// let kml_str = String::from_utf8(buf).unwrap();
+    /// assert!(kml_str.contains(r#"xmlns="http://www.opengis.net/kml/2.3""#));
+    /// assert!(kml_str.contains("<Track></Track>"));
+    /// ```
+    pub fn version(mut self, version: KmlVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    // Creates a writer with the same options for writing to another output, like entries of a KMZ
+    #[cfg(feature = "zip")]
+    pub(crate) fn with_options<V: Write>(&self, writer: V) -> KmlWriter<V, T> {
         KmlWriter {
-            writer,
+            writer: self.options.xml_writer(ByteCount::new(writer)),
+            options: self.options.clone(),
+            orient_polygons: self.orient_polygons,
+            normalize_altitude_modes: self.normalize_altitude_modes,
+            version: self.version.clone(),
+            document_version: None,
+            #[cfg(feature = "zip")]
+            split_kmz_folders: self.split_kmz_folders,
             _phantom: PhantomData,
         }
     }
 
+    // Writes to the underlying writer directly, like the archive of a KMZ
+    #[cfg(any(feature = "zip", feature = "async"))]
+    pub(crate) fn inner(&mut self) -> &mut W {
+        &mut self.writer.inner().inner
+    }
+
+    #[cfg(feature = "zip")]
+    pub(crate) fn version_or_default(&self) -> KmlVersion {
+        self.version.clone().unwrap_or(KmlVersion::V22)
+    }
+
     /// Writes KML to a `Writer`
     ///
     /// # Example
@@ -68,21 +348,63 @@ where
     /// writer.write(&kml).unwrap();
     /// ```
     pub fn write(&mut self, kml: &Kml<T>) -> Result<(), Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("write_kml").entered();
+        #[cfg(feature = "tracing")]
+        let start = self.writer.inner().bytes;
+        self.write_root(kml)?;
+        #[cfg(feature = "tracing")]
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            tracing::debug!(
+                elements = kml.count_elements(),
+                bytes = self.writer.inner().bytes - start,
+                "wrote KML"
+            );
+        }
+        Ok(())
+    }
+
+    fn write_root(&mut self, kml: &Kml<T>) -> Result<(), Error> {
+        if self.options.xml_declaration {
+            self.writer
+                .write_event(Event::Decl(BytesDecl::new(b"1.0", Some(b"UTF-8"), None)))?;
+        }
         self.write_kml(kml)
     }
 
     fn write_kml(&mut self, k: &Kml<T>) -> Result<(), Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("write_element", element = k.variant_name()).entered();
         match k {
-            Kml::KmlDocument(d) => self.write_container(b"kml", &d.attrs, &d.elements)?,
+            Kml::KmlDocument(d) => self.write_document(d)?,
             Kml::Scale(s) => self.write_scale(s)?,
             Kml::Orientation(o) => self.write_orientation(o)?,
             Kml::Point(p) => self.write_point(p)?,
             Kml::Location(l) => self.write_location(l)?,
+            Kml::Model(m) => self.write_model(m)?,
             Kml::LineString(l) => self.write_line_string(l)?,
             Kml::LinearRing(l) => self.write_linear_ring(l)?,
             Kml::Polygon(p) => self.write_polygon(p)?,
             Kml::MultiGeometry(g) => self.write_multi_geometry(g)?,
+            Kml::Track(t) => self.write_extension(t.to_element())?,
+            Kml::MultiTrack(t) => self.write_extension(t.to_element())?,
             Kml::Placemark(p) => self.write_placemark(p)?,
+            Kml::Tour(t) => self.write_extension(t.to_element())?,
+            Kml::TimeStamp(t) => self.write_element(&t.to_element())?,
+            Kml::TimeSpan(t) => self.write_element(&t.to_element())?,
+            Kml::NetworkLink(n) => self.write_extension(n.to_element())?,
+            Kml::Region(r) => self.write_element(&r.to_element())?,
+            Kml::Lod(l) => self.write_element(&l.to_element())?,
+            Kml::LatLonAltBox(b) => self.write_element(&b.to_element())?,
+            Kml::GroundOverlay(o) => self.write_extension(o.to_element())?,
+            Kml::LatLonBox(b) => self.write_element(&b.to_element())?,
+            Kml::ScreenOverlay(o) => self.write_element(&o.to_element())?,
+            Kml::PhotoOverlay(o) => self.write_extension(o.to_element())?,
+            Kml::ViewVolume(v) => self.write_element(&v.to_element())?,
+            Kml::ImagePyramid(i) => self.write_element(&i.to_element())?,
+            Kml::Schema(s) => self.write_element(&s.to_element())?,
+            Kml::Camera(c) => self.write_extension(c.to_element())?,
+            Kml::LookAt(l) => self.write_extension(l.to_element())?,
             Kml::Style(s) => self.write_style(s)?,
             Kml::StyleMap(s) => self.write_style_map(s)?,
             Kml::Pair(p) => self.write_pair(p)?,
@@ -93,19 +415,28 @@ where
             Kml::LineStyle(l) => self.write_line_style(l)?,
             Kml::PolyStyle(p) => self.write_poly_style(p)?,
             Kml::ListStyle(l) => self.write_list_style(l)?,
-            Kml::Document { attrs, elements } => {
-                self.write_container(b"Document", attrs, elements)?
-            }
-            Kml::Folder { attrs, elements } => self.write_container(b"Folder", attrs, elements)?,
+            Kml::Document {
+                attrs,
+                props,
+                elements,
+            } => self.write_container(b"Document", attrs, Some(props), elements)?,
+            Kml::Folder {
+                attrs,
+                props,
+                elements,
+            } => self.write_container(b"Folder", attrs, Some(props), elements)?,
             Kml::Element(e) => self.write_element(e)?,
+            Kml::Custom(c) => self.write_element(c.element())?,
         }
 
         Ok(())
     }
 
     fn write_scale(&mut self, scale: &Scale<T>) -> Result<(), Error> {
-        self.writer
-            .write_event(Event::Start(BytesStart::owned_name(b"Scale".to_vec())))?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"Scale",
+            self.typed_attrs(&scale.attrs),
+        )))?;
         self.write_text_element(b"x", &scale.x.to_string())?;
         self.write_text_element(b"y", &scale.y.to_string())?;
         self.write_text_element(b"z", &scale.z.to_string())?;
@@ -115,32 +446,38 @@ where
     }
 
     fn write_orientation(&mut self, orientation: &Orientation<T>) -> Result<(), Error> {
-        self.writer
-            .write_event(Event::Start(BytesStart::owned_name(
-                b"Orientation".to_vec(),
-            )))?;
-        self.write_text_element(b"roll", &orientation.roll.to_string())?;
-        self.write_text_element(b"tilt", &orientation.tilt.to_string())?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"Orientation",
+            self.typed_attrs(&orientation.attrs),
+        )))?;
         self.write_text_element(b"heading", &orientation.heading.to_string())?;
+        self.write_text_element(b"tilt", &orientation.tilt.to_string())?;
+        self.write_text_element(b"roll", &orientation.roll.to_string())?;
         Ok(self
             .writer
             .write_event(Event::End(BytesEnd::owned(b"Orientation".to_vec())))?)
     }
 
     fn write_point(&mut self, point: &Point<T>) -> Result<(), Error> {
-        self.writer
-            .write_event(Event::Start(BytesStart::owned_name(b"Point".to_vec())))?;
-        self.write_text_element(b"extrude", if point.extrude { "1" } else { "0" })?;
-        self.write_text_element(b"altitudeMode", &point.altitude_mode.to_string())?;
-        self.write_text_element(b"coordinates", &point.coord.to_string())?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"Point",
+            self.typed_attrs(&point.attrs),
+        )))?;
+        let extrude =
+            point.extrude && (!self.normalize_altitude_modes || !point.altitude_mode.is_clamped());
+        self.write_text_element(b"extrude", if extrude { "1" } else { "0" })?;
+        self.write_altitude_mode(point.altitude_mode)?;
+        self.write_coordinates(std::iter::once(&point.coord))?;
         Ok(self
             .writer
             .write_event(Event::End(BytesEnd::owned(b"Point".to_vec())))?)
     }
 
     fn write_location(&mut self, location: &Location<T>) -> Result<(), Error> {
-        self.writer
-            .write_event(Event::Start(BytesStart::owned_name(b"Location".to_vec())))?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"Location",
+            self.typed_attrs(&location.attrs),
+        )))?;
         self.write_text_element(b"longitude", &location.longitude.to_string())?;
         self.write_text_element(b"latitude", &location.latitude.to_string())?;
         self.write_text_element(b"altitude", &location.altitude.to_string())?;
@@ -149,52 +486,99 @@ where
             .write_event(Event::End(BytesEnd::owned(b"Location".to_vec())))?)
     }
 
+    fn write_model(&mut self, model: &Model<T>) -> Result<(), Error> {
+        self.writer.write_event(Event::Start(start_tag(
+            b"Model",
+            self.typed_attrs(&model.attrs),
+        )))?;
+        if model.altitude_mode != AltitudeMode::default() {
+            self.write_altitude_mode(model.altitude_mode)?;
+        }
+        if let Some(location) = &model.location {
+            self.write_location(location)?;
+        }
+        if let Some(orientation) = &model.orientation {
+            self.write_orientation(orientation)?;
+        }
+        if let Some(scale) = &model.scale {
+            self.write_scale(scale)?;
+        }
+        if let Some(link) = &model.link {
+            self.write_element(&link.to_element())?;
+        }
+        if let Some(resource_map) = &model.resource_map {
+            self.write_element(&resource_map.to_element())?;
+        }
+        for c in model.children.iter() {
+            self.write_element(c)?;
+        }
+        Ok(self
+            .writer
+            .write_event(Event::End(BytesEnd::borrowed(b"Model")))?)
+    }
+
     fn write_line_string(&mut self, line_string: &LineString<T>) -> Result<(), Error> {
-        self.writer
-            .write_event(Event::Start(BytesStart::owned_name(b"LineString".to_vec())))?;
-        // TODO: Avoid clone here?
-        self.write_geom_props(GeomProps {
-            coords: line_string.coords.clone(),
+        self.writer.write_event(Event::Start(start_tag(
+            b"LineString",
+            self.typed_attrs(&line_string.attrs),
+        )))?;
+        let props = GeomProps {
+            coords: line_string.coords.as_slice(),
             altitude_mode: line_string.altitude_mode,
             extrude: line_string.extrude,
             tessellate: line_string.tessellate,
-        })?;
+        };
+        self.write_geom_props(props, false)?;
         Ok(self
             .writer
             .write_event(Event::End(BytesEnd::owned(b"LineString".to_vec())))?)
     }
 
     fn write_linear_ring(&mut self, linear_ring: &LinearRing<T>) -> Result<(), Error> {
-        self.writer
-            .write_event(Event::Start(BytesStart::owned_name(b"LinearRing".to_vec())))?;
-        self.write_geom_props(GeomProps {
-            // TODO: Avoid clone if possible
-            coords: linear_ring.coords.clone(),
+        self.write_oriented_linear_ring(linear_ring, false)
+    }
+
+    fn write_oriented_linear_ring(
+        &mut self,
+        linear_ring: &LinearRing<T>,
+        reverse: bool,
+    ) -> Result<(), Error> {
+        self.writer.write_event(Event::Start(start_tag(
+            b"LinearRing",
+            self.typed_attrs(&linear_ring.attrs),
+        )))?;
+        let props = GeomProps {
+            coords: linear_ring.coords.as_slice(),
             altitude_mode: linear_ring.altitude_mode,
             extrude: linear_ring.extrude,
             tessellate: linear_ring.tessellate,
-        })?;
+        };
+        self.write_geom_props(props, reverse)?;
         Ok(self
             .writer
             .write_event(Event::End(BytesEnd::owned(b"LinearRing".to_vec())))?)
     }
 
     fn write_polygon(&mut self, polygon: &Polygon<T>) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"Polygon".to_vec())
-                .with_attributes(self.hash_map_as_attrs(&polygon.attrs)),
-        ))?;
-        self.write_geom_props(GeomProps {
-            coords: Vec::new(),
+        self.writer.write_event(Event::Start(start_tag(
+            b"Polygon",
+            self.typed_attrs(&polygon.attrs),
+        )))?;
+        let props = GeomProps {
+            coords: &[][..],
             altitude_mode: polygon.altitude_mode,
             extrude: polygon.extrude,
             tessellate: polygon.tessellate,
-        })?;
+        };
+        self.write_geom_props(props, false)?;
         self.writer
             .write_event(Event::Start(BytesStart::owned_name(
                 b"outerBoundaryIs".to_vec(),
             )))?;
-        self.write_linear_ring(&polygon.outer)?;
+        self.write_oriented_linear_ring(
+            &polygon.outer,
+            self.orient_polygons && polygon.outer.is_cw(),
+        )?;
         self.writer
             .write_event(Event::End(BytesEnd::borrowed(b"outerBoundaryIs")))?;
 
@@ -204,7 +588,7 @@ where
                     b"innerBoundaryIs".to_vec(),
                 )))?;
             for b in &polygon.inner {
-                self.write_linear_ring(b)?;
+                self.write_oriented_linear_ring(b, self.orient_polygons && b.is_ccw())?;
             }
             self.writer
                 .write_event(Event::End(BytesEnd::borrowed(b"innerBoundaryIs")))?;
@@ -215,10 +599,10 @@ where
     }
 
     fn write_multi_geometry(&mut self, multi_geometry: &MultiGeometry<T>) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"MultiGeometry".to_vec())
-                .with_attributes(self.hash_map_as_attrs(&multi_geometry.attrs)),
-        ))?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"MultiGeometry",
+            self.typed_attrs(&multi_geometry.attrs),
+        )))?;
 
         for g in multi_geometry.geometries.iter() {
             self.write_geometry(g)?;
@@ -229,28 +613,143 @@ where
     }
 
     fn write_placemark(&mut self, placemark: &Placemark<T>) -> Result<(), Error> {
-        self.writer
-            .write_event(Event::Start(BytesStart::owned_name(b"Placemark".to_vec())))?;
-        if let Some(name) = &placemark.name {
-            self.write_text_element(b"name", name)?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"Placemark",
+            self.typed_attrs(&placemark.attrs),
+        )))?;
+        self.write_optional_text(b"name", &placemark.name)?;
+        self.write_optional_bool(b"visibility", placemark.visibility)?;
+        self.write_optional_bool(b"open", placemark.open)?;
+        self.write_atom(&placemark.author, &placemark.link)?;
+        self.write_optional_text(b"address", &placemark.address)?;
+        self.write_children(&placemark.children, |name| name == "AddressDetails")?;
+        self.write_optional_text(b"phoneNumber", &placemark.phone_number)?;
+        if let Some(snippet) = &placemark.snippet {
+            self.write_element(&snippet.to_element())?;
         }
-        if let Some(description) = &placemark.description {
-            self.write_text_element(b"description", description)?;
+        self.write_optional_text(b"description", &placemark.description)?;
+        if let Some(view) = &placemark.view {
+            self.write_extension(view.to_element())?;
         }
-        for c in placemark.children.iter() {
-            self.write_element(c)?;
+        if let Some(time_primitive) = &placemark.time_primitive {
+            self.write_element(&time_primitive.to_element())?;
+        }
+        self.write_optional_text(b"styleUrl", &placemark.style_url)?;
+        if let Some(style) = &placemark.style {
+            self.write_style(style)?;
         }
+        self.write_children(&placemark.children, |name| {
+            matches!(name, "Style" | "StyleMap" | "Region")
+        })?;
+        if let Some(extended_data) = &placemark.extended_data {
+            self.write_element(&extended_data.to_element())?;
+        }
+        self.write_children(&placemark.children, |name| name == "Metadata")?;
         if let Some(geometry) = &placemark.geometry {
             self.write_geometry(geometry)?;
         }
+        // Extensions and anything else without a place in the schema
+        self.write_children(&placemark.children, |name| {
+            !matches!(
+                name,
+                "AddressDetails" | "Style" | "StyleMap" | "Region" | "Metadata"
+            )
+        })?;
         Ok(self
             .writer
             .write_event(Event::End(BytesEnd::borrowed(b"Placemark")))?)
     }
 
+    // Writes the children of a typed element with a local name matching the filter, so they can be
+    // written where the schema puts them between its fields
+    fn write_children(
+        &mut self,
+        children: &[Element],
+        filter: impl Fn(&str) -> bool,
+    ) -> Result<(), Error> {
+        for c in children.iter().filter(|c| filter(c.local_name())) {
+            self.write_element(c)?;
+        }
+        Ok(())
+    }
+
+    fn write_document(&mut self, d: &KmlDocument<T>) -> Result<(), Error> {
+        let outer_version = self.document_version.replace(d.version.clone());
+        let result = self.write_document_with_version(d);
+        self.document_version = outer_version;
+        result
+    }
+
+    fn write_document_with_version(&mut self, d: &KmlDocument<T>) -> Result<(), Error> {
+        let mut attrs = Cow::Borrowed(&d.attrs);
+        if let Some(version) = &self.version {
+            if let Some(namespace) = version.namespace() {
+                attrs
+                    .to_mut()
+                    .insert("xmlns".to_string(), namespace.to_string());
+            }
+            if *version == KmlVersion::V22 {
+                let prefix = self.options.prefix("gx").unwrap_or("gx");
+                attrs
+                    .to_mut()
+                    .entry(format!("xmlns:{}", prefix))
+                    .or_insert_with(|| GX_NAMESPACE.to_string());
+            }
+        }
+        if self.options.declare_namespaces {
+            let version = self.version.as_ref().unwrap_or(&d.version);
+            let namespace = version
+                .namespace()
+                .or_else(|| KmlVersion::V22.namespace())
+                .unwrap_or_default();
+            attrs
+                .to_mut()
+                .entry("xmlns".to_string())
+                .or_insert_with(|| namespace.to_string());
+        }
+        for (prefix, namespace) in STANDARD_PREFIXES {
+            let prefix = match self.options.prefix(prefix) {
+                Some(prefix) => prefix,
+                None if self.options.declare_namespaces => prefix,
+                None => continue,
+            };
+            attrs
+                .to_mut()
+                .entry(format!("xmlns:{}", prefix))
+                .or_insert_with(|| namespace.to_string());
+        }
+        self.write_container(b"kml", &attrs, None, &d.elements)
+    }
+
+    // Adds or removes the gx prefix of extension elements based on the version being written, and
+    // replaces the prefixes changed in the options
+    fn element_name<'e>(&self, name: &'e str) -> Cow<'e, str> {
+        let name = match &self.version {
+            Some(version) => version.element_name(name),
+            None => Cow::Borrowed(name),
+        };
+        let replaced = name
+            .split_once(':')
+            .and_then(|(prefix, local_name)| Some((self.options.prefix(prefix)?, local_name)))
+            .map(|(prefix, local_name)| format!("{}:{}", prefix, local_name));
+        match replaced {
+            Some(replaced) => Cow::Owned(replaced),
+            None => name,
+        }
+    }
+
+    // Typed extensions like tracks are converted to elements named for KML 2.2, so without a
+    // version set on the writer they're written for the version of the document instead
+    fn write_extension(&mut self, mut element: Element) -> Result<(), Error> {
+        if self.version.is_none() && self.document_version == Some(KmlVersion::V23) {
+            crate::version::convert_element(&mut element, &KmlVersion::V23);
+        }
+        self.write_element(&element)
+    }
+
     fn write_element(&mut self, e: &Element) -> Result<(), Error> {
-        let start = BytesStart::borrowed_name(e.name.as_bytes())
-            .with_attributes(self.hash_map_as_attrs(&e.attrs));
+        let name = self.element_name(&e.name);
+        let start = start_tag(name.as_bytes(), self.hash_map_as_attrs(&e.attrs));
         self.writer.write_event(Event::Start(start))?;
         if let Some(content) = &e.content {
             self.writer
@@ -261,16 +760,14 @@ where
         }
         Ok(self
             .writer
-            .write_event(Event::End(BytesEnd::borrowed(e.name.as_bytes())))?)
+            .write_event(Event::End(BytesEnd::borrowed(name.as_bytes())))?)
     }
 
     fn write_style(&mut self, style: &Style) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"Style".to_vec()).with_attributes(vec![("id", &*style.id)]),
-        ))?;
-        if let Some(balloon) = &style.balloon {
-            self.write_balloon_style(balloon)?;
-        }
+        self.writer.write_event(Event::Start(start_tag(
+            b"Style",
+            self.attrs_with_id(&style.id, &style.attrs),
+        )))?;
         if let Some(icon) = &style.icon {
             self.write_icon_style(icon)?;
         }
@@ -283,6 +780,9 @@ where
         if let Some(poly) = &style.poly {
             self.write_poly_style(poly)?;
         }
+        if let Some(balloon) = &style.balloon {
+            self.write_balloon_style(balloon)?;
+        }
         if let Some(list) = &style.list {
             self.write_list_style(list)?;
         }
@@ -292,10 +792,10 @@ where
     }
 
     fn write_style_map(&mut self, style_map: &StyleMap) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"StyleMap".to_vec())
-                .with_attributes(vec![("id", &*style_map.id)]),
-        ))?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"StyleMap",
+            self.attrs_with_id(&style_map.id, &style_map.attrs),
+        )))?;
         for p in style_map.pairs.iter() {
             self.write_pair(p)?;
         }
@@ -305,10 +805,10 @@ where
     }
 
     fn write_pair(&mut self, pair: &Pair) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"Pair".to_vec())
-                .with_attributes(self.hash_map_as_attrs(&pair.attrs)),
-        ))?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"Pair",
+            self.typed_attrs(&pair.attrs),
+        )))?;
         self.write_text_element(b"key", &pair.key)?;
         self.write_text_element(b"styleUrl", &pair.style_url)?;
         Ok(self
@@ -317,14 +817,14 @@ where
     }
 
     fn write_balloon_style(&mut self, balloon_style: &BalloonStyle) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"BalloonStyle".to_vec())
-                .with_attributes(vec![("id", &*balloon_style.id)]),
-        ))?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"BalloonStyle",
+            self.attrs_with_id(&balloon_style.id, &balloon_style.attrs),
+        )))?;
         if let Some(bg_color) = &balloon_style.bg_color {
-            self.write_text_element(b"bgColor", bg_color)?;
+            self.write_text_element(b"bgColor", &bg_color.to_string())?;
         }
-        self.write_text_element(b"textColor", &balloon_style.text_color)?;
+        self.write_text_element(b"textColor", &balloon_style.text_color.to_string())?;
         if let Some(text) = &balloon_style.text {
             self.write_text_element(b"text", text)?;
         }
@@ -337,35 +837,38 @@ where
     }
 
     fn write_icon_style(&mut self, icon_style: &IconStyle) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"IconStyle".to_vec())
-                .with_attributes(vec![("id", &*icon_style.id)]),
-        ))?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"IconStyle",
+            self.attrs_with_id(&icon_style.id, &icon_style.attrs),
+        )))?;
+        self.write_text_element(b"color", &icon_style.color.to_string())?;
+        self.write_text_element(b"colorMode", &icon_style.color_mode.to_string())?;
         self.write_text_element(b"scale", &icon_style.scale.to_string())?;
         self.write_text_element(b"heading", &icon_style.heading.to_string())?;
+        self.write_icon(&icon_style.icon)?;
         if let Some(hot_spot) = &icon_style.hot_spot {
-            self.writer.write_event(Event::Start(
-                BytesStart::owned_name(b"hotSpot".to_vec()).with_attributes(vec![
+            self.writer.write_event(Event::Start(start_tag(
+                b"hotSpot",
+                vec![
                     ("x", &*hot_spot.x.to_string()),
                     ("y", &*hot_spot.y.to_string()),
                     ("xunits", &*hot_spot.xunits.to_string()),
                     ("yunits", &*hot_spot.yunits.to_string()),
-                ]),
-            ))?;
+                ],
+            )))?;
             self.writer
                 .write_event(Event::End(BytesEnd::borrowed(b"hotSpot")))?;
         }
-        self.write_text_element(b"color", &icon_style.color)?;
-        self.write_text_element(b"colorMode", &icon_style.color_mode.to_string())?;
-        self.write_icon(&icon_style.icon)?;
         Ok(self
             .writer
             .write_event(Event::End(BytesEnd::borrowed(b"IconStyle")))?)
     }
 
     fn write_icon(&mut self, icon: &Icon) -> Result<(), Error> {
-        self.writer
-            .write_event(Event::Start(BytesStart::owned_name(b"Icon".to_vec())))?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"Icon",
+            self.typed_attrs(&icon.attrs),
+        )))?;
         self.write_text_element(b"href", &icon.href)?;
         Ok(self
             .writer
@@ -373,11 +876,11 @@ where
     }
 
     fn write_label_style(&mut self, label_style: &LabelStyle) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"LabelStyle".to_vec())
-                .with_attributes(vec![("id", &*label_style.id)]),
-        ))?;
-        self.write_text_element(b"color", &label_style.color)?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"LabelStyle",
+            self.attrs_with_id(&label_style.id, &label_style.attrs),
+        )))?;
+        self.write_text_element(b"color", &label_style.color.to_string())?;
         self.write_text_element(b"colorMode", &label_style.color_mode.to_string())?;
         self.write_text_element(b"scale", &label_style.scale.to_string())?;
         Ok(self
@@ -386,11 +889,11 @@ where
     }
 
     fn write_line_style(&mut self, line_style: &LineStyle) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"LineStyle".to_vec())
-                .with_attributes(vec![("id", &*line_style.id)]),
-        ))?;
-        self.write_text_element(b"color", &line_style.color)?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"LineStyle",
+            self.attrs_with_id(&line_style.id, &line_style.attrs),
+        )))?;
+        self.write_text_element(b"color", &line_style.color.to_string())?;
         self.write_text_element(b"colorMode", &line_style.color_mode.to_string())?;
         self.write_text_element(b"width", &line_style.width.to_string())?;
         Ok(self
@@ -399,11 +902,11 @@ where
     }
 
     fn write_poly_style(&mut self, poly_style: &PolyStyle) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"PolyStyle".to_vec())
-                .with_attributes(vec![("id", &*poly_style.id)]),
-        ))?;
-        self.write_text_element(b"color", &poly_style.color)?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"PolyStyle",
+            self.attrs_with_id(&poly_style.id, &poly_style.attrs),
+        )))?;
+        self.write_text_element(b"color", &poly_style.color.to_string())?;
         self.write_text_element(b"colorMode", &poly_style.color_mode.to_string())?;
         self.write_text_element(b"fill", &poly_style.fill.to_string())?;
         self.write_text_element(b"outline", &poly_style.outline.to_string())?;
@@ -413,11 +916,11 @@ where
     }
 
     fn write_list_style(&mut self, list_style: &ListStyle) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(b"ListStyle".to_vec())
-                .with_attributes(vec![("id", &*list_style.id)]),
-        ))?;
-        self.write_text_element(b"bgColor", &list_style.bg_color)?;
+        self.writer.write_event(Event::Start(start_tag(
+            b"ListStyle",
+            self.attrs_with_id(&list_style.id, &list_style.attrs),
+        )))?;
+        self.write_text_element(b"bgColor", &list_style.bg_color.to_string())?;
         self.write_text_element(
             b"maxSnippetLines",
             &list_style.max_snippet_lines.to_string(),
@@ -434,37 +937,128 @@ where
             Geometry::LinearRing(l) => self.write_linear_ring(l),
             Geometry::Polygon(p) => self.write_polygon(p),
             Geometry::MultiGeometry(g) => self.write_multi_geometry(g),
-            _ => Ok(()),
+            Geometry::Track(t) => self.write_extension(t.to_element()),
+            Geometry::MultiTrack(t) => self.write_extension(t.to_element()),
+            Geometry::Model(m) => self.write_model(m),
+            Geometry::Element(e) => self.write_element(e),
+        }
+    }
+
+    // Sea floor modes are only in the KML namespace from 2.3, and don't exist before 2.2
+    fn write_altitude_mode(&mut self, altitude_mode: AltitudeMode) -> Result<(), Error> {
+        if !altitude_mode.is_sea_floor() {
+            return self.write_text_element(b"altitudeMode", &altitude_mode.to_string());
+        }
+        match self.version {
+            Some(KmlVersion::V23) => {
+                self.write_text_element(b"altitudeMode", &altitude_mode.to_string())
+            }
+            Some(KmlVersion::V20) | Some(KmlVersion::V21) => {
+                self.write_text_element(b"altitudeMode", &altitude_mode.to_ground().to_string())
+            }
+            _ => {
+                let name = self.element_name("gx:altitudeMode").into_owned();
+                self.write_text_element(name.as_bytes(), &altitude_mode.to_string())
+            }
+        }
+    }
+
+    // Writes the coordinates in reverse order when `reverse` is set, for orienting rings
+    fn write_geom_props(
+        &mut self,
+        props: GeomProps<&[Coord<T>]>,
+        reverse: bool,
+    ) -> Result<(), Error> {
+        let clamped = props.altitude_mode.is_clamped();
+        let extrude = props.extrude && (!self.normalize_altitude_modes || !clamped);
+        let tessellate = props.tessellate && (!self.normalize_altitude_modes || clamped);
+        self.write_text_element(b"extrude", if extrude { "1" } else { "0" })?;
+        self.write_text_element(b"tessellate", if tessellate { "1" } else { "0" })?;
+        self.write_altitude_mode(props.altitude_mode)?;
+        if props.coords.is_empty() {
+            return Ok(());
+        }
+        if reverse {
+            self.write_coordinates(props.coords.iter().rev())
+        } else {
+            self.write_coordinates(props.coords.iter())
         }
     }
 
-    fn write_geom_props(&mut self, props: GeomProps<T>) -> Result<(), Error> {
-        self.write_text_element(b"extrude", if props.extrude { "1" } else { "0" })?;
-        self.write_text_element(b"tessellate", if props.tessellate { "1" } else { "0" })?;
-        self.write_text_element(b"altitudeMode", &props.altitude_mode.to_string())?;
-        if !props.coords.is_empty() {
-            self.write_text_element(
-                b"coordinates",
-                &props
-                    .coords
-                    .iter()
-                    .map(Coord::to_string)
-                    .collect::<Vec<String>>()
-                    .join("\n"),
-            )?
+    // Streams the tuples into the writer in chunks as they're formatted, rather than joining every
+    // tuple first
+    fn write_coordinates<'c>(
+        &mut self,
+        coords: impl Iterator<Item = &'c Coord<T>>,
+    ) -> Result<(), Error>
+    where
+        T: 'c,
+    {
+        self.writer
+            .write_event(Event::Start(BytesStart::borrowed_name(b"coordinates")))?;
+        let mut chunk = String::with_capacity(COORDS_CHUNK_LEN);
+        for (i, coord) in coords.enumerate() {
+            if i > 0 {
+                chunk.push('\n');
+            }
+            self.push_coord(&mut chunk, coord);
+            if chunk.len() >= COORDS_CHUNK_LEN {
+                self.write_coords_chunk(&mut chunk)?;
+            }
         }
+        self.write_coords_chunk(&mut chunk)?;
+        Ok(self
+            .writer
+            .write_event(Event::End(BytesEnd::borrowed(b"coordinates")))?)
+    }
+
+    fn write_coords_chunk(&mut self, chunk: &mut String) -> Result<(), Error> {
+        // Formatted numbers never need escaping
+        self.writer
+            .write_event(Event::Text(BytesText::from_escaped_str(chunk.as_str())))?;
+        chunk.clear();
         Ok(())
     }
 
+    fn push_coord(&self, buf: &mut String, coord: &Coord<T>) {
+        let precision = match self.options.coord_precision {
+            Some(precision) => precision,
+            None => {
+                let _ = write!(buf, "{}", coord);
+                return;
+            }
+        };
+        push_precision(buf, &coord.x, precision);
+        buf.push(',');
+        push_precision(buf, &coord.y, precision);
+        if let Some(z) = &coord.z {
+            buf.push(',');
+            push_precision(buf, z, precision);
+        }
+    }
+
     fn write_container(
         &mut self,
         tag: &[u8],
         attrs: &HashMap<String, String>,
+        props: Option<&FeatureProps>,
         elements: &[Kml<T>],
     ) -> Result<(), Error> {
-        self.writer.write_event(Event::Start(
-            BytesStart::owned_name(tag).with_attributes(self.hash_map_as_attrs(attrs)),
-        ))?;
+        self.writer
+            .write_event(Event::Start(start_tag(tag, self.hash_map_as_attrs(attrs))))?;
+        if let Some(props) = props {
+            self.write_optional_text(b"name", &props.name)?;
+            self.write_optional_bool(b"visibility", props.visibility)?;
+            self.write_optional_bool(b"open", props.open)?;
+            self.write_atom(&props.author, &props.link)?;
+            self.write_optional_text(b"address", &props.address)?;
+            self.write_optional_text(b"phoneNumber", &props.phone_number)?;
+            if let Some(snippet) = &props.snippet {
+                self.write_element(&snippet.to_element())?;
+            }
+            self.write_optional_text(b"description", &props.description)?;
+            self.write_optional_text(b"styleUrl", &props.style_url)?;
+        }
         for e in elements.iter() {
             self.write_kml(e)?;
         }
@@ -484,11 +1078,138 @@ where
             .write_event(Event::End(BytesEnd::borrowed(tag)))?)
     }
 
+    fn write_optional_text(&mut self, tag: &[u8], content: &Option<String>) -> Result<(), Error> {
+        match content {
+            Some(content) => self.write_text_element(tag, content),
+            None => Ok(()),
+        }
+    }
+
+    fn write_optional_bool(&mut self, tag: &[u8], value: Option<bool>) -> Result<(), Error> {
+        match value {
+            Some(value) => self.write_text_element(tag, if value { "1" } else { "0" }),
+            None => Ok(()),
+        }
+    }
+
+    // Writes the `atom:author` and `atom:link` of a feature, where the prefix is declared by the
+    // document
+    fn write_atom(&mut self, author: &Option<String>, link: &Option<String>) -> Result<(), Error> {
+        if let Some(author) = author {
+            let name = Element::new("atom:name").with_content(author.clone());
+            self.write_element(&Element::new("atom:author").with_child(name))?;
+        }
+        if let Some(link) = link {
+            self.write_element(&Element::new("atom:link").with_attr("href", link.clone()))?;
+        }
+        Ok(())
+    }
+
     fn hash_map_as_attrs(&self, hash_map: &'a HashMap<String, String>) -> Vec<(&'a str, &'a str)> {
-        hash_map
+        // Sorted for deterministic output, with namespace declarations first
+        let mut attrs = hash_map
             .iter()
             .map(|(k, v)| (&k[..], &v[..]))
-            .collect::<Vec<(&str, &str)>>()
+            .collect::<Vec<(&str, &str)>>();
+        attrs.sort_by_key(|(k, _)| (*k != "xmlns" && !k.starts_with("xmlns:"), *k));
+        attrs
+    }
+
+    // Attributes of typed elements like geometries, which omit an empty id
+    fn typed_attrs(&self, hash_map: &'a HashMap<String, String>) -> Vec<(&'a str, &'a str)> {
+        let mut attrs = self.hash_map_as_attrs(hash_map);
+        attrs.retain(|(k, v)| *k != "id" || !v.is_empty());
+        attrs
+    }
+
+    // Attributes of styles, which keep their id in a field rather than in `attrs`
+    fn attrs_with_id(
+        &self,
+        id: &'a str,
+        hash_map: &'a HashMap<String, String>,
+    ) -> Vec<(&'a str, &'a str)> {
+        let mut attrs = self.hash_map_as_attrs(hash_map);
+        attrs.retain(|(k, _)| *k != "id");
+        if !id.is_empty() {
+            attrs.push(("id", id));
+            attrs.sort_by_key(|(k, _)| (*k != "xmlns" && !k.starts_with("xmlns:"), *k));
+        }
+        attrs
+    }
+}
+
+// Escapes markup characters and both quote styles, as well as whitespace that would otherwise be
+// normalized to spaces by attribute-value normalization when read back
+fn escape_attr(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Length of formatted coordinates buffered before they're written
+const COORDS_CHUNK_LEN: usize = 8 * 1024;
+
+// Appends the value with at most `precision` decimal places, without trailing zeros
+fn push_precision<V: fmt::Display>(buf: &mut String, value: &V, precision: usize) {
+    let start = buf.len();
+    let _ = write!(buf, "{:.*}", precision, value);
+    if buf[start..].contains('.') {
+        let trimmed = buf[start..]
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .len();
+        buf.truncate(start + trimmed);
+    }
+    if &buf[start..] == "-0" {
+        buf.truncate(start);
+        buf.push('0');
+    }
+}
+
+// Every start tag with attributes is created here so values are always escaped the same way
+fn start_tag<'b>(tag: &'b [u8], attrs: Vec<(&'b str, &'b str)>) -> BytesStart<'b> {
+    BytesStart::borrowed_name(tag).with_attributes(attrs.into_iter().map(|(key, value)| {
+        Attribute {
+            key: key.as_bytes(),
+            value: Cow::Owned(escape_attr(value).into_bytes()),
+        }
+    }))
+}
+
+// Output of a `KmlWriter`, counting the bytes written to it since quick_xml doesn't report how
+// much it has written
+struct ByteCount<W> {
+    inner: W,
+    bytes: usize,
+}
+
+impl<W> ByteCount<W> {
+    fn new(inner: W) -> Self {
+        ByteCount { inner, bytes: 0 }
+    }
+}
+
+impl<W: Write> Write for ByteCount<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bytes += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
     }
 }
 
@@ -500,15 +1221,17 @@ where
         let mut buf = Vec::new();
         KmlWriter::from_writer(&mut buf)
             .write(self)
-            .map_err(|_| fmt::Error)
-            .and_then(|_| f.write_str(str::from_utf8(&buf).unwrap()))
+            .map_err(|_| fmt::Error)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(bytes = buf.len(), "formatted KML");
+        f.write_str(str::from_utf8(&buf).unwrap())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types;
+    use crate::types::{self, Snippet};
 
     #[test]
     fn test_write_point() {
@@ -564,9 +1287,9 @@ mod tests {
             ..Default::default()
         });
         let expected_string = "<Orientation>\
-            <roll>-170.279</roll>\
-            <tilt>13</tilt>\
             <heading>45.07</heading>\
+            <tilt>13</tilt>\
+            <roll>-170.279</roll>\
         </Orientation>";
         assert_eq!(expected_string, kml.to_string());
     }
@@ -612,4 +1335,377 @@ mod tests {
             kml.to_string()
         );
     }
+
+    #[test]
+    fn test_write_oriented_polygon() {
+        let ring = |coords: Vec<(f64, f64)>| {
+            LinearRing::from(coords.into_iter().map(Coord::from).collect::<Vec<_>>())
+        };
+        let polygon = Polygon::new(
+            ring(vec![(0., 0.), (4., 0.), (4., 4.), (0., 0.)]),
+            vec![ring(vec![(1., 1.), (2., 1.), (2., 2.), (1., 1.)])],
+        );
+
+        let mut buf = Vec::new();
+        KmlWriter::from_writer(&mut buf)
+            .orient_polygons(true)
+            .write(&Kml::Polygon(polygon.clone()))
+            .unwrap();
+        let oriented: Kml = str::from_utf8(&buf).unwrap().parse().unwrap();
+        let oriented = match oriented {
+            Kml::Polygon(p) => p,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(oriented.outer, polygon.outer);
+        assert!(oriented.inner[0].is_cw());
+        assert_eq!(oriented.inner[0].coords[1], Coord::from((2., 2.)));
+    }
+
+    const SPECIAL: &str = "a\"b'<c>&\n\td\r";
+    const ESCAPED: &str = "a&quot;b&apos;&lt;c&gt;&amp;&#10;&#9;d&#13;";
+
+    fn special_attrs() -> HashMap<String, String> {
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), SPECIAL.to_string());
+        attrs
+    }
+
+    // Checks that the value is escaped in the output and that reading it back gives the same KML
+    fn assert_attrs_escaped(kml: Kml) {
+        let written = kml.to_string();
+        assert!(
+            written.contains(&format!("name=\"{}\"", ESCAPED)),
+            "{}",
+            written
+        );
+        assert_eq!(written.parse::<Kml>().unwrap(), kml);
+    }
+
+    #[test]
+    fn test_write_escaped_geometry_attrs() {
+        let ring = LinearRing::from(vec![
+            Coord::from((0., 0.)),
+            Coord::from((1., 0.)),
+            Coord::from((1., 1.)),
+            Coord::from((0., 0.)),
+        ]);
+        assert_attrs_escaped(Kml::Polygon(Polygon {
+            attrs: special_attrs(),
+            ..Polygon::new(ring, vec![])
+        }));
+        assert_attrs_escaped(Kml::MultiGeometry(MultiGeometry {
+            attrs: special_attrs(),
+            ..MultiGeometry::new(vec![Geometry::Point(Point::new(1., 1., None))])
+        }));
+    }
+
+    #[test]
+    fn test_write_escaped_element_attrs() {
+        assert_attrs_escaped(Kml::Element(Element {
+            name: "SimpleData".to_string(),
+            attrs: special_attrs(),
+            content: Some("value".to_string()),
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn test_write_escaped_container_attrs() {
+        let placemark = Kml::Placemark(Placemark {
+            name: Some("placemark".to_string()),
+            ..Default::default()
+        });
+        assert_attrs_escaped(Kml::Document {
+            attrs: special_attrs(),
+            props: FeatureProps::default(),
+            elements: vec![placemark.clone()],
+        });
+        assert_attrs_escaped(Kml::Folder {
+            attrs: special_attrs(),
+            props: FeatureProps::default(),
+            elements: vec![placemark],
+        });
+    }
+
+    #[test]
+    fn test_write_escaped_style_ids() {
+        let id = SPECIAL.to_string();
+        let style = Style {
+            id: id.clone(),
+            balloon: Some(BalloonStyle {
+                id: id.clone(),
+                ..Default::default()
+            }),
+            icon: Some(IconStyle {
+                id: id.clone(),
+                ..Default::default()
+            }),
+            label: Some(LabelStyle {
+                id: id.clone(),
+                ..Default::default()
+            }),
+            line: Some(LineStyle {
+                id: id.clone(),
+                ..Default::default()
+            }),
+            poly: Some(PolyStyle {
+                id: id.clone(),
+                ..Default::default()
+            }),
+            list: Some(ListStyle {
+                id: id.clone(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let written = Kml::<f64>::Style(style.clone()).to_string();
+        assert_eq!(written.matches(ESCAPED).count(), 7, "{}", written);
+        assert_eq!(written.parse::<Kml>().unwrap(), Kml::Style(style));
+
+        assert_attrs_escaped(Kml::StyleMap(StyleMap {
+            id,
+            pairs: vec![Pair {
+                key: "normal".to_string(),
+                style_url: "#style".to_string(),
+                attrs: special_attrs(),
+            }],
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn test_write_attrs_round_trip() {
+        let kml_str = r#"<Document>
+          <Style targetId="a"><IconStyle id="i" targetId="b"><Icon targetId="c"><href>x.png</href></Icon></IconStyle></Style>
+          <StyleMap id="m" targetId="d"></StyleMap>
+          <Placemark id="p" targetId="e">
+            <Style><LineStyle targetId="f"><width>2</width></LineStyle></Style>
+            <MultiGeometry>
+              <Point targetId="g"><coordinates>1,1</coordinates></Point>
+              <LineString id="l"><coordinates>0,0 1,1</coordinates></LineString>
+            </MultiGeometry>
+          </Placemark>
+        </Document>"#;
+        let kml: Kml = kml_str.parse().unwrap();
+        let written = kml.to_string();
+        for attr in ["a", "b", "c", "d", "e", "f", "g"] {
+            assert!(
+                written.contains(&format!("targetId=\"{}\"", attr)),
+                "{}",
+                written
+            );
+        }
+        assert!(written.contains(r#"<IconStyle id="i" targetId="b">"#));
+        assert!(!written.contains("id=\"\""), "{}", written);
+        assert_eq!(written.parse::<Kml>().unwrap(), kml);
+
+        let kml: Kml<f64> = Kml::Style(Style::default());
+        assert_eq!(kml.to_string(), "<Style></Style>");
+
+        let kml: Kml<f64> = Kml::Element(Element::new("Snippet").with_attr("id", ""));
+        assert_eq!(kml.to_string(), r#"<Snippet id=""></Snippet>"#);
+    }
+
+    #[test]
+    fn test_write_feature_fields() {
+        let placemark: Placemark = Placemark::default()
+            .with_style_url("#trail")
+            .with_description("Start here")
+            .with_snippet(Snippet::new("Parking").with_max_lines(1u32))
+            .with_link("https://example.com/trail")
+            .with_author("Parks")
+            .with_visibility(false)
+            .with_name("Trailhead");
+        let kml = Kml::Folder {
+            attrs: HashMap::new(),
+            props: FeatureProps::default().with_name("Trails").with_open(true),
+            elements: vec![Kml::Placemark(placemark)],
+        };
+        assert_eq!(
+            kml.to_string(),
+            concat!(
+                "<Folder><name>Trails</name><open>1</open><Placemark><name>Trailhead</name>",
+                "<visibility>0</visibility><atom:author><atom:name>Parks</atom:name></atom:author>",
+                r#"<atom:link href="https://example.com/trail"></atom:link>"#,
+                r#"<Snippet maxLines="1">Parking</Snippet><description>Start here</description>"#,
+                "<styleUrl>#trail</styleUrl></Placemark></Folder>",
+            )
+        );
+        assert_eq!(kml.to_string().parse::<Kml>().unwrap(), kml);
+    }
+
+    #[test]
+    fn test_write_placemark_children_in_schema_order() {
+        let mut placemark: Placemark = Placemark::default()
+            .with_style_url("#trail")
+            .with_geometry(Geometry::Point(Point::new(1., 1., None)));
+        placemark.style = Some(Style::default());
+        placemark.children = vec![
+            Element::new("gx:balloonVisibility").with_content("1"),
+            Element::new("Region"),
+            Element::new("xal:AddressDetails"),
+        ];
+        let written = Kml::Placemark(placemark).to_string();
+        let order = [
+            "<xal:AddressDetails>",
+            "<styleUrl>",
+            "<Style>",
+            "<Region>",
+            "<Point>",
+            "<gx:balloonVisibility>",
+        ]
+        .map(|tag| written.find(tag).unwrap());
+        assert!(order.windows(2).all(|w| w[0] < w[1]), "{}", written);
+    }
+
+    #[test]
+    fn test_write_version() {
+        // Prefixes are dropped when reading without KmlReader::lossless
+        let kml: Kml = r#"<kml xmlns="http://www.opengis.net/kml/2.3"><Placemark>
+          <Track><coord>1 2 3</coord></Track>
+        </Placemark></kml>"#
+            .parse()
+            .unwrap();
+
+        let write = |version: KmlVersion| {
+            let mut buf = Vec::new();
+            KmlWriter::from_writer(&mut buf)
+                .version(version)
+                .write(&kml)
+                .unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+
+        let v22 = write(KmlVersion::V22);
+        assert!(v22.contains(r#"xmlns="http://www.opengis.net/kml/2.2""#));
+        assert!(v22.contains(r#"xmlns:gx="http://www.google.com/kml/ext/2.2""#));
+        assert!(v22.contains("<gx:Track><gx:coord>1 2 3</gx:coord></gx:Track>"));
+
+        let v23 = write(KmlVersion::V23);
+        assert!(v23.contains(r#"xmlns="http://www.opengis.net/kml/2.3""#));
+        assert!(v23.contains("<Track><coord>1 2 3</coord></Track>"));
+        assert_eq!(v23, kml.to_string());
+    }
+
+    #[test]
+    fn test_write_namespace_prefixes() {
+        let kml: Kml = r#"<kml xmlns="http://www.opengis.net/kml/2.3"><Placemark>
+          <Point><coordinates>1,2</coordinates><altitudeMode>clampToSeaFloor</altitudeMode></Point>
+        </Placemark></kml>"#
+            .parse()
+            .unwrap();
+
+        let mut buf = Vec::new();
+        KmlWriter::from_writer_with_options(
+            &mut buf,
+            KmlWriterOptions::default().with_prefix(GX_NAMESPACE, "google"),
+        )
+        .version(KmlVersion::V22)
+        .write(&kml)
+        .unwrap();
+        let kml_str = String::from_utf8(buf).unwrap();
+        assert!(kml_str.starts_with(
+            r#"<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:google="http://www.google.com/kml/ext/2.2">"#
+        ));
+        assert!(kml_str.contains("<google:altitudeMode>clampToSeaFloor</google:altitudeMode>"));
+
+        let mut buf = Vec::new();
+        KmlWriter::<_, f64>::from_writer_with_options(
+            &mut buf,
+            KmlWriterOptions::default().with_declare_namespaces(true),
+        )
+        .write(&Kml::KmlDocument(KmlDocument::new(KmlVersion::V23)))
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            r#"<kml xmlns="http://www.opengis.net/kml/2.3" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:gx="http://www.google.com/kml/ext/2.2" xmlns:xal="urn:oasis:names:tc:ciq:xsdschema:xAL:2.0"></kml>"#
+        );
+    }
+
+    #[test]
+    fn test_write_indented() {
+        let kml: Kml = r#"<kml xmlns="http://www.opengis.net/kml/2.2"><Document>
+          <Placemark><name>One</name><Point><coordinates>1,2</coordinates></Point></Placemark>
+        </Document></kml>"#
+            .parse()
+            .unwrap();
+
+        let mut buf = Vec::new();
+        KmlWriter::from_writer_with_options(
+            &mut buf,
+            KmlWriterOptions::default()
+                .with_indent(b'\t', 1)
+                .with_xml_declaration(true),
+        )
+        .write(&kml)
+        .unwrap();
+        let kml_str = String::from_utf8(buf).unwrap();
+
+        assert!(kml_str.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml"));
+        assert!(kml_str.contains("\n\t<Document>\n\t\t<Placemark>\n\t\t\t<name>One</name>"));
+        assert_eq!(kml_str.parse::<Kml>().unwrap(), kml);
+    }
+
+    #[test]
+    fn test_write_coord_precision() {
+        let kml: Kml = r#"<MultiGeometry>
+          <Point><coordinates>1.23456789,-0.0000001,10</coordinates></Point>
+          <LineString><coordinates>0.5,2.100000001 -3.99999,4</coordinates></LineString>
+        </MultiGeometry>"#
+            .parse()
+            .unwrap();
+        let write = |precision: usize| {
+            let mut buf = Vec::new();
+            KmlWriter::from_writer_with_options(
+                &mut buf,
+                KmlWriterOptions::default().with_coord_precision(precision),
+            )
+            .write(&kml)
+            .unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+
+        let kml_str = write(3);
+        assert!(kml_str.contains("<coordinates>1.235,0,10</coordinates>"));
+        assert!(kml_str.contains("<coordinates>0.5,2.1\n-4,4</coordinates>"));
+        assert!(write(0).contains("<coordinates>1,0,10</coordinates>"));
+
+        let kml: Kml<f32> = "<Point><coordinates>0.1,2.55555</coordinates></Point>"
+            .parse()
+            .unwrap();
+        let mut buf = Vec::new();
+        KmlWriter::from_writer_with_options(
+            &mut buf,
+            KmlWriterOptions::default().with_coord_precision(2),
+        )
+        .write(&kml)
+        .unwrap();
+        assert!(String::from_utf8(buf)
+            .unwrap()
+            .contains("<coordinates>0.1,2.56</coordinates>"));
+    }
+
+    #[test]
+    fn test_write_sea_floor_altitude_mode() {
+        let kml: Kml = "<Point><gx:altitudeMode>clampToSeaFloor</gx:altitudeMode><coordinates>1,1</coordinates></Point>"
+            .parse()
+            .unwrap();
+        let write = |version: Option<KmlVersion>| {
+            let mut buf = Vec::new();
+            let mut writer = KmlWriter::from_writer(&mut buf);
+            if let Some(version) = version {
+                writer = writer.version(version);
+            }
+            writer.write(&kml).unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+
+        assert!(write(None).contains("<gx:altitudeMode>clampToSeaFloor</gx:altitudeMode>"));
+        assert!(
+            write(Some(KmlVersion::V23)).contains("<altitudeMode>clampToSeaFloor</altitudeMode>")
+        );
+        assert!(write(Some(KmlVersion::V21)).contains("<altitudeMode>clampToGround</altitudeMode>"));
+        assert_eq!(write(None).parse::<Kml>().unwrap(), kml);
+    }
 }
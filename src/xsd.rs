@@ -0,0 +1,496 @@
+//! Module for checking KML documents against the content models of the [OGC KML 2.2 schema](http://schemas.opengis.net/kml/2.2.0/ogckml22.xsd)
+//!
+//! Each modeled element's `xsd:sequence` is checked for unexpected children and children out of
+//! the order required by the schema, which are the most common reasons generated KML is rejected.
+//! Simple type facets (i.e. the format of `kml:color`) are covered by [`validation`](../validation/index.html)
+//! instead. Prefixed elements other than `atom:` and `xal:` children of features are treated as
+//! extensions, which the schema allows in every content model.
+//!
+//! The content models are transcribed from the `xsd:complexType` definitions of `ogckml22.xsd`,
+//! which are cited next to each, with the elements of base types like
+//! `kml:AbstractFeatureType` ahead of those of the derived type. Substitution groups like
+//! `kml:AbstractGeometryGroup` are expanded to the elements of the schema that substitute for them.
+//!
+//! # Example
+//!
+//! ```
+//! use kml::{xsd, validation::IssueKind};
+//!
+//! let kml_str = "<Point><coordinates>1,1</coordinates><extrude>1</extrude></Point>";
+//! let issues = xsd::validate_str(kml_str).unwrap();
+//!
+//! assert_eq!(issues[0].path, "Point");
+//! assert_eq!(issues[0].kind, IssueKind::OutOfOrder("extrude".to_string()));
+//! ```
+use std::fmt;
+use std::io::BufRead;
+use std::str;
+use std::str::FromStr;
+
+use quick_xml::events::Event;
+
+use crate::errors::Error;
+use crate::types::{CoordType, Kml};
+use crate::validation::{IssueKind, Severity, ValidationIssue};
+
+/// Position in an `xsd:sequence`, listing every element name allowed there and whether it repeats
+type Slot = (&'static [&'static str], bool);
+
+const ONCE: bool = false;
+const REPEATED: bool = true;
+
+const FEATURES: &[&str] = &[
+    "Placemark",
+    "Document",
+    "Folder",
+    "NetworkLink",
+    "GroundOverlay",
+    "ScreenOverlay",
+    "PhotoOverlay",
+];
+
+const GEOMETRIES: &[&str] = &[
+    "Point",
+    "LineString",
+    "LinearRing",
+    "Polygon",
+    "MultiGeometry",
+    "Model",
+];
+
+// kml:AbstractFeatureType
+const FEATURE: &[Slot] = &[
+    (&["name"], ONCE),
+    (&["visibility"], ONCE),
+    (&["open"], ONCE),
+    (&["atom:author"], ONCE),
+    (&["atom:link"], ONCE),
+    (&["address"], ONCE),
+    (&["xal:AddressDetails"], ONCE),
+    (&["phoneNumber"], ONCE),
+    (&["Snippet", "snippet"], ONCE),
+    (&["description"], ONCE),
+    (&["LookAt", "Camera"], ONCE),
+    (&["TimeStamp", "TimeSpan"], ONCE),
+    (&["styleUrl"], ONCE),
+    (&["Style", "StyleMap"], REPEATED),
+    (&["Region"], ONCE),
+    (&["Metadata", "ExtendedData"], ONCE),
+];
+
+// kml:AbstractColorStyleType
+const COLOR_STYLE: &[Slot] = &[(&["color"], ONCE), (&["colorMode"], ONCE)];
+
+// kml:LinkType
+const LINK: &[Slot] = &[
+    (&["href"], ONCE),
+    (&["refreshMode"], ONCE),
+    (&["refreshInterval"], ONCE),
+    (&["viewRefreshMode"], ONCE),
+    (&["viewRefreshTime"], ONCE),
+    (&["viewBoundScale"], ONCE),
+    (&["viewFormat"], ONCE),
+    (&["httpQuery"], ONCE),
+];
+
+fn content_model(name: &str) -> Option<Vec<&'static Slot>> {
+    let (base, own): (&[Slot], &[Slot]) = match name {
+        // kml:KmlType
+        "kml" => (&[], &[(&["NetworkLinkControl"], ONCE), (FEATURES, ONCE)]),
+        // kml:DocumentType, extending kml:AbstractContainerType
+        "Document" => (FEATURE, &[(&["Schema"], REPEATED), (FEATURES, REPEATED)]),
+        // kml:FolderType, extending kml:AbstractContainerType
+        "Folder" => (FEATURE, &[(FEATURES, REPEATED)]),
+        // kml:PlacemarkType
+        "Placemark" => (FEATURE, &[(GEOMETRIES, ONCE)]),
+        // kml:PointType
+        "Point" => (
+            &[],
+            &[
+                (&["extrude"], ONCE),
+                (&["altitudeMode"], ONCE),
+                (&["coordinates"], ONCE),
+            ],
+        ),
+        // kml:LineStringType and kml:LinearRingType, which are the same apart from attributes
+        "LineString" | "LinearRing" => (
+            &[],
+            &[
+                (&["extrude"], ONCE),
+                (&["tessellate"], ONCE),
+                (&["altitudeMode"], ONCE),
+                (&["coordinates"], ONCE),
+            ],
+        ),
+        // kml:PolygonType
+        "Polygon" => (
+            &[],
+            &[
+                (&["extrude"], ONCE),
+                (&["tessellate"], ONCE),
+                (&["altitudeMode"], ONCE),
+                (&["outerBoundaryIs"], ONCE),
+                (&["innerBoundaryIs"], REPEATED),
+            ],
+        ),
+        // kml:BoundaryType
+        "outerBoundaryIs" | "innerBoundaryIs" => (&[], &[(&["LinearRing"], ONCE)]),
+        // kml:MultiGeometryType
+        "MultiGeometry" => (&[], &[(GEOMETRIES, REPEATED)]),
+        // kml:ModelType
+        "Model" => (
+            &[],
+            &[
+                (&["altitudeMode"], ONCE),
+                (&["Location"], ONCE),
+                (&["Orientation"], ONCE),
+                (&["Scale"], ONCE),
+                (&["Link"], ONCE),
+                (&["ResourceMap"], ONCE),
+            ],
+        ),
+        // kml:LocationType
+        "Location" => (
+            &[],
+            &[
+                (&["longitude"], ONCE),
+                (&["latitude"], ONCE),
+                (&["altitude"], ONCE),
+            ],
+        ),
+        // kml:OrientationType
+        "Orientation" => (
+            &[],
+            &[(&["heading"], ONCE), (&["tilt"], ONCE), (&["roll"], ONCE)],
+        ),
+        // kml:ScaleType
+        "Scale" => (&[], &[(&["x"], ONCE), (&["y"], ONCE), (&["z"], ONCE)]),
+        // kml:StyleType
+        "Style" => (
+            &[],
+            &[
+                (&["IconStyle"], ONCE),
+                (&["LabelStyle"], ONCE),
+                (&["LineStyle"], ONCE),
+                (&["PolyStyle"], ONCE),
+                (&["BalloonStyle"], ONCE),
+                (&["ListStyle"], ONCE),
+            ],
+        ),
+        // kml:StyleMapType
+        "StyleMap" => (&[], &[(&["Pair"], REPEATED)]),
+        // kml:PairType
+        "Pair" => (
+            &[],
+            &[
+                (&["key"], ONCE),
+                (&["styleUrl"], ONCE),
+                (&["Style", "StyleMap"], ONCE),
+            ],
+        ),
+        // kml:IconStyleType, where `Icon` is a kml:BasicLinkType that only has `href`
+        "IconStyle" => (
+            COLOR_STYLE,
+            &[
+                (&["scale"], ONCE),
+                (&["heading"], ONCE),
+                (&["Icon"], ONCE),
+                (&["hotSpot"], ONCE),
+            ],
+        ),
+        // kml:LabelStyleType
+        "LabelStyle" => (COLOR_STYLE, &[(&["scale"], ONCE)]),
+        // kml:LineStyleType
+        "LineStyle" => (COLOR_STYLE, &[(&["width"], ONCE)]),
+        // kml:PolyStyleType
+        "PolyStyle" => (COLOR_STYLE, &[(&["fill"], ONCE), (&["outline"], ONCE)]),
+        // kml:BalloonStyleType, where `color` and `bgColor` are a choice rather than a sequence
+        "BalloonStyle" => (
+            &[],
+            &[
+                (&["color"], ONCE),
+                (&["bgColor"], ONCE),
+                (&["textColor"], ONCE),
+                (&["text"], ONCE),
+                (&["displayMode"], ONCE),
+            ],
+        ),
+        // kml:ListStyleType
+        "ListStyle" => (
+            &[],
+            &[
+                (&["listItemType"], ONCE),
+                (&["bgColor"], ONCE),
+                (&["ItemIcon"], REPEATED),
+                (&["maxSnippetLines"], ONCE),
+            ],
+        ),
+        // kml:LinkType
+        "Icon" | "Link" => (LINK, &[]),
+        // kml:TimeStampType
+        "TimeStamp" => (&[], &[(&["when"], ONCE)]),
+        // kml:TimeSpanType
+        "TimeSpan" => (&[], &[(&["begin"], ONCE), (&["end"], ONCE)]),
+        // kml:LookAtType
+        "LookAt" => (
+            &[],
+            &[
+                (&["longitude"], ONCE),
+                (&["latitude"], ONCE),
+                (&["altitude"], ONCE),
+                (&["heading"], ONCE),
+                (&["tilt"], ONCE),
+                (&["range"], ONCE),
+                (&["altitudeMode"], ONCE),
+            ],
+        ),
+        // kml:CameraType
+        "Camera" => (
+            &[],
+            &[
+                (&["longitude"], ONCE),
+                (&["latitude"], ONCE),
+                (&["altitude"], ONCE),
+                (&["heading"], ONCE),
+                (&["tilt"], ONCE),
+                (&["roll"], ONCE),
+                (&["altitudeMode"], ONCE),
+            ],
+        ),
+        // kml:ExtendedDataType
+        "ExtendedData" => (&[], &[(&["Data"], REPEATED), (&["SchemaData"], REPEATED)]),
+        // kml:DataType
+        "Data" => (&[], &[(&["displayName"], ONCE), (&["value"], ONCE)]),
+        // kml:SchemaDataType
+        "SchemaData" => (&[], &[(&["SimpleData"], REPEATED)]),
+        // kml:SchemaType
+        "Schema" => (&[], &[(&["SimpleField"], REPEATED)]),
+        // kml:SimpleFieldType
+        "SimpleField" => (&[], &[(&["displayName"], ONCE)]),
+        // kml:RegionType
+        "Region" => (&[], &[(&["LatLonAltBox"], ONCE), (&["Lod"], ONCE)]),
+        _ => return None,
+    };
+    Some(base.iter().chain(own.iter()).collect())
+}
+
+struct Frame {
+    name: String,
+    model: Option<Vec<&'static Slot>>,
+    position: usize,
+    used: bool,
+}
+
+impl Frame {
+    fn new(name: String) -> Self {
+        let model = content_model(&name);
+        Frame {
+            name,
+            model,
+            position: 0,
+            used: false,
+        }
+    }
+
+    fn check_child(&mut self, child: &str) -> Option<IssueKind> {
+        let model = self.model.as_ref()?;
+        let slot = model.iter().position(|s| s.0.contains(&child));
+        match slot {
+            Some(i) if i > self.position || (i == self.position && !self.used) => {
+                self.position = i;
+                self.used = true;
+                None
+            }
+            Some(i) if i == self.position && model[i].1 => None,
+            Some(_) => Some(IssueKind::OutOfOrder(child.to_string())),
+            // Prefixed names outside of the content model are extensions
+            None if child.contains(':') => None,
+            None => Some(IssueKind::UnexpectedElement(child.to_string())),
+        }
+    }
+}
+
+/// Checks KML from any reader against the content models of the schema
+pub fn validate_reader<R: BufRead>(r: R) -> Result<Vec<ValidationIssue>, Error> {
+    let mut reader = quick_xml::Reader::from_reader(r);
+    reader.trim_text(true);
+    reader.expand_empty_elements(true);
+    let mut buf = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut issues: Vec<ValidationIssue> = Vec::new();
+
+    loop {
+        match reader.read_event(&mut buf)? {
+            Event::Start(e) => {
+                let name = str::from_utf8(e.name())
+                    .map_err(|_| Error::InvalidInput)?
+                    .trim_start_matches("kml:")
+                    .to_string();
+                if let Some(parent) = stack.last_mut() {
+                    if let Some(kind) = parent.check_child(&name) {
+                        issues.push(ValidationIssue {
+                            path: stack
+                                .iter()
+                                .map(|f| f.name.as_str())
+                                .collect::<Vec<&str>>()
+                                .join("/"),
+                            severity: Severity::Error,
+                            kind,
+                        });
+                    }
+                }
+                stack.push(Frame::new(name));
+            }
+            Event::End(_) => {
+                stack.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(issues)
+}
+
+/// Checks a KML string against the content models of the schema
+pub fn validate_str(s: &str) -> Result<Vec<ValidationIssue>, Error> {
+    validate_reader(s.as_bytes())
+}
+
+impl<T> Kml<T>
+where
+    T: CoordType + FromStr + Default + fmt::Display,
+{
+    /// Serializes KML and checks the output against the content models of the schema
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kml::{Kml, types::Point};
+    ///
+    /// let kml = Kml::Point(Point::new(1., 1., None));
+    /// assert!(kml.validate_schema().unwrap().is_empty());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "xsd")))]
+    pub fn validate_schema(&self) -> Result<Vec<ValidationIssue>, Error> {
+        validate_str(&self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BalloonStyle, IconStyle, LineStyle, Orientation, Style};
+
+    // Child elements of each complex type in the order of its `xsd:sequence`, with substitution
+    // groups and elements repeated by `maxOccurs="unbounded"` represented by one element
+    const SCHEMA_SEQUENCES: &[(&str, &str)] = &[
+        ("Point", "extrude altitudeMode coordinates"),
+        ("LineString", "extrude tessellate altitudeMode coordinates"),
+        ("LinearRing", "extrude tessellate altitudeMode coordinates"),
+        ("Polygon", "extrude tessellate altitudeMode outerBoundaryIs innerBoundaryIs"),
+        ("Model", "altitudeMode Location Orientation Scale Link ResourceMap"),
+        ("Location", "longitude latitude altitude"),
+        ("Orientation", "heading tilt roll"),
+        ("Scale", "x y z"),
+        ("Style", "IconStyle LabelStyle LineStyle PolyStyle BalloonStyle ListStyle"),
+        ("Pair", "key styleUrl Style"),
+        ("IconStyle", "color colorMode scale heading Icon hotSpot"),
+        ("LabelStyle", "color colorMode scale"),
+        ("LineStyle", "color colorMode width"),
+        ("PolyStyle", "color colorMode fill outline"),
+        ("BalloonStyle", "bgColor textColor text displayMode"),
+        ("ListStyle", "listItemType bgColor ItemIcon maxSnippetLines"),
+        ("Link", "href refreshMode refreshInterval viewRefreshMode viewRefreshTime viewBoundScale viewFormat httpQuery"),
+        ("TimeSpan", "begin end"),
+        ("LookAt", "longitude latitude altitude heading tilt range altitudeMode"),
+        ("Camera", "longitude latitude altitude heading tilt roll altitudeMode"),
+        ("ExtendedData", "Data SchemaData"),
+        ("Data", "displayName value"),
+        ("Region", "LatLonAltBox Lod"),
+        ("Placemark", "name visibility open address phoneNumber Snippet description LookAt TimeStamp styleUrl Style Region ExtendedData Point"),
+        ("Document", "name visibility open address phoneNumber Snippet description Camera TimeSpan styleUrl StyleMap Region ExtendedData Schema Placemark"),
+        ("Folder", "name visibility open address phoneNumber Snippet description LookAt TimeStamp styleUrl Style Region ExtendedData Folder"),
+    ];
+
+    #[test]
+    fn test_content_models_follow_schema() {
+        for (name, sequence) in SCHEMA_SEQUENCES {
+            let children: Vec<&str> = sequence.split(' ').collect();
+            let element = |children: &[&str]| {
+                let children: String = children.iter().map(|c| format!("<{0}></{0}>", c)).collect();
+                format!("<{0}>{1}</{0}>", name, children)
+            };
+            let issues = validate_str(&element(&children)).unwrap();
+            assert!(
+                issues.iter().all(|i| i.path != *name),
+                "{}: {:?}",
+                name,
+                issues
+            );
+
+            let mut reversed = children.clone();
+            reversed.reverse();
+            let issues = validate_str(&element(&reversed)).unwrap();
+            let expected = IssueKind::OutOfOrder(children[children.len() - 2].to_string());
+            assert!(
+                issues.iter().any(|i| i.path == *name && i.kind == expected),
+                "{}: {:?}",
+                name,
+                issues
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_unexpected_element() {
+        let kml_str = r#"
+        <kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+          <Placemark>
+            <name>Test</name>
+            <gx:balloonVisibility>1</gx:balloonVisibility>
+            <Point><coordinates>1,1</coordinates><tessellate>1</tessellate></Point>
+          </Placemark>
+        </kml>"#;
+        assert_eq!(
+            validate_str(kml_str).unwrap(),
+            vec![ValidationIssue {
+                path: "kml/Placemark/Point".to_string(),
+                severity: Severity::Error,
+                kind: IssueKind::UnexpectedElement("tessellate".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_repeated_element() {
+        let kml_str = "<Placemark><name>a</name><name>b</name></Placemark>";
+        assert_eq!(
+            validate_str(kml_str).unwrap()[0].kind,
+            IssueKind::OutOfOrder("name".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_written_styles() {
+        let kml: Kml = Kml::Style(Style {
+            id: "style".to_string(),
+            balloon: Some(BalloonStyle::default()),
+            icon: Some(IconStyle::default()),
+            line: Some(LineStyle::default()),
+            ..Default::default()
+        });
+        assert_eq!(kml.validate_schema().unwrap(), vec![]);
+
+        let kml: Kml = Kml::Orientation(Orientation::new(1., 2., 3.));
+        assert_eq!(kml.validate_schema().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_validate_written_fixture() {
+        let kml: Kml = include_str!("../tests/fixtures/countries.kml")
+            .parse()
+            .unwrap();
+        assert_eq!(kml.validate_schema().unwrap(), vec![]);
+    }
+}
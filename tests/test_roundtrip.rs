@@ -34,7 +34,7 @@ mod roundtrip_tests {
 
     // Confirms that parsing from KML and writing back doesn't drop any currently tracked data
     fn test_round_trip(file_path: &str) {
-        let mut file = File::open(&file_path).unwrap();
+        let mut file = File::open(file_path).unwrap();
         let mut file_contents = String::new();
         let _ = file.read_to_string(&mut file_contents);
 
@@ -48,4 +48,119 @@ mod roundtrip_tests {
 
         assert_eq!(original_kml, roundtrip_kml)
     }
+
+    // Unknown elements read in lossless mode are written back byte for byte when the input is in
+    // the writer's canonical form, i.e. no whitespace between elements and sorted attributes
+    #[test]
+    fn test_lossless_round_trip() {
+        let kml_str = concat!(
+            r#"<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">"#,
+            r#"<Document>"#,
+            r#"<gx:Tour><gx:Playlist><gx:FlyTo><gx:duration>5</gx:duration></gx:FlyTo></gx:Playlist></gx:Tour>"#,
+            r#"<atom:author xmlns:atom="http://www.w3.org/2005/Atom"><atom:name>A &amp; B</atom:name></atom:author>"#,
+            r#"<ExtendedData><Data name="a&amp;b&quot;"><value>1 &lt; 2</value></Data></ExtendedData>"#,
+            r#"</Document>"#,
+            r#"</kml>"#
+        );
+        let kml = kml::KmlReader::<_, f64>::from_string(kml_str)
+            .lossless(true)
+            .read()
+            .unwrap();
+        assert_eq!(kml.to_string(), kml_str);
+    }
+
+    #[test]
+    fn test_lossless_cdata() {
+        let kml_str = r#"<ExtendedData><Data name="html"><value><![CDATA[<b>bold</b>]]></value><!-- comment --></Data></ExtendedData>"#;
+        let kml = kml::KmlReader::<_, f64>::from_string(kml_str)
+            .lossless(true)
+            .read()
+            .unwrap();
+        match kml {
+            Kml::Element(e) => {
+                assert_eq!(
+                    e.children[0].children[0].content.as_deref(),
+                    Some("<b>bold</b>")
+                );
+            }
+            _ => panic!("expected element"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use kml::types::{Custom, CustomElement, Element};
+    use kml::Kml;
+
+    struct Beacon;
+
+    impl CustomElement for Beacon {
+        fn from_element(_: &Element) -> Option<Self> {
+            Some(Beacon)
+        }
+
+        fn to_element(&self) -> Element {
+            Element::new("acme:Beacon")
+        }
+    }
+
+    fn test_serde_round_trip(kml: &Kml) {
+        let json = serde_json::to_string(kml).unwrap();
+        let roundtrip_kml: Kml = serde_json::from_str(&json).unwrap();
+        assert_eq!(kml, &roundtrip_kml);
+    }
+
+    #[test]
+    fn test_serde_fixtures() {
+        for file_name in ["polygon.kml", "sample.kml", "countries.kml"] {
+            let file_path = format!("tests/fixtures/{}", file_name);
+            let kml: Kml = std::fs::read_to_string(file_path).unwrap().parse().unwrap();
+            test_serde_round_trip(&kml);
+        }
+    }
+
+    // Covers the typed elements that the fixtures don't include
+    #[test]
+    fn test_serde_typed_elements() {
+        let kml_str = r##"<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+          <Document id="doc">
+            <Schema name="Trail" id="trail"><SimpleField name="length" type="double"/></Schema>
+            <Style id="s"><IconStyle><heading>45</heading></IconStyle><LineStyle><width>2</width></LineStyle></Style>
+            <StyleMap id="m"><Pair><key>normal</key><styleUrl>#s</styleUrl></Pair></StyleMap>
+            <Placemark>
+              <name>Summit</name>
+              <LookAt><gx:TimeSpan><begin>2020-01</begin></gx:TimeSpan><longitude>1</longitude><latitude>2</latitude><range>3</range></LookAt>
+              <TimeStamp><when>2020-01-02T03:04:05Z</when></TimeStamp>
+              <ExtendedData><Data name="elevation"><value>1200</value></Data></ExtendedData>
+              <gx:Track><when>2020-01-01T00:00:00Z</when><gx:coord>1 2 3</gx:coord></gx:Track>
+            </Placemark>
+            <Placemark>
+              <Model><Location><longitude>1</longitude><latitude>2</latitude><altitude>3</altitude></Location><Link><href>m.dae</href></Link></Model>
+            </Placemark>
+            <NetworkLink><Region><LatLonAltBox><north>1</north><south>0</south><east>1</east><west>0</west></LatLonAltBox></Region><Link><href>a.kml</href></Link></NetworkLink>
+            <GroundOverlay><Icon><href>a.png</href></Icon><LatLonBox><north>1</north><south>0</south><east>1</east><west>0</west></LatLonBox></GroundOverlay>
+            <ScreenOverlay><Icon><href>b.png</href></Icon></ScreenOverlay>
+            <PhotoOverlay><Camera><longitude>1</longitude><latitude>2</latitude><altitude>3</altitude></Camera><shape>cylinder</shape></PhotoOverlay>
+            <gx:Tour><name>Tour</name><gx:Playlist><gx:Wait><gx:duration>1</gx:duration></gx:Wait></gx:Playlist></gx:Tour>
+            <Folder><atom:author xmlns:atom="http://www.w3.org/2005/Atom"><atom:name>A</atom:name></atom:author></Folder>
+          </Document>
+        </kml>"##;
+        let kml: Kml = kml_str.parse().unwrap();
+        test_serde_round_trip(&kml);
+    }
+
+    // Custom values are deserialized as the element they were written as
+    #[test]
+    fn test_serde_custom() {
+        let custom = Kml::<f64>::Custom(Custom::new(Beacon));
+        let json = serde_json::to_string(&custom).unwrap();
+        match serde_json::from_str(&json).unwrap() {
+            Kml::<f64>::Custom(c) => {
+                assert_eq!(c.element().name, "acme:Beacon");
+                assert!(c.downcast_ref::<Element>().is_some());
+            }
+            k => panic!("expected Custom, found {:?}", k),
+        }
+    }
 }